@@ -0,0 +1,48 @@
+#!/bin/bash
+
+# Compares how long two builds take to check references, on an app made by
+# `pks generate-fixture`, so it needs neither a Rails app nor hyperfine, e.g.
+# bash dev/benchmark_check.sh ../pks-baseline/target/release/pks target/release/pks
+#
+# Each build checks the app RUNS times with a warm cache, and the mean and
+# min of the "checking references" stage it reports with --timing are printed
+# as a markdown table. The size of the app can be set with PACKS,
+# FILES_PER_PACK and VIOLATIONS.
+
+set -e
+
+if [ $# -ne 2 ]; then
+  echo "Usage: bash dev/benchmark_check.sh BASELINE_PKS CANDIDATE_PKS"
+  exit 1
+fi
+
+RUNS=${RUNS:-10}
+APP=tmp/benchmark_app_${PACKS:-300}x${FILES_PER_PACK:-100}x${VIOLATIONS:-3000}
+
+if [ ! -d "$APP" ]; then
+  "$2" generate-fixture "$APP" \
+    --packs "${PACKS:-300}" \
+    --files-per-pack "${FILES_PER_PACK:-100}" \
+    --violations "${VIOLATIONS:-3000}" > /dev/null
+fi
+
+# The duration of the checking stage of one run, in milliseconds
+checking_ms() {
+  "$1" --project-root "$APP" --timing check 2>&1 >/dev/null \
+    | grep "checking references took" \
+    | sed -E 's/.* took ([0-9.]+)(s|ms|µs)$/\1 \2/' \
+    | awk '{ if ($2 == "s") print $1 * 1000; else if ($2 == "µs") print $1 / 1000; else print $1 }'
+}
+
+echo "| Command | Mean [ms] | Min [ms] |"
+echo "|:---|---:|---:|"
+for pks in "$1" "$2"; do
+  # Fills the cache, so only checking is measured afterwards
+  "$pks" --project-root "$APP" check > /dev/null 2>&1 || true
+  for _ in $(seq "$RUNS"); do
+    checking_ms "$pks"
+  done | awk -v pks="$pks" '
+    NR == 1 || $1 < min { min = $1 }
+    { sum += $1 }
+    END { printf "| `%s check` | %.2f | %.2f |\n", pks, sum / NR, min }'
+done
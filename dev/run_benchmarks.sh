@@ -28,4 +28,35 @@ hyperfine --warmup=2 --runs=3 --export-markdown tmp/bm.md \
 
 cat tmp/bm.md >> tmp/packs_benchmarks.md
 
+echo -e "\n## Hot Cache, JSON vs. bincode cache_format, entire codebase" >> tmp/packs_benchmarks.md
+
+# Each format is written to its own cache directory, so the warmup runs fill
+# the bincode cache before it is measured. packwerk.yml is restored afterwards.
+cp packwerk.yml tmp/packwerk.yml.bak
+hyperfine --warmup=2 --runs=3 --export-markdown tmp/bm.md \
+  --parameter-list format json,bincode \
+  --prepare 'cp tmp/packwerk.yml.bak packwerk.yml && echo "cache_format: {format}" >> packwerk.yml' \
+  '../pks/target/release/pks update # cache_format: {format}'
+mv tmp/packwerk.yml.bak packwerk.yml
+
+cat tmp/bm.md >> tmp/packs_benchmarks.md
+
+# Compares with a build from before references were checked in a group per
+# referencing pack, rather than checker by checker over every reference, e.g.
+# BASELINE_PKS=../pks-baseline/target/release/pks
+if [ -n "$BASELINE_PKS" ]; then
+  echo -e "\n## Hot Cache, checking references by referencing pack vs. by checker, entire codebase" >> tmp/packs_benchmarks.md
+
+  # `check` fails when there are violations, which is fine here
+  hyperfine --warmup=2 --runs=5 --ignore-failure --export-markdown tmp/bm.md \
+    "$BASELINE_PKS check" \
+    '../pks/target/release/pks check'
+
+  cat tmp/bm.md >> tmp/packs_benchmarks.md
+
+  echo -e "\n## Checking stage only, by referencing pack vs. by checker, generated app (300 packs, 100 files each, 3000 violations)" >> tmp/packs_benchmarks.md
+
+  RUNS=20 bash ../pks/dev/benchmark_check.sh "$BASELINE_PKS" ../pks/target/release/pks >> tmp/packs_benchmarks.md
+fi
+
 mv tmp/packs_benchmarks.md ../pks/BENCHMARKS.md
@@ -6,11 +6,13 @@ mod test_util {
     use packs::parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver;
     use std::collections::{HashMap, HashSet};
     use std::path::PathBuf;
+    use std::sync::Arc;
 
     use packs::configuration;
 
     use crate::packs::configuration::from_raw;
     use crate::packs::constant_resolver::ConstantResolver;
+    use crate::packs::file_provider::WorkingTree;
     use crate::packs::pack::Pack;
     use crate::packs::raw_configuration::RawConfiguration;
     use crate::packs::walk_directory::WalkDirectoryResult;
@@ -19,21 +21,17 @@ mod test_util {
     pub const SIMPLE_APP: &str = "tests/fixtures/simple_app";
 
     pub fn get_absolute_root(fixture_name: &str) -> PathBuf {
-        PathBuf::from(fixture_name).canonicalize().unwrap()
+        dunce::canonicalize(fixture_name).unwrap()
     }
 
     pub fn get_zeitwerk_constant_resolver_for_fixture(
         fixture_name: &str,
     ) -> Box<dyn ConstantResolver> {
         let absolute_root = get_absolute_root(fixture_name);
-        let configuration = configuration::get(&absolute_root);
+        let mut configuration = configuration::get(&absolute_root).unwrap();
+        configuration.cache_enabled = false;
 
-        get_zeitwerk_constant_resolver(
-            &configuration.pack_set,
-            &absolute_root,
-            &configuration.cache_directory,
-            true,
-        )
+        get_zeitwerk_constant_resolver(&configuration)
     }
 
     // Note that instead, we could derive the `Default` trait on `Pack`
@@ -50,14 +48,23 @@ mod test_util {
                 ignored_dependencies: Default::default(),
                 ignored_private_constants: Default::default(),
                 private_constants: Default::default(),
+                public_constants: Default::default(),
                 package_todo: Default::default(),
+                public_api: Default::default(),
+                todo_expires_on: Default::default(),
                 visible_to: Default::default(),
                 public_folder: Default::default(),
                 layer: Default::default(),
+                root_namespace: Default::default(),
+                autoload_paths: Default::default(),
+                facade: Default::default(),
                 enforce_dependencies: Default::default(),
                 enforce_privacy: Default::default(),
                 enforce_visibility: Default::default(),
                 enforce_architecture: Default::default(),
+                enforce_upward_references: Default::default(),
+                enforce_privacy_from: Default::default(),
+                inherited_enforcement: Default::default(),
                 client_keys: Default::default(),
                 owner: Default::default(),
             }
@@ -79,11 +86,13 @@ mod test_util {
                 included_files: HashSet::new(),
                 included_packs,
                 owning_package_yml_for_file: HashMap::new(),
+                timings: Default::default(),
             };
             from_raw(
                 &default_absolute_root,
                 RawConfiguration::default(),
                 walk_directory_result,
+                Arc::new(WorkingTree),
             )
         }
     }
@@ -1,44 +1,85 @@
-// Currently there are no supported library APIs for packs. The public API is the CLI.
+// The public API is mostly the CLI. The library supports `ConstantIndex`, for
+// tools that look up constants the way packs resolves them, `pipeline`, for
+// tools that run the stages of `check` themselves, and `Pack`, for tools that
+// edit package.yml files.
 // This may change in the future! Please file an issue if you have a use case for a library API.
 pub mod cli;
+pub mod pipeline;
 
 // Module declarations
 pub(crate) mod caching;
 pub(crate) mod checker;
 pub(crate) mod configuration;
 pub(crate) mod constant_resolver;
+pub(crate) mod file_provider;
+pub(crate) mod graph;
 pub(crate) mod monkey_patch_detection;
 pub(crate) mod pack;
 pub(crate) mod parsing;
 pub(crate) mod raw_configuration;
 pub(crate) mod walk_directory;
 
+mod completions;
+mod constant_index;
+mod dead_code;
+mod determinism;
+mod export;
+mod facade;
 mod file_utils;
+#[cfg(feature = "fixture-generator")]
+pub(crate) mod fixture_generator;
+mod git;
+mod health_report;
+mod heatmap;
+mod history;
+mod impacted_tests;
+mod import;
+mod init;
+mod list_packs;
+mod load_paths;
 mod logger;
+mod owners_report;
 mod pack_set;
 mod package_todo;
+mod parse;
 mod reference_extractor;
+mod reference_index;
+mod server;
+mod suggest_dependencies;
+mod trace;
+mod warm_cache;
+mod warning;
 
 use crate::packs;
 use crate::packs::pack::write_pack_to_disk;
-use crate::packs::pack::Pack;
 
 // Internal imports
 pub(crate) use self::checker::Violation;
+pub use self::constant_index::{Constant, ConstantIndex};
+pub(crate) use self::health_report::WebhookFormat;
+pub(crate) use self::heatmap::HeatmapFormat;
+pub(crate) use self::history::HistoryFormat;
+pub(crate) use self::owners_report::OwnersReportFormat;
+pub use self::pack::{Enforcement, EnforcementSetting, Pack};
 pub(crate) use self::pack_set::PackSet;
 pub(crate) use self::parsing::process_files_with_cache;
 pub(crate) use self::parsing::ruby::experimental::get_experimental_constant_resolver;
 pub(crate) use self::parsing::ruby::zeitwerk::get_zeitwerk_constant_resolver;
+pub(crate) use self::parsing::DefinitionKind;
+pub(crate) use self::parsing::FileSigils;
 pub(crate) use self::parsing::ParsedDefinition;
+pub(crate) use self::parsing::ReferenceConfidence;
+pub(crate) use self::parsing::ReferenceKind;
 pub(crate) use self::parsing::UnresolvedReference;
 pub(crate) use configuration::Configuration;
 pub(crate) use package_todo::PackageTodo;
+pub(crate) use warning::Warning;
 
 // External imports
 use serde::Deserialize;
 use serde::Serialize;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn greet() {
     println!("👋 Hello! Welcome to packs 📦 🔥 🎉 🌈. This tool is under construction.")
@@ -87,6 +128,13 @@ See https://github.com/rubyatscale/packs#readme for more info!",
     println!("Successfully created `{}`!", name);
 }
 
+pub fn init(
+    absolute_root: &Path,
+    starter_pack: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    init::init(absolute_root, starter_pack)
+}
+
 pub fn check(
     configuration: &Configuration,
     files: Vec<String>,
@@ -94,10 +142,81 @@ pub fn check(
     checker::check_all(configuration, files)
 }
 
+pub fn files_affected_by(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> Vec<String> {
+    reference_index::files_affected_by(configuration, files)
+}
+
+pub fn explain(
+    configuration: &Configuration,
+    location: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    checker::explain(configuration, location)
+}
+
+pub fn churn_report(
+    configuration: &Configuration,
+    months: u32,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    checker::churn_report(configuration, months, json)
+}
+
+#[cfg(feature = "fixture-generator")]
+pub fn generate_fixture(
+    path: &Path,
+    size: fixture_generator::FixtureSize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fixture_generator::generate_fixture(path, size)
+}
+
+pub fn graph(
+    configuration: &Configuration,
+    options: &graph::GraphOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    graph::graph(configuration, options)
+}
+
+pub fn impacted_tests(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    impacted_tests::impacted_tests(configuration, files)
+}
+
+pub fn order(configuration: &Configuration) -> Result<(), Box<dyn Error>> {
+    graph::order(configuration)
+}
+
 pub fn update(
     configuration: &Configuration,
+    add_dependencies: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    checker::update(configuration, add_dependencies)
+}
+
+pub fn consolidate_todos(configuration: &Configuration) {
+    package_todo::consolidate(configuration)
+}
+
+pub fn split_todos(configuration: &Configuration) {
+    package_todo::split(configuration)
+}
+
+pub fn list_oldest_todos(
+    configuration: &Configuration,
+    limit: Option<usize>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    checker::update(configuration)
+    package_todo::list_oldest(configuration, limit)
+}
+
+pub fn list_top_offender_todos(
+    configuration: &Configuration,
+    limit: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    package_todo::list_top_offenders(configuration, limit)
 }
 
 pub fn add_dependency(
@@ -124,16 +243,21 @@ pub fn add_dependency(
         return Ok(());
     }
 
-    let new_from_pack = from_pack.add_dependency(to_pack);
-
-    write_pack_to_disk(&new_from_pack);
+    let mut new_from_pack = from_pack.clone();
+    new_from_pack.add_dependency(&to_pack.name);
+    new_from_pack.write()?;
 
     // Note: Ideally we wouldn't have to refetch the configuration and could instead
     // either update the existing one OR modify the existing one and return a new one
     // (which takes ownership over the previous one).
     // For now, we simply refetch the entire configuration for simplicity,
     // since we don't mind the slowdown for this CLI command.
-    let new_configuration = configuration::get(&configuration.absolute_root);
+    // The first configuration already had a root pack, or was allowed not to
+    let new_configuration = configuration::load(
+        &configuration.absolute_root,
+        true,
+        configuration.file_provider.clone(),
+    )?;
     let validation_result = packs::validate(&new_configuration);
     if validation_result.is_err() {
         println!("Added `{}` as a dependency to `{}`!", to, from);
@@ -145,6 +269,77 @@ pub fn add_dependency(
     Ok(())
 }
 
+pub fn suggest_dependencies(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    suggest_dependencies::suggest_dependencies(configuration, pack_name)
+}
+
+pub fn trace(
+    configuration: &Configuration,
+    from_pack: &str,
+    to_pack: &str,
+) -> Result<(), Box<dyn Error>> {
+    trace::trace(configuration, from_pack, to_pack)
+}
+
+pub fn plan_removal(
+    configuration: &Configuration,
+    from_pack: &str,
+    to_pack: &str,
+) -> Result<(), Box<dyn Error>> {
+    checker::plan_removal(configuration, from_pack, to_pack)
+}
+
+pub fn annotate(
+    configuration: &Configuration,
+    remove: bool,
+) -> Result<(), Box<dyn Error>> {
+    checker::annotate(configuration, remove)
+}
+
+pub fn extract(
+    configuration: &Configuration,
+    namespace: &str,
+    new_pack: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    checker::extract(configuration, namespace, new_pack, dry_run)
+}
+
+pub fn dead_code(
+    configuration: &Configuration,
+    public_only: bool,
+) -> Result<(), Box<dyn Error>> {
+    dead_code::dead_code(configuration, public_only)
+}
+
+pub fn api_usage(
+    configuration: &Configuration,
+    pack_name: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    checker::api_usage(configuration, pack_name)
+}
+
+pub fn verify_load_paths(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    load_paths::verify_load_paths(configuration)
+}
+
+pub fn verify_determinism(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    determinism::verify_determinism(configuration)
+}
+
+pub fn check_file_placement(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    parsing::ruby::zeitwerk::check_file_placement(configuration)
+}
+
 pub fn list_included_files(
     configuration: Configuration,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -162,8 +357,8 @@ pub fn validate(
 }
 
 pub fn configuration(project_root: PathBuf) -> Configuration {
-    let absolute_root = project_root.canonicalize().unwrap();
-    configuration::get(&absolute_root)
+    let absolute_root = dunce::canonicalize(project_root).unwrap();
+    configuration::get(&absolute_root).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn check_unnecessary_dependencies(
@@ -177,10 +372,52 @@ pub fn check_unnecessary_dependencies(
     }
 }
 
-pub fn list(configuration: Configuration) {
-    for pack in configuration.pack_set.packs {
-        println!("{}", pack.yml.display())
-    }
+// Compares against Ruby packwerk, using its saved output if there is some,
+// and otherwise running `bin/packwerk check` in the project root
+pub fn parity_check(
+    configuration: &Configuration,
+    packwerk_output: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let packwerk_output = match packwerk_output {
+        Some(packwerk_output) => std::fs::read_to_string(packwerk_output)?,
+        None => {
+            // `packwerk check` exits with an error when it finds violations,
+            // so only its output matters
+            let output = std::process::Command::new("bin/packwerk")
+                .arg("check")
+                .current_dir(&configuration.absolute_root)
+                .output()
+                .map_err(|e| {
+                    format!("Failed to run `bin/packwerk check`: {}", e)
+                })?;
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        }
+    };
+
+    checker::parity_check(configuration, &packwerk_output)
+}
+
+pub fn completions(
+    configuration: &Configuration,
+    command: clap::Command,
+    shell: clap_complete::Shell,
+) -> Result<(), Box<dyn Error>> {
+    completions::completions(configuration, command, shell)
+}
+
+pub fn parse(
+    configuration: &Configuration,
+    file: &str,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    parse::parse(configuration, file, json)
+}
+
+pub fn list(
+    configuration: &Configuration,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    list_packs::list_packs(configuration, json)
 }
 
 pub fn lint_package_yml_files(configuration: &Configuration) {
@@ -189,6 +426,88 @@ pub fn lint_package_yml_files(configuration: &Configuration) {
     }
 }
 
+pub fn lint_todos(
+    configuration: &Configuration,
+    fix: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    checker::lint_todos(configuration, fix)
+}
+
+pub fn metrics(configuration: &Configuration) {
+    let budgets = &configuration.pack_budgets;
+    for metrics in checker::pack_budget::pack_metrics(configuration) {
+        println!(
+            "{}: {} file(s), {} public constant(s), {} dependency(ies)",
+            metrics.pack_name,
+            metrics.file_count,
+            metrics.public_constant_count,
+            metrics.dependency_count
+        );
+        for over_budget in metrics.over_budget(budgets) {
+            println!("  over budget: {}", over_budget);
+        }
+    }
+}
+
+pub fn health_report(
+    configuration: &Configuration,
+    webhook: Option<&str>,
+    format: WebhookFormat,
+    state_file: Option<PathBuf>,
+    top: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    health_report::health_report(
+        configuration,
+        webhook,
+        format,
+        state_file,
+        top,
+    )
+}
+
+pub fn history(
+    configuration: &Configuration,
+    months: u32,
+    since: Option<&str>,
+    format: HistoryFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    history::history(configuration, months, since, format)
+}
+
+pub fn owners_report(
+    configuration: &Configuration,
+    format: OwnersReportFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    owners_report::owners_report(configuration, format)
+}
+
+pub fn export_sorbet_packages(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    export::sorbet_packages(configuration)
+}
+
+pub fn export_heatmap(
+    configuration: &Configuration,
+    format: HeatmapFormat,
+    normalize: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    heatmap::heatmap(configuration, format, normalize)
+}
+
+pub fn generate_facade(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    facade::generate_facade(configuration, pack_name)
+}
+
+pub fn import_sorbet_packages(
+    absolute_root: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    import::sorbet_packages(absolute_root)
+}
+
 pub fn delete_cache(configuration: Configuration) {
     let absolute_cache_dir = configuration.cache_directory;
     if let Err(err) = std::fs::remove_dir_all(&absolute_cache_dir) {
@@ -200,11 +519,29 @@ pub fn delete_cache(configuration: Configuration) {
     }
 }
 
+pub fn warm_cache(
+    configuration: &Configuration,
+    all: bool,
+    since: &str,
+    foreground: bool,
+) -> Result<(), Box<dyn Error>> {
+    warm_cache::warm_cache(configuration, all, since, foreground)
+}
+
+pub fn serve(
+    configuration: &Configuration,
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    server::serve(configuration, address)
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct ProcessedFile {
     pub absolute_path: PathBuf,
     pub unresolved_references: Vec<UnresolvedReference>,
     pub definitions: Vec<ParsedDefinition>,
+    #[serde(default)]
+    pub sigils: FileSigils,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Default, Eq, Clone)]
@@ -214,29 +551,10 @@ pub struct SourceLocation {
 }
 
 pub(crate) fn list_definitions(configuration: &Configuration, ambiguous: bool) {
-    let constant_resolver = if configuration.experimental_parser {
-        let processed_files: Vec<ProcessedFile> = process_files_with_cache(
-            &configuration.included_files,
-            configuration.get_cache(),
-            configuration,
-        );
-
-        get_experimental_constant_resolver(
-            &configuration.absolute_root,
-            &processed_files,
-            &configuration.ignored_definitions,
-        )
-    } else {
-        if ambiguous {
-            panic!("Ambiguous mode is not supported for the Zeitwerk parser");
-        }
-        get_zeitwerk_constant_resolver(
-            &configuration.pack_set,
-            &configuration.absolute_root,
-            &configuration.cache_directory,
-            !configuration.cache_enabled,
-        )
-    };
+    if ambiguous && !configuration.experimental_parser {
+        panic!("Ambiguous mode is not supported for the Zeitwerk parser");
+    }
+    let constant_resolver = constant_index::constant_resolver(configuration);
 
     let constant_definition_map = constant_resolver
         .fully_qualified_constant_name_to_constant_definition_map();
@@ -252,7 +570,14 @@ pub(crate) fn list_definitions(configuration: &Configuration, ambiguous: bool) {
                 .strip_prefix(&configuration.absolute_root)
                 .unwrap();
 
-            println!("{:?} is defined at {:?}", name, relative_path);
+            match definition.kind {
+                DefinitionKind::Constant => {
+                    println!("{:?} is defined at {:?}", name, relative_path)
+                }
+                DefinitionKind::Autoload => {
+                    println!("{:?} is autoloaded at {:?}", name, relative_path)
+                }
+            }
         }
     }
 }
@@ -284,7 +609,8 @@ mod tests {
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
         let absolute_file_path = configuration
             .absolute_root
             .join("packs/foo/app/services/foo.rb")
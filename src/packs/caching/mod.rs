@@ -1,6 +1,9 @@
 use std::path::{Path, PathBuf};
 
-use super::{file_utils::file_content_digest, ProcessedFile};
+use super::{
+    file_utils::{file_content_digest, to_slash_path},
+    ProcessedFile,
+};
 pub(crate) mod cache;
 pub(crate) mod noop_cache;
 pub(crate) mod per_file_cache;
@@ -20,7 +23,7 @@ pub struct EmptyCacheEntry {
 
 impl EmptyCacheEntry {
     pub fn new(cache_directory: &Path, filepath: &Path) -> EmptyCacheEntry {
-        let file_digest = md5::compute(filepath.to_str().unwrap());
+        let file_digest = md5::compute(to_slash_path(filepath));
         let file_name_digest = format!("{:x}", file_digest);
         let cache_file_path = cache_directory.join(&file_name_digest);
 
@@ -35,7 +38,16 @@ impl EmptyCacheEntry {
     }
 }
 
-pub fn create_cache_dir_idempotently(cache_dir: &Path) {
+pub fn create_cache_dir_idempotently(cache_dir: &Path) -> std::io::Result<()> {
     std::fs::create_dir_all(cache_dir)
-        .expect("Failed to create cache directory");
+}
+
+// Whether an IO error means the cache location can't be written to at all,
+// e.g. in a hermetic build where the source tree is mounted read-only
+pub fn is_read_only_error(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::PermissionDenied
+            | std::io::ErrorKind::ReadOnlyFilesystem
+    )
 }
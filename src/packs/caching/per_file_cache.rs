@@ -1,5 +1,8 @@
+use crate::packs::raw_configuration::CacheFormat;
 use crate::packs::ProcessedFile;
+use bincode::Options;
 use serde::{Deserialize, Serialize};
+use tracing::debug;
 
 use std::fs::File;
 use std::io::Write;
@@ -7,17 +10,26 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use super::cache::Cache;
+use super::is_read_only_error;
 use super::CacheResult;
 use super::EmptyCacheEntry;
 
+// Bincode entries can't be read once `ProcessedFile` changes shape, so the
+// version is part of their cache directory. Bump it when that happens, or
+// when a parser starts recording something entries written before lack.
+pub(crate) const BINCODE_CACHE_VERSION: u32 = 3;
+
 pub struct PerFileCache {
     pub cache_dir: PathBuf,
+    pub cache_format: CacheFormat,
+    pub read_only: bool,
 }
 
 impl Cache for PerFileCache {
     fn get(&self, path: &Path) -> CacheResult {
         let empty_cache_entry = EmptyCacheEntry::new(&self.cache_dir, path);
-        let cache_entry = CacheEntry::from_empty(&empty_cache_entry);
+        let cache_entry =
+            CacheEntry::from_empty(&empty_cache_entry, self.cache_format);
         if let Some(cache_entry) = cache_entry {
             let file_digests_match = cache_entry.file_contents_digest
                 == empty_cache_entry.file_contents_digest;
@@ -38,6 +50,10 @@ impl Cache for PerFileCache {
         empty_cache_entry: &EmptyCacheEntry,
         processed_file: &ProcessedFile,
     ) {
+        if self.read_only {
+            return;
+        }
+
         let file_contents_digest =
             empty_cache_entry.file_contents_digest.to_owned();
 
@@ -48,17 +64,26 @@ impl Cache for PerFileCache {
             processed_file: processed_file.clone(),
         };
 
-        let cache_data = serde_json::to_string(&cache_entry)
-            .expect("Failed to serialize references");
-        let mut file = File::create(&empty_cache_entry.cache_file_path)
-            .unwrap_or_else(|e| {
-                panic!(
-                    "Failed to create cache file {:?}: {}",
-                    empty_cache_entry.cache_file_path, e
-                )
-            });
-
-        file.write_all(cache_data.as_bytes())
+        let cache_data = match self.cache_format {
+            CacheFormat::Json => serde_json::to_vec(&cache_entry)
+                .expect("Failed to serialize references"),
+            CacheFormat::Bincode => {
+                let encoded = bincode::serialize(&cache_entry)
+                    .expect("Failed to serialize references");
+                zstd::encode_all(encoded.as_slice(), 0)
+                    .expect("Failed to compress references")
+            }
+        };
+        let mut file = match File::create(&empty_cache_entry.cache_file_path) {
+            Ok(file) => file,
+            Err(e) if is_read_only_error(&e) => return,
+            Err(e) => panic!(
+                "Failed to create cache file {:?}: {}",
+                empty_cache_entry.cache_file_path, e
+            ),
+        };
+
+        file.write_all(&cache_data)
             .expect("Failed to write cache file");
     }
 }
@@ -70,15 +95,30 @@ pub struct CacheEntry {
 }
 
 impl CacheEntry {
-    pub fn from_empty(empty: &EmptyCacheEntry) -> Option<CacheEntry> {
+    pub fn from_empty(
+        empty: &EmptyCacheEntry,
+        cache_format: CacheFormat,
+    ) -> Option<CacheEntry> {
         let cache_file_path = &empty.cache_file_path;
 
-        if cache_file_path.exists() {
-            Some(read_json_file(cache_file_path).unwrap_or_else(|_| {
-                panic!("Failed to read cache file {:?}", cache_file_path)
-            }))
-        } else {
-            None
+        if !cache_file_path.exists() {
+            return None;
+        }
+        let cache_entry = match cache_format {
+            CacheFormat::Json => read_json_file(cache_file_path),
+            CacheFormat::Bincode => read_bincode_file(cache_file_path),
+        };
+        // An entry that can't be read, e.g. a truncated file or one written
+        // by another version, is a miss, and the file is parsed again
+        match cache_entry {
+            Ok(cache_entry) => Some(cache_entry),
+            Err(err) => {
+                debug!(
+                    "Ignoring unreadable cache file {:?}: {}",
+                    cache_file_path, err
+                );
+                None
+            }
         }
     }
 }
@@ -92,20 +132,40 @@ pub fn read_json_file(
     Ok(data)
 }
 
+pub fn read_bincode_file(
+    path: &PathBuf,
+) -> Result<CacheEntry, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let decoded = zstd::decode_all(std::io::BufReader::new(file))?;
+    // The same options as `bincode::serialize`, limited to the size of the
+    // entry, so a corrupt length can't make it allocate more than that
+    let data = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(decoded.len() as u64)
+        .deserialize(&decoded)?;
+    Ok(data)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::packs::{
         self, configuration,
         file_utils::file_content_digest,
-        parsing::{Range, UnresolvedReference},
+        parsing::{
+            FileSigils, ParsedDefinition, Range, ReferenceConfidence,
+            ReferenceKind, UnresolvedReference,
+        },
+        DefinitionKind,
     };
 
     use super::*;
 
     fn teardown() {
-        packs::delete_cache(configuration::get(&PathBuf::from(
-            "tests/fixtures/simple_app",
-        )));
+        packs::delete_cache(
+            configuration::get(&PathBuf::from("tests/fixtures/simple_app"))
+                .unwrap(),
+        );
     }
 
     #[test]
@@ -151,9 +211,12 @@ mod tests {
                         start_col: 22,
                         end_row: 8,
                         end_col: 25,
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 }],
                 definitions: vec![],
+                sigils: FileSigils::default(),
             }
         };
 
@@ -164,4 +227,105 @@ mod tests {
 
         teardown();
     }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let cache_dir = std::env::temp_dir().join("packs_bincode_cache_test");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let cache = PerFileCache {
+            cache_dir: cache_dir.clone(),
+            cache_format: CacheFormat::Bincode,
+            read_only: false,
+        };
+        let path = PathBuf::from(
+            "tests/fixtures/simple_app/packs/bar/app/services/bar.rb",
+        );
+
+        let processed_file = ProcessedFile {
+            absolute_path: path.clone(),
+            unresolved_references: vec![UnresolvedReference {
+                name: "Bar".to_owned(),
+                namespace_path: vec!["Foo".to_owned()],
+                location: Range {
+                    start_row: 1,
+                    start_col: 2,
+                    end_row: 1,
+                    end_col: 5,
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            }],
+            definitions: vec![
+                ParsedDefinition {
+                    fully_qualified_name: "::Bar".to_owned(),
+                    location: Range {
+                        start_row: 1,
+                        start_col: 0,
+                        end_row: 3,
+                        end_col: 3,
+                    },
+                    alias_of: None,
+                    kind: DefinitionKind::Constant,
+                },
+                ParsedDefinition {
+                    fully_qualified_name: "::LegacyBar".to_owned(),
+                    location: Range {
+                        start_row: 5,
+                        start_col: 0,
+                        end_row: 5,
+                        end_col: 16,
+                    },
+                    alias_of: Some("Bar".to_owned()),
+                    kind: DefinitionKind::Constant,
+                },
+            ],
+            sigils: FileSigils {
+                pack_public: true,
+                packs_strict: false,
+            },
+        };
+
+        match cache.get(&path) {
+            CacheResult::Miss(empty_cache_entry) => {
+                cache.write(&empty_cache_entry, &processed_file)
+            }
+            CacheResult::Processed(_) => panic!("Expected a cache miss"),
+        }
+
+        match cache.get(&path) {
+            CacheResult::Processed(cached) => {
+                assert_eq!(processed_file, cached)
+            }
+            CacheResult::Miss(_) => panic!("Expected a cache hit"),
+        }
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_entry_is_a_miss() {
+        let cache_dir = std::env::temp_dir().join("packs_corrupt_cache_test");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let path = PathBuf::from(
+            "tests/fixtures/simple_app/packs/bar/app/services/bar.rb",
+        );
+        let empty_cache_entry = EmptyCacheEntry::new(&cache_dir, &path);
+
+        for cache_format in [CacheFormat::Json, CacheFormat::Bincode] {
+            let cache = PerFileCache {
+                cache_dir: cache_dir.clone(),
+                cache_format,
+                read_only: false,
+            };
+            std::fs::write(
+                &empty_cache_entry.cache_file_path,
+                zstd::encode_all(&b"not a cache entry"[..], 0).unwrap(),
+            )
+            .unwrap();
+
+            assert!(matches!(cache.get(&path), CacheResult::Miss(_)));
+        }
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
 }
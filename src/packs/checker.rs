@@ -1,29 +1,112 @@
 // Module declarations
+mod annotate;
+mod api_usage;
 pub(crate) mod architecture;
+mod churn;
+mod context;
 mod dependency;
+mod explain;
+mod extract;
+mod messages;
+pub(crate) mod pack_budget;
+mod parity;
+mod plan_removal;
 mod privacy;
+mod quick_fix;
 pub(crate) mod reference;
+mod reference_filter;
+mod reopened_constants;
+mod report;
+mod todo_lint;
+mod upward;
 mod visibility;
 
 // Internal imports
-use crate::packs::pack::write_pack_to_disk;
+use crate::packs::file_utils::to_slash_path;
 use crate::packs::pack::Pack;
 use crate::packs::package_todo;
 use crate::packs::Configuration;
 use crate::packs::PackSet;
+use crate::packs::ReferenceConfidence;
 
 // External imports
+use context::CheckContext;
+use messages::ViolationMessages;
 use rayon::prelude::IntoParallelIterator;
 use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
 use reference::Reference;
-use std::collections::HashMap;
+use reference_filter::ReferenceFilter;
+use report::CheckReport;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
 use std::{collections::HashSet, path::PathBuf};
 use tracing::debug;
 
-use super::reference_extractor::get_all_references;
+use super::parsing::ParseError;
+use super::reference_extractor::{
+    get_all_references, get_all_references_and_parse_errors,
+};
+
+pub use reference_filter::FilterCounts;
+
+// Which violations make `check` fail
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    // Only violations that are not recorded in a todo file
+    #[default]
+    New,
+    // Any violation, whether it is recorded or not
+    All,
+}
+
+// How `check` prints its report
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    // One JSON object per line for each violation, then one for the rest of
+    // the report, written as they're serialized rather than all at once
+    Ndjson,
+    // The JSON report, rendered with a user's Handlebars template
+    Template,
+    // GitHub-flavored Markdown, with the violations of each pack in a
+    // collapsed section
+    Markdown,
+}
+
+// The order `check` reports violations in. Ties are broken by file, then
+// message, so the order is the same across runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ViolationSort {
+    // By the referencing file
+    #[default]
+    File,
+    // By the pack that defines the referenced constant
+    Pack,
+    // By the referenced constant
+    Constant,
+    // Files with the most violations first
+    Count,
+}
+
+// How `check` prints the file of each violation
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PathDisplay {
+    // Relative to the project root
+    #[default]
+    Relative,
+    Absolute,
+    // Relative to the directory `check` runs in
+    Cwd,
+}
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(
+    PartialEq, Eq, Hash, Debug, Serialize, Deserialize, PartialOrd, Ord, Clone,
+)]
 pub struct ViolationIdentifier {
     pub violation_type: String,
     pub file: String,
@@ -32,6 +115,19 @@ pub struct ViolationIdentifier {
     pub defining_pack_name: String,
 }
 
+// Stable codes for each violation type, so docs and tooling can refer to a
+// kind of violation without depending on the wording of its message
+pub(crate) fn violation_code(violation_type: &str) -> Option<&'static str> {
+    match violation_type {
+        "dependency" => Some("P001"),
+        "privacy" => Some("P002"),
+        "visibility" => Some("P003"),
+        "architecture" => Some("P004"),
+        "upward" => Some("P005"),
+        _ => None,
+    }
+}
+
 pub fn get_defining_pack<'a>(
     violation: &ViolationIdentifier,
     packset: &'a PackSet,
@@ -71,17 +167,31 @@ pub fn get_referencing_pack<'a>(
     referencing_pack_result.unwrap_or_else(error_closure)
 }
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone)]
 pub struct Violation {
     message: String,
     pub identifier: ViolationIdentifier,
+    pub confidence: ReferenceConfidence,
+    // Reported even if a todo file records it, e.g. a privacy violation on a
+    // line introduced after the defining pack's `enforce_privacy_from`
+    pub strict: bool,
+    // Where the referenced constant is defined, relative to the root
+    pub relative_defining_file: Option<String>,
+}
+
+impl Violation {
+    // The message printed for the violation, before `violation_messages`
+    // and codes are applied
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 pub(crate) trait CheckerInterface {
     fn check(
         &self,
         reference: &Reference,
-        configuration: &Configuration,
+        context: &CheckContext,
     ) -> Option<Violation>;
 
     fn is_strict_mode_violation(
@@ -90,6 +200,14 @@ pub(crate) trait CheckerInterface {
         configuration: &Configuration,
     ) -> bool;
 
+    // Whether the pack that decides if this kind of violation is reported
+    // still enforces it. If not, recording the violation serves no purpose.
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool;
+
     fn violation_type(&self) -> String;
 }
 
@@ -97,31 +215,194 @@ pub(crate) trait ValidatorInterface {
     fn validate(&self, configuration: &Configuration) -> Option<String>;
 }
 
-// TODO: Break this function up into smaller functions
+// Everything `check` finds, for the reporters to print, or for a program
+// using packs as a library to look at
+#[derive(Debug, Default)]
+pub struct CheckResult {
+    // The violations that aren't recorded in todo files, or are strict,
+    // sorted by `--sort`
+    pub violations: Vec<Violation>,
+    pub recorded_violations_count: usize,
+    // Violations of `violations` that were recorded in a todo entry that
+    // expired
+    pub expired_violations_count: usize,
+    // Violations recorded for the checked files that weren't found
+    pub stale_todos: Vec<ViolationIdentifier>,
+    pub strict_mode_violations: Vec<ViolationIdentifier>,
+    // Files that were skipped, so their references weren't checked
+    pub parse_errors: Vec<ParseError>,
+    pub timings: Vec<Timing>,
+    // How many references the reference filter kept and dropped, if the
+    // references were found in this run
+    pub filter_counts: Option<FilterCounts>,
+    // Files that weren't parsed because nothing in them looks like a
+    // constant
+    pub skipped_files_count: usize,
+}
+
+// How long a stage of `check` took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub stage: String,
+    pub duration: Duration,
+}
+
 pub(crate) fn check_all(
     configuration: &Configuration,
     files: Vec<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // Which pack's rules apply to files of a duplicated pack is arbitrary,
+    // so there's no point in checking them
+    let duplicate_pack_errors = configuration.pack_set.duplicate_pack_errors();
+    if !duplicate_pack_errors.is_empty() {
+        return Err(duplicate_pack_errors.join("\n\n").into());
+    }
+    report(configuration, &check(configuration, files))
+}
+
+// Finds the violations in `files` (or in every included file, if there are
+// none) without printing anything
+pub(crate) fn check(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> CheckResult {
     let checkers = get_checkers(configuration);
 
     debug!("Intersecting input files with configuration included files");
     let absolute_paths: HashSet<PathBuf> = configuration.intersect_files(files);
 
-    let found_violations: HashSet<Violation> =
-        get_all_violations(configuration, &absolute_paths, &checkers);
+    let references_start = Instant::now();
+    let (references, parse_errors) =
+        get_all_references_and_parse_errors(configuration, &absolute_paths);
+    let references_duration = references_start.elapsed();
+    let skipped_files_count = configuration.skipped_files.take();
+
+    let checkers_start = Instant::now();
+    let (found_violations, filter_counts) = check_references(
+        configuration,
+        references,
+        &checkers,
+        &ReferenceFilter::new(configuration).keeping_root_references(),
+    );
+    let checkers_duration = checkers_start.elapsed();
+
+    CheckResult {
+        parse_errors,
+        timings: vec![
+            Timing {
+                stage: String::from("finding references"),
+                duration: references_duration,
+            },
+            Timing {
+                stage: String::from("checking references"),
+                duration: checkers_duration,
+            },
+        ],
+        filter_counts: Some(filter_counts),
+        skipped_files_count,
+        ..check_result(configuration, &absolute_paths, &found_violations)
+    }
+}
+
+// Prints the violations found in `absolute_paths` that aren't recorded,
+// along with stale and strict mode violations of the recorded ones, and
+// errors if any of them fail the check
+pub(crate) fn report_violations(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+    found_violations: &HashSet<Violation>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    report(
+        configuration,
+        &check_result(configuration, absolute_paths, found_violations),
+    )
+}
+
+// Prints the result in the configured format, with parse errors and
+// `--timing` on stderr, and errors if the check fails
+pub(crate) fn report(
+    configuration: &Configuration,
+    result: &CheckResult,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for parse_error in &result.parse_errors {
+        eprintln!("Error: {}", parse_error);
+    }
+    if configuration.timing {
+        for timing in &result.timings {
+            eprintln!("Timing: {} took {:?}", timing.stage, timing.duration);
+        }
+        if let Some(filter_counts) = &result.filter_counts {
+            eprintln!("Timing: reference filter: {}", filter_counts);
+        }
+        print_skipped_files_count(result.skipped_files_count);
+    }
+
+    let report = check_report(configuration, result);
+    report.print(configuration)?;
+
+    if report.errors_present(configuration) {
+        Err("Packwerk check failed".into())
+    } else {
+        Ok(())
+    }
+}
 
+// The result as `check --format json` prints it
+pub(crate) fn json_report(
+    configuration: &Configuration,
+    result: &CheckResult,
+) -> String {
+    check_report(configuration, result).render_json(configuration)
+}
+
+fn check_report<'a>(
+    configuration: &'a Configuration,
+    result: &'a CheckResult,
+) -> CheckReport<'a> {
+    CheckReport {
+        reportable_violations: result.violations.iter().collect(),
+        recorded_violations_count: result.recorded_violations_count,
+        expired_violations_count: result.expired_violations_count,
+        stale_violations: result.stale_todos.iter().collect(),
+        strict_mode_violations: result.strict_mode_violations.iter().collect(),
+        parse_errors: &result.parse_errors,
+        warnings: &configuration.warnings,
+    }
+}
+
+// Sorts out which of the found violations are reported, and which recorded
+// violations are stale or break strict mode
+fn check_result(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+    found_violations: &HashSet<Violation>,
+) -> CheckResult {
+    let checkers = get_checkers(configuration);
     let recorded_violations = &configuration.pack_set.all_violations;
 
     debug!("Filtering out recorded violations");
 
-    let reportable_violations: Vec<&Violation> =
+    // With `enforce_from_root: false`, violations from the root pack are
+    // only found so the ones recorded for it aren't taken for stale ones
+    let enforced_violations: Vec<&Violation> = found_violations
+        .iter()
+        .filter(|v| {
+            configuration.enforce_from_root
+                || v.identifier.referencing_pack_name != "."
+        })
+        .collect();
+
+    let mut reportable_violations: Vec<&Violation> =
         if configuration.ignore_recorded_violations {
             debug!("Filtering recorded violations is disabled in config");
-            found_violations.iter().collect()
+            enforced_violations.clone()
         } else {
-            found_violations
+            enforced_violations
                 .iter()
-                .filter(|v| !recorded_violations.contains(&v.identifier))
+                .copied()
+                .filter(|v| {
+                    v.strict || !recorded_violations.contains(&v.identifier)
+                })
                 .collect()
         };
 
@@ -134,17 +415,14 @@ pub(crate) fn check_all(
     let relative_files = absolute_paths
         .iter()
         .map(|p| {
-            p.strip_prefix(&configuration.absolute_root)
-                .unwrap()
-                .to_str()
-                .unwrap()
+            to_slash_path(p.strip_prefix(&configuration.absolute_root).unwrap())
         })
-        .collect::<HashSet<&str>>();
+        .collect::<HashSet<String>>();
 
-    let stale_violations = recorded_violations
+    let mut stale_violations = recorded_violations
         .par_iter()
         .filter(|v_identifier| {
-            relative_files.contains(&v_identifier.file.as_str())
+            relative_files.contains(&v_identifier.file)
                 && !found_violation_identifiers.contains(v_identifier)
         })
         .collect::<Vec<&ViolationIdentifier>>();
@@ -163,73 +441,142 @@ pub(crate) fn check_all(
         indexed_checkers.insert(checker.violation_type(), checker);
     }
 
-    let strict_mode_violations: Vec<&ViolationIdentifier> = recorded_violations
-        .iter()
-        .filter(|v| {
-            indexed_checkers
-                .get(&v.violation_type)
-                .unwrap()
-                .is_strict_mode_violation(v, configuration)
-        })
-        .collect();
+    let mut strict_mode_violations: Vec<&ViolationIdentifier> =
+        recorded_violations
+            .iter()
+            .filter(|v| {
+                indexed_checkers
+                    .get(&v.violation_type)
+                    .unwrap()
+                    .is_strict_mode_violation(v, configuration)
+            })
+            .collect();
 
     debug!("Finished finding strict mode violations");
 
-    let mut errors_present = false;
-
-    if !reportable_violations.is_empty() {
-        for violation in reportable_violations.iter() {
-            println!("{}\n", violation.message);
-        }
-
-        println!("{} violation(s) detected:", reportable_violations.len());
-
-        errors_present = true;
+    let recorded_violations_count =
+        enforced_violations.len() - reportable_violations.len();
+    let expired_violations = &configuration.pack_set.expired_violations;
+    let expired_violations_count = reportable_violations
+        .iter()
+        .filter(|v| expired_violations.contains(&v.identifier))
+        .count();
+
+    // Violations are found in parallel and kept in hash sets, so sort them to
+    // make the report identical across runs and thread counts
+    sort_violations(&mut reportable_violations, configuration.sort);
+    stale_violations.sort();
+    strict_mode_violations.sort();
+
+    CheckResult {
+        violations: reportable_violations.into_iter().cloned().collect(),
+        recorded_violations_count,
+        expired_violations_count,
+        stale_todos: stale_violations.into_iter().cloned().collect(),
+        strict_mode_violations: strict_mode_violations
+            .into_iter()
+            .cloned()
+            .collect(),
+        ..CheckResult::default()
     }
+}
 
-    if !stale_violations.is_empty() {
-        println!(
-            "There were stale violations found, please run `packs update`"
-        );
-        errors_present = true;
+fn sort_violations(violations: &mut [&Violation], sort: ViolationSort) {
+    let mut counts_by_file: HashMap<String, usize> = HashMap::new();
+    for violation in violations.iter() {
+        *counts_by_file
+            .entry(violation.identifier.file.clone())
+            .or_default() += 1;
     }
-
-    if !strict_mode_violations.is_empty() {
-        for v in strict_mode_violations {
-            let error_message = format!("{} cannot have {} violations on {} because strict mode is enabled for {} violations in the enforcing pack's package.yml file",
-                v.referencing_pack_name,
-                v.violation_type,
-                v.defining_pack_name,
-                v.violation_type
-            );
-            println!("{}", error_message);
+    match sort {
+        ViolationSort::File => {
+            violations.sort_by(|a, b| tiebreaker(a).cmp(&tiebreaker(b)))
         }
-
-        errors_present = true;
+        ViolationSort::Pack => violations.sort_by(|a, b| {
+            (&a.identifier.defining_pack_name, tiebreaker(a))
+                .cmp(&(&b.identifier.defining_pack_name, tiebreaker(b)))
+        }),
+        ViolationSort::Constant => violations.sort_by(|a, b| {
+            (&a.identifier.constant_name, tiebreaker(a))
+                .cmp(&(&b.identifier.constant_name, tiebreaker(b)))
+        }),
+        ViolationSort::Count => violations.sort_by(|a, b| {
+            let count =
+                |v: &Violation| Reverse(counts_by_file[&v.identifier.file]);
+            (count(a), tiebreaker(a)).cmp(&(count(b), tiebreaker(b)))
+        }),
     }
+}
 
-    if errors_present {
-        Err("Packwerk check failed".into())
-    } else {
-        println!("No violations detected!");
-        Ok(())
-    }
+fn tiebreaker(violation: &Violation) -> (&str, &str, &ViolationIdentifier) {
+    (
+        &violation.identifier.file,
+        &violation.message,
+        &violation.identifier,
+    )
 }
 
 fn validate(configuration: &Configuration) -> Vec<String> {
     debug!("Running validators against packages");
-    let validators: Vec<Box<dyn ValidatorInterface + Send + Sync>> =
-        vec![Box::new(dependency::Checker {})];
+    let validators: Vec<Box<dyn ValidatorInterface + Send + Sync>> = vec![
+        Box::new(dependency::Checker {}),
+        Box::new(pack_budget::Checker {}),
+        Box::new(privacy::Checker::default()),
+        Box::new(reopened_constants::Checker {}),
+        Box::new(todo_lint::Checker {}),
+    ];
 
-    let validation_errors = validators
+    let mut validation_errors: Vec<String> = validators
         .iter()
         .filter_map(|v| v.validate(configuration))
         .collect();
     debug!("Finished validators against packages");
 
+    validation_errors.extend(configuration.pack_set.duplicate_pack_errors());
+    validation_errors.extend(configuration.namespace_pack_mapping_errors());
+
+    let unenforced_violations = unenforced_recorded_violations(configuration);
+    if !unenforced_violations.is_empty() {
+        let descriptions: Vec<String> = unenforced_violations
+            .iter()
+            .map(|v| {
+                format!(
+                    "- {} violation on `{}` in {}",
+                    v.violation_type, v.constant_name, v.file
+                )
+            })
+            .collect();
+        validation_errors.push(format!(
+            "{} recorded violation(s) are no longer enforced, run `packs update` to remove them:\n{}",
+            unenforced_violations.len(),
+            descriptions.join("\n")
+        ));
+    }
+
     validation_errors
 }
 
+// Recorded violations whose type the relevant pack no longer enforces, e.g.
+// privacy violations on a pack that turned off `enforce_privacy`
+fn unenforced_recorded_violations(
+    configuration: &Configuration,
+) -> Vec<&ViolationIdentifier> {
+    let checkers = get_checkers(configuration);
+    let mut unenforced_violations: Vec<&ViolationIdentifier> = configuration
+        .pack_set
+        .all_violations
+        .iter()
+        .filter(|violation| {
+            checkers
+                .iter()
+                .find(|c| c.violation_type() == violation.violation_type)
+                .is_some_and(|c| !c.is_enforced(violation, configuration))
+        })
+        .collect();
+    unenforced_violations.sort();
+    unenforced_violations
+}
+
 pub(crate) fn validate_all(
     configuration: &Configuration,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -249,30 +596,188 @@ pub(crate) fn validate_all(
 
 pub(crate) fn update(
     configuration: &Configuration,
+    add_dependencies: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let checkers = get_checkers(configuration);
 
-    let violations = get_all_violations(
+    let mut violations = get_all_violations(
         configuration,
         &configuration.included_files,
         &checkers,
     );
 
+    if add_dependencies {
+        violations = add_missing_dependencies(configuration, violations)?;
+    }
+
+    // Checkers that aren't enforced don't report violations, so these are
+    // dropped from the todo files
+    let unenforced_violations_count =
+        unenforced_recorded_violations(configuration).len();
+    if unenforced_violations_count > 0 {
+        println!(
+            "Removed {} recorded violation(s) that are no longer enforced",
+            unenforced_violations_count
+        );
+    }
+
     package_todo::write_violations_to_disk(configuration, violations);
     println!("Successfully updated package_todo.yml files!");
     Ok(())
 }
 
+// Records the violations in the files of `pack_names` in their todo files,
+// leaving the todo files of the other packs as they are
+pub(crate) fn update_packs(
+    configuration: &Configuration,
+    pack_names: &BTreeSet<String>,
+) {
+    let checkers = get_checkers(configuration);
+    let absolute_paths: HashSet<PathBuf> = pack_names
+        .iter()
+        .flat_map(|pack_name| configuration.included_files_in_pack(pack_name))
+        .collect();
+    let violations =
+        get_all_violations(configuration, &absolute_paths, &checkers);
+    package_todo::write_pack_violations_to_disk(
+        configuration,
+        pack_names,
+        violations,
+    );
+}
+
+// Resolves dependency violations by adding the missing dependencies to
+// package.yml files, except those that would create a cycle. Returns the
+// violations that are left to record.
+fn add_missing_dependencies(
+    configuration: &Configuration,
+    violations: HashSet<Violation>,
+) -> Result<HashSet<Violation>, Box<dyn std::error::Error>> {
+    let is_dependency_violation = |violation: &Violation| {
+        violation.identifier.violation_type == "dependency"
+    };
+    let missing_dependencies: BTreeSet<(String, String)> = violations
+        .iter()
+        .filter(|violation| is_dependency_violation(violation))
+        .map(|violation| {
+            (
+                violation.identifier.referencing_pack_name.clone(),
+                violation.identifier.defining_pack_name.clone(),
+            )
+        })
+        .collect();
+    let dependencies_to_add =
+        dependency::dependencies_to_add(configuration, &missing_dependencies);
+
+    let mut dependencies_by_pack: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (from, to) in &dependencies_to_add {
+        dependencies_by_pack.entry(from).or_default().push(to);
+    }
+    for (from, tos) in dependencies_by_pack {
+        let mut pack = configuration.pack_set.for_pack(from)?.clone();
+        for to in &tos {
+            pack.add_dependency(to);
+        }
+        pack.write()?;
+        println!("Added `{}` as dependencies of `{}`", tos.join("`, `"), from);
+    }
+
+    let cyclic_dependencies_count =
+        missing_dependencies.len() - dependencies_to_add.len();
+    if cyclic_dependencies_count > 0 {
+        println!(
+            "{} missing dependencies would create a cycle, so their violations were recorded instead",
+            cyclic_dependencies_count
+        );
+    }
+
+    Ok(violations
+        .into_iter()
+        .filter(|violation| {
+            !(is_dependency_violation(violation)
+                && dependencies_to_add.contains(&(
+                    violation.identifier.referencing_pack_name.as_str(),
+                    violation.identifier.defining_pack_name.as_str(),
+                )))
+        })
+        .collect())
+}
+
 pub(crate) fn remove_unnecessary_dependencies(
     configuration: &Configuration,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let unnecessary_dependencies = get_unnecessary_dependencies(configuration);
     for (pack, dependency_names) in unnecessary_dependencies.iter() {
-        remove_reference_to_dependency(pack, dependency_names);
+        let mut pack = pack.clone();
+        for dependency_name in dependency_names {
+            pack.remove_dependency(dependency_name);
+        }
+        pack.write()?;
     }
     Ok(())
 }
 
+pub(crate) fn lint_todos(
+    configuration: &Configuration,
+    fix: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    todo_lint::lint_todos(configuration, fix)
+}
+
+pub(crate) fn explain(
+    configuration: &Configuration,
+    location: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    explain::explain(configuration, location)
+}
+
+pub(crate) fn churn_report(
+    configuration: &Configuration,
+    months: u32,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    churn::churn_report(configuration, months, json)
+}
+
+pub(crate) fn api_usage(
+    configuration: &Configuration,
+    pack_name: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    api_usage::api_usage(configuration, pack_name)
+}
+
+pub(crate) fn plan_removal(
+    configuration: &Configuration,
+    from_pack: &str,
+    to_pack: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    plan_removal::plan_removal(configuration, from_pack, to_pack)
+}
+
+pub(crate) fn annotate(
+    configuration: &Configuration,
+    remove: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    annotate::annotate(configuration, remove)
+}
+
+pub(crate) fn extract(
+    configuration: &Configuration,
+    namespace: &str,
+    new_pack: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    extract::extract(configuration, namespace, new_pack, dry_run)
+}
+
+pub(crate) fn parity_check(
+    configuration: &Configuration,
+    packwerk_output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let packwerk_violations = parity::parse_packwerk_output(packwerk_output)?;
+    parity::parity_check(configuration, packwerk_violations)
+}
+
 pub(crate) fn check_unnecessary_dependencies(
     configuration: &Configuration,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -329,51 +834,122 @@ fn get_unnecessary_dependencies(
     unnecessary_dependencies
 }
 
-fn get_all_violations(
+pub(crate) fn get_all_violations(
     configuration: &Configuration,
     absolute_paths: &HashSet<PathBuf>,
     checkers: &Vec<Box<dyn CheckerInterface + Send + Sync>>,
 ) -> HashSet<Violation> {
+    let references_start = Instant::now();
     let references = get_all_references(configuration, absolute_paths);
+    let skipped_files_count = configuration.skipped_files.take();
+    if configuration.timing {
+        eprintln!(
+            "Timing: finding references took {:?}",
+            references_start.elapsed()
+        );
+        print_skipped_files_count(skipped_files_count);
+    }
 
-    debug!("Running checkers on resolved references");
+    violations_for_references(configuration, references, checkers)
+}
 
-    let violations: HashSet<Violation> = checkers
+fn print_skipped_files_count(skipped_files_count: usize) {
+    eprintln!(
+        "Timing: skipped parsing {} file(s) with nothing that looks like a constant",
+        skipped_files_count
+    );
+}
+
+pub(crate) fn violations_for_references(
+    configuration: &Configuration,
+    references: Vec<Reference>,
+    checkers: &Vec<Box<dyn CheckerInterface + Send + Sync>>,
+) -> HashSet<Violation> {
+    let checkers_start = Instant::now();
+    let (violations, filter_counts) = check_references(
+        configuration,
+        references,
+        checkers,
+        &ReferenceFilter::new(configuration),
+    );
+
+    if configuration.timing {
+        eprintln!("Timing: reference filter: {}", filter_counts);
+        eprintln!(
+            "Timing: checking references took {:?}",
+            checkers_start.elapsed()
+        );
+    }
+
+    violations
+}
+
+fn check_references(
+    configuration: &Configuration,
+    references: Vec<Reference>,
+    checkers: &Vec<Box<dyn CheckerInterface + Send + Sync>>,
+    reference_filter: &ReferenceFilter,
+) -> (HashSet<Violation>, FilterCounts) {
+    debug!("Filtering references");
+    let (references, filter_counts) = reference_filter.apply(references);
+    debug!("Finished filtering references");
+
+    debug!("Running checkers on resolved references");
+    let context = CheckContext::new(configuration, &references);
+    let messages = ViolationMessages::new(configuration);
+
+    // The references of each referencing pack are checked together, so the
+    // settings of the same packs are looked up over and over while they're
+    // at hand. Packs are checked in parallel, and so are the references of
+    // a pack, so one big pack doesn't hold up the check.
+    let mut references_by_pack: HashMap<&str, Vec<&Reference>> = HashMap::new();
+    for reference in &references {
+        references_by_pack
+            .entry(&reference.referencing_pack_name)
+            .or_default()
+            .push(reference);
+    }
+    let violations: HashSet<Violation> = references_by_pack
         .into_par_iter()
-        .flat_map(|c| {
-            references
-                .par_iter()
-                .flat_map(|r| c.check(r, configuration))
-                .collect::<HashSet<Violation>>()
+        .flat_map(|(_, pack_references)| {
+            pack_references.into_par_iter().flat_map_iter(|reference| {
+                checkers
+                    .iter()
+                    .filter_map(|checker| checker.check(reference, &context))
+                    // Every violation in a file marked
+                    // `# packs_strict: true` is strict, whatever the pack's
+                    // settings
+                    .map(|violation| Violation {
+                        message: messages.format(
+                            &violation.identifier.violation_type,
+                            reference,
+                            context.defining_pack(reference),
+                            violation.message,
+                        ),
+                        strict: violation.strict
+                            || reference.strict_referencing_file,
+                        ..violation
+                    })
+                    .collect::<Vec<Violation>>()
+            })
         })
         .collect();
 
     debug!("Finished running checkers");
 
-    violations
+    (violations, filter_counts)
 }
 
-fn get_checkers(
+pub(crate) fn get_checkers(
     configuration: &Configuration,
 ) -> Vec<Box<dyn CheckerInterface + Send + Sync>> {
     vec![
         Box::new(dependency::Checker {}),
-        Box::new(privacy::Checker {}),
+        Box::new(privacy::Checker::new(configuration)),
         Box::new(visibility::Checker {}),
         Box::new(architecture::Checker {
             layers: configuration.layers.clone(),
         }),
+        Box::new(upward::Checker {}),
     ]
 }
-
-fn remove_reference_to_dependency(pack: &Pack, dependency_names: &[String]) {
-    let without_dependency = pack
-        .dependencies
-        .iter()
-        .filter(|dependency| !dependency_names.contains(dependency));
-    let updated_pack = Pack {
-        dependencies: without_dependency.cloned().collect(),
-        ..pack.clone()
-    };
-    write_pack_to_disk(&updated_pack);
-}
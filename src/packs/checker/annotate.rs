@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::path::Path;
+
+use super::context::CheckContext;
+use super::reference_filter::ReferenceFilter;
+use super::{get_checkers, violation_code};
+use crate::packs::reference_extractor::get_all_references;
+use crate::packs::Configuration;
+
+const ANNOTATION_PREFIX: &str = "# TODO(packs):";
+
+// The annotations to write above each line of a file, by line number
+type Annotations = BTreeMap<usize, BTreeSet<String>>;
+
+// Writes a comment above each line with a violation, e.g.
+// `# TODO(packs): dependency violation on ::Bar`, whether the violation is
+// recorded in a todo file or not. Annotations from a previous run are
+// removed first, so running it again leaves one annotation per violation.
+// With `remove`, annotations are only removed.
+pub(crate) fn annotate(
+    configuration: &Configuration,
+    remove: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut removed = 0;
+    for absolute_path in &configuration.included_files {
+        if !is_ruby_file(absolute_path) {
+            continue;
+        }
+        let contents = std::fs::read_to_string(absolute_path)?;
+        let (stripped, count) = remove_annotations(&contents);
+        if count > 0 {
+            std::fs::write(absolute_path, stripped)?;
+            removed += count;
+        }
+    }
+    if remove {
+        println!("Removed {} annotation(s)", removed);
+        return Ok(());
+    }
+
+    let (references, _) = ReferenceFilter::new(configuration).apply(
+        get_all_references(configuration, &configuration.included_files),
+    );
+    let checkers = get_checkers(configuration);
+    let context = CheckContext::new(configuration, &references);
+
+    let mut annotations_by_file: BTreeMap<&str, Annotations> = BTreeMap::new();
+    for reference in &references {
+        if !is_ruby_file(Path::new(&reference.relative_referencing_file)) {
+            continue;
+        }
+        for checker in &checkers {
+            let Some(violation) = checker.check(reference, &context) else {
+                continue;
+            };
+            annotations_by_file
+                .entry(&reference.relative_referencing_file)
+                .or_default()
+                .entry(reference.source_location.line)
+                .or_default()
+                .insert(annotation(
+                    configuration,
+                    &violation.identifier.violation_type,
+                    &reference.constant_name,
+                    &violation.identifier.defining_pack_name,
+                ));
+        }
+    }
+
+    let mut annotated = 0;
+    for (file, annotations) in &annotations_by_file {
+        let absolute_path = configuration.absolute_root.join(file);
+        let contents = std::fs::read_to_string(&absolute_path)?;
+        std::fs::write(
+            &absolute_path,
+            add_annotations(&contents, annotations),
+        )?;
+        annotated += annotations.values().map(BTreeSet::len).sum::<usize>();
+    }
+    println!(
+        "Annotated {} violation(s) in {} file(s)",
+        annotated,
+        annotations_by_file.len()
+    );
+    Ok(())
+}
+
+// Comments can't be added to ERB templates the same way, so they're left
+// alone
+fn is_ruby_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "rb" || extension == "rake")
+}
+
+// Links to the docs of the violation's code if there are any, or else to the
+// docs of the defining pack
+fn annotation(
+    configuration: &Configuration,
+    violation_type: &str,
+    constant_name: &str,
+    defining_pack_name: &str,
+) -> String {
+    let url = violation_code(violation_type)
+        .and_then(|code| configuration.violation_code_urls.get(code))
+        .map(String::as_str)
+        .or_else(|| {
+            configuration
+                .pack_set
+                .for_pack(defining_pack_name)
+                .ok()
+                .and_then(|pack| pack.docs_url())
+        });
+    let annotation = format!(
+        "{} {} violation on {}",
+        ANNOTATION_PREFIX, violation_type, constant_name
+    );
+    match url {
+        Some(url) => format!("{} — see {}", annotation, url),
+        None => annotation,
+    }
+}
+
+fn remove_annotations(contents: &str) -> (String, usize) {
+    let mut count = 0;
+    let stripped = contents
+        .split_inclusive('\n')
+        .filter(|line| {
+            let is_annotation =
+                line.trim_start().starts_with(ANNOTATION_PREFIX);
+            count += usize::from(is_annotation);
+            !is_annotation
+        })
+        .collect();
+    (stripped, count)
+}
+
+// Each annotation is indented like the line it's above
+fn add_annotations(contents: &str, annotations: &Annotations) -> String {
+    let mut annotated = String::new();
+    for (index, line) in contents.split_inclusive('\n').enumerate() {
+        if let Some(line_annotations) = annotations.get(&(index + 1)) {
+            let indentation = &line[..line.len() - line.trim_start().len()];
+            for annotation in line_annotations {
+                annotated.push_str(indentation);
+                annotated.push_str(annotation);
+                annotated.push('\n');
+            }
+        }
+        annotated.push_str(line);
+    }
+    annotated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_add_and_remove_annotations() {
+        let contents = "class Foo\n  def bar\n    Bar\n  end\nend\n";
+        let annotations = BTreeMap::from([(
+            3,
+            BTreeSet::from([String::from(
+                "# TODO(packs): dependency violation on ::Bar",
+            )]),
+        )]);
+
+        let annotated = add_annotations(contents, &annotations);
+
+        assert_eq!(
+            "class Foo\n  def bar\n    # TODO(packs): dependency violation on ::Bar\n    Bar\n  end\nend\n",
+            annotated
+        );
+        assert_eq!((String::from(contents), 1), remove_annotations(&annotated));
+    }
+}
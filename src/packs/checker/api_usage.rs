@@ -0,0 +1,190 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::path::PathBuf;
+
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::context::CheckContext;
+use super::reference::Reference;
+use crate::packs::constant_index::constant_resolver;
+use crate::packs::file_utils::{get_file_type, SupportedFileType};
+use crate::packs::parsing::ruby::method_calls::{
+    method_calls_from_path, MethodCall,
+};
+use crate::packs::reference_extractor::mark_public_defining_files;
+use crate::packs::Configuration;
+
+// How often other packs call a method on a public constant, and which packs
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MethodUsage {
+    calls: usize,
+    referencing_packs: BTreeSet<String>,
+}
+
+// Public constant (by defining pack), then method name
+type Usage = BTreeMap<(String, String), BTreeMap<String, MethodUsage>>;
+
+// Lists the methods other packs call on each public constant, e.g.
+// `create!` on `::Billing::Invoice`, to see which parts of a pack's public
+// API are actually used
+pub(crate) fn api_usage(
+    configuration: &Configuration,
+    pack_name: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(pack_name) = pack_name {
+        configuration.pack_set.for_pack(pack_name).map_err(|_| {
+            format!(
+                "`{}` not found. Try `packs list-packs` to debug.",
+                pack_name
+            )
+        })?;
+    }
+
+    let ruby_files: Vec<&PathBuf> = configuration
+        .included_files
+        .iter()
+        .filter(|path| get_file_type(path) == Some(SupportedFileType::Ruby))
+        .collect();
+    let calls: Vec<(&PathBuf, MethodCall)> = ruby_files
+        .par_iter()
+        .flat_map(|path| {
+            method_calls_from_path(path, configuration)
+                .into_iter()
+                .map(|call| (*path, call))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let constant_resolver = constant_resolver(configuration);
+    let mut calls_by_reference: Vec<(&String, Reference)> = calls
+        .iter()
+        .flat_map(|(path, call)| {
+            Reference::from_unresolved_reference(
+                configuration,
+                constant_resolver.as_ref(),
+                &call.reference,
+                path,
+            )
+            .into_iter()
+            .map(|reference| (&call.method_name, reference))
+        })
+        .collect();
+    mark_public_defining_files(
+        configuration,
+        &[],
+        calls_by_reference
+            .iter_mut()
+            .map(|(_, reference)| reference),
+    );
+
+    let context = CheckContext::new(
+        configuration,
+        calls_by_reference.iter().map(|(_, reference)| reference),
+    );
+    let mut usage: Usage = BTreeMap::new();
+    for (method_name, reference) in &calls_by_reference {
+        let Some(defining_pack_name) = &reference.defining_pack_name else {
+            continue;
+        };
+        if *defining_pack_name == reference.referencing_pack_name
+            || pack_name.is_some_and(|name| name != defining_pack_name)
+            || !context.is_public(reference)
+        {
+            continue;
+        }
+        let method_usage = usage
+            .entry((
+                defining_pack_name.clone(),
+                reference.constant_name.clone(),
+            ))
+            .or_default()
+            .entry((*method_name).clone())
+            .or_default();
+        method_usage.calls += 1;
+        method_usage
+            .referencing_packs
+            .insert(reference.referencing_pack_name.clone());
+    }
+
+    print!("{}", to_text(&usage));
+    Ok(())
+}
+
+fn to_text(usage: &Usage) -> String {
+    if usage.is_empty() {
+        return String::from(
+            "No pack calls a method on another pack's public constants\n",
+        );
+    }
+
+    let mut text = String::new();
+    let mut current_pack = None;
+    for ((pack_name, constant_name), methods) in usage {
+        if current_pack != Some(pack_name) {
+            current_pack = Some(pack_name);
+            text.push_str(&format!("{}\n", pack_name));
+        }
+        text.push_str(&format!("  {}\n", constant_name));
+        for (method_name, method_usage) in methods {
+            let referencing_packs: Vec<&str> = method_usage
+                .referencing_packs
+                .iter()
+                .map(String::as_str)
+                .collect();
+            text.push_str(&format!(
+                "    .{}: {} call(s) from {}\n",
+                method_name,
+                method_usage.calls,
+                referencing_packs.join(", ")
+            ));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_text() {
+        let usage: Usage = BTreeMap::from([(
+            (
+                String::from("packs/billing"),
+                String::from("::Billing::Invoice"),
+            ),
+            BTreeMap::from([
+                (
+                    String::from("create!"),
+                    MethodUsage {
+                        calls: 3,
+                        referencing_packs: BTreeSet::from([
+                            String::from("packs/checkout"),
+                            String::from("packs/orders"),
+                        ]),
+                    },
+                ),
+                (
+                    String::from("find"),
+                    MethodUsage {
+                        calls: 1,
+                        referencing_packs: BTreeSet::from([String::from(
+                            "packs/orders",
+                        )]),
+                    },
+                ),
+            ]),
+        )]);
+
+        assert_eq!(
+            "\
+packs/billing
+  ::Billing::Invoice
+    .create!: 3 call(s) from packs/checkout, packs/orders
+    .find: 1 call(s) from packs/orders
+",
+            to_text(&usage)
+        );
+    }
+}
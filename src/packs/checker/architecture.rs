@@ -1,4 +1,7 @@
-use super::{get_referencing_pack, CheckerInterface, ViolationIdentifier};
+use super::{
+    context::CheckContext, get_referencing_pack, CheckerInterface,
+    ViolationIdentifier,
+};
 use crate::packs::checker::Reference;
 use crate::packs::{Configuration, Violation};
 
@@ -42,22 +45,16 @@ impl CheckerInterface for Checker {
     fn check(
         &self,
         reference: &Reference,
-        configuration: &Configuration,
+        context: &CheckContext,
     ) -> Option<Violation> {
-        let pack_set = &configuration.pack_set;
-
-        let referencing_pack = &reference.referencing_pack(pack_set);
+        let referencing_pack = context.referencing_pack(reference);
 
         let relative_defining_file = &reference.relative_defining_file;
 
         let referencing_pack_name = &referencing_pack.name;
-        let defining_pack = &reference.defining_pack(pack_set);
-        if defining_pack.is_none() {
-            return None;
-        }
-        let defining_pack = defining_pack.unwrap();
+        let defining_pack = context.defining_pack(reference)?;
 
-        if referencing_pack.enforce_architecture().is_false() {
+        if !referencing_pack.enforce_architecture().is_enforced() {
             return None;
         }
 
@@ -79,16 +76,13 @@ impl CheckerInterface for Checker {
                 }
 
                 let message = format!(
-                    "{}:{}:{}\nArchitecture violation: `{}` belongs to `{}` (whose layer is `{}`) cannot be accessed from `{}` (whose layer is `{}`)",
-                    reference.relative_referencing_file,
-                    reference.source_location.line,
-                    reference.source_location.column,
-                    reference.constant_name,
-                    defining_pack_name,
-                    defining_layer,
-                    referencing_pack_name,
-                    referencing_layer,
-                );
+                        "Architecture violation: `{}` belongs to `{}` (whose layer is `{}`) cannot be accessed from `{}` (whose layer is `{}`)",
+                        reference.constant_name,
+                        defining_pack_name,
+                        defining_layer,
+                        referencing_pack_name,
+                        referencing_layer,
+                    );
 
                 let violation_type = String::from("architecture");
                 let file = reference.relative_referencing_file.clone();
@@ -103,6 +97,11 @@ impl CheckerInterface for Checker {
                 Some(Violation {
                     message,
                     identifier,
+                    confidence: reference.confidence,
+                    relative_defining_file: reference
+                        .relative_defining_file
+                        .clone(),
+                    strict: false,
                 })
             }
             _ => None,
@@ -120,6 +119,17 @@ impl CheckerInterface for Checker {
         referencing_pack.enforce_architecture().is_strict()
     }
 
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let referencing_pack =
+            get_referencing_pack(violation, &configuration.pack_set);
+
+        referencing_pack.enforce_architecture().is_enforced()
+    }
+
     fn violation_type(&self) -> String {
         "architecture".to_owned()
     }
@@ -131,8 +141,8 @@ mod tests {
     use std::collections::{HashMap, HashSet};
 
     use crate::packs::{
-        pack::{CheckerSetting, Pack},
-        PackSet, SourceLocation,
+        pack::{EnforcementSetting, Pack},
+        PackSet, ReferenceConfidence, ReferenceKind, SourceLocation,
     };
 
     use super::*;
@@ -145,7 +155,7 @@ mod tests {
 
         let defining_pack = Pack {
             name: String::from("packs/foo"),
-            enforce_visibility: Some(CheckerSetting::True),
+            enforce_visibility: Some(EnforcementSetting::On),
             ..Pack::default()
         };
         let referencing_pack = Pack {
@@ -164,6 +174,10 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -182,7 +196,13 @@ mod tests {
             ),
             ..Configuration::default()
         };
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
@@ -203,7 +223,7 @@ mod tests {
         let referencing_pack = Pack {
             name: String::from("packs/bar"),
             layer: Some(String::from("utilities")),
-            enforce_architecture: Some(CheckerSetting::True),
+            enforce_architecture: Some(EnforcementSetting::On),
             ..Pack::default()
         };
 
@@ -223,6 +243,10 @@ mod tests {
                 "packs/foo/app/services/foo.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let configuration = Configuration {
@@ -238,7 +262,10 @@ mod tests {
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/bar/app/services/bar.rb:3:1\nArchitecture violation: `::Foo` belongs to `packs/foo` (whose layer is `product`) cannot be accessed from `packs/bar` (whose layer is `utilities`)"),
+            message: String::from("Architecture violation: `::Foo` belongs to `packs/foo` (whose layer is `product`) cannot be accessed from `packs/bar` (whose layer is `utilities`)"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("architecture"),
                 file: String::from("packs/bar/app/services/bar.rb"),
@@ -249,7 +276,12 @@ mod tests {
         };
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
@@ -271,7 +303,7 @@ mod tests {
         let referencing_pack = Pack {
             name: String::from("packs/bar"),
             layer: Some(String::from("product")),
-            enforce_architecture: Some(CheckerSetting::True),
+            enforce_architecture: Some(EnforcementSetting::On),
             ..Pack::default()
         };
 
@@ -286,6 +318,10 @@ mod tests {
                 "packs/foo/app/services/foo.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -305,6 +341,12 @@ mod tests {
             ..Configuration::default()
         };
 
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 }
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use serde::Serialize;
+
+use super::{get_all_violations, get_checkers, ViolationIdentifier};
+use crate::packs::git::commits_per_file;
+use crate::packs::Configuration;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct ChurnWeightedViolation {
+    score: usize,
+    commits: usize,
+    occurrences: usize,
+    recorded: bool,
+    #[serde(flatten)]
+    identifier: ViolationIdentifier,
+}
+
+// Lists violations in the files that changed the most recently first, since
+// those are the ones getting in the way of the most work
+pub(crate) fn churn_report(
+    configuration: &Configuration,
+    months: u32,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let commits = commits_per_file(&configuration.absolute_root, months)?;
+    let checkers = get_checkers(configuration);
+    let mut occurrences: BTreeMap<ViolationIdentifier, usize> = BTreeMap::new();
+    for violation in get_all_violations(
+        configuration,
+        &configuration.included_files,
+        &checkers,
+    ) {
+        *occurrences.entry(violation.identifier).or_default() += 1;
+    }
+
+    let recorded_violations = &configuration.pack_set.all_violations;
+    let violations = prioritize(
+        occurrences
+            .into_iter()
+            .map(|(identifier, occurrences)| {
+                let file_commits =
+                    commits.get(&identifier.file).copied().unwrap_or(0);
+                ChurnWeightedViolation {
+                    score: score(file_commits, occurrences),
+                    commits: file_commits,
+                    occurrences,
+                    recorded: recorded_violations.contains(&identifier),
+                    identifier,
+                }
+            })
+            .collect(),
+    );
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&violations)?);
+        return Ok(());
+    }
+
+    if violations.is_empty() {
+        println!("No violations detected!");
+        return Ok(());
+    }
+
+    println!(
+        "Violations by churn in the last {} month(s), most worth fixing first:",
+        months
+    );
+    println!(
+        "{:>7} {:>7} {:>11}  {:<12} {:<8} constant",
+        "score", "commits", "occurrences", "type", "recorded"
+    );
+    for violation in &violations {
+        println!(
+            "{:>7} {:>7} {:>11}  {:<12} {:<8} {} in {}",
+            violation.score,
+            violation.commits,
+            violation.occurrences,
+            violation.identifier.violation_type,
+            if violation.recorded { "yes" } else { "no" },
+            violation.identifier.constant_name,
+            violation.identifier.file
+        );
+    }
+
+    Ok(())
+}
+
+// Every reference to the constant has to be touched to fix the violation,
+// and every commit to the file runs into each of them
+fn score(commits: usize, occurrences: usize) -> usize {
+    commits * occurrences
+}
+
+// Highest score first, ties broken by the identifier so the output is stable
+fn prioritize(
+    mut violations: Vec<ChurnWeightedViolation>,
+) -> Vec<ChurnWeightedViolation> {
+    violations.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.identifier.cmp(&b.identifier))
+    });
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(file: &str, commits: usize) -> ChurnWeightedViolation {
+        ChurnWeightedViolation {
+            score: score(commits, 1),
+            commits,
+            occurrences: 1,
+            recorded: false,
+            identifier: ViolationIdentifier {
+                violation_type: String::from("privacy"),
+                file: String::from(file),
+                constant_name: String::from("::Bar"),
+                referencing_pack_name: String::from("packs/foo"),
+                defining_pack_name: String::from("packs/bar"),
+            },
+        }
+    }
+
+    #[test]
+    fn test_prioritize() {
+        let violations = prioritize(vec![
+            violation("packs/foo/b.rb", 1),
+            violation("packs/foo/c.rb", 5),
+            violation("packs/foo/a.rb", 1),
+        ]);
+
+        let files: Vec<&str> = violations
+            .iter()
+            .map(|violation| violation.identifier.file.as_str())
+            .collect();
+        assert_eq!(
+            vec!["packs/foo/c.rb", "packs/foo/a.rb", "packs/foo/b.rb"],
+            files
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::packs::checker::Reference;
+use crate::packs::pack::Pack;
+use crate::packs::Configuration;
+
+// What the checkers look up for each reference, derived once before any
+// checker runs: each pack by name, with its resolved enforcement settings,
+// the pack defining each referenced constant, and which of those constants
+// are public. It is only ever read afterwards, so a single instance is
+// shared across the rayon threads checking references.
+pub(crate) struct CheckContext<'a> {
+    packs: HashMap<&'a str, &'a Pack>,
+    defining_packs: HashMap<&'a str, &'a Pack>,
+    public_constants: HashSet<&'a str>,
+}
+
+impl<'a> CheckContext<'a> {
+    pub fn new(
+        configuration: &'a Configuration,
+        references: impl IntoIterator<Item = &'a Reference>,
+    ) -> CheckContext<'a> {
+        let packs: HashMap<&str, &Pack> = configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(|pack| (pack.name.as_str(), pack))
+            .collect();
+        // `public_folders` of each pack as strings, ready to be compared
+        // against the relative defining file of a reference
+        let public_folders: HashMap<&str, Vec<String>> = configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(|pack| {
+                let public_folders = pack
+                    .public_folders()
+                    .iter()
+                    .map(|folder| folder.to_string_lossy().into_owned())
+                    .collect();
+                (pack.name.as_str(), public_folders)
+            })
+            .collect();
+
+        let mut defining_packs = HashMap::new();
+        let mut public_constants = HashSet::new();
+        for reference in references {
+            let Some(defining_pack_name) = &reference.defining_pack_name else {
+                continue;
+            };
+            let constant_name = reference.constant_name.as_str();
+            let Entry::Vacant(entry) = defining_packs.entry(constant_name)
+            else {
+                continue;
+            };
+            let defining_pack = *entry.insert(pack(&packs, defining_pack_name));
+            if is_public(
+                defining_pack,
+                &public_folders[defining_pack_name.as_str()],
+                reference,
+            ) {
+                public_constants.insert(constant_name);
+            }
+        }
+
+        CheckContext {
+            packs,
+            defining_packs,
+            public_constants,
+        }
+    }
+
+    pub fn referencing_pack(&self, reference: &Reference) -> &'a Pack {
+        pack(&self.packs, &reference.referencing_pack_name)
+    }
+
+    pub fn defining_pack(&self, reference: &Reference) -> Option<&'a Pack> {
+        reference.defining_pack_name.as_ref()?;
+        let defining_pack = self
+            .defining_packs
+            .get(reference.constant_name.as_str())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Reference to {} was not given to CheckContext::new",
+                    reference.constant_name
+                )
+            });
+        Some(defining_pack)
+    }
+
+    pub fn is_public(&self, reference: &Reference) -> bool {
+        self.public_constants
+            .contains(reference.constant_name.as_str())
+    }
+}
+
+fn pack<'a>(packs: &HashMap<&str, &'a Pack>, pack_name: &str) -> &'a Pack {
+    packs.get(pack_name).unwrap_or_else(|| {
+        panic!(
+            "Reference refers to pack {}, but that pack is not found in pack set.",
+            pack_name
+        )
+    })
+}
+
+// Whether the constant is listed in `public_constants` of its pack, if the
+// pack has `enforce_privacy: explicit`, or else in the public_api.yml of its
+// pack, if the pack has one, or else whether it is defined inside the public
+// folder of its pack, or in a file marked `# pack_public: true`
+fn is_public(
+    defining_pack: &Pack,
+    public_folders: &[String],
+    reference: &Reference,
+) -> bool {
+    let Some(relative_defining_file) = &reference.relative_defining_file else {
+        return false;
+    };
+    if defining_pack.enforce_privacy().is_explicit() {
+        return defining_pack.exports(&reference.constant_name);
+    }
+    if let Some(public_api) = &defining_pack.public_api {
+        return public_api.includes(&reference.constant_name);
+    }
+    public_folders
+        .iter()
+        .any(|folder| relative_defining_file.starts_with(folder))
+        || reference.public_defining_file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::*;
+    use std::path::PathBuf;
+
+    fn reference_to(
+        constant_name: &str,
+        relative_defining_file: &str,
+    ) -> Reference {
+        Reference {
+            constant_name: String::from(constant_name),
+            defining_pack_name: Some(String::from("packs/bar")),
+            referencing_pack_name: String::from("packs/foo"),
+            relative_referencing_file: String::from(
+                "packs/foo/app/services/foo.rb",
+            ),
+            relative_defining_file: Some(String::from(relative_defining_file)),
+            source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
+        }
+    }
+
+    fn configuration(fixture: &str) -> Configuration {
+        configuration::get(
+            PathBuf::from("tests/fixtures")
+                .join(fixture)
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_packs_and_public_constants() {
+        let configuration = configuration("simple_app");
+        let private_reference =
+            reference_to("::Bar", "packs/bar/app/services/bar.rb");
+        let public_reference =
+            reference_to("::Bar::Api", "packs/bar/app/public/bar/api.rb");
+        let undefined_reference = Reference {
+            defining_pack_name: None,
+            relative_defining_file: None,
+            ..reference_to("::Baz", "")
+        };
+        let references =
+            [private_reference, public_reference, undefined_reference];
+        let context = CheckContext::new(&configuration, &references);
+
+        assert_eq!("packs/foo", context.referencing_pack(&references[0]).name);
+        assert_eq!(
+            Some("packs/bar"),
+            context
+                .defining_pack(&references[0])
+                .map(|pack| pack.name.as_str())
+        );
+        assert!(!context.is_public(&references[0]));
+        assert!(context.is_public(&references[1]));
+        assert_eq!(None, context.defining_pack(&references[2]));
+        assert!(!context.is_public(&references[2]));
+    }
+
+    #[test]
+    fn test_root_pack_public_folder() {
+        let configuration = configuration("app_with_root_pack_privacy");
+        let reference_to_root =
+            |constant_name: &str, relative_defining_file: &str| Reference {
+                defining_pack_name: Some(String::from(".")),
+                ..reference_to(constant_name, relative_defining_file)
+            };
+        let references = [
+            reference_to_root("::RootApi", "app/public/root_api.rb"),
+            reference_to_root(
+                "::RootInternal",
+                "app/services/root_internal.rb",
+            ),
+        ];
+        let context = CheckContext::new(&configuration, &references);
+
+        assert!(context.is_public(&references[0]));
+        assert!(!context.is_public(&references[1]));
+    }
+
+    #[test]
+    fn test_pack_public_sigil() {
+        let configuration = configuration("app_with_pack_public_sigil");
+        let references = [
+            Reference {
+                public_defining_file: true,
+                ..reference_to("::Bar", "packs/bar/app/services/bar.rb")
+            },
+            reference_to(
+                "::Bar::PrivateThing",
+                "packs/bar/app/services/bar/private_thing.rb",
+            ),
+        ];
+        let context = CheckContext::new(&configuration, &references);
+
+        assert!(context.is_public(&references[0]));
+        assert!(!context.is_public(&references[1]));
+    }
+}
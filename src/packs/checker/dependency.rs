@@ -1,14 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use super::{
-    get_referencing_pack, CheckerInterface, ValidatorInterface,
-    ViolationIdentifier,
+    context::CheckContext, get_referencing_pack, CheckerInterface,
+    ValidatorInterface, ViolationIdentifier,
 };
 use crate::packs::checker::Reference;
 use crate::packs::pack::Pack;
 use crate::packs::{Configuration, Violation};
-use petgraph::algo::tarjan_scc;
-use petgraph::prelude::DiGraph;
+use petgraph::algo::{has_path_connecting, tarjan_scc};
+use petgraph::prelude::{DiGraph, DiGraphMap};
 
 pub struct Checker {}
 impl ValidatorInterface for Checker {
@@ -82,28 +82,46 @@ The following groups of packages form a cycle:
     }
 }
 
+// The missing dependencies, as (referencing pack, defining pack), that can be
+// added without creating a cycle. They're considered in order, so a dependency
+// added earlier can rule out a later one.
+pub(crate) fn dependencies_to_add<'a>(
+    configuration: &'a Configuration,
+    missing_dependencies: &'a BTreeSet<(String, String)>,
+) -> BTreeSet<(&'a str, &'a str)> {
+    let mut graph = DiGraphMap::<&str, ()>::new();
+    for pack in &configuration.pack_set.packs {
+        graph.add_node(&pack.name);
+        for dependency_pack_name in &pack.dependencies {
+            graph.add_edge(&pack.name, dependency_pack_name, ());
+        }
+    }
+
+    let mut dependencies_to_add = BTreeSet::new();
+    for (from, to) in missing_dependencies {
+        if !has_path_connecting(&graph, to, from, None) {
+            graph.add_edge(from, to, ());
+            dependencies_to_add.insert((from.as_str(), to.as_str()));
+        }
+    }
+    dependencies_to_add
+}
+
 // TODO: Add test for does not enforce dependencies
 impl CheckerInterface for Checker {
     fn check(
         &self,
         reference: &Reference,
-        configuration: &Configuration,
+        context: &CheckContext,
     ) -> Option<Violation> {
-        let referencing_pack =
-            reference.referencing_pack(&configuration.pack_set);
+        let referencing_pack = context.referencing_pack(reference);
 
-        if referencing_pack.enforce_dependencies().is_false() {
+        if !referencing_pack.enforce_dependencies().is_enforced() {
             return None;
         }
 
         let referencing_pack_name = &referencing_pack.name;
-        let defining_pack = &reference.defining_pack(&configuration.pack_set);
-
-        if defining_pack.is_none() {
-            return None;
-        }
-
-        let defining_pack = defining_pack.unwrap();
+        let defining_pack = context.defining_pack(reference)?;
 
         let defining_pack_name = &defining_pack.name;
         if referencing_pack_name == defining_pack_name {
@@ -129,15 +147,12 @@ impl CheckerInterface for Checker {
             // END: Original packwerk message
 
             let message = format!(
-                "{}:{}:{}\nDependency violation: `{}` belongs to `{}`, but `{}` does not specify a dependency on `{}`.",
-                reference.relative_referencing_file,
-                reference.source_location.line,
-                reference.source_location.column,
-                reference.constant_name,
-                defining_pack_name,
-                referencing_pack.relative_yml().to_string_lossy(),
-                defining_pack_name,
-            );
+                    "Dependency violation: `{}` belongs to `{}`, but `{}` does not specify a dependency on `{}`.",
+                    reference.constant_name,
+                    defining_pack_name,
+                    referencing_pack.relative_yml().to_string_lossy(),
+                    defining_pack_name,
+                );
 
             let violation_type = String::from("dependency");
             let file = reference.relative_referencing_file.clone();
@@ -152,6 +167,11 @@ impl CheckerInterface for Checker {
             return Some(Violation {
                 message,
                 identifier,
+                confidence: reference.confidence,
+                relative_defining_file: reference
+                    .relative_defining_file
+                    .clone(),
+                strict: false,
             });
         }
 
@@ -169,6 +189,17 @@ impl CheckerInterface for Checker {
         referencing_pack.enforce_dependencies().is_strict()
     }
 
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let referencing_pack =
+            get_referencing_pack(violation, &configuration.pack_set);
+
+        referencing_pack.enforce_dependencies().is_enforced()
+    }
+
     fn violation_type(&self) -> String {
         "dependency".to_owned()
     }
@@ -189,7 +220,8 @@ mod tests {
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
         let reference = Reference {
             constant_name: String::from("::Foo"),
             defining_pack_name: Some(String::from("packs/foo")),
@@ -201,8 +233,18 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
@@ -213,11 +255,15 @@ mod tests {
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
         let reference = build_foo_reference_bar_reference();
 
         let expected_violation = Violation {
-            message: String::from("packs/foo/app/services/foo.rb:3:1\nDependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`."),
+            message: String::from("Dependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`."),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("dependency"),
                 file: String::from("packs/foo/app/services/foo.rb"),
@@ -228,7 +274,12 @@ mod tests {
         };
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
@@ -240,10 +291,17 @@ mod tests {
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
         let reference = build_foo_reference_bar_reference();
 
-        assert_eq!(checker.check(&reference, &configuration), None)
+        assert_eq!(
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            ),
+            None
+        )
     }
 
     fn build_foo_reference_bar_reference() -> Reference {
@@ -258,6 +316,10 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
         reference
     }
@@ -270,7 +332,8 @@ mod tests {
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
 
         let error = checker.validate(&configuration);
         let expected_message = String::from(
@@ -291,7 +354,8 @@ packs/foo, packs/bar",
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
 
         let error = checker.validate(&configuration);
         assert_eq!(error, None);
@@ -308,8 +372,36 @@ packs/foo, packs/bar",
                 .canonicalize()
                 .expect("Could not canonicalize path")
                 .as_path(),
-        );
+        )
+        .unwrap();
 
         checker.validate(&configuration);
     }
+
+    #[test]
+    fn test_dependencies_to_add() {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+        )
+        .unwrap();
+
+        // packs/foo already depends on packs/baz, and adding packs/bar's
+        // dependency on packs/foo rules out packs/foo's on packs/bar
+        let missing_dependencies: BTreeSet<(String, String)> = [
+            ("packs/bar", "packs/foo"),
+            ("packs/baz", "packs/foo"),
+            ("packs/foo", "packs/bar"),
+        ]
+        .into_iter()
+        .map(|(from, to)| (from.to_owned(), to.to_owned()))
+        .collect();
+
+        assert_eq!(
+            BTreeSet::from([("packs/bar", "packs/foo")]),
+            dependencies_to_add(&configuration, &missing_dependencies)
+        );
+    }
 }
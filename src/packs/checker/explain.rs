@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use super::context::CheckContext;
+use super::reference::Reference;
+use super::reference_filter::ReferenceFilter;
+use super::{get_checkers, CheckerInterface, ViolationIdentifier};
+use crate::packs::constant_resolver::ConstantDefinition;
+use crate::packs::file_utils::{get_absolute_path, to_slash_path};
+use crate::packs::pack::Pack;
+use crate::packs::parsing::process_file;
+use crate::packs::parsing::ruby::namespace_calculator::possible_fully_qualified_constants;
+use crate::packs::reference_extractor::{
+    get_all_references_and_resolver, mark_public_defining_files,
+};
+use crate::packs::{Configuration, UnresolvedReference};
+
+// Walks through how each reference at `file:line` (or `file:line:column`) is
+// resolved and checked, to debug a violation that is (or isn't) reported
+pub(crate) fn explain(
+    configuration: &Configuration,
+    location: &str,
+) -> Result<(), Box<dyn Error>> {
+    let (file, line, column) = parse_location(location)?;
+    let absolute_path = get_absolute_path(file.to_owned(), configuration);
+    if !configuration.included_files.contains(&absolute_path) {
+        return Err(format!(
+            "`{}` is not one of the files packs checks, see `list-included-files`",
+            file
+        )
+        .into());
+    }
+
+    let processed_file = process_file(&absolute_path, configuration);
+    let unresolved_references: Vec<UnresolvedReference> = processed_file
+        .unresolved_references
+        .iter()
+        .filter(|reference| {
+            reference.location.start_row == line
+                && column.map_or(true, |column| {
+                    reference.location.start_col == column
+                })
+        })
+        .cloned()
+        .collect();
+    if unresolved_references.is_empty() {
+        return Err(format!("No constant is referenced at {}", location).into());
+    }
+
+    let (_, constant_resolver) = get_all_references_and_resolver(
+        configuration,
+        &HashSet::from([absolute_path.clone()]),
+    );
+    let definitions = constant_resolver
+        .fully_qualified_constant_name_to_constant_definition_map();
+    let filter = ReferenceFilter::new(configuration);
+    let checkers = get_checkers(configuration);
+
+    for unresolved_reference in &unresolved_references {
+        println!(
+            "Reference to `{}` at {}:{}:{}",
+            unresolved_reference.name,
+            file,
+            unresolved_reference.location.start_row,
+            unresolved_reference.location.start_col
+        );
+        let namespace_path: Vec<&str> = unresolved_reference
+            .namespace_path
+            .iter()
+            .map(String::as_str)
+            .collect();
+        println!("  Confidence: {}", unresolved_reference.confidence);
+        if namespace_path.is_empty() {
+            println!("  Nesting: none (top level)");
+        } else {
+            println!("  Nesting: {}", namespace_path.join(" > "));
+        }
+
+        // Ruby looks in the innermost namespace first, the top level last
+        let mut candidates = possible_fully_qualified_constants(
+            &namespace_path,
+            &unresolved_reference.name,
+        );
+        candidates.rotate_left(1);
+        println!("  Candidates, in lookup order:");
+        for candidate in &candidates {
+            match definitions.get(candidate) {
+                Some(candidate_definitions) => {
+                    for definition in candidate_definitions {
+                        println!(
+                            "  - {} defined in {}",
+                            candidate,
+                            relative_path(configuration, definition)
+                        );
+                    }
+                }
+                None => println!("  - {} not defined", candidate),
+            }
+        }
+
+        let mut references = Reference::from_unresolved_reference(
+            configuration,
+            constant_resolver.as_ref(),
+            unresolved_reference,
+            &absolute_path,
+        );
+        mark_public_defining_files(
+            configuration,
+            &[&processed_file],
+            &mut references,
+        );
+        let context = CheckContext::new(configuration, &references);
+        for reference in &references {
+            explain_reference(
+                configuration,
+                &filter,
+                &checkers,
+                &context,
+                reference,
+            );
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+fn explain_reference(
+    configuration: &Configuration,
+    filter: &ReferenceFilter,
+    checkers: &[Box<dyn CheckerInterface + Send + Sync>],
+    context: &CheckContext,
+    reference: &Reference,
+) {
+    let (Some(defining_pack_name), Some(relative_defining_file)) = (
+        &reference.defining_pack_name,
+        &reference.relative_defining_file,
+    ) else {
+        println!(
+            "  Resolved to: nothing, so no checker looks at this reference"
+        );
+        return;
+    };
+    println!(
+        "  Resolved to: {} in {} (pack `{}`)",
+        reference.constant_name, relative_defining_file, defining_pack_name
+    );
+
+    let referencing_pack = context.referencing_pack(reference);
+    println!(
+        "  Referencing pack `{}`: {}",
+        referencing_pack.name,
+        enforcement_settings(referencing_pack)
+    );
+    if let Some(defining_pack) = context.defining_pack(reference) {
+        println!(
+            "  Defining pack `{}`: {}",
+            defining_pack.name,
+            enforcement_settings(defining_pack)
+        );
+    }
+
+    let (kept, counts) = filter.apply(vec![reference.clone()]);
+    if kept.is_empty() {
+        println!("  Not checked, it is dropped before checking: {}", counts);
+        return;
+    }
+
+    for checker in checkers {
+        let violation_type = checker.violation_type();
+        let identifier = ViolationIdentifier {
+            violation_type: violation_type.clone(),
+            file: reference.relative_referencing_file.clone(),
+            constant_name: reference.constant_name.clone(),
+            referencing_pack_name: reference.referencing_pack_name.clone(),
+            defining_pack_name: defining_pack_name.clone(),
+        };
+
+        let outcome = if !checker.is_enforced(&identifier, configuration) {
+            String::from("not enforced")
+        } else {
+            match checker.check(reference, context) {
+                None => String::from("no violation"),
+                Some(violation)
+                    if configuration
+                        .pack_set
+                        .all_violations
+                        .contains(&violation.identifier) =>
+                {
+                    String::from("violation, recorded in a todo file")
+                }
+                Some(_) => String::from("violation"),
+            }
+        };
+        println!("  {}: {}", violation_type, outcome);
+    }
+}
+
+// `file:line` or `file:line:column`, as printed with each violation
+fn parse_location(
+    location: &str,
+) -> Result<(&str, usize, Option<usize>), Box<dyn Error>> {
+    let invalid = || {
+        format!(
+            "`{}` is not a location, expected `file:line` or `file:line:column`",
+            location
+        )
+    };
+    let mut parts = location.rsplitn(3, ':');
+    let last = parts.next().ok_or_else(invalid)?;
+    let middle = parts.next().ok_or_else(invalid)?;
+    match (parts.next(), middle.parse::<usize>()) {
+        (Some(file), Ok(line)) => {
+            let column = last.parse().map_err(|_| invalid())?;
+            Ok((file, line, Some(column)))
+        }
+        _ => {
+            let line = last.parse().map_err(|_| invalid())?;
+            let file = location.rsplit_once(':').ok_or_else(invalid)?.0;
+            Ok((file, line, None))
+        }
+    }
+}
+
+fn enforcement_settings(pack: &Pack) -> String {
+    format!(
+        "enforce_dependencies: {}, enforce_privacy: {}, enforce_visibility: {}, enforce_architecture: {}, enforce_upward_references: {}",
+        pack.enforce_dependencies(),
+        pack.enforce_privacy(),
+        pack.enforce_visibility(),
+        pack.enforce_architecture(),
+        pack.enforce_upward_references()
+    )
+}
+
+fn relative_path(
+    configuration: &Configuration,
+    definition: &ConstantDefinition,
+) -> String {
+    to_slash_path(
+        definition
+            .absolute_path_of_definition
+            .strip_prefix(&configuration.absolute_root)
+            .unwrap_or(&definition.absolute_path_of_definition),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            ("packs/foo/app/services/foo.rb", 3, None),
+            parse_location("packs/foo/app/services/foo.rb:3").unwrap()
+        );
+        assert_eq!(
+            ("packs/foo/app/services/foo.rb", 3, Some(4)),
+            parse_location("packs/foo/app/services/foo.rb:3:4").unwrap()
+        );
+        assert!(parse_location("packs/foo/app/services/foo.rb").is_err());
+        assert!(parse_location("packs/foo/app/services/foo.rb:x").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use super::{get_all_violations, get_checkers};
+use crate::packs::constant_index::ConstantIndex;
+use crate::packs::file_utils::to_slash_path;
+use crate::packs::pack::{write_pack_to_disk, Pack};
+use crate::packs::reference_extractor::get_all_references;
+use crate::packs::{Configuration, PackageTodo};
+
+// A file to move into the new pack, both paths relative to the root
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Move {
+    from: String,
+    to: String,
+}
+
+// Moves the files defining `namespace` and the constants in it into a new
+// pack, at the same path within the pack as they were within theirs, and
+// writes the new pack's package.yml with the dependencies those files need.
+// Prints how many violations there are before and after. With `dry_run`,
+// only prints what it would do.
+pub(crate) fn extract(
+    configuration: &Configuration,
+    namespace: &str,
+    new_pack: &str,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let namespace = format!("::{}", namespace.trim_matches(':'));
+    let new_pack = new_pack.trim_end_matches('/');
+    if configuration.pack_set.for_pack(new_pack).is_ok() {
+        return Err(format!("`{}` already exists", new_pack).into());
+    }
+
+    let constant_index = ConstantIndex::new(configuration);
+    let moves = moves(configuration, &constant_index, &namespace, new_pack)?;
+    let dependencies = dependencies(configuration, &moves);
+
+    println!(
+        "Moving {} file(s) defining `{}` into `{}`:",
+        moves.len(),
+        namespace,
+        new_pack
+    );
+    for file_move in &moves {
+        println!("- {} -> {}", file_move.from, file_move.to);
+    }
+    if dependencies.is_empty() {
+        println!("\n`{}` needs no dependencies", new_pack);
+    } else {
+        println!("\n`{}` needs these dependencies:", new_pack);
+        for dependency in &dependencies {
+            println!("- {}", dependency);
+        }
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let violations_before = violation_counts(configuration);
+
+    let package_yml = configuration
+        .absolute_root
+        .join(new_pack)
+        .join("package.yml");
+    let pack = Pack {
+        dependencies: dependencies.into_iter().collect(),
+        ..Pack::from_contents(
+            &package_yml,
+            &configuration.absolute_root,
+            "enforce_dependencies: true",
+            PackageTodo::default(),
+        )
+    };
+    write_pack_to_disk(&pack);
+    for file_move in &moves {
+        let to = configuration.absolute_root.join(&file_move.to);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(configuration.absolute_root.join(&file_move.from), to)?;
+    }
+
+    let violations_after = violation_counts(&configuration.reload()?);
+    println!("\nViolations before and after:");
+    print!("{}", violation_delta(&violations_before, &violations_after));
+    println!(
+        "\nTodo files still list violations of the moved files under their old paths, run `pks update` to record them again"
+    );
+    Ok(())
+}
+
+// Files that also define constants outside of the namespace are left where
+// they are
+fn moves(
+    configuration: &Configuration,
+    constant_index: &ConstantIndex,
+    namespace: &str,
+    new_pack: &str,
+) -> Result<Vec<Move>, Box<dyn Error>> {
+    let files: BTreeSet<&str> = constant_index
+        .get(namespace)
+        .iter()
+        .chain(constant_index.in_namespace(namespace))
+        .map(|constant| constant.file.as_str())
+        .collect();
+    if files.is_empty() {
+        return Err(format!(
+            "No file defines `{}` or a constant in it",
+            namespace
+        )
+        .into());
+    }
+
+    let in_namespace = |name: &str| {
+        name == namespace || name.starts_with(&format!("{}::", namespace))
+    };
+    let mut moves = vec![];
+    for file in files {
+        if let Some(other) = constant_index
+            .defined_in(file)
+            .into_iter()
+            .find(|constant| !in_namespace(&constant.name))
+        {
+            println!(
+                "Leaving {} where it is, it also defines `{}`",
+                file, other.name
+            );
+            continue;
+        }
+
+        let absolute_path = configuration.absolute_root.join(file);
+        let path_in_pack = match configuration.pack_set.for_file(&absolute_path)
+        {
+            Some(pack) => Path::new(file)
+                .strip_prefix(&pack.relative_path)
+                .unwrap_or(Path::new(file))
+                .to_path_buf(),
+            None => PathBuf::from(file),
+        };
+        let to = to_slash_path(&Path::new(new_pack).join(path_in_pack));
+        if configuration.absolute_root.join(&to).exists() {
+            return Err(format!(
+                "Cannot move {} to {}, which already exists",
+                file, to
+            )
+            .into());
+        }
+        moves.push(Move {
+            from: file.to_owned(),
+            to,
+        });
+    }
+    Ok(moves)
+}
+
+// The packs defining the constants the moved files reference, other than
+// the ones being moved
+fn dependencies(
+    configuration: &Configuration,
+    moves: &[Move],
+) -> BTreeSet<String> {
+    let moved_files: HashSet<&str> = moves
+        .iter()
+        .map(|file_move| file_move.from.as_str())
+        .collect();
+    let absolute_paths: HashSet<PathBuf> = moves
+        .iter()
+        .map(|file_move| configuration.absolute_root.join(&file_move.from))
+        .collect();
+
+    get_all_references(configuration, &absolute_paths)
+        .into_iter()
+        .filter(|reference| {
+            reference
+                .relative_defining_file
+                .as_deref()
+                .is_some_and(|file| !moved_files.contains(file))
+        })
+        .filter_map(|reference| reference.defining_pack_name)
+        .collect()
+}
+
+// How many violations of each type there are, recorded or not
+fn violation_counts(configuration: &Configuration) -> BTreeMap<String, usize> {
+    let checkers = get_checkers(configuration);
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for violation in get_all_violations(
+        configuration,
+        &configuration.included_files,
+        &checkers,
+    ) {
+        *counts
+            .entry(violation.identifier.violation_type)
+            .or_default() += 1;
+    }
+    counts
+}
+
+fn violation_delta(
+    before: &BTreeMap<String, usize>,
+    after: &BTreeMap<String, usize>,
+) -> String {
+    let violation_types: BTreeSet<&String> =
+        before.keys().chain(after.keys()).collect();
+    let line = |name: &str, before: usize, after: usize| {
+        format!(
+            "  {}: {} -> {} ({:+})\n",
+            name,
+            before,
+            after,
+            after as i64 - before as i64
+        )
+    };
+
+    let mut delta = String::new();
+    for violation_type in violation_types {
+        delta.push_str(&line(
+            violation_type,
+            before.get(violation_type).copied().unwrap_or(0),
+            after.get(violation_type).copied().unwrap_or(0),
+        ));
+    }
+    delta.push_str(&line("total", before.values().sum(), after.values().sum()));
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_violation_delta() {
+        let before = BTreeMap::from([
+            (String::from("dependency"), 4),
+            (String::from("privacy"), 1),
+        ]);
+        let after = BTreeMap::from([
+            (String::from("dependency"), 1),
+            (String::from("visibility"), 2),
+        ]);
+
+        assert_eq!(
+            "  dependency: 4 -> 1 (-3)
+  privacy: 1 -> 0 (-1)
+  visibility: 0 -> 2 (+2)
+  total: 5 -> 3 (-2)
+",
+            violation_delta(&before, &after)
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::packs::checker::{PathDisplay, Reference};
+use crate::packs::file_utils::{relative_path_from, to_slash_path};
+use crate::packs::pack::Pack;
+use crate::packs::Configuration;
+
+// Turns the default message of a checker into the message printed for the
+// violation, following the `violation_messages`, `path_display` and
+// `hyperlinks` settings
+pub(crate) struct ViolationMessages<'a> {
+    templates: &'a HashMap<String, String>,
+    absolute_root: &'a Path,
+    path_display: PathDisplay,
+    hyperlinks: bool,
+    current_dir: PathBuf,
+}
+
+impl<'a> ViolationMessages<'a> {
+    pub fn new(configuration: &'a Configuration) -> ViolationMessages<'a> {
+        ViolationMessages {
+            templates: &configuration.violation_messages,
+            absolute_root: &configuration.absolute_root,
+            path_display: configuration.path_display,
+            hyperlinks: configuration.hyperlinks,
+            current_dir: std::env::current_dir()
+                .unwrap_or_else(|_| configuration.absolute_root.clone()),
+        }
+    }
+
+    // The location of the reference, followed by the `violation_messages`
+    // template for the violation type if there is one, or `default_message`.
+    // Templates can use {constant}, {defining_pack}, {referencing_pack},
+    // {file}, {line}, {column} and {help_url} (the defining pack's docs_url).
+    pub fn format(
+        &self,
+        violation_type: &str,
+        reference: &Reference,
+        defining_pack: Option<&Pack>,
+        default_message: String,
+    ) -> String {
+        let message = match self.templates.get(violation_type) {
+            Some(template) => template
+                .replace("{constant}", &reference.constant_name)
+                .replace(
+                    "{defining_pack}",
+                    reference.defining_pack_name.as_deref().unwrap_or(""),
+                )
+                .replace("{referencing_pack}", &reference.referencing_pack_name)
+                .replace("{file}", &reference.relative_referencing_file)
+                .replace("{line}", &reference.source_location.line.to_string())
+                .replace(
+                    "{column}",
+                    &reference.source_location.column.to_string(),
+                )
+                .replace(
+                    "{help_url}",
+                    defining_pack
+                        .and_then(|pack| pack.docs_url())
+                        .unwrap_or(""),
+                ),
+            None => default_message,
+        };
+
+        format!("{}\n{}", self.location(reference), message)
+    }
+
+    // `file:line:column`, with the file as `path_display` asks, wrapped in
+    // an OSC 8 hyperlink if `hyperlinks` is set
+    fn location(&self, reference: &Reference) -> String {
+        let absolute_path = self
+            .absolute_root
+            .join(&reference.relative_referencing_file);
+        let file = match self.path_display {
+            PathDisplay::Relative => {
+                reference.relative_referencing_file.clone()
+            }
+            PathDisplay::Absolute => to_slash_path(&absolute_path),
+            PathDisplay::Cwd => to_slash_path(&relative_path_from(
+                &absolute_path,
+                &self.current_dir,
+            )),
+        };
+        let location = format!(
+            "{}:{}:{}",
+            file,
+            reference.source_location.line,
+            reference.source_location.column
+        );
+        if self.hyperlinks {
+            format!(
+                "\x1b]8;;file://{}\x1b\\{}\x1b]8;;\x1b\\",
+                to_slash_path(&absolute_path),
+                location
+            )
+        } else {
+            location
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_violation_message_templates() {
+        let mut configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+        )
+        .unwrap();
+        configuration.violation_messages.insert(
+            String::from("privacy"),
+            String::from("`{constant}` from `{referencing_pack}` ({line})"),
+        );
+        let messages = ViolationMessages::new(&configuration);
+        let reference = Reference {
+            constant_name: String::from("::Bar"),
+            defining_pack_name: Some(String::from("packs/bar")),
+            referencing_pack_name: String::from("packs/foo"),
+            relative_referencing_file: String::from(
+                "packs/foo/app/services/foo.rb",
+            ),
+            relative_defining_file: Some(String::from(
+                "packs/bar/app/services/bar.rb",
+            )),
+            source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
+        };
+
+        assert_eq!(
+            "packs/foo/app/services/foo.rb:3:1\n`::Bar` from `packs/foo` (3)",
+            messages.format(
+                "privacy",
+                &reference,
+                None,
+                String::from("Privacy violation")
+            )
+        );
+        assert_eq!(
+            "packs/foo/app/services/foo.rb:3:1\nDependency violation",
+            messages.format(
+                "dependency",
+                &reference,
+                None,
+                String::from("Dependency violation")
+            )
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use super::ValidatorInterface;
+use crate::packs::file_utils::to_slash_path;
+use crate::packs::pack::Pack;
+use crate::packs::raw_configuration::PackBudgets;
+use crate::packs::Configuration;
+
+pub struct Checker {}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PackMetrics {
+    pub pack_name: String,
+    pub file_count: usize,
+    pub public_constant_count: usize,
+    pub dependency_count: usize,
+}
+
+impl PackMetrics {
+    // Returns one line per budget that this pack exceeds
+    pub fn over_budget(&self, budgets: &PackBudgets) -> Vec<String> {
+        let measurements = [
+            ("max_files", budgets.max_files, self.file_count),
+            (
+                "max_public_constants",
+                budgets.max_public_constants,
+                self.public_constant_count,
+            ),
+            (
+                "max_dependencies",
+                budgets.max_dependencies,
+                self.dependency_count,
+            ),
+        ];
+
+        measurements
+            .iter()
+            .filter_map(|(budget_name, budget, actual)| match budget {
+                Some(budget) if actual > budget => Some(format!(
+                    "{} is {} (budget is {})",
+                    budget_name, actual, budget
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl ValidatorInterface for Checker {
+    fn validate(&self, configuration: &Configuration) -> Option<String> {
+        let budgets = &configuration.pack_budgets;
+        let mut error_messages: Vec<String> = vec![];
+
+        for metrics in pack_metrics(configuration) {
+            for over_budget in metrics.over_budget(budgets) {
+                error_messages.push(format!(
+                    "{} is over budget: {}",
+                    metrics.pack_name, over_budget
+                ));
+            }
+        }
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages.join("\n"))
+        }
+    }
+}
+
+// Budgets are meant to keep packs from growing too large. The root pack holds
+// everything that hasn't been packaged yet, so it is not measured.
+pub(crate) fn pack_metrics(configuration: &Configuration) -> Vec<PackMetrics> {
+    let mut files_by_pack: HashMap<&str, Vec<String>> = HashMap::new();
+    for absolute_path in &configuration.included_files {
+        if let Some(pack) = configuration.pack_set.for_file(absolute_path) {
+            let relative_path = to_slash_path(
+                absolute_path
+                    .strip_prefix(&configuration.absolute_root)
+                    .unwrap(),
+            );
+            files_by_pack
+                .entry(&pack.name)
+                .or_default()
+                .push(relative_path);
+        }
+    }
+
+    let mut metrics: Vec<PackMetrics> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|pack| pack.name != ".")
+        .map(|pack| {
+            let files = files_by_pack
+                .get(pack.name.as_str())
+                .cloned()
+                .unwrap_or_default();
+            metrics_for_pack(pack, &files)
+        })
+        .collect();
+
+    metrics.sort_by(|a, b| a.pack_name.cmp(&b.pack_name));
+    metrics
+}
+
+fn metrics_for_pack(pack: &Pack, relative_files: &[String]) -> PackMetrics {
+    let public_folders: Vec<String> = pack
+        .public_folders()
+        .iter()
+        .map(|folder| folder.to_string_lossy().into_owned())
+        .collect();
+
+    // Under zeitwerk, each file in the public folder defines one constant
+    let public_constant_count = relative_files
+        .iter()
+        .filter(|file| {
+            public_folders.iter().any(|folder| file.starts_with(folder))
+        })
+        .count();
+
+    PackMetrics {
+        pack_name: pack.name.clone(),
+        file_count: relative_files.len(),
+        public_constant_count,
+        dependency_count: pack.dependencies.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_over_budget() {
+        let metrics = PackMetrics {
+            pack_name: String::from("packs/foo"),
+            file_count: 3,
+            public_constant_count: 1,
+            dependency_count: 2,
+        };
+        let budgets = PackBudgets {
+            max_files: Some(2),
+            max_public_constants: Some(1),
+            max_dependencies: None,
+        };
+
+        assert_eq!(
+            metrics.over_budget(&budgets),
+            vec![String::from("max_files is 3 (budget is 2)")]
+        );
+    }
+
+    #[test]
+    fn test_over_budget_with_no_budgets() {
+        let metrics = PackMetrics {
+            pack_name: String::from("packs/foo"),
+            file_count: 300,
+            ..PackMetrics::default()
+        };
+
+        assert!(metrics.over_budget(&PackBudgets::default()).is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use super::{get_checkers, violations_for_references};
+use crate::packs::reference_extractor::get_all_references;
+use crate::packs::Configuration;
+
+// A violation as reported by Ruby packwerk. Its output doesn't always say
+// which pack defines the constant, so that part is optional.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub(crate) struct PackwerkViolation {
+    pub violation_type: String,
+    pub file: String,
+    pub constant_name: String,
+    #[serde(default)]
+    pub defining_pack_name: Option<String>,
+}
+
+impl PackwerkViolation {
+    fn key(&self) -> (String, String, String) {
+        (
+            self.violation_type.clone(),
+            self.file.clone(),
+            self.constant_name.clone(),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PackwerkJson {
+    // The shape of `check --json`
+    Report { violations: Vec<PackwerkViolation> },
+    List(Vec<PackwerkViolation>),
+}
+
+// Reads the output of `packwerk check`: either JSON (a list of violations,
+// or a `check --json` report) or the default text formatter
+pub(crate) fn parse_packwerk_output(
+    output: &str,
+) -> Result<Vec<PackwerkViolation>, Box<dyn Error>> {
+    let trimmed = output.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        let violations = match serde_json::from_str(trimmed)? {
+            PackwerkJson::Report { violations } => violations,
+            PackwerkJson::List(violations) => violations,
+        };
+        return Ok(violations);
+    }
+
+    let location = Regex::new(r"^(\S+):\d+:\d+$").unwrap();
+    let message =
+        Regex::new(r"^(\w+) violation: ['`]?(::[\w:]+)['`]?").unwrap();
+    let defining_pack =
+        Regex::new(r"(?:belongs to|is private to) ['`]([^'`]+)['`]").unwrap();
+
+    let lines: Vec<&str> = output.lines().map(str::trim).collect();
+    let violations = lines
+        .windows(2)
+        .filter_map(|window| {
+            let file = location.captures(window[0])?.get(1)?.as_str();
+            let captures = message.captures(window[1])?;
+            Some(PackwerkViolation {
+                violation_type: captures[1].to_lowercase(),
+                file: file.to_owned(),
+                constant_name: captures[2].to_owned(),
+                defining_pack_name: defining_pack
+                    .captures(window[1])
+                    .map(|captures| captures[1].to_owned()),
+            })
+        })
+        .collect();
+
+    Ok(violations)
+}
+
+// Compares the new violations packwerk reported with the ones packs finds,
+// grouping every disagreement by its most likely cause
+pub(crate) fn parity_check(
+    configuration: &Configuration,
+    packwerk_violations: Vec<PackwerkViolation>,
+) -> Result<(), Box<dyn Error>> {
+    let checkers = get_checkers(configuration);
+    let references =
+        get_all_references(configuration, &configuration.included_files);
+    let referenced_constants: HashSet<(String, String)> = references
+        .iter()
+        .map(|reference| {
+            (
+                reference.relative_referencing_file.clone(),
+                reference.constant_name.clone(),
+            )
+        })
+        .collect();
+
+    let recorded_violations = &configuration.pack_set.all_violations;
+    let packs_violations: BTreeMap<(String, String, String), String> =
+        violations_for_references(configuration, references, &checkers)
+            .into_iter()
+            .filter(|violation| {
+                configuration.ignore_recorded_violations
+                    || !recorded_violations.contains(&violation.identifier)
+            })
+            .map(|violation| {
+                let identifier = violation.identifier;
+                (
+                    (
+                        identifier.violation_type,
+                        identifier.file,
+                        identifier.constant_name,
+                    ),
+                    identifier.defining_pack_name,
+                )
+            })
+            .collect();
+    let packwerk_violations: BTreeMap<
+        (String, String, String),
+        Option<String>,
+    > = packwerk_violations
+        .into_iter()
+        .map(|violation| (violation.key(), violation.defining_pack_name))
+        .collect();
+
+    let mut causes: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (key, packwerk_pack) in &packwerk_violations {
+        let (violation_type, file, constant_name) = key;
+        let description =
+            format!("{} {} {}", violation_type, file, constant_name);
+        match (packs_violations.get(key), packwerk_pack) {
+            (Some(packs_pack), Some(packwerk_pack))
+                if packs_pack != packwerk_pack =>
+            {
+                causes.entry(DIFFERENT_PACK).or_default().push(format!(
+                    "{} (packwerk: `{}`, packs: `{}`)",
+                    description, packwerk_pack, packs_pack
+                ));
+            }
+            (Some(_), _) => {}
+            (None, _) => {
+                let cause = if referenced_constants
+                    .contains(&(file.clone(), constant_name.clone()))
+                {
+                    ONLY_PACKWERK_REFERENCED
+                } else {
+                    ONLY_PACKWERK_UNREFERENCED
+                };
+                causes.entry(cause).or_default().push(description);
+            }
+        }
+    }
+    for key in packs_violations.keys() {
+        if !packwerk_violations.contains_key(key) {
+            let (violation_type, file, constant_name) = key;
+            causes
+                .entry(ONLY_PACKS)
+                .or_default()
+                .push(format!("{} {} {}", violation_type, file, constant_name));
+        }
+    }
+
+    println!(
+        "packwerk reported {} violation(s), packs reported {}",
+        packwerk_violations.len(),
+        packs_violations.len()
+    );
+
+    if causes.is_empty() {
+        println!("packs and packwerk agree on every violation");
+        return Ok(());
+    }
+
+    let mut disagreements_count = 0;
+    for (cause, descriptions) in &causes {
+        println!("\n{} ({}):", cause, descriptions.len());
+        for description in descriptions {
+            println!("- {}", description);
+        }
+        disagreements_count += descriptions.len();
+    }
+
+    Err(format!(
+        "{} disagreement(s) between packs and packwerk",
+        disagreements_count
+    )
+    .into())
+}
+
+const DIFFERENT_PACK: &str =
+    "Reported by both, but the constant resolves to a different pack";
+const ONLY_PACKWERK_UNREFERENCED: &str =
+    "Only reported by packwerk, packs found no reference to the constant in the file";
+const ONLY_PACKWERK_REFERENCED: &str =
+    "Only reported by packwerk, packs found the reference but no violation";
+const ONLY_PACKS: &str = "Only reported by packs";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_packwerk_text_output() {
+        let output = "\
+📦 Packwerk is inspecting 3 files
+\\
+📦 Finished in 0.1 seconds
+
+packs/foo/app/services/foo.rb:3:4
+Dependency violation: ::Bar belongs to 'packs/bar', but 'packs/foo/package.yml' does not specify a dependency on 'packs/bar'.
+Are we missing an abstraction?
+
+packs/foo/app/services/foo.rb:3:4
+Privacy violation: '::Bar' is private to 'packs/bar' but referenced from 'packs/foo'.
+
+2 offenses detected
+";
+
+        assert_eq!(
+            vec![
+                PackwerkViolation {
+                    violation_type: String::from("dependency"),
+                    file: String::from("packs/foo/app/services/foo.rb"),
+                    constant_name: String::from("::Bar"),
+                    defining_pack_name: Some(String::from("packs/bar")),
+                },
+                PackwerkViolation {
+                    violation_type: String::from("privacy"),
+                    file: String::from("packs/foo/app/services/foo.rb"),
+                    constant_name: String::from("::Bar"),
+                    defining_pack_name: Some(String::from("packs/bar")),
+                },
+            ],
+            parse_packwerk_output(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_packwerk_json_output() {
+        let output = r#"[
+            {
+                "violation_type": "privacy",
+                "file": "packs/foo/app/services/foo.rb",
+                "constant_name": "::Bar"
+            }
+        ]"#;
+
+        assert_eq!(
+            vec![PackwerkViolation {
+                violation_type: String::from("privacy"),
+                file: String::from("packs/foo/app/services/foo.rb"),
+                constant_name: String::from("::Bar"),
+                defining_pack_name: None,
+            }],
+            parse_packwerk_output(output).unwrap()
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+
+use super::context::CheckContext;
+use super::reference::Reference;
+use crate::packs::reference_extractor::get_all_references;
+use crate::packs::Configuration;
+
+// How a constant referenced along the edge can stop being a reference to the
+// other pack, in the order `plan-removal` suggests them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FixType {
+    // Only the referencing pack uses the constant
+    MoveCode,
+    // The defining pack references the referencing pack too
+    InvertDependency,
+    // Other packs use the constant, but it isn't public
+    MakePublic,
+    // A public constant, used by other packs as well
+    IntroduceInterface,
+}
+
+impl FixType {
+    fn heading(&self) -> &'static str {
+        match self {
+            FixType::MoveCode => "Move code",
+            FixType::InvertDependency => "Invert the dependency",
+            FixType::MakePublic => "Make public",
+            FixType::IntroduceInterface => "Introduce an interface",
+        }
+    }
+
+    fn advice(&self, from: &str, to: &str) -> String {
+        match self {
+            FixType::MoveCode => format!(
+                "No pack other than `{}` uses these constants, so they can move into it.",
+                from
+            ),
+            FixType::InvertDependency => format!(
+                "`{}` also references `{}`. Have `{}` call into `{}` instead, e.g. with a callback or an event, so the dependency only goes one way.",
+                to, from, to, from
+            ),
+            FixType::MakePublic => format!(
+                "Other packs use these private constants too. Make them part of the public API of `{}`, then decide whether `{}` should declare the dependency or move the code.",
+                to, from
+            ),
+            FixType::IntroduceInterface => format!(
+                "These public constants are used widely. Define the behavior `{}` needs in an interface it owns, and have `{}` implement it.",
+                from, to
+            ),
+        }
+    }
+}
+
+// One constant referenced along the edge, and where
+#[derive(Debug, PartialEq, Eq)]
+struct PlannedConstant {
+    fix_type: FixType,
+    constant_name: String,
+    relative_defining_file: Option<String>,
+    locations: Vec<String>,
+}
+
+// Lists every reference from one pack to another as a Markdown checklist,
+// grouped by how each referenced constant could be dealt with, to plan how
+// to remove the dependency
+pub(crate) fn plan_removal(
+    configuration: &Configuration,
+    from_pack_name: &str,
+    to_pack_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    for pack_name in [from_pack_name, to_pack_name] {
+        configuration.pack_set.for_pack(pack_name).map_err(|_| {
+            format!(
+                "`{}` not found. Try `packs list-packs` to debug.",
+                pack_name
+            )
+        })?;
+    }
+
+    let references =
+        get_all_references(configuration, &configuration.included_files);
+    let context = CheckContext::new(configuration, &references);
+    let planned = plan(&references, &context, from_pack_name, to_pack_name);
+    print!("{}", to_markdown(&planned, from_pack_name, to_pack_name));
+    Ok(())
+}
+
+fn plan(
+    references: &[Reference],
+    context: &CheckContext,
+    from: &str,
+    to: &str,
+) -> Vec<PlannedConstant> {
+    let mut referencing_packs: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut edge_references: BTreeMap<&str, Vec<&Reference>> = BTreeMap::new();
+    let mut is_inverted = false;
+    for reference in references {
+        let defining_pack_name = reference.defining_pack_name.as_deref();
+        if defining_pack_name == Some(to) {
+            referencing_packs
+                .entry(&reference.constant_name)
+                .or_default()
+                .insert(&reference.referencing_pack_name);
+            if reference.referencing_pack_name == from {
+                edge_references
+                    .entry(&reference.constant_name)
+                    .or_default()
+                    .push(reference);
+            }
+        }
+        is_inverted |= reference.referencing_pack_name == to
+            && defining_pack_name == Some(from);
+    }
+
+    let mut planned: Vec<PlannedConstant> = edge_references
+        .into_iter()
+        .map(|(constant_name, references)| {
+            let only_from = referencing_packs[constant_name]
+                .iter()
+                .all(|pack| *pack == from);
+            let fix_type = if only_from {
+                FixType::MoveCode
+            } else if is_inverted {
+                FixType::InvertDependency
+            } else if !context.is_public(references[0]) {
+                FixType::MakePublic
+            } else {
+                FixType::IntroduceInterface
+            };
+            let mut locations: Vec<(&str, usize, usize)> = references
+                .iter()
+                .map(|reference| {
+                    (
+                        reference.relative_referencing_file.as_str(),
+                        reference.source_location.line,
+                        reference.source_location.column,
+                    )
+                })
+                .collect();
+            locations.sort();
+            locations.dedup();
+            PlannedConstant {
+                fix_type,
+                constant_name: constant_name.to_owned(),
+                relative_defining_file: references[0]
+                    .relative_defining_file
+                    .clone(),
+                locations: locations
+                    .into_iter()
+                    .map(|(file, line, column)| {
+                        format!("{}:{}:{}", file, line, column)
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    planned.sort_by(|a, b| {
+        (a.fix_type, &a.constant_name).cmp(&(b.fix_type, &b.constant_name))
+    });
+    planned
+}
+
+fn to_markdown(planned: &[PlannedConstant], from: &str, to: &str) -> String {
+    let mut markdown =
+        format!("# Removing the dependency of `{}` on `{}`\n\n", from, to);
+    if planned.is_empty() {
+        markdown.push_str(&format!(
+            "`{}` doesn't reference `{}`, so `{}` can be removed from its dependencies.\n",
+            from, to, to
+        ));
+        return markdown;
+    }
+
+    let reference_count: usize = planned
+        .iter()
+        .map(|constant| constant.locations.len())
+        .sum();
+    writeln!(
+        markdown,
+        "{} reference(s) to {} constant(s).",
+        reference_count,
+        planned.len()
+    )
+    .unwrap();
+
+    let mut fix_type = None;
+    for constant in planned {
+        if fix_type != Some(constant.fix_type) {
+            fix_type = Some(constant.fix_type);
+            writeln!(
+                markdown,
+                "\n## {}\n\n{}\n",
+                constant.fix_type.heading(),
+                constant.fix_type.advice(from, to)
+            )
+            .unwrap();
+        }
+        match &constant.relative_defining_file {
+            Some(file) => writeln!(
+                markdown,
+                "- [ ] `{}` ({})",
+                constant.constant_name, file
+            ),
+            None => writeln!(markdown, "- [ ] `{}`", constant.constant_name),
+        }
+        .unwrap();
+        for location in &constant.locations {
+            writeln!(markdown, "  - [ ] {}", location).unwrap();
+        }
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_markdown() {
+        let planned = vec![
+            PlannedConstant {
+                fix_type: FixType::MoveCode,
+                constant_name: String::from("::Bar::Helper"),
+                relative_defining_file: Some(String::from(
+                    "packs/bar/app/services/bar/helper.rb",
+                )),
+                locations: vec![
+                    String::from("packs/foo/app/services/foo.rb:3:4"),
+                    String::from("packs/foo/app/services/foo.rb:7:4"),
+                ],
+            },
+            PlannedConstant {
+                fix_type: FixType::MakePublic,
+                constant_name: String::from("::Bar"),
+                relative_defining_file: None,
+                locations: vec![String::from(
+                    "packs/foo/app/services/foo.rb:9:4",
+                )],
+            },
+        ];
+
+        assert_eq!(
+            "\
+# Removing the dependency of `packs/foo` on `packs/bar`
+
+3 reference(s) to 2 constant(s).
+
+## Move code
+
+No pack other than `packs/foo` uses these constants, so they can move into it.
+
+- [ ] `::Bar::Helper` (packs/bar/app/services/bar/helper.rb)
+  - [ ] packs/foo/app/services/foo.rb:3:4
+  - [ ] packs/foo/app/services/foo.rb:7:4
+
+## Make public
+
+Other packs use these private constants too. Make them part of the public API of `packs/bar`, then decide whether `packs/foo` should declare the dependency or move the code.
+
+- [ ] `::Bar`
+  - [ ] packs/foo/app/services/foo.rb:9:4
+",
+            to_markdown(&planned, "packs/foo", "packs/bar")
+        );
+    }
+}
@@ -1,27 +1,264 @@
-use super::{get_defining_pack, CheckerInterface, ViolationIdentifier};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use regex::Regex;
+
+use super::{
+    context::CheckContext, get_defining_pack, CheckerInterface,
+    ValidatorInterface, ViolationIdentifier,
+};
 use crate::packs::checker::Reference;
+use crate::packs::constant_index::ConstantIndex;
+use crate::packs::git::{self, LineOrigin};
+use crate::packs::pack::{EnforcementSetting, Pack};
+use crate::packs::package_todo::is_date;
 use crate::packs::{Configuration, Violation};
 
-pub struct Checker {}
+#[derive(Default)]
+pub struct Checker {
+    line_origins: LineOrigins,
+}
+
+impl Checker {
+    pub fn new(configuration: &Configuration) -> Checker {
+        Checker {
+            line_origins: LineOrigins {
+                absolute_root: configuration.absolute_root.clone(),
+                ..LineOrigins::default()
+            },
+        }
+    }
+}
+
+// `git blame` of each referencing file, for `enforce_privacy_from`. Each file
+// is blamed once, by the first thread to ask, while other threads asking for
+// the same file wait for it.
+#[derive(Default)]
+struct LineOrigins {
+    absolute_root: PathBuf,
+    by_file: Mutex<HashMap<String, Arc<FileOrigins>>>,
+}
+
+// The origin of each line of a file, once it is blamed, or `None` if it
+// can't be
+type FileOrigins = OnceLock<Option<Vec<Option<LineOrigin>>>>;
+
+impl LineOrigins {
+    // Whether the line of the reference was committed before `from`, a
+    // YYYY-MM-DD date or a commit. Lines that aren't committed yet, or files
+    // outside of a git repository, are considered new.
+    fn predates(&self, reference: &Reference, from: &str) -> bool {
+        let Some(origin) = self.line_origin(reference) else {
+            return false;
+        };
+        if is_date(from) {
+            origin.date.as_str() < from
+        } else {
+            git::is_ancestor(&self.absolute_root, &origin.commit, from)
+        }
+    }
+
+    fn line_origin(&self, reference: &Reference) -> Option<LineOrigin> {
+        let file = &reference.relative_referencing_file;
+        let index = reference.source_location.line.checked_sub(1)?;
+        let origins = self
+            .by_file
+            .lock()
+            .unwrap()
+            .entry(file.to_owned())
+            .or_default()
+            .clone();
+        origins
+            .get_or_init(|| git::blame(&self.absolute_root, file))
+            .as_ref()?
+            .get(index)
+            .cloned()
+            .flatten()
+    }
+}
+
+impl ValidatorInterface for Checker {
+    fn validate(&self, configuration: &Configuration) -> Option<String> {
+        let mut error_messages = explicit_privacy_errors(configuration);
+        error_messages.extend(public_api_errors(configuration));
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages.join("\n"))
+        }
+    }
+}
+
+// `explicit` only means something for `enforce_privacy`, and each constant
+// `public_constants` lists has to be defined in its pack
+fn explicit_privacy_errors(configuration: &Configuration) -> Vec<String> {
+    let mut packs: Vec<&Pack> = configuration.pack_set.packs.iter().collect();
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut error_messages: Vec<String> = vec![];
+    for pack in &packs {
+        // Only the keys the package.yml sets, so a root pack passing one on
+        // is reported once
+        for (key, setting) in [
+            ("enforce_dependencies", &pack.enforce_dependencies),
+            ("enforce_visibility", &pack.enforce_visibility),
+            ("enforce_architecture", &pack.enforce_architecture),
+            ("enforce_upward_references", &pack.enforce_upward_references),
+        ] {
+            if setting
+                .as_ref()
+                .is_some_and(EnforcementSetting::is_explicit)
+            {
+                error_messages.push(format!(
+                    "{} sets `{}: explicit`, which only applies to `enforce_privacy`",
+                    pack.relative_yml().display(),
+                    key
+                ));
+            }
+        }
+        if !pack.public_constants.is_empty()
+            && !pack.enforce_privacy().is_explicit()
+        {
+            error_messages.push(format!(
+                "{} lists `public_constants`, which are only used with `enforce_privacy: explicit`",
+                pack.relative_yml().display()
+            ));
+        }
+    }
+
+    let explicit_packs: Vec<&&Pack> = packs
+        .iter()
+        .filter(|pack| {
+            pack.enforce_privacy().is_explicit()
+                && !pack.public_constants.is_empty()
+        })
+        .collect();
+    if explicit_packs.is_empty() {
+        return error_messages;
+    }
+    let constant_index = ConstantIndex::new(configuration);
+    for pack in explicit_packs {
+        let mut public_constants: Vec<&String> =
+            pack.public_constants.iter().collect();
+        public_constants.sort();
+        for constant in public_constants {
+            let is_defined = constant_index
+                .get(&format!("::{}", constant.trim_start_matches("::")))
+                .iter()
+                .any(|definition| {
+                    definition.pack_name.as_deref() == Some(pack.name.as_str())
+                });
+            if !is_defined {
+                error_messages.push(format!(
+                    "{} lists `{}` in `public_constants`, which is not defined in {}",
+                    pack.relative_yml().display(),
+                    constant,
+                    pack.name
+                ));
+            }
+        }
+    }
+    error_messages
+}
+
+// Each constant a public_api.yml lists has to be defined in its pack, and
+// each method it lists has to be defined in a file defining the constant
+fn public_api_errors(configuration: &Configuration) -> Vec<String> {
+    let mut packs_with_public_api: Vec<_> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter_map(|pack| Some((pack, pack.public_api.as_ref()?)))
+        .collect();
+    if packs_with_public_api.is_empty() {
+        return vec![];
+    }
+    packs_with_public_api.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+    let constant_index = ConstantIndex::new(configuration);
+    let mut file_contents: HashMap<&str, String> = HashMap::new();
+    let mut error_messages: Vec<String> = vec![];
+    for (pack, public_api) in packs_with_public_api {
+        let defining_files = |constant: &str| -> Vec<&str> {
+            constant_index
+                .get(constant)
+                .iter()
+                .filter(|definition| {
+                    definition.pack_name.as_deref() == Some(pack.name.as_str())
+                })
+                .map(|definition| definition.file.as_str())
+                .collect()
+        };
+
+        for constant in &public_api.constants {
+            if defining_files(constant).is_empty() {
+                error_messages.push(format!(
+                    "{}/public_api.yml lists `{}`, which is not defined in {}",
+                    pack.relative_path.display(),
+                    constant,
+                    pack.name
+                ));
+            }
+        }
+
+        for (constant, methods) in &public_api.methods {
+            if !public_api.includes(constant) {
+                error_messages.push(format!(
+                    "{}/public_api.yml lists methods on `{}`, which is not one of its constants",
+                    pack.relative_path.display(),
+                    constant
+                ));
+                continue;
+            }
+            let files = defining_files(constant);
+            for method in methods {
+                // Not `\b`, which fails after names like `paid?` and matches
+                // `void` in `def void!`
+                let definition = Regex::new(&format!(
+                    r"\bdef\s+(self\.)?{}(?:[^A-Za-z0-9_?!=]|$)",
+                    regex::escape(method)
+                ))
+                .unwrap();
+                let is_defined = files.iter().any(|file| {
+                    definition.is_match(
+                        file_contents.entry(file).or_insert_with(|| {
+                            std::fs::read_to_string(
+                                configuration.absolute_root.join(file),
+                            )
+                            .unwrap_or_default()
+                        }),
+                    )
+                });
+                if !is_defined {
+                    error_messages.push(format!(
+                        "{}/public_api.yml lists `{}#{}`, which is not defined",
+                        pack.relative_path.display(),
+                        constant,
+                        method
+                    ));
+                }
+            }
+        }
+    }
+
+    error_messages
+}
 
 impl CheckerInterface for Checker {
     fn check(
         &self,
         reference: &Reference,
-        configuration: &Configuration,
+        context: &CheckContext,
     ) -> Option<Violation> {
-        let referencing_pack =
-            &reference.referencing_pack(&configuration.pack_set);
+        let referencing_pack = context.referencing_pack(reference);
         let relative_defining_file = &reference.relative_defining_file;
 
         let referencing_pack_name = &referencing_pack.name;
-        let defining_pack = &reference.defining_pack(&configuration.pack_set);
-        if defining_pack.is_none() {
-            return None;
-        }
-        let defining_pack = defining_pack.unwrap();
+        let defining_pack = context.defining_pack(reference)?;
 
-        if defining_pack.enforce_privacy().is_false() {
+        if !defining_pack.enforce_privacy().is_enforced() {
             return None;
         }
 
@@ -44,13 +281,7 @@ impl CheckerInterface for Checker {
 
         // This is a hack for now – we need to read package.yml file public_paths at some point,
         // and probably find a better way to check if the constant is public
-
-        let public_folder = &defining_pack.public_folder();
-
-        let is_public = relative_defining_file
-            .as_ref()
-            .unwrap()
-            .starts_with(public_folder.to_string_lossy().as_ref());
+        let is_public = context.is_public(reference);
 
         // Note this means that if the constant is ALSO in the list of private_constants,
         // it will be considered public.
@@ -63,7 +294,13 @@ impl CheckerInterface for Checker {
 
         let private_constants = &defining_pack.private_constants;
 
-        if !private_constants.is_empty() {
+        // A public_api.yml or `enforce_privacy: explicit` lists every public
+        // constant, so the ones not listed are private whatever
+        // `private_constants` says
+        if defining_pack.public_api.is_none()
+            && !defining_pack.enforce_privacy().is_explicit()
+            && !private_constants.is_empty()
+        {
             let constant_is_private =
                 private_constants.contains(&reference.constant_name);
 
@@ -87,14 +324,16 @@ impl CheckerInterface for Checker {
         // END: Original packwerk message
 
         let message = format!(
-            "{}:{}:{}\nPrivacy violation: `{}` is private to `{}`, but referenced from `{}`",
-            reference.relative_referencing_file,
-            reference.source_location.line,
-            reference.source_location.column,
-            reference.constant_name,
-            defining_pack_name,
-            referencing_pack_name,
-        );
+                "Privacy violation: `{}` is private to `{}`, but referenced from `{}`",
+                reference.constant_name,
+                defining_pack_name,
+                referencing_pack_name,
+            );
+
+        let strict = defining_pack
+            .enforce_privacy_from
+            .as_deref()
+            .is_some_and(|from| !self.line_origins.predates(reference, from));
 
         let violation_type = String::from("privacy");
         let file = reference.relative_referencing_file.clone();
@@ -109,6 +348,9 @@ impl CheckerInterface for Checker {
         Some(Violation {
             message,
             identifier,
+            confidence: reference.confidence,
+            relative_defining_file: reference.relative_defining_file.clone(),
+            strict,
         })
     }
 
@@ -123,6 +365,17 @@ impl CheckerInterface for Checker {
         defining_pack.enforce_privacy().is_strict()
     }
 
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let defining_pack =
+            get_defining_pack(violation, &configuration.pack_set);
+
+        defining_pack.enforce_privacy().is_enforced()
+    }
+
     fn violation_type(&self) -> String {
         "privacy".to_owned()
     }
@@ -130,21 +383,21 @@ impl CheckerInterface for Checker {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeSet, HashMap, HashSet};
 
     use super::*;
     use crate::packs::{
-        pack::{CheckerSetting, Pack},
+        pack::{EnforcementSetting, Pack, PublicApi},
         *,
     };
 
     #[test]
     fn referencing_and_defining_pack_are_identical() {
-        let checker = Checker {};
+        let checker = Checker::default();
 
         let defining_pack = Pack {
             name: String::from("packs/foo"),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             ..Pack::default()
         };
 
@@ -163,6 +416,10 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -182,15 +439,21 @@ mod tests {
             ..Configuration::default()
         };
 
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
     fn test_check() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             public_folder: Some(PathBuf::from("packs/bar/app/public")),
             ..Pack::default()
         };
@@ -211,10 +474,17 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            message: String::from("Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("privacy"),
                 file: String::from("packs/foo/app/services/foo.rb"),
@@ -243,16 +513,21 @@ mod tests {
 
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
     #[test]
     fn test_ignored_private_constants() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/foo"),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             ignored_private_constants: HashSet::from([String::from("::Foo")]),
             ..Pack::default()
         };
@@ -278,6 +553,10 @@ mod tests {
                 "packs/foo/app/services/foo.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let configuration = Configuration {
@@ -292,15 +571,86 @@ mod tests {
             ..Configuration::default()
         };
 
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
+    }
+
+    #[test]
+    fn test_public_api_takes_precedence_over_public_folder() {
+        let checker = Checker::default();
+        let defining_pack = Pack {
+            name: String::from("packs/bar"),
+            enforce_privacy: Some(EnforcementSetting::On),
+            public_api: Some(PublicApi {
+                constants: BTreeSet::from([String::from("::Bar::Api")]),
+                ..PublicApi::default()
+            }),
+            ..Pack::default()
+        };
+
+        let referencing_pack = Pack {
+            name: String::from("packs/foo"),
+            ..Pack::default()
+        };
+
+        let reference_to =
+            |constant_name: &str, relative_defining_file: &str| Reference {
+                constant_name: String::from(constant_name),
+                defining_pack_name: Some(defining_pack.name.to_owned()),
+                referencing_pack_name: referencing_pack.name.to_owned(),
+                relative_referencing_file: String::from(
+                    "packs/foo/app/services/foo.rb",
+                ),
+                relative_defining_file: Some(String::from(
+                    relative_defining_file,
+                )),
+                source_location: SourceLocation { line: 3, column: 1 },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+                public_defining_file: false,
+                strict_referencing_file: false,
+            };
+        let listed_reference =
+            reference_to("::Bar::Api", "packs/bar/app/services/bar/api.rb");
+        let unlisted_reference =
+            reference_to("::Bar", "packs/bar/app/public/bar.rb");
+
+        let root_pack = Pack {
+            name: String::from("."),
+            ..Pack::default()
+        };
+
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from_iter(vec![
+                    root_pack,
+                    defining_pack.clone(),
+                    referencing_pack.clone(),
+                ]),
+                HashMap::new(),
+            ),
+            ..Configuration::default()
+        };
+        let context = CheckContext::new(
+            &configuration,
+            [&listed_reference, &unlisted_reference],
+        );
+
+        assert_eq!(None, checker.check(&listed_reference, &context));
+        assert!(checker.check(&unlisted_reference, &context).is_some());
     }
 
     #[test]
     fn test_public_folder_detection_works() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             public_folder: Some(PathBuf::from("packs/bar/app/public")),
             ..Pack::default()
         };
@@ -321,10 +671,17 @@ mod tests {
                 "packs/bar/app/services/public/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            message: String::from("Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("privacy"),
                 file: String::from("packs/foo/app/services/foo.rb"),
@@ -353,17 +710,22 @@ mod tests {
 
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
     #[test]
     fn test_custom_public_folder_detection_works() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
             public_folder: Some(PathBuf::from("packs/bar/app/api")),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             ..Pack::default()
         };
 
@@ -383,6 +745,10 @@ mod tests {
                 "packs/bar/app/api/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -402,18 +768,24 @@ mod tests {
             ..Configuration::default()
         };
 
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
     fn test_private_constants_includes_referenced_constant() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
             private_constants: vec![String::from("::Bar")]
                 .into_iter()
                 .collect(),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             public_folder: Some(PathBuf::from("packs/bar/app/public")),
             ..Pack::default()
         };
@@ -434,10 +806,17 @@ mod tests {
                 "packs/bar/app/api/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            message: String::from("Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("privacy"),
                 file: String::from("packs/foo/app/services/foo.rb"),
@@ -466,19 +845,24 @@ mod tests {
 
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
     #[test]
     fn test_private_constants_includes_parent_of_referenced_constant() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
             private_constants: vec![String::from("::Bar")]
                 .into_iter()
                 .collect(),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             public_folder: Some(PathBuf::from("packs/bar/app/public")),
             ..Pack::default()
         };
@@ -499,10 +883,17 @@ mod tests {
                 "packs/bar/app/api/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/foo/app/services/foo.rb:3:1\nPrivacy violation: `::Bar::BarChild` is private to `packs/bar`, but referenced from `packs/foo`"),
+            message: String::from("Privacy violation: `::Bar::BarChild` is private to `packs/bar`, but referenced from `packs/foo`"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("privacy"),
                 file: String::from("packs/foo/app/services/foo.rb"),
@@ -531,19 +922,24 @@ mod tests {
 
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
     #[test]
     fn test_private_constants_does_not_include_referenced_constant() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
             private_constants: vec![String::from("::DifferentConstant")]
                 .into_iter()
                 .collect(),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             ..Pack::default()
         };
 
@@ -563,6 +959,10 @@ mod tests {
                 "packs/bar/app/api/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -581,18 +981,24 @@ mod tests {
             ),
             ..Configuration::default()
         };
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
     fn test_private_constants_does_include_referenced_public_constant() {
-        let checker = Checker {};
+        let checker = Checker::default();
         let defining_pack = Pack {
             name: String::from("packs/bar"),
             private_constants: vec![String::from("::Bar")]
                 .into_iter()
                 .collect(),
-            enforce_privacy: Some(CheckerSetting::True),
+            enforce_privacy: Some(EnforcementSetting::On),
             public_folder: Some(PathBuf::from("packs/bar/app/public")),
             ..Pack::default()
         };
@@ -618,8 +1024,71 @@ mod tests {
                 "packs/bar/app/public/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
+        };
+
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from_iter(vec![
+                    root_pack,
+                    defining_pack,
+                    referencing_pack,
+                ]),
+                HashMap::new(),
+            ),
+            ..Configuration::default()
+        };
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
+    }
+
+    #[test]
+    fn test_explicit_privacy() {
+        let checker = Checker::default();
+        let defining_pack = Pack {
+            name: String::from("packs/bar"),
+            enforce_privacy: Some(EnforcementSetting::Explicit),
+            public_constants: HashSet::from([String::from("Bar::Api")]),
+            public_folder: Some(PathBuf::from("packs/bar/app/public")),
+            ..Pack::default()
+        };
+
+        let referencing_pack = Pack {
+            name: String::from("packs/foo"),
+            ..Pack::default()
+        };
+
+        let root_pack = Pack {
+            name: String::from("."),
+            ..Pack::default()
         };
 
+        let reference_to =
+            |constant_name: &str, relative_defining_file: &str| Reference {
+                constant_name: String::from(constant_name),
+                defining_pack_name: Some(String::from("packs/bar")),
+                referencing_pack_name: String::from("packs/foo"),
+                relative_referencing_file: String::from(
+                    "packs/foo/app/services/foo.rb",
+                ),
+                relative_defining_file: Some(String::from(
+                    relative_defining_file,
+                )),
+                source_location: SourceLocation { line: 3, column: 1 },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+                public_defining_file: false,
+                strict_referencing_file: false,
+            };
+
         let configuration = Configuration {
             pack_set: PackSet::build(
                 HashSet::from_iter(vec![
@@ -631,6 +1100,23 @@ mod tests {
             ),
             ..Configuration::default()
         };
-        assert_eq!(None, checker.check(&reference, &configuration))
+        let api_reference =
+            reference_to("::Bar::Api", "packs/bar/app/services/bar/api.rb");
+        let thing_reference =
+            reference_to("::Bar::Thing", "packs/bar/app/public/bar/thing.rb");
+        let context = CheckContext::new(
+            &configuration,
+            [&api_reference, &thing_reference],
+        );
+
+        // Listed, so public even though it's outside of the public folder
+        assert_eq!(None, checker.check(&api_reference, &context));
+        // Not listed, so private even though it's in the public folder
+        assert_eq!(
+            Some(String::from("::Bar::Thing")),
+            checker
+                .check(&thing_reference, &context)
+                .map(|violation| violation.identifier.constant_name)
+        );
     }
 }
@@ -0,0 +1,188 @@
+use std::collections::BTreeSet;
+use std::path::{Component, Path};
+
+use serde::Serialize;
+
+use super::Violation;
+use crate::packs::file_utils::to_slash_path;
+use crate::packs::pack::Pack;
+use crate::packs::Configuration;
+
+// A change that resolves a violation, for editor plugins to offer as a
+// one-click fix. Paths are relative to the project root.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct QuickFix {
+    // What the fix does, e.g. for an editor's list of code actions
+    pub title: String,
+    #[serde(flatten)]
+    pub edit: Edit,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Edit {
+    // Add `value` to the `key` list of the package.yml at `file`, creating
+    // the list if it isn't there
+    AddToList {
+        file: String,
+        key: &'static str,
+        value: String,
+    },
+    // Move the file defining the constant into the defining pack's public
+    // folder, keeping the path below its autoload root
+    MoveFile {
+        from: String,
+        to: String,
+    },
+}
+
+// The fixes for a violation. Dependencies are only suggested if
+// `dependencies_to_add` has them, i.e. if `update --add-dependencies` would
+// add them, so fixes never introduce a dependency cycle.
+pub(crate) fn quick_fixes(
+    violation: &Violation,
+    configuration: &Configuration,
+    dependencies_to_add: &BTreeSet<(&str, &str)>,
+) -> Vec<QuickFix> {
+    let identifier = &violation.identifier;
+    let pack_set = &configuration.pack_set;
+    let (Ok(referencing_pack), Ok(defining_pack)) = (
+        pack_set.for_pack(&identifier.referencing_pack_name),
+        pack_set.for_pack(&identifier.defining_pack_name),
+    ) else {
+        return vec![];
+    };
+
+    let fix = match identifier.violation_type.as_str() {
+        "dependency" => dependencies_to_add
+            .contains(&(
+                referencing_pack.name.as_str(),
+                defining_pack.name.as_str(),
+            ))
+            .then(|| QuickFix {
+                title: format!(
+                    "Add `{}` to the dependencies of `{}`",
+                    defining_pack.name, referencing_pack.name
+                ),
+                edit: Edit::AddToList {
+                    file: package_yml(referencing_pack, configuration),
+                    key: "dependencies",
+                    value: defining_pack.name.clone(),
+                },
+            }),
+        "visibility" => Some(QuickFix {
+            title: format!(
+                "Make `{}` visible to `{}`",
+                defining_pack.name, referencing_pack.name
+            ),
+            edit: Edit::AddToList {
+                file: package_yml(defining_pack, configuration),
+                key: "visible_to",
+                value: referencing_pack.name.clone(),
+            },
+        }),
+        // A constant listed in `private_constants` stays private wherever
+        // it's defined
+        "privacy"
+            if !defining_pack
+                .private_constants
+                .contains(&identifier.constant_name) =>
+        {
+            violation.relative_defining_file.as_ref().and_then(|from| {
+                let to = public_destination(defining_pack, from)?;
+                Some(QuickFix {
+                    title: format!(
+                        "Move `{}` into the public folder of `{}`",
+                        identifier.constant_name, defining_pack.name
+                    ),
+                    edit: Edit::MoveFile {
+                        from: from.clone(),
+                        to,
+                    },
+                })
+            })
+        }
+        _ => None,
+    };
+    fix.into_iter().collect()
+}
+
+fn package_yml(pack: &Pack, configuration: &Configuration) -> String {
+    to_slash_path(
+        pack.yml
+            .strip_prefix(&configuration.absolute_root)
+            .unwrap_or(&pack.yml),
+    )
+}
+
+// Where a file of the pack would be public, e.g.
+// `packs/bar/app/services/bar/baz.rb` becomes `packs/bar/app/public/bar/baz.rb`.
+// None if the file isn't in the pack, or is already in its public folder.
+fn public_destination(pack: &Pack, relative_file: &str) -> Option<String> {
+    let public_folder = pack.public_folder();
+    let file = Path::new(relative_file);
+    if file.starts_with(&public_folder) {
+        return None;
+    }
+
+    let mut components = file
+        .strip_prefix(without_current_dir(&pack.relative_path))
+        .ok()?
+        .components();
+    // Skip the autoload root, e.g. `app/services` or `lib`
+    if components.next()? == Component::Normal("app".as_ref()) {
+        components.next()?;
+    }
+    let path_in_autoload_root = components.as_path();
+    if path_in_autoload_root.as_os_str().is_empty() {
+        return None;
+    }
+    Some(to_slash_path(&public_folder.join(path_in_autoload_root)))
+}
+
+// The root pack's paths start with `./`, which files' paths don't
+fn without_current_dir(path: &Path) -> &Path {
+    path.strip_prefix(".").unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_public_destination() {
+        let pack = Pack {
+            name: String::from("packs/bar"),
+            relative_path: PathBuf::from("packs/bar"),
+            ..Pack::default()
+        };
+        let root_pack = Pack {
+            name: String::from("."),
+            relative_path: PathBuf::from("."),
+            ..Pack::default()
+        };
+
+        assert_eq!(
+            Some(String::from("packs/bar/app/public/bar/baz.rb")),
+            public_destination(&pack, "packs/bar/app/services/bar/baz.rb")
+        );
+        assert_eq!(
+            Some(String::from("packs/bar/app/public/bar.rb")),
+            public_destination(&pack, "packs/bar/lib/bar.rb")
+        );
+        assert_eq!(
+            Some(String::from("app/public/widget.rb")),
+            public_destination(&root_pack, "app/models/widget.rb")
+        );
+        assert_eq!(
+            None,
+            public_destination(&pack, "packs/bar/app/public/bar.rb")
+        );
+        assert_eq!(
+            None,
+            public_destination(&pack, "packs/baz/app/services/baz.rb")
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use std::path::Path;
 
+use serde::{Deserialize, Serialize};
+
 use crate::packs::{
-    constant_resolver::ConstantResolver, pack::Pack,
-    parsing::UnresolvedReference, Configuration, PackSet, SourceLocation,
+    constant_resolver::{ConstantDefinition, ConstantResolver},
+    file_utils::to_slash_path,
+    parsing::{ReferenceConfidence, ReferenceKind, UnresolvedReference},
+    raw_configuration::DefinitionScope,
+    Configuration, SourceLocation,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reference {
     pub constant_name: String,
     pub defining_pack_name: Option<String>,
@@ -13,20 +18,14 @@ pub struct Reference {
     pub referencing_pack_name: String,
     pub relative_referencing_file: String,
     pub source_location: SourceLocation,
-}
-
-impl Reference {
-    pub fn defining_pack<'a>(&self, pack_set: &'a PackSet) -> Option<&'a Pack> {
-        if let Some(name) = &self.defining_pack_name {
-            Some(pack_set.for_pack(name).unwrap_or_else(|_| panic!("Reference#defining_pack_name is {}, but that pack is not found in pack set.", &name)))
-        } else {
-            None
-        }
-    }
-
-    pub fn referencing_pack<'a>(&self, pack_set: &'a PackSet) -> &'a Pack {
-        pack_set.for_pack(&self.referencing_pack_name).unwrap_or_else(|_| panic!("Reference#referencing_pack_name is {}, but that pack is not found in pack set.", &self.referencing_pack_name))
-    }
+    pub kind: ReferenceKind,
+    pub confidence: ReferenceConfidence,
+    // Whether the defining file is marked `# pack_public: true`
+    #[serde(default)]
+    pub public_defining_file: bool,
+    // Whether the referencing file is marked `# packs_strict: true`
+    #[serde(default)]
+    pub strict_referencing_file: bool,
 }
 
 impl Reference {
@@ -53,13 +52,11 @@ impl Reference {
             column: loc.start_col,
         };
 
-        let relative_referencing_file_path = referencing_file_path
-            .strip_prefix(&configuration.absolute_root)
-            .unwrap()
-            .to_path_buf();
-
-        let relative_referencing_file =
-            relative_referencing_file_path.to_str().unwrap().to_string();
+        let relative_referencing_file = to_slash_path(
+            referencing_file_path
+                .strip_prefix(&configuration.absolute_root)
+                .unwrap(),
+        );
 
         let str_namespace_path: Vec<&str> = unresolved_reference
             .namespace_path
@@ -71,23 +68,42 @@ impl Reference {
             .resolve(&unresolved_reference.name, &str_namespace_path);
 
         if let Some(constant_definitions) = &maybe_constant_definition {
+            let constant_definitions = match configuration.definition_scope {
+                DefinitionScope::File => constant_definitions.iter().collect(),
+                DefinitionScope::App => nearest_definitions(
+                    constant_definitions,
+                    referencing_file_path,
+                    |absolute_path_of_definition| {
+                        configuration
+                            .pack_set
+                            .for_file(absolute_path_of_definition)
+                            .is_some_and(|pack| {
+                                pack.name == referencing_pack_name
+                            })
+                    },
+                ),
+            };
+
             constant_definitions
-                .iter()
+                .into_iter()
                 .map(move |constant| {
                     let absolute_path_of_definition =
                         &constant.absolute_path_of_definition;
-                    let relative_defining_file = absolute_path_of_definition
-                        .strip_prefix(&configuration.absolute_root)
-                        .unwrap()
-                        .to_path_buf()
-                        .to_str()
-                        .unwrap()
-                        .to_string();
-
-                    let defining_pack_name = configuration
-                        .pack_set
-                        .for_file(absolute_path_of_definition)
-                        .map(|pack| pack.name.clone());
+                    let relative_defining_file = to_slash_path(
+                        absolute_path_of_definition
+                            .strip_prefix(&configuration.absolute_root)
+                            .unwrap(),
+                    );
+
+                    let defining_pack_name = match configuration
+                        .mapped_pack(&constant.fully_qualified_name)
+                    {
+                        Some(pack_name) => Some(pack_name.to_owned()),
+                        None => configuration
+                            .pack_set
+                            .for_file(absolute_path_of_definition)
+                            .map(|pack| pack.name.clone()),
+                    };
 
                     let relative_defining_file = Some(relative_defining_file);
                     let constant_name = constant.fully_qualified_name.clone();
@@ -100,6 +116,10 @@ impl Reference {
                             .clone(),
                         source_location: source_location.clone(),
                         relative_defining_file,
+                        kind: unresolved_reference.kind,
+                        confidence: unresolved_reference.confidence,
+                        public_defining_file: false,
+                        strict_referencing_file: false,
                     }
                 })
                 .collect()
@@ -116,7 +136,107 @@ impl Reference {
                 relative_referencing_file,
                 source_location,
                 relative_defining_file,
+                kind: unresolved_reference.kind,
+                confidence: unresolved_reference.confidence,
+                public_defining_file: false,
+                strict_referencing_file: false,
             }]
         }
     }
 }
+
+// When a constant is defined in more than one place, the definitions nearest to
+// the reference shadow the others: a definition in the referencing file wins over
+// one elsewhere in the referencing pack, which wins over definitions in other packs.
+fn nearest_definitions<'a>(
+    constant_definitions: &'a [ConstantDefinition],
+    referencing_file_path: &Path,
+    is_in_referencing_pack: impl Fn(&Path) -> bool,
+) -> Vec<&'a ConstantDefinition> {
+    let in_referencing_file: Vec<&ConstantDefinition> = constant_definitions
+        .iter()
+        .filter(|d| d.absolute_path_of_definition == referencing_file_path)
+        .collect();
+    if !in_referencing_file.is_empty() {
+        return in_referencing_file;
+    }
+
+    let in_referencing_pack: Vec<&ConstantDefinition> = constant_definitions
+        .iter()
+        .filter(|d| is_in_referencing_pack(&d.absolute_path_of_definition))
+        .collect();
+    if !in_referencing_pack.is_empty() {
+        return in_referencing_pack;
+    }
+
+    constant_definitions.iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::DefinitionKind;
+
+    fn definition(path: &str) -> ConstantDefinition {
+        ConstantDefinition {
+            fully_qualified_name: String::from("::Shared"),
+            absolute_path_of_definition: PathBuf::from(path),
+            kind: DefinitionKind::Constant,
+        }
+    }
+
+    fn is_in_foo(path: &Path) -> bool {
+        path.starts_with("packs/foo")
+    }
+
+    #[test]
+    fn test_same_file_definition_shadows_others() {
+        let definitions = vec![
+            definition("packs/bar/app/services/shared.rb"),
+            definition("packs/foo/app/models/shared.rb"),
+            definition("packs/foo/app/services/foo.rb"),
+        ];
+
+        let actual = nearest_definitions(
+            &definitions,
+            Path::new("packs/foo/app/services/foo.rb"),
+            is_in_foo,
+        );
+
+        assert_eq!(actual, vec![&definitions[2]]);
+    }
+
+    #[test]
+    fn test_same_pack_definition_shadows_others() {
+        let definitions = vec![
+            definition("packs/bar/app/services/shared.rb"),
+            definition("packs/foo/app/models/shared.rb"),
+        ];
+
+        let actual = nearest_definitions(
+            &definitions,
+            Path::new("packs/foo/app/services/foo.rb"),
+            is_in_foo,
+        );
+
+        assert_eq!(actual, vec![&definitions[1]]);
+    }
+
+    #[test]
+    fn test_cross_pack_definitions_are_all_kept() {
+        let definitions = vec![
+            definition("packs/bar/app/services/shared.rb"),
+            definition("packs/baz/app/services/shared.rb"),
+        ];
+
+        let actual = nearest_definitions(
+            &definitions,
+            Path::new("packs/foo/app/services/foo.rb"),
+            is_in_foo,
+        );
+
+        assert_eq!(actual, vec![&definitions[0], &definitions[1]]);
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use super::reference::Reference;
+use crate::packs::file_utils::build_glob_set;
+use crate::packs::{Configuration, ReferenceConfidence, ReferenceKind};
+
+// Drops references before they reach the checkers, e.g. references made from
+// vendored or generated code that no one is expected to fix.
+pub(crate) struct ReferenceFilter {
+    ignored_paths: GlobSet,
+    ignored_constants: GlobSet,
+    ignore_signatures: bool,
+    pack_groups: Vec<GlobSet>,
+    enforce_from_root: bool,
+    min_confidence: Option<ReferenceConfidence>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FilterCounts {
+    pub kept: usize,
+    pub dropped_by_path: usize,
+    pub dropped_by_constant: usize,
+    pub dropped_as_signature: usize,
+    pub dropped_within_group: usize,
+    pub dropped_from_root: usize,
+    pub dropped_below_confidence: usize,
+}
+
+impl fmt::Display for FilterCounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} reference(s) kept, {} dropped by path, {} dropped by constant, {} dropped as signatures, {} dropped within a pack group, {} dropped from the root pack, {} dropped below the minimum confidence",
+            self.kept,
+            self.dropped_by_path,
+            self.dropped_by_constant,
+            self.dropped_as_signature,
+            self.dropped_within_group,
+            self.dropped_from_root,
+            self.dropped_below_confidence
+        )
+    }
+}
+
+impl ReferenceFilter {
+    pub(crate) fn new(configuration: &Configuration) -> ReferenceFilter {
+        let settings = &configuration.reference_filter;
+        ReferenceFilter {
+            ignored_paths: build_glob_set(&settings.paths),
+            ignored_constants: build_constant_glob_set(&settings.constants),
+            ignore_signatures: settings.signatures,
+            pack_groups: settings
+                .pack_groups
+                .iter()
+                .map(|group| build_glob_set(group))
+                .collect(),
+            enforce_from_root: configuration.enforce_from_root,
+            min_confidence: configuration.min_confidence,
+        }
+    }
+
+    // Keeps references from the root pack even with `enforce_from_root:
+    // false`, so the violations recorded for it can be told apart from
+    // stale ones
+    pub(crate) fn keeping_root_references(self) -> ReferenceFilter {
+        ReferenceFilter {
+            enforce_from_root: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn apply(
+        &self,
+        references: Vec<Reference>,
+    ) -> (Vec<Reference>, FilterCounts) {
+        let mut counts = FilterCounts::default();
+
+        let kept: Vec<Reference> = references
+            .into_iter()
+            .filter(|reference| {
+                if self
+                    .ignored_paths
+                    .is_match(&reference.relative_referencing_file)
+                {
+                    counts.dropped_by_path += 1;
+                    false
+                } else if self
+                    .ignored_constants
+                    .is_match(&reference.constant_name)
+                {
+                    counts.dropped_by_constant += 1;
+                    false
+                } else if self.ignore_signatures
+                    && reference.kind == ReferenceKind::Signature
+                {
+                    counts.dropped_as_signature += 1;
+                    false
+                } else if self.is_within_group(reference) {
+                    counts.dropped_within_group += 1;
+                    false
+                } else if !self.enforce_from_root
+                    && reference.referencing_pack_name == "."
+                {
+                    counts.dropped_from_root += 1;
+                    false
+                } else if self
+                    .min_confidence
+                    .is_some_and(|min| reference.confidence < min)
+                {
+                    counts.dropped_below_confidence += 1;
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        counts.kept = kept.len();
+        (kept, counts)
+    }
+
+    // Whether the reference is between two packs of the same group
+    fn is_within_group(&self, reference: &Reference) -> bool {
+        let Some(defining_pack_name) = &reference.defining_pack_name else {
+            return false;
+        };
+        self.pack_groups.iter().any(|group| {
+            group.is_match(&reference.referencing_pack_name)
+                && group.is_match(defining_pack_name)
+        })
+    }
+}
+
+// Unlike path globs, `*` in a constant pattern also matches `::`, so
+// `::Generated::*` matches every constant nested under `::Generated`.
+pub(super) fn build_constant_glob_set(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(GlobBuilder::new(pattern).build().unwrap());
+    }
+    builder.build().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packs::SourceLocation;
+
+    fn reference(
+        constant_name: &str,
+        referencing_pack_name: &str,
+        relative_referencing_file: &str,
+    ) -> Reference {
+        Reference {
+            constant_name: constant_name.to_owned(),
+            defining_pack_name: Some(String::from("packs/bar")),
+            relative_defining_file: None,
+            referencing_pack_name: referencing_pack_name.to_owned(),
+            relative_referencing_file: relative_referencing_file.to_owned(),
+            source_location: SourceLocation::default(),
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
+        }
+    }
+
+    fn signature_reference(
+        constant_name: &str,
+        referencing_pack_name: &str,
+        relative_referencing_file: &str,
+    ) -> Reference {
+        Reference {
+            kind: ReferenceKind::Signature,
+            confidence: ReferenceConfidence::Exact,
+            ..reference(
+                constant_name,
+                referencing_pack_name,
+                relative_referencing_file,
+            )
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let filter = ReferenceFilter {
+            ignored_paths: build_glob_set(&[String::from(
+                "app/graphql/generated/**",
+            )]),
+            ignored_constants: build_constant_glob_set(&[String::from(
+                "::Generated::*",
+            )]),
+            ignore_signatures: true,
+            pack_groups: vec![build_glob_set(&[
+                String::from("packs/bar"),
+                String::from("packs/bar_*"),
+            ])],
+            enforce_from_root: false,
+            min_confidence: Some(ReferenceConfidence::InferredAssociation),
+        };
+
+        let references = vec![
+            reference("::Bar", "packs/foo", "packs/foo/app/services/foo.rb"),
+            reference("::Bar", ".", "app/graphql/generated/types/bar.rb"),
+            reference(
+                "::Generated::Types::Bar",
+                "packs/foo",
+                "packs/foo/app/services/foo.rb",
+            ),
+            reference("::Bar", ".", "app/services/root.rb"),
+            reference(
+                "::Bar",
+                "packs/bar_admin",
+                "packs/bar_admin/app/services/bar_admin.rb",
+            ),
+            signature_reference(
+                "::Bar",
+                "packs/foo",
+                "packs/foo/app/services/foo.rb",
+            ),
+            Reference {
+                confidence: ReferenceConfidence::MetaprogrammedString,
+                ..reference(
+                    "::Bar",
+                    "packs/foo",
+                    "packs/foo/app/services/foo.rb",
+                )
+            },
+        ];
+
+        let (kept, counts) = filter.apply(references.clone());
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].referencing_pack_name, "packs/foo");
+        assert_eq!(
+            counts,
+            FilterCounts {
+                kept: 1,
+                dropped_by_path: 1,
+                dropped_by_constant: 1,
+                dropped_as_signature: 1,
+                dropped_within_group: 1,
+                dropped_from_root: 1,
+                dropped_below_confidence: 1,
+            }
+        );
+
+        let (kept, counts) = filter.keeping_root_references().apply(references);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].relative_referencing_file, "app/services/root.rb");
+        assert_eq!(counts.dropped_from_root, 0);
+    }
+}
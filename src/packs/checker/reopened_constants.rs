@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use super::reference_filter::build_constant_glob_set;
+use super::ValidatorInterface;
+use crate::packs::constant_index::{Constant, ConstantIndex};
+use crate::packs::Configuration;
+
+pub struct Checker {}
+
+// With `reopened_constants` in packwerk.yml, a class or module has to be
+// defined in a single pack, unless it's allowed. Reopening a constant from
+// another pack, e.g. `Billing::Invoice` from packs/reports, couples the packs
+// in a way references don't show.
+impl ValidatorInterface for Checker {
+    fn validate(&self, configuration: &Configuration) -> Option<String> {
+        let settings = configuration.reopened_constants.as_ref()?;
+        // The default parser infers one constant per file from its path, so
+        // it can't tell a constant is reopened
+        if !configuration.experimental_parser {
+            return Some(String::from(
+                "`reopened_constants` in packwerk.yml needs `experimental_parser: true`",
+            ));
+        }
+        let allowed: Vec<String> = settings
+            .allowed
+            .iter()
+            .map(|pattern| format!("::{}", pattern.trim_start_matches("::")))
+            .collect();
+        let allowed = build_constant_glob_set(&allowed);
+
+        let constant_index = ConstantIndex::new(configuration);
+        let error_messages: Vec<String> = reopened_constants(&constant_index)
+            .into_iter()
+            .filter(|(name, _)| !allowed.is_match(name))
+            .map(|(name, definitions)| {
+                let mut message =
+                    format!("`{}` is defined in more than one pack:", name);
+                for definition in definitions {
+                    message.push_str(&format!(
+                        "\n- {} ({})",
+                        definition.file,
+                        definition.pack_name.as_deref().unwrap_or_default()
+                    ));
+                }
+                message
+            })
+            .collect();
+
+        if error_messages.is_empty() {
+            None
+        } else {
+            Some(error_messages.join("\n"))
+        }
+    }
+}
+
+// The definitions of each constant defined in more than one pack, by name
+fn reopened_constants(
+    constant_index: &ConstantIndex,
+) -> BTreeMap<&str, Vec<&Constant>> {
+    let mut definitions_by_name: BTreeMap<&str, Vec<&Constant>> =
+        BTreeMap::new();
+    for constant in constant_index.iter() {
+        if constant.pack_name.is_some() {
+            definitions_by_name
+                .entry(&constant.name)
+                .or_default()
+                .push(constant);
+        }
+    }
+    definitions_by_name.retain(|_, definitions| {
+        definitions
+            .iter()
+            .any(|definition| definition.pack_name != definitions[0].pack_name)
+    });
+    definitions_by_name
+}
@@ -0,0 +1,604 @@
+use handlebars::Handlebars;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+use super::dependency::dependencies_to_add;
+use super::quick_fix::{quick_fixes, QuickFix};
+use super::{
+    violation_code, FailOn, OutputFormat, Violation, ViolationIdentifier,
+};
+use crate::packs::pack::Pack;
+use crate::packs::parsing::ParseError;
+use crate::packs::{Configuration, ReferenceConfidence, Warning};
+
+// Everything `check` found, before it's printed
+pub(crate) struct CheckReport<'a> {
+    pub reportable_violations: Vec<&'a Violation>,
+    pub recorded_violations_count: usize,
+    // Reportable violations that were recorded in a todo entry that expired
+    pub expired_violations_count: usize,
+    pub stale_violations: Vec<&'a ViolationIdentifier>,
+    pub strict_mode_violations: Vec<&'a ViolationIdentifier>,
+    pub parse_errors: &'a [ParseError],
+    pub warnings: &'a [Warning],
+}
+
+impl<'a> CheckReport<'a> {
+    fn fails_on_recorded_violations(
+        &self,
+        configuration: &Configuration,
+    ) -> bool {
+        configuration.fail_on == FailOn::All
+            && self.recorded_violations_count > 0
+    }
+
+    fn fails_on_stale_violations(&self, configuration: &Configuration) -> bool {
+        !self.stale_violations.is_empty() && !configuration.warn_on_stale
+    }
+
+    fn fails_on_warnings(&self, configuration: &Configuration) -> bool {
+        configuration.deny_warnings && !self.warnings.is_empty()
+    }
+
+    pub(crate) fn errors_present(&self, configuration: &Configuration) -> bool {
+        !self.reportable_violations.is_empty()
+            || self.fails_on_recorded_violations(configuration)
+            || self.fails_on_stale_violations(configuration)
+            || !self.strict_mode_violations.is_empty()
+            || self.fails_on_warnings(configuration)
+    }
+
+    // Prints the report, or writes it to `--output` if one was given
+    pub(crate) fn print(
+        &self,
+        configuration: &Configuration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let rendered = match configuration.output_format {
+            OutputFormat::Text => self.render_text(configuration),
+            OutputFormat::Json => self.render_json(configuration),
+            OutputFormat::Markdown => self.render_markdown(configuration),
+            OutputFormat::Template => self.render_template(configuration)?,
+            OutputFormat::Ndjson => return self.stream_ndjson(configuration),
+        };
+
+        match &configuration.output_path {
+            Some(output_path) => std::fs::write(output_path, rendered)?,
+            None => print!("{}", rendered),
+        }
+
+        Ok(())
+    }
+
+    fn render_text(&self, configuration: &Configuration) -> String {
+        let mut out = String::new();
+
+        if !self.reportable_violations.is_empty() {
+            for violation in self.reportable_violations.iter() {
+                writeln!(out, "{}\n", full_message(violation, configuration))
+                    .unwrap();
+            }
+
+            writeln!(
+                out,
+                "{} violation(s) detected:",
+                self.reportable_violations.len()
+            )
+            .unwrap();
+
+            // Code that still lives in the root pack is usually the biggest
+            // source of violations, so we call it out to help prioritize extraction.
+            let unpacked_violations_count = self
+                .reportable_violations
+                .iter()
+                .filter(|v| v.identifier.referencing_pack_name == ".")
+                .count();
+            if unpacked_violations_count > 0 {
+                writeln!(
+                    out,
+                    "{} violation(s) from unpacked code (the root pack)",
+                    unpacked_violations_count
+                )
+                .unwrap();
+            }
+            if self.expired_violations_count > 0 {
+                writeln!(
+                    out,
+                    "{} violation(s) were recorded in todo entries that expired, see `expires_on`",
+                    self.expired_violations_count
+                )
+                .unwrap();
+            }
+        }
+
+        if self.fails_on_recorded_violations(configuration) {
+            writeln!(
+                out,
+                "{} recorded violation(s) detected, failing because of \
+                 `--fail-on all`",
+                self.recorded_violations_count
+            )
+            .unwrap();
+        }
+
+        if !self.stale_violations.is_empty() {
+            let message = if configuration.warn_on_stale {
+                "Warning: there were stale violations found, please run \
+                 `packs update`"
+            } else {
+                "There were stale violations found, please run `packs update`"
+            };
+            writeln!(out, "{}", message).unwrap();
+        }
+
+        for v in &self.strict_mode_violations {
+            writeln!(
+                out,
+                "{} cannot have {} violations on {} because strict mode is \
+                 enabled for {} violations in the enforcing pack's package.yml \
+                 file",
+                v.referencing_pack_name,
+                v.violation_type,
+                v.defining_pack_name,
+                v.violation_type
+            )
+            .unwrap();
+        }
+
+        for warning in self.warnings {
+            writeln!(out, "{}", warning.text()).unwrap();
+        }
+        if self.fails_on_warnings(configuration) {
+            writeln!(
+                out,
+                "{} warning(s) detected, failing because of `--deny-warnings`",
+                self.warnings.len()
+            )
+            .unwrap();
+        }
+
+        if !self.errors_present(configuration) {
+            writeln!(out, "No violations detected!").unwrap();
+        }
+
+        out
+    }
+
+    pub(crate) fn render_json(&self, configuration: &Configuration) -> String {
+        let json_report = self.json_report(configuration);
+        format!("{}\n", serde_json::to_string_pretty(&json_report).unwrap())
+    }
+
+    // A summary table, then the violations of each referencing pack in a
+    // collapsed section, to paste into a pull request or post as a comment
+    fn render_markdown(&self, configuration: &Configuration) -> String {
+        let mut out = String::from("## packs check\n");
+        if !self.errors_present(configuration) {
+            out.push_str("\nNo violations detected!\n");
+        }
+
+        let mut counts_by_type: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut violations_by_pack: BTreeMap<&str, Vec<&Violation>> =
+            BTreeMap::new();
+        for violation in &self.reportable_violations {
+            *counts_by_type
+                .entry(&violation.identifier.violation_type)
+                .or_default() += 1;
+            violations_by_pack
+                .entry(&violation.identifier.referencing_pack_name)
+                .or_default()
+                .push(violation);
+        }
+
+        out.push_str("\n| | Count |\n| --- | ---: |\n");
+        for (violation_type, count) in &counts_by_type {
+            writeln!(out, "| {} violations | {} |", violation_type, count)
+                .unwrap();
+        }
+        for (name, count) in [
+            ("New violations", self.reportable_violations.len()),
+            ("Recorded violations", self.recorded_violations_count),
+            ("Expired todo entries", self.expired_violations_count),
+            ("Stale todo entries", self.stale_violations.len()),
+            ("Strict mode violations", self.strict_mode_violations.len()),
+            ("Parse errors", self.parse_errors.len()),
+            ("Warnings", self.warnings.len()),
+        ] {
+            writeln!(out, "| {} | {} |", name, count).unwrap();
+        }
+
+        for (pack_name, violations) in &violations_by_pack {
+            writeln!(
+                out,
+                "\n<details>\n<summary><code>{}</code>: {} violation(s)</summary>\n",
+                pack_name,
+                violations.len()
+            )
+            .unwrap();
+            out.push_str(
+                "| Type | Constant | Defining pack | File |\n| --- | --- | --- | --- |\n",
+            );
+            for violation in violations {
+                let identifier = &violation.identifier;
+                writeln!(
+                    out,
+                    "| {} | `{}` | `{}` | `{}` |",
+                    identifier.violation_type,
+                    identifier.constant_name,
+                    markdown_cell(&identifier.defining_pack_name),
+                    markdown_cell(&identifier.file)
+                )
+                .unwrap();
+            }
+            out.push_str("\n</details>\n");
+        }
+
+        if !self.strict_mode_violations.is_empty() {
+            out.push_str(
+                "\n<details>\n<summary>Strict mode violations</summary>\n\n\
+                 | Type | Constant | Referencing pack | Defining pack |\n| --- | --- | --- | --- |\n",
+            );
+            for identifier in &self.strict_mode_violations {
+                writeln!(
+                    out,
+                    "| {} | `{}` | `{}` | `{}` |",
+                    identifier.violation_type,
+                    identifier.constant_name,
+                    markdown_cell(&identifier.referencing_pack_name),
+                    markdown_cell(&identifier.defining_pack_name)
+                )
+                .unwrap();
+            }
+            out.push_str("\n</details>\n");
+        }
+
+        if !self.warnings.is_empty() {
+            out.push_str("\n**Warnings**\n\n");
+            for warning in self.warnings {
+                writeln!(out, "- {}", warning.text()).unwrap();
+            }
+        }
+
+        out
+    }
+
+    // Renders the same data as `--format json` with the user's template, so
+    // that any format can be produced without a new built-in reporter
+    fn render_template(
+        &self,
+        configuration: &Configuration,
+    ) -> Result<String, Box<dyn Error>> {
+        let template_path = configuration
+            .template_path
+            .as_ref()
+            .ok_or("`--format template` needs a `--template`")?;
+        let template = std::fs::read_to_string(template_path).map_err(|e| {
+            format!(
+                "Failed to read the template at {}: {}",
+                template_path.display(),
+                e
+            )
+        })?;
+
+        let mut handlebars = Handlebars::new();
+        // A typo in a field name fails loudly instead of rendering nothing
+        handlebars.set_strict_mode(true);
+        let rendered = handlebars
+            .render_template(&template, &self.json_report(configuration))
+            .map_err(|e| {
+                format!(
+                    "Failed to render the template at {}: {}",
+                    template_path.display(),
+                    e
+                )
+            })?;
+        Ok(rendered)
+    }
+
+    // Writes each violation as soon as it's serialized, so a report with
+    // hundreds of thousands of violations is never held in memory at once
+    fn stream_ndjson(
+        &self,
+        configuration: &Configuration,
+    ) -> Result<(), Box<dyn Error>> {
+        match &configuration.output_path {
+            Some(output_path) => self.write_ndjson(
+                configuration,
+                BufWriter::new(File::create(output_path)?),
+            ),
+            None => self.write_ndjson(
+                configuration,
+                BufWriter::new(io::stdout().lock()),
+            ),
+        }
+    }
+
+    fn write_ndjson(
+        &self,
+        configuration: &Configuration,
+        mut out: impl io::Write,
+    ) -> Result<(), Box<dyn Error>> {
+        let missing_dependencies = self.missing_dependencies();
+        let dependencies_to_add =
+            dependencies_to_add(configuration, &missing_dependencies);
+        for violation in &self.reportable_violations {
+            serde_json::to_writer(
+                &mut out,
+                &NdjsonRecord::Violation(json_violation(
+                    violation,
+                    configuration,
+                    &dependencies_to_add,
+                )),
+            )?;
+            out.write_all(b"\n")?;
+        }
+        serde_json::to_writer(
+            &mut out,
+            &NdjsonRecord::Summary(NdjsonSummary {
+                violations_count: self.reportable_violations.len(),
+                recorded_violations_count: self.recorded_violations_count,
+                expired_violations_count: self.expired_violations_count,
+                stale_violations: &self.stale_violations,
+                strict_mode_violations: &self.strict_mode_violations,
+                parse_errors: self.parse_errors,
+                warnings: self.warnings,
+            }),
+        )?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        Ok(())
+    }
+
+    // The (referencing pack, defining pack) of each dependency violation,
+    // for the quick fixes of each violation
+    fn missing_dependencies(&self) -> BTreeSet<(String, String)> {
+        self.reportable_violations
+            .iter()
+            .filter(|violation| {
+                violation.identifier.violation_type == "dependency"
+            })
+            .map(|violation| {
+                (
+                    violation.identifier.referencing_pack_name.clone(),
+                    violation.identifier.defining_pack_name.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn json_report<'b>(
+        &'b self,
+        configuration: &'b Configuration,
+    ) -> JsonReport<'b> {
+        let missing_dependencies = self.missing_dependencies();
+        let dependencies_to_add =
+            dependencies_to_add(configuration, &missing_dependencies);
+        let violations: Vec<JsonViolation> = self
+            .reportable_violations
+            .iter()
+            .map(|violation| {
+                json_violation(violation, configuration, &dependencies_to_add)
+            })
+            .collect();
+
+        JsonReport {
+            violations,
+            recorded_violations_count: self.recorded_violations_count,
+            expired_violations_count: self.expired_violations_count,
+            stale_violations: &self.stale_violations,
+            strict_mode_violations: &self.strict_mode_violations,
+            parse_errors: self.parse_errors,
+            warnings: self.warnings,
+        }
+    }
+}
+
+fn json_violation<'a>(
+    violation: &'a Violation,
+    configuration: &'a Configuration,
+    dependencies_to_add: &BTreeSet<(&str, &str)>,
+) -> JsonViolation<'a> {
+    let defining_pack = defining_pack(violation, configuration);
+    let code = violation_code(&violation.identifier.violation_type);
+    JsonViolation {
+        identifier: &violation.identifier,
+        code,
+        help_url: code.and_then(|code| {
+            configuration
+                .violation_code_urls
+                .get(code)
+                .map(String::as_str)
+        }),
+        message: full_message(violation, configuration),
+        confidence: violation.confidence,
+        defining_pack_owner: defining_pack.and_then(|pack| pack.owner_name()),
+        defining_pack_docs_url: defining_pack.and_then(|pack| pack.docs_url()),
+        fixes: quick_fixes(violation, configuration, dependencies_to_add),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    violations: Vec<JsonViolation<'a>>,
+    recorded_violations_count: usize,
+    expired_violations_count: usize,
+    stale_violations: &'a Vec<&'a ViolationIdentifier>,
+    strict_mode_violations: &'a Vec<&'a ViolationIdentifier>,
+    parse_errors: &'a [ParseError],
+    warnings: &'a [Warning],
+}
+
+// A line of `--format ndjson`, tagged with its `type`
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NdjsonRecord<'a> {
+    Violation(JsonViolation<'a>),
+    Summary(NdjsonSummary<'a>),
+}
+
+#[derive(Serialize)]
+struct NdjsonSummary<'a> {
+    violations_count: usize,
+    recorded_violations_count: usize,
+    expired_violations_count: usize,
+    stale_violations: &'a Vec<&'a ViolationIdentifier>,
+    strict_mode_violations: &'a Vec<&'a ViolationIdentifier>,
+    parse_errors: &'a [ParseError],
+    warnings: &'a [Warning],
+}
+
+#[derive(Serialize)]
+struct JsonViolation<'a> {
+    #[serde(flatten)]
+    identifier: &'a ViolationIdentifier,
+    code: Option<&'static str>,
+    help_url: Option<&'a str>,
+    message: String,
+    confidence: ReferenceConfidence,
+    defining_pack_owner: Option<&'a str>,
+    defining_pack_docs_url: Option<&'a str>,
+    fixes: Vec<QuickFix>,
+}
+
+fn defining_pack<'a>(
+    violation: &Violation,
+    configuration: &'a Configuration,
+) -> Option<&'a Pack> {
+    configuration
+        .pack_set
+        .for_pack(&violation.identifier.defining_pack_name)
+        .ok()
+}
+
+// The violation message, followed by who owns the defining pack and where
+// its docs live (if it says), so whoever hits the violation knows where to go.
+// Ends with the violation's code, and the docs configured for that code.
+fn full_message(
+    violation: &Violation,
+    configuration: &Configuration,
+) -> String {
+    let mut message = violation.message.to_owned();
+    if let Some(note) =
+        defining_pack(violation, configuration).and_then(pack_note)
+    {
+        message = format!("{}\n{}", message, note);
+    }
+    if let Some(code) = violation_code(&violation.identifier.violation_type) {
+        message = match configuration.violation_code_urls.get(code) {
+            Some(url) => format!("{}\n[{}] See {}", message, code, url),
+            None => format!("{}\n[{}]", message, code),
+        };
+    }
+    message
+}
+
+// Pipes would end the table cell
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn pack_note(pack: &Pack) -> Option<String> {
+    match (pack.owner_name(), pack.docs_url()) {
+        (Some(owner), Some(docs_url)) => Some(format!(
+            "`{}` is owned by {} — see {} for its public API",
+            pack.name, owner, docs_url
+        )),
+        (Some(owner), None) => {
+            Some(format!("`{}` is owned by {}", pack.name, owner))
+        }
+        (None, Some(docs_url)) => Some(format!(
+            "See {} for the public API of `{}`",
+            docs_url, pack.name
+        )),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn violation(
+        violation_type: &str,
+        file: &str,
+        referencing_pack_name: &str,
+    ) -> Violation {
+        Violation {
+            message: String::new(),
+            identifier: ViolationIdentifier {
+                violation_type: String::from(violation_type),
+                file: String::from(file),
+                constant_name: String::from("::Bar"),
+                referencing_pack_name: String::from(referencing_pack_name),
+                defining_pack_name: String::from("packs/bar"),
+            },
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let violations = [
+            violation(
+                "dependency",
+                "packs/foo/app/services/foo.rb",
+                "packs/foo",
+            ),
+            violation("privacy", "packs/foo/app/services/foo.rb", "packs/foo"),
+            violation("privacy", "app/services/a|b.rb", "."),
+        ];
+        let report = CheckReport {
+            reportable_violations: violations.iter().collect(),
+            recorded_violations_count: 4,
+            expired_violations_count: 0,
+            stale_violations: vec![],
+            strict_mode_violations: vec![],
+            parse_errors: &[],
+            warnings: &[],
+        };
+
+        assert_eq!(
+            "\
+## packs check
+
+| | Count |
+| --- | ---: |
+| dependency violations | 1 |
+| privacy violations | 2 |
+| New violations | 3 |
+| Recorded violations | 4 |
+| Expired todo entries | 0 |
+| Stale todo entries | 0 |
+| Strict mode violations | 0 |
+| Parse errors | 0 |
+| Warnings | 0 |
+
+<details>
+<summary><code>.</code>: 1 violation(s)</summary>
+
+| Type | Constant | Defining pack | File |
+| --- | --- | --- | --- |
+| privacy | `::Bar` | `packs/bar` | `app/services/a\\|b.rb` |
+
+</details>
+
+<details>
+<summary><code>packs/foo</code>: 2 violation(s)</summary>
+
+| Type | Constant | Defining pack | File |
+| --- | --- | --- | --- |
+| dependency | `::Bar` | `packs/bar` | `packs/foo/app/services/foo.rb` |
+| privacy | `::Bar` | `packs/bar` | `packs/foo/app/services/foo.rb` |
+
+</details>
+",
+            report.render_markdown(&Configuration::default())
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value};
+
+use super::{get_checkers, ValidatorInterface};
+use crate::packs::file_utils::to_slash_path;
+use crate::packs::package_todo::{
+    consolidated_todo_path, delete_package_todo_from_disk, is_date,
+    package_todo_path, write_consolidated_todo_to_disk,
+    write_package_todo_to_disk, ViolationGroup,
+};
+use crate::packs::raw_configuration::TodoStorage;
+use crate::packs::{Configuration, PackageTodo};
+
+pub struct Checker {}
+
+impl ValidatorInterface for Checker {
+    fn validate(&self, configuration: &Configuration) -> Option<String> {
+        let problems = lint(configuration);
+        if problems.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "{} problem(s) in todo files, which may have been edited by hand. Run `packs lint-todos --fix` to fix them:\n{}",
+            problems.len(),
+            problems
+                .iter()
+                .map(|problem| format!("- {}", problem))
+                .collect::<Vec<String>>()
+                .join("\n")
+        ))
+    }
+}
+
+// Reports todo files that `update` would not have written, and with `fix`,
+// rewrites them without the entries that can't be right
+pub(crate) fn lint_todos(
+    configuration: &Configuration,
+    fix: bool,
+) -> Result<(), Box<dyn Error>> {
+    let problems = lint(configuration);
+    if problems.is_empty() {
+        println!("No problems found in todo files");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("- {}", problem);
+    }
+
+    if !fix {
+        return Err(format!(
+            "{} problem(s) in todo files, run `packs lint-todos --fix` to fix them",
+            problems.len()
+        )
+        .into());
+    }
+
+    let linter = TodoLinter::new(configuration);
+    match configuration.todo_storage {
+        TodoStorage::PerPack => {
+            for pack in &configuration.pack_set.packs {
+                if !package_todo_path(pack).exists() {
+                    continue;
+                }
+                let package_todo = linter.fixed(&pack.package_todo);
+                if package_todo.violations_by_defining_pack.is_empty() {
+                    delete_package_todo_from_disk(pack);
+                } else {
+                    write_package_todo_to_disk(
+                        pack,
+                        &package_todo,
+                        configuration.packs_first_mode,
+                    );
+                }
+            }
+        }
+        TodoStorage::Consolidated => {
+            let package_todos_by_pack_name: BTreeMap<String, PackageTodo> =
+                configuration
+                    .pack_set
+                    .packs
+                    .iter()
+                    .map(|pack| {
+                        (pack.name.clone(), linter.fixed(&pack.package_todo))
+                    })
+                    .filter(|(_, package_todo)| {
+                        !package_todo.violations_by_defining_pack.is_empty()
+                    })
+                    .collect();
+            write_consolidated_todo_to_disk(
+                configuration,
+                package_todos_by_pack_name,
+            );
+        }
+    }
+
+    println!("Fixed {} problem(s) in todo files", problems.len());
+    Ok(())
+}
+
+fn lint(configuration: &Configuration) -> Vec<String> {
+    let linter = TodoLinter::new(configuration);
+    match configuration.todo_storage {
+        TodoStorage::PerPack => configuration
+            .pack_set
+            .packs
+            .iter()
+            .map(package_todo_path)
+            .filter(|path| path.exists())
+            .flat_map(|path| linter.lint_file(&path, false))
+            .collect(),
+        TodoStorage::Consolidated => {
+            let path = consolidated_todo_path(&configuration.absolute_root);
+            if path.exists() {
+                linter.lint_file(&path, true)
+            } else {
+                vec![]
+            }
+        }
+    }
+}
+
+struct TodoLinter<'a> {
+    absolute_root: &'a Path,
+    violation_types: HashSet<String>,
+}
+
+impl<'a> TodoLinter<'a> {
+    fn new(configuration: &'a Configuration) -> TodoLinter<'a> {
+        TodoLinter {
+            absolute_root: &configuration.absolute_root,
+            violation_types: get_checkers(configuration)
+                .iter()
+                .map(|checker| checker.violation_type())
+                .collect(),
+        }
+    }
+
+    // The file is read as plain YAML, since reading it into a `PackageTodo`
+    // would sort it and silently keep only the last of any duplicate keys
+    fn lint_file(&self, path: &Path, consolidated: bool) -> Vec<String> {
+        let relative_path = to_slash_path(
+            path.strip_prefix(self.absolute_root).unwrap_or(path),
+        );
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => return vec![format!("{}: {}", relative_path, e)],
+        };
+        let todo: Value = match serde_yaml::from_str(&contents) {
+            Ok(todo) => todo,
+            Err(e) => return vec![format!("{}: {}", relative_path, e)],
+        };
+
+        let mut problems = vec![];
+        let mut unsorted = false;
+        if let Some(todo) = todo.as_mapping() {
+            if consolidated {
+                unsorted |= !is_sorted(todo.keys());
+                for package_todo in todo.values().filter_map(Value::as_mapping)
+                {
+                    unsorted |= self.lint_todo(package_todo, &mut problems);
+                }
+            } else {
+                unsorted |= self.lint_todo(todo, &mut problems);
+            }
+        }
+
+        let mut problems: Vec<String> = problems
+            .into_iter()
+            .map(|problem| format!("{}: {}", relative_path, problem))
+            .collect();
+        if unsorted {
+            problems.insert(
+                0,
+                format!("{}: entries are not sorted", relative_path),
+            );
+        }
+        problems
+    }
+
+    // Returns whether any part of the todo is out of order
+    fn lint_todo(&self, todo: &Mapping, problems: &mut Vec<String>) -> bool {
+        let mut unsorted = !is_sorted(todo.keys());
+        for violations_by_constant in
+            todo.values().filter_map(Value::as_mapping)
+        {
+            unsorted |= !is_sorted(violations_by_constant.keys());
+            for (constant_name, violation_group) in violations_by_constant {
+                let constant_name = constant_name.as_str().unwrap_or_default();
+                let violation_types = sequence(violation_group, "violations");
+                let files = sequence(violation_group, "files");
+                unsorted |= !is_sorted(violation_types.iter())
+                    || !is_sorted(files.iter());
+
+                for violation_type in
+                    violation_types.iter().filter_map(Value::as_str)
+                {
+                    if !self.violation_types.contains(violation_type) {
+                        problems.push(format!(
+                            "`{}` recorded for `{}` is not a violation type",
+                            violation_type, constant_name
+                        ));
+                    }
+                }
+                for file in files.iter().filter_map(Value::as_str) {
+                    if !self.file_exists(file) {
+                        problems.push(format!(
+                            "`{}` recorded for `{}` does not exist",
+                            file, constant_name
+                        ));
+                    }
+                }
+                if let Some(expires_on) = violation_group.get("expires_on") {
+                    if !expires_on.as_str().is_some_and(is_date) {
+                        problems.push(format!(
+                            "`expires_on` of `{}` is not a YYYY-MM-DD date",
+                            constant_name
+                        ));
+                    }
+                }
+            }
+        }
+        unsorted
+    }
+
+    // The todo as `update` would write it, without unknown violation types
+    // or files that don't exist
+    fn fixed(&self, package_todo: &PackageTodo) -> PackageTodo {
+        let violations_by_defining_pack = package_todo
+            .violations_by_defining_pack
+            .iter()
+            .map(|(defining_pack_name, violations_by_constant)| {
+                let violations_by_constant: BTreeMap<String, ViolationGroup> =
+                    violations_by_constant
+                        .iter()
+                        .map(|(constant_name, violation_group)| {
+                            let violation_group = ViolationGroup {
+                                violation_types: violation_group
+                                    .violation_types
+                                    .iter()
+                                    .filter(|violation_type| {
+                                        self.violation_types
+                                            .contains(*violation_type)
+                                    })
+                                    .cloned()
+                                    .collect(),
+                                files: violation_group
+                                    .files
+                                    .iter()
+                                    .filter(|file| self.file_exists(file))
+                                    .cloned()
+                                    .collect(),
+                                expires_on: violation_group
+                                    .expires_on
+                                    .clone()
+                                    .filter(|date| is_date(date)),
+                                ..violation_group.clone()
+                            };
+                            (constant_name.clone(), violation_group)
+                        })
+                        .filter(|(_, violation_group)| {
+                            !violation_group.violation_types.is_empty()
+                                && !violation_group.files.is_empty()
+                        })
+                        .collect();
+                (defining_pack_name.clone(), violations_by_constant)
+            })
+            .filter(|(_, violations_by_constant)| {
+                !violations_by_constant.is_empty()
+            })
+            .collect();
+
+        PackageTodo {
+            violations_by_defining_pack,
+        }
+    }
+
+    fn file_exists(&self, file: &str) -> bool {
+        self.absolute_root.join(file).is_file()
+    }
+}
+
+fn sequence<'v>(violation_group: &'v Value, key: &str) -> &'v [Value] {
+    violation_group
+        .get(key)
+        .and_then(Value::as_sequence)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn is_sorted<'v>(values: impl Iterator<Item = &'v Value>) -> bool {
+    let values: Vec<&str> = values.filter_map(Value::as_str).collect();
+    values.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_sorted() {
+        let sorted: Vec<Value> = serde_yaml::from_str("[a, b, b, c]").unwrap();
+        let unsorted: Vec<Value> = serde_yaml::from_str("[b, a]").unwrap();
+        assert!(is_sorted(sorted.iter()));
+        assert!(!is_sorted(unsorted.iter()));
+    }
+}
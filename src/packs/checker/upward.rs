@@ -0,0 +1,232 @@
+use super::{
+    context::CheckContext, get_defining_pack, CheckerInterface,
+    ViolationIdentifier,
+};
+use crate::packs::checker::Reference;
+use crate::packs::{Configuration, Violation};
+
+pub struct Checker {}
+
+// A pack nested in another one may only use the public constants of the pack
+// it is nested in, whether or not it declares a dependency on it. Anything
+// else should be made public, or extracted into a sibling pack.
+impl CheckerInterface for Checker {
+    fn check(
+        &self,
+        reference: &Reference,
+        context: &CheckContext,
+    ) -> Option<Violation> {
+        let referencing_pack = context.referencing_pack(reference);
+        let defining_pack = context.defining_pack(reference)?;
+
+        if !defining_pack.enforce_upward_references().is_enforced() {
+            return None;
+        }
+
+        if !referencing_pack.is_nested_in(defining_pack) {
+            return None;
+        }
+
+        if context.is_public(reference) {
+            return None;
+        }
+
+        let referencing_pack_name = &referencing_pack.name;
+        let defining_pack_name = &defining_pack.name;
+        let message = format!(
+                "Upward reference violation: `{}` is private to `{}`, which `{}` is nested in. Make it public, or move it into a sibling pack",
+                reference.constant_name,
+                defining_pack_name,
+                referencing_pack_name,
+            );
+
+        let identifier = ViolationIdentifier {
+            violation_type: self.violation_type(),
+            file: reference.relative_referencing_file.clone(),
+            constant_name: reference.constant_name.clone(),
+            referencing_pack_name: referencing_pack_name.clone(),
+            defining_pack_name: defining_pack_name.clone(),
+        };
+
+        Some(Violation {
+            message,
+            identifier,
+            confidence: reference.confidence,
+            relative_defining_file: reference.relative_defining_file.clone(),
+            strict: false,
+        })
+    }
+
+    fn is_strict_mode_violation(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let defining_pack =
+            get_defining_pack(violation, &configuration.pack_set);
+
+        defining_pack.enforce_upward_references().is_strict()
+    }
+
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let defining_pack =
+            get_defining_pack(violation, &configuration.pack_set);
+
+        defining_pack.enforce_upward_references().is_enforced()
+    }
+
+    fn violation_type(&self) -> String {
+        "upward".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::{
+        pack::{EnforcementSetting, Pack},
+        *,
+    };
+
+    fn configuration_with(packs: Vec<Pack>) -> Configuration {
+        let root_pack = Pack {
+            name: String::from("."),
+            ..Pack::default()
+        };
+        // Public folders are relative to the pack's directory
+        let mut packs: Vec<Pack> = packs
+            .into_iter()
+            .map(|pack| Pack {
+                relative_path: PathBuf::from(&pack.name),
+                ..pack
+            })
+            .collect();
+        packs.push(root_pack);
+
+        Configuration {
+            pack_set: PackSet::build(HashSet::from_iter(packs), HashMap::new()),
+            ..Configuration::default()
+        }
+    }
+
+    fn reference_from(
+        referencing_pack_name: &str,
+        relative_defining_file: &str,
+    ) -> Reference {
+        Reference {
+            constant_name: String::from("::Foo::Internal"),
+            defining_pack_name: Some(String::from("packs/foo")),
+            referencing_pack_name: String::from(referencing_pack_name),
+            relative_referencing_file: format!(
+                "{}/app/services/child.rb",
+                referencing_pack_name
+            ),
+            relative_defining_file: Some(String::from(relative_defining_file)),
+            source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
+        }
+    }
+
+    #[test]
+    fn reference_is_an_upward_violation() {
+        let configuration = configuration_with(vec![
+            Pack {
+                name: String::from("packs/foo"),
+                enforce_upward_references: Some(EnforcementSetting::On),
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/foo/child"),
+                dependencies: HashSet::from([String::from("packs/foo")]),
+                ..Pack::default()
+            },
+        ]);
+        let reference = reference_from(
+            "packs/foo/child",
+            "packs/foo/app/services/foo/internal.rb",
+        );
+
+        let expected_violation = Violation {
+            message: String::from("Upward reference violation: `::Foo::Internal` is private to `packs/foo`, which `packs/foo/child` is nested in. Make it public, or move it into a sibling pack"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
+            identifier: ViolationIdentifier {
+                violation_type: String::from("upward"),
+                file: String::from("packs/foo/child/app/services/child.rb"),
+                constant_name: String::from("::Foo::Internal"),
+                referencing_pack_name: String::from("packs/foo/child"),
+                defining_pack_name: String::from("packs/foo"),
+            },
+        };
+        assert_eq!(
+            Some(expected_violation),
+            Checker {}.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        );
+    }
+
+    #[test]
+    fn public_constants_of_the_parent_can_be_referenced() {
+        let configuration = configuration_with(vec![
+            Pack {
+                name: String::from("packs/foo"),
+                enforce_upward_references: Some(EnforcementSetting::On),
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/foo/child"),
+                ..Pack::default()
+            },
+        ]);
+        let reference =
+            reference_from("packs/foo/child", "packs/foo/app/public/foo.rb");
+
+        assert_eq!(
+            None,
+            Checker {}.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        );
+    }
+
+    #[test]
+    fn sibling_packs_are_not_checked() {
+        let configuration = configuration_with(vec![
+            Pack {
+                name: String::from("packs/foo"),
+                enforce_upward_references: Some(EnforcementSetting::On),
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/foobar"),
+                ..Pack::default()
+            },
+        ]);
+        let reference = reference_from(
+            "packs/foobar",
+            "packs/foo/app/services/foo/internal.rb",
+        );
+
+        assert_eq!(
+            None,
+            Checker {}.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 
-use super::{get_defining_pack, CheckerInterface, ViolationIdentifier};
+use super::{
+    context::CheckContext, get_defining_pack, CheckerInterface,
+    ViolationIdentifier,
+};
 use crate::packs::checker::Reference;
 use crate::packs::{Configuration, Violation};
 
@@ -13,20 +16,15 @@ impl CheckerInterface for Checker {
     fn check(
         &self,
         reference: &Reference,
-        configuration: &Configuration,
+        context: &CheckContext,
     ) -> Option<Violation> {
-        let referencing_pack =
-            &reference.referencing_pack(&configuration.pack_set);
+        let referencing_pack = context.referencing_pack(reference);
         let relative_defining_file = &reference.relative_defining_file;
 
         let referencing_pack_name = &referencing_pack.name;
-        let defining_pack = &reference.defining_pack(&configuration.pack_set);
-        if defining_pack.is_none() {
-            return None;
-        }
-        let defining_pack = defining_pack.unwrap();
+        let defining_pack = context.defining_pack(reference)?;
 
-        if defining_pack.enforce_visibility().is_false() {
+        if !defining_pack.enforce_visibility().is_enforced() {
             return None;
         }
 
@@ -50,14 +48,11 @@ impl CheckerInterface for Checker {
         }
 
         let message = format!(
-            "{}:{}:{}\nVisibility violation: `{}` belongs to `{}`, which is not visible to `{}`",
-            reference.relative_referencing_file,
-            reference.source_location.line,
-            reference.source_location.column,
-            reference.constant_name,
-            defining_pack_name,
-            referencing_pack_name,
-        );
+                "Visibility violation: `{}` belongs to `{}`, which is not visible to `{}`",
+                reference.constant_name,
+                defining_pack_name,
+                referencing_pack_name,
+            );
 
         let violation_type = String::from("visibility");
         let file = reference.relative_referencing_file.clone();
@@ -72,6 +67,9 @@ impl CheckerInterface for Checker {
         Some(Violation {
             message,
             identifier,
+            confidence: reference.confidence,
+            relative_defining_file: reference.relative_defining_file.clone(),
+            strict: false,
         })
     }
 
@@ -86,6 +84,17 @@ impl CheckerInterface for Checker {
         defining_pack.enforce_visibility().is_strict()
     }
 
+    fn is_enforced(
+        &self,
+        violation: &ViolationIdentifier,
+        configuration: &Configuration,
+    ) -> bool {
+        let defining_pack =
+            get_defining_pack(violation, &configuration.pack_set);
+
+        defining_pack.enforce_visibility().is_enforced()
+    }
+
     fn violation_type(&self) -> String {
         "visibility".to_owned()
     }
@@ -97,7 +106,7 @@ mod tests {
 
     use super::*;
     use crate::packs::{
-        pack::{CheckerSetting, Pack},
+        pack::{EnforcementSetting, Pack},
         *,
     };
 
@@ -107,7 +116,7 @@ mod tests {
 
         let defining_pack = Pack {
             name: String::from("packs/foo"),
-            enforce_visibility: Some(CheckerSetting::True),
+            enforce_visibility: Some(EnforcementSetting::On),
             ..Pack::default()
         };
         let referencing_pack = Pack {
@@ -126,6 +135,10 @@ mod tests {
                 "packs/bar/app/services/bar.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -145,7 +158,13 @@ mod tests {
             ..Configuration::default()
         };
 
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 
     #[test]
@@ -154,7 +173,7 @@ mod tests {
 
         let defining_pack = Pack {
             name: String::from("packs/foo"),
-            enforce_visibility: Some(CheckerSetting::True),
+            enforce_visibility: Some(EnforcementSetting::On),
             ..Pack::default()
         };
         let referencing_pack = Pack {
@@ -173,10 +192,17 @@ mod tests {
                 "packs/foo/app/services/foo.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let expected_violation = Violation {
-            message: String::from("packs/bar/app/services/bar.rb:3:1\nVisibility violation: `::Foo` belongs to `packs/foo`, which is not visible to `packs/bar`"),
+            message: String::from("Visibility violation: `::Foo` belongs to `packs/foo`, which is not visible to `packs/bar`"),
+            confidence: ReferenceConfidence::Exact,
+            strict: false,
+            relative_defining_file: reference.relative_defining_file.clone(),
             identifier: ViolationIdentifier {
                 violation_type: String::from("visibility"),
                 file: String::from("packs/bar/app/services/bar.rb"),
@@ -204,7 +230,12 @@ mod tests {
         };
         assert_eq!(
             expected_violation,
-            checker.check(&reference, &configuration).unwrap()
+            checker
+                .check(
+                    &reference,
+                    &CheckContext::new(&configuration, [&reference])
+                )
+                .unwrap()
         )
     }
 
@@ -218,7 +249,7 @@ mod tests {
         let defining_pack = Pack {
             name: String::from("packs/foo"),
             visible_to: Some(visible_to),
-            enforce_visibility: Some(CheckerSetting::True),
+            enforce_visibility: Some(EnforcementSetting::On),
             ..Pack::default()
         };
         let referencing_pack = Pack {
@@ -237,6 +268,10 @@ mod tests {
                 "packs/foo/app/services/foo.rb",
             )),
             source_location: SourceLocation { line: 3, column: 1 },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
+            public_defining_file: false,
+            strict_referencing_file: false,
         };
 
         let root_pack = Pack {
@@ -255,6 +290,12 @@ mod tests {
             ),
             ..Configuration::default()
         };
-        assert_eq!(None, checker.check(&reference, &configuration))
+        assert_eq!(
+            None,
+            checker.check(
+                &reference,
+                &CheckContext::new(&configuration, [&reference])
+            )
+        )
     }
 }
@@ -1,9 +1,20 @@
 use crate::packs;
-
+use crate::packs::checker::{FailOn, OutputFormat, PathDisplay, ViolationSort};
+use crate::packs::graph::{ColorBy, GraphOptions};
+use crate::packs::{
+    HeatmapFormat, HistoryFormat, OwnersReportFormat, ReferenceConfidence,
+    Warning, WebhookFormat,
+};
+
+use crate::packs::file_provider::{FileProvider, WorkingTree};
 use crate::packs::file_utils::get_absolute_path;
-use clap::{Parser, Subcommand};
+#[cfg(feature = "fixture-generator")]
+use crate::packs::fixture_generator::FixtureSize;
+use crate::packs::git;
+use clap::{CommandFactory, Parser, Subcommand};
 use clap_derive::Args;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::debug;
 
 use super::logger::install_logger;
@@ -31,9 +42,25 @@ struct Args {
     #[arg(long)]
     no_cache: bool,
 
+    /// Read from the cache but never write to it (for read-only source trees, e.g. Bazel or Nix sandboxes)
+    #[arg(long)]
+    no_cache_write: bool,
+
     /// Print to console when files begin and finish processing (to identify files that panic when processing files concurrently)
     #[arg(short, long)]
     print_files: bool,
+
+    /// Use an implicit root pack with the default settings if there is no root package.yml
+    #[arg(long)]
+    allow_no_root: bool,
+
+    /// Check the files of this commit (or branch, or tag) instead of the working tree, without checking it out. Commands that write files can't be used with it
+    #[arg(long)]
+    git_ref: Option<String>,
+
+    /// Print how long finding packs and files, reading package.yml files, and finding and checking references took, and how many references were filtered out
+    #[arg(long)]
+    timing: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -44,12 +71,81 @@ enum Command {
     #[clap(about = "Create a new pack")]
     Create { name: String },
 
+    #[clap(
+        about = "Set up packwerk.yml and a root package.yml, then record existing violations"
+    )]
+    Init {
+        /// Move the top-level `app/` directory into a new pack with this name, e.g. `packs/app`
+        #[arg(long)]
+        starter_pack: Option<String>,
+    },
+
     #[clap(about = "Look for violations in the codebase")]
     Check {
         /// Ignore recorded violations when reporting violations
         #[arg(long)]
         ignore_recorded_violations: bool,
 
+        /// Fail only on violations that are not recorded in todo files (`new`), or on any violation (`all`)
+        #[arg(long, value_enum, default_value_t = FailOn::New)]
+        fail_on: FailOn,
+
+        /// Warn about stale violations instead of failing
+        #[arg(long)]
+        warn_on_stale: bool,
+
+        /// Deprecated, use `--format json`
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+
+        /// How to print the results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// The Handlebars template `--format template` renders the JSON results with
+        #[arg(long, required_if_eq("format", "template"))]
+        template: Option<PathBuf>,
+
+        /// Write the results to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Print violation paths relative to the project root, as absolute paths, or relative to the current directory
+        #[arg(long, value_enum, default_value_t = PathDisplay::Relative)]
+        paths: PathDisplay,
+
+        /// The order to report violations in: by file, by the pack defining the constant, by constant, or files with the most violations first
+        #[arg(long, value_enum, default_value_t = ViolationSort::File)]
+        sort: ViolationSort,
+
+        /// Make violation locations OSC 8 hyperlinks, so supporting terminals open the file when clicked
+        #[arg(long)]
+        hyperlinks: bool,
+
+        /// Explain how the references at this location are resolved and checked, instead of checking
+        #[arg(long, value_name = "FILE:LINE[:COLUMN]")]
+        explain: Option<String>,
+
+        /// Only check references the parser is at least this sure of
+        #[arg(long, value_enum)]
+        min_confidence: Option<ReferenceConfidence>,
+
+        /// Also check the packs that reference constants defined in the given files
+        #[arg(long)]
+        with_dependents: bool,
+
+        /// List violations by how often their files changed recently, to pick which ones to fix first
+        #[arg(long)]
+        churn_weighted: bool,
+
+        /// How many months of git history `--churn-weighted` looks at
+        #[arg(long, default_value_t = 6)]
+        churn_months: u32,
+
+        /// Fail when there are warnings, like uses of deprecated options
+        #[arg(long)]
+        deny_warnings: bool,
+
         files: Vec<String>,
     },
 
@@ -65,11 +161,21 @@ enum Command {
     #[clap(
         about = "Update package_todo.yml files with the current violations"
     )]
-    Update,
+    Update {
+        /// Add the dependencies that dependency violations are missing, recording only those that would create a cycle
+        #[arg(long)]
+        add_dependencies: bool,
+    },
 
     #[clap(about = "Look for validation errors in the codebase")]
     Validate,
 
+    #[clap(about = "Manage recorded violations")]
+    Todo {
+        #[command(subcommand)]
+        command: TodoCommand,
+    },
+
     #[clap(about = "Add a dependency from one pack to another")]
     AddDependency {
         /// The pack that depends on another pack
@@ -79,6 +185,86 @@ enum Command {
         to: String,
     },
 
+    #[clap(
+        about = "Suggest a `dependencies:` list for a pack based on what it references"
+    )]
+    SuggestDependencies {
+        /// The pack to suggest dependencies for
+        pack: String,
+    },
+
+    #[clap(about = "List every reference from one pack to another")]
+    Trace {
+        /// The pack making the references
+        from_pack: String,
+        /// The pack defining the referenced constants
+        to_pack: String,
+    },
+
+    #[clap(
+        about = "Print a Markdown checklist of the work to remove the dependency of one pack on another"
+    )]
+    PlanRemoval {
+        /// The pack making the references
+        from_pack: String,
+        /// The pack defining the referenced constants
+        to_pack: String,
+    },
+
+    #[clap(
+        about = "Write a `# TODO(packs):` comment above each line with a violation"
+    )]
+    Annotate {
+        /// Only remove the comments written by a previous run
+        #[arg(long)]
+        remove: bool,
+    },
+
+    #[clap(
+        about = "Move the files defining the constants in a namespace into a new pack, with the dependencies they need"
+    )]
+    Extract {
+        /// The namespace to extract, e.g. `Billing`
+        namespace: String,
+
+        /// The name of the new pack, e.g. `packs/billing`
+        new_pack: String,
+
+        /// Only print the files that would move and the dependencies the new pack needs
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    #[clap(about = "List constants that are never referenced")]
+    DeadCode {
+        /// Only list constants in public folders that no other pack references
+        #[arg(long)]
+        public_only: bool,
+    },
+
+    #[clap(
+        about = "List the methods other packs call on each public constant"
+    )]
+    ApiUsage {
+        /// Only list the public constants of this pack
+        pack: Option<String>,
+    },
+
+    #[clap(
+        about = "Compare the load paths in `load_paths_dump` to the ones inferred from packs"
+    )]
+    VerifyLoadPaths,
+
+    #[clap(
+        about = "Run the check twice, on different thread counts and file orders, and make sure the results are identical"
+    )]
+    VerifyDeterminism,
+
+    #[clap(
+        about = "Check that each autoloaded file defines the constant its path implies, and nothing outside of it"
+    )]
+    CheckFilePlacement,
+
     #[clap(
         about = "Check for dependencies that when removed produce no violations."
     )]
@@ -87,9 +273,101 @@ enum Command {
         auto_correct: bool,
     },
 
+    #[clap(
+        about = "Compare the violations found by packs with those found by Ruby packwerk"
+    )]
+    ParityCheck {
+        /// Saved output of `packwerk check` (text or JSON), instead of running `bin/packwerk check`
+        #[arg(long)]
+        packwerk_output: Option<PathBuf>,
+    },
+
+    #[clap(
+        about = "Show the size of each pack and whether it is within the budgets in packwerk.yml"
+    )]
+    Metrics,
+
+    #[clap(
+        about = "Summarize violations for scheduled runs, with what changed since the previous report, optionally posting it to a webhook"
+    )]
+    Report {
+        /// Post the summary to this URL instead of printing it
+        #[arg(long)]
+        webhook: Option<String>,
+
+        /// Post the summary as it is, or as a Slack message
+        #[arg(long, value_enum, default_value_t = WebhookFormat::Json)]
+        format: WebhookFormat,
+
+        /// Where the violations are kept between reports, by default in the cache directory
+        #[arg(long)]
+        state_file: Option<PathBuf>,
+
+        /// How many of the packs with the most violations to list
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
+
+    #[clap(
+        about = "Count the violations recorded in todo files at the end of each month, from git history, e.g. for burn-down charts"
+    )]
+    History {
+        /// How many months to count, including this one
+        #[arg(long, default_value_t = 12)]
+        months: u32,
+
+        /// Count from this month (YYYY-MM) on, instead of the last `--months` months
+        #[arg(long, conflicts_with = "months")]
+        since: Option<String>,
+
+        /// Print the counts as CSV rows, or as JSON grouped by month
+        #[arg(long, value_enum, default_value_t = HistoryFormat::Csv)]
+        format: HistoryFormat,
+    },
+
+    #[clap(
+        about = "Count the violations recorded in todo files by the team owning each pack, with the change over the last week"
+    )]
+    OwnersReport {
+        /// Print a Markdown table to post in a team channel, or JSON
+        #[arg(long, value_enum, default_value_t = OwnersReportFormat::Markdown)]
+        format: OwnersReportFormat,
+    },
+
+    #[clap(
+        about = "Generate configuration for other tools from package.yml files"
+    )]
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+
+    #[clap(
+        about = "Generate package.yml files from other tools' configuration"
+    )]
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+
+    #[clap(
+        about = "Write the public module re-exporting the constants listed under `facade` in a pack's package.yml"
+    )]
+    GenerateFacade {
+        /// The pack to write the facade of
+        pack: String,
+    },
+
     #[clap(about = "Lint package.yml files")]
     LintPackageYmlFiles,
 
+    #[clap(about = "Look for todo files that were edited by hand")]
+    LintTodos {
+        /// Rewrite the todo files without these problems
+        #[arg(long)]
+        fix: bool,
+    },
+
     #[clap(
         about = "Expose monkey patches of the Ruby stdlib, gems your app uses, and your application itself"
     )]
@@ -100,10 +378,41 @@ enum Command {
     )]
     DeleteCache,
 
+    #[clap(
+        about = "Parse the files changed since the last checkout into the cache, in the background, e.g. from a post-checkout git hook"
+    )]
+    WarmCache {
+        /// Parse every file, in the foreground, with a progress bar
+        #[arg(long)]
+        all: bool,
+
+        /// The commit to look for changed files since
+        #[arg(long, default_value = "HEAD@{1}", conflicts_with = "all")]
+        since: String,
+
+        /// Parse the changed files in this process instead of in the background
+        #[arg(long, hide = true)]
+        foreground: bool,
+    },
+
+    #[clap(
+        about = "Serve violations and packs as JSON over HTTP, for editors, dashboards and scripts"
+    )]
+    Server {
+        /// The address to listen on, e.g. `127.0.0.1:0` for any free port
+        #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:7766")]
+        http: String,
+    },
+
     #[clap(
         about = "List packs based on configuration in packwerk.yml (for debugging purposes)"
     )]
-    ListPacks,
+    ListPacks {
+        /// Print each pack's settings, owner, file count and todo status as
+        /// JSON, for scripts
+        #[arg(long)]
+        json: bool,
+    },
 
     #[clap(
         about = "List analyzed files based on configuration in packwerk.yml (for debugging purposes)"
@@ -114,6 +423,145 @@ enum Command {
         about = "List the constants that packs sees and where it sees them (for debugging purposes)"
     )]
     ListDefinitions(ListDefinitionsArgs),
+
+    #[cfg(feature = "fixture-generator")]
+    #[clap(
+        about = "Generate a synthetic app of a given size, to share reproducible performance issues"
+    )]
+    GenerateFixture {
+        /// Where to write the app, which must not exist yet or be empty
+        path: PathBuf,
+
+        /// How many packs to generate
+        #[arg(long, default_value_t = 10)]
+        packs: usize,
+
+        /// How many files to generate in each pack
+        #[arg(long, default_value_t = 10)]
+        files_per_pack: usize,
+
+        /// How many dependency violations `check` should find
+        #[arg(long, default_value_t = 0)]
+        violations: usize,
+    },
+
+    #[clap(
+        about = "Print the graph of references between packs, in the DOT format"
+    )]
+    Graph {
+        /// Only show references that are part of a cycle
+        #[arg(long)]
+        cycles_only: bool,
+
+        /// Only show edges with at least this many references
+        #[arg(long, default_value_t = 1)]
+        min_references: usize,
+
+        /// Only show packs connected to this pack
+        #[arg(long)]
+        focus: Option<String>,
+
+        /// How many edges away from the focus pack to go
+        #[arg(long, requires = "focus")]
+        max_depth: Option<usize>,
+
+        /// Color the packs by their architecture layer or owner
+        #[arg(long, value_enum)]
+        color_by: Option<ColorBy>,
+    },
+
+    #[clap(
+        about = "List packs in dependency order, starting with the packs that depend on nothing"
+    )]
+    Order,
+
+    #[clap(
+        about = "List the spec files of the packs containing the given files and of the packs that depend on them, for rspec"
+    )]
+    ImpactedTests {
+        /// The files that changed, e.g. from `git diff --name-only`
+        files: Vec<String>,
+    },
+
+    #[clap(
+        about = "Print the references and definitions the parser extracts from a file (for debugging purposes)"
+    )]
+    Parse {
+        /// The file to parse, relative to the project root
+        file: String,
+
+        /// Print JSON, e.g. to attach to a bug report or feed to another tool
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[clap(
+        about = "Print a completion script for your shell, including the names of your packs"
+    )]
+    Completions {
+        /// The shell to print the completion script for
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TodoCommand {
+    #[clap(
+        about = "Move every package_todo.yml into a single packwerk_todo.yml"
+    )]
+    Consolidate,
+
+    #[clap(
+        about = "Move the violations in packwerk_todo.yml into each pack's package_todo.yml"
+    )]
+    Split,
+
+    #[clap(
+        about = "List recorded violations by when they were first recorded, oldest first (needs `todo_format: v2`)"
+    )]
+    Oldest {
+        /// Only list this many violations
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    #[clap(
+        about = "Rank the constants, packs and files with the most recorded violations"
+    )]
+    TopOffenders {
+        /// How many to list in each ranking
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ExportCommand {
+    #[clap(
+        about = "Write a Sorbet __package.rb next to each package.yml, importing its dependencies and exporting its public constants"
+    )]
+    SorbetPackages,
+
+    #[clap(
+        about = "Print the number of references from each pack to each pack, as a matrix for heatmaps"
+    )]
+    Heatmap {
+        /// Print CSV, with a row per referencing pack and a column per defining pack, or JSON
+        #[arg(long, value_enum, default_value_t = HeatmapFormat::Csv)]
+        format: HeatmapFormat,
+
+        /// Divide the counts by the number of files in the referencing pack
+        #[arg(long)]
+        normalize: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ImportCommand {
+    #[clap(
+        about = "Write a package.yml next to each Sorbet __package.rb, with its imports as dependencies"
+    )]
+    SorbetPackages,
 }
 
 #[derive(Debug, Args)]
@@ -142,6 +590,71 @@ impl Args {
     }
 }
 
+impl Command {
+    // Whether the command writes to the project, e.g. to todo files,
+    // package.yml files or the cache. `--git-ref` reads the files of a
+    // commit from the git object database, so there is nowhere to write
+    // them. Every command is listed, so a new one has to be decided on.
+    fn writes_to_project(&self) -> bool {
+        match self {
+            Command::Create { .. }
+            | Command::Init { .. }
+            | Command::Update { .. }
+            | Command::AddDependency { .. }
+            | Command::Annotate { .. }
+            | Command::Import { .. }
+            | Command::GenerateFacade { .. }
+            | Command::Server { .. }
+            | Command::DeleteCache
+            | Command::WarmCache { .. } => true,
+            Command::Todo { command } => match command {
+                TodoCommand::Consolidate | TodoCommand::Split => true,
+                TodoCommand::Oldest { .. }
+                | TodoCommand::TopOffenders { .. } => false,
+            },
+            Command::Export { command } => match command {
+                ExportCommand::SorbetPackages => true,
+                ExportCommand::Heatmap { .. } => false,
+            },
+            Command::Extract { dry_run, .. } => !dry_run,
+            Command::CheckUnnecessaryDependencies { auto_correct } => {
+                *auto_correct
+            }
+            Command::LintTodos { fix } => *fix,
+            // Without a state file, its state is kept in the cache directory
+            Command::Report { state_file, .. } => state_file.is_none(),
+            Command::Greet
+            | Command::Check { .. }
+            | Command::CheckContents { .. }
+            | Command::Validate
+            | Command::SuggestDependencies { .. }
+            | Command::Trace { .. }
+            | Command::PlanRemoval { .. }
+            | Command::DeadCode { .. }
+            | Command::ApiUsage { .. }
+            | Command::VerifyLoadPaths
+            | Command::VerifyDeterminism
+            | Command::CheckFilePlacement
+            | Command::ParityCheck { .. }
+            | Command::Metrics
+            | Command::History { .. }
+            | Command::OwnersReport { .. }
+            | Command::LintPackageYmlFiles
+            | Command::ExposeMonkeyPatches(_)
+            | Command::ListPacks { .. }
+            | Command::ListIncludedFiles
+            | Command::ListDefinitions(_)
+            | Command::Graph { .. }
+            | Command::Order
+            | Command::ImpactedTests { .. }
+            | Command::Parse { .. }
+            | Command::Completions { .. } => false,
+            #[cfg(feature = "fixture-generator")]
+            Command::GenerateFixture { .. } => false,
+        }
+    }
+}
+
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
     let absolute_root = args
@@ -150,12 +663,81 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     install_logger(args.debug);
 
-    let mut configuration = packs::configuration::get(&absolute_root);
+    if args.git_ref.is_some() && args.command.writes_to_project() {
+        return Err(
+            "`--git-ref` only works with commands that don't write to the project, since the files of the commit are read from git"
+                .into(),
+        );
+    }
+
+    // `init` creates the files that building a configuration depends on,
+    // so it needs to run before we build one.
+    if let Command::Init { starter_pack } = &args.command {
+        packs::init(&absolute_root, starter_pack.as_deref())?;
+    }
+
+    // Importing creates the package.yml files a configuration is built from
+    if let Command::Import { command } = &args.command {
+        return match command {
+            ImportCommand::SorbetPackages => {
+                packs::import_sorbet_packages(&absolute_root)
+            }
+        };
+    }
+
+    // The generated app doesn't have to be in the project root, which may
+    // not have a configuration at all
+    #[cfg(feature = "fixture-generator")]
+    if let Command::GenerateFixture {
+        path,
+        packs,
+        files_per_pack,
+        violations,
+    } = &args.command
+    {
+        return packs::generate_fixture(
+            path,
+            FixtureSize {
+                packs: *packs,
+                files_per_pack: *files_per_pack,
+                violations: *violations,
+            },
+        );
+    }
+
+    let file_provider: Arc<dyn FileProvider> = match &args.git_ref {
+        Some(git_ref) => {
+            Arc::new(git::GitObjects::new(&absolute_root, git_ref)?)
+        }
+        None => Arc::new(WorkingTree),
+    };
+
+    let mut configuration = packs::configuration::load(
+        &absolute_root,
+        args.allow_no_root,
+        file_provider,
+    )?;
+
+    // The cache is keyed by the files in the working tree
+    if args.git_ref.is_some() {
+        debug!("Cache is disabled for a commit");
+        configuration.cache_enabled = false;
+    }
 
     if args.print_files {
         configuration.print_files = true;
     }
 
+    if args.timing {
+        configuration.timing = true;
+        let walk_timings = configuration.walk_timings;
+        eprintln!("Timing: walking the directory took {:?}", walk_timings.walk);
+        eprintln!(
+            "Timing: reading {} package.yml file(s) took {:?}",
+            walk_timings.pack_count, walk_timings.read_packs
+        );
+    }
+
     if args.experimental_parser {
         debug!("Using experimental parser");
         configuration.experimental_parser = true;
@@ -166,25 +748,95 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         configuration.cache_enabled = false;
     }
 
+    if args.no_cache_write {
+        debug!("Cache writes are disabled");
+        configuration.cache_read_only = true;
+    }
+
     match args.command {
         Command::Greet => {
             packs::greet();
             Ok(())
         }
-        Command::ListPacks => {
-            packs::list(configuration);
-            Ok(())
-        }
+        Command::ListPacks { json } => packs::list(&configuration, json),
         Command::AddDependency { from, to } => {
             packs::add_dependency(&configuration, from, to)
         }
         Command::ListIncludedFiles => packs::list_included_files(configuration),
+        Command::SuggestDependencies { pack } => {
+            packs::suggest_dependencies(&configuration, &pack)
+        }
+        Command::Trace { from_pack, to_pack } => {
+            packs::trace(&configuration, &from_pack, &to_pack)
+        }
+        Command::PlanRemoval { from_pack, to_pack } => {
+            packs::plan_removal(&configuration, &from_pack, &to_pack)
+        }
+        Command::Annotate { remove } => packs::annotate(&configuration, remove),
+        Command::Extract {
+            namespace,
+            new_pack,
+            dry_run,
+        } => packs::extract(&configuration, &namespace, &new_pack, dry_run),
+        Command::DeadCode { public_only } => {
+            packs::dead_code(&configuration, public_only)
+        }
+        Command::ApiUsage { pack } => {
+            packs::api_usage(&configuration, pack.as_deref())
+        }
+        Command::VerifyLoadPaths => packs::verify_load_paths(&configuration),
+        Command::VerifyDeterminism => packs::verify_determinism(&configuration),
+        Command::CheckFilePlacement => {
+            packs::check_file_placement(&configuration)
+        }
         Command::Check {
             ignore_recorded_violations,
+            fail_on,
+            warn_on_stale,
+            json,
+            format,
+            template,
+            output,
+            paths,
+            sort,
+            hyperlinks,
+            explain,
+            min_confidence,
+            with_dependents,
+            churn_weighted,
+            churn_months,
+            deny_warnings,
             files,
         } => {
+            configuration.min_confidence = min_confidence;
+            if let Some(location) = explain {
+                return packs::explain(&configuration, &location);
+            }
+            if churn_weighted {
+                return packs::churn_report(&configuration, churn_months, json);
+            }
             configuration.ignore_recorded_violations =
                 ignore_recorded_violations;
+            configuration.fail_on = fail_on;
+            configuration.warn_on_stale = warn_on_stale;
+            if json {
+                configuration.warnings.push(Warning::deprecation(
+                    "`--json` will be removed in a future version, use `--format json`",
+                ));
+            }
+            configuration.deny_warnings = deny_warnings;
+            configuration.output_format =
+                if json { OutputFormat::Json } else { format };
+            configuration.template_path = template;
+            configuration.output_path = output;
+            configuration.path_display = paths;
+            configuration.sort = sort;
+            configuration.hyperlinks = hyperlinks;
+            let files = if with_dependents {
+                packs::files_affected_by(&configuration, files)
+            } else {
+                files
+            };
             packs::check(&configuration, files)
         }
         Command::CheckContents {
@@ -198,23 +850,121 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             configuration.stdin_file_path = Some(absolute_path);
             packs::check(&configuration, vec![file])
         }
-        Command::Update => packs::update(&configuration),
+        Command::Init { .. } => packs::update(&configuration, false),
+        Command::Update { add_dependencies } => {
+            packs::update(&configuration, add_dependencies)
+        }
         Command::Validate => {
             packs::validate(&configuration)
             // Err("💡 Please use `packs check` to detect dependency cycles and run other configuration validations".into())
         }
+        Command::Todo { command } => match command {
+            TodoCommand::Consolidate => {
+                packs::consolidate_todos(&configuration);
+                Ok(())
+            }
+            TodoCommand::Split => {
+                packs::split_todos(&configuration);
+                Ok(())
+            }
+            TodoCommand::Oldest { limit } => {
+                packs::list_oldest_todos(&configuration, limit)
+            }
+            TodoCommand::TopOffenders { limit } => {
+                packs::list_top_offender_todos(&configuration, limit)
+            }
+        },
         Command::CheckUnnecessaryDependencies { auto_correct } => {
             packs::check_unnecessary_dependencies(&configuration, auto_correct)
         }
+        Command::ParityCheck { packwerk_output } => {
+            packs::parity_check(&configuration, packwerk_output)
+        }
+        Command::Metrics => {
+            packs::metrics(&configuration);
+            Ok(())
+        }
+        Command::Report {
+            webhook,
+            format,
+            state_file,
+            top,
+        } => packs::health_report(
+            &configuration,
+            webhook.as_deref(),
+            format,
+            state_file,
+            top,
+        ),
+        Command::History {
+            months,
+            since,
+            format,
+        } => packs::history(&configuration, months, since.as_deref(), format),
+        Command::OwnersReport { format } => {
+            packs::owners_report(&configuration, format)
+        }
+        Command::GenerateFacade { pack } => {
+            packs::generate_facade(&configuration, &pack)
+        }
+        Command::Export { command } => match command {
+            ExportCommand::SorbetPackages => {
+                packs::export_sorbet_packages(&configuration)
+            }
+            ExportCommand::Heatmap { format, normalize } => {
+                packs::export_heatmap(&configuration, format, normalize)
+            }
+        },
         Command::DeleteCache => {
             packs::delete_cache(configuration);
             Ok(())
         }
+        Command::WarmCache {
+            all,
+            since,
+            foreground,
+        } => packs::warm_cache(&configuration, all, &since, foreground),
+        Command::Server { http } => packs::serve(&configuration, &http),
         Command::ListDefinitions(args) => {
             let ambiguous = args.ambiguous;
             packs::list_definitions(&configuration, ambiguous);
             Ok(())
         }
+        Command::Import { .. } => {
+            unreachable!("import returns before the configuration is built")
+        }
+        #[cfg(feature = "fixture-generator")]
+        Command::GenerateFixture { .. } => {
+            unreachable!(
+                "generate-fixture returns before the configuration is built"
+            )
+        }
+        Command::Graph {
+            cycles_only,
+            min_references,
+            focus,
+            max_depth,
+            color_by,
+        } => packs::graph(
+            &configuration,
+            &GraphOptions {
+                cycles_only,
+                min_references,
+                focus,
+                max_depth,
+                color_by,
+            },
+        ),
+        Command::Order => packs::order(&configuration),
+        Command::ImpactedTests { files } => {
+            packs::impacted_tests(&configuration, files)
+        }
+        Command::Parse { file, json } => {
+            packs::parse(&configuration, &file, json)
+        }
+        Command::Completions { shell } => {
+            packs::completions(&configuration, Args::command(), shell)
+        }
         Command::ExposeMonkeyPatches(args) => {
             packs::expose_monkey_patches(
                 &configuration,
@@ -227,6 +977,7 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
             packs::lint_package_yml_files(&configuration);
             Ok(())
         }
+        Command::LintTodos { fix } => packs::lint_todos(&configuration, fix),
         Command::Create { name } => {
             packs::create(&configuration, name);
             Ok(())
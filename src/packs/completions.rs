@@ -0,0 +1,55 @@
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+use clap::builder::PossibleValuesParser;
+use clap_complete::{generate, Shell};
+
+use super::Configuration;
+
+// Arguments that take a pack name, as (subcommand, argument)
+const PACK_NAME_ARGUMENTS: [(&str, &str); 5] = [
+    ("add-dependency", "from"),
+    ("add-dependency", "to"),
+    ("suggest-dependencies", "pack"),
+    ("trace", "from_pack"),
+    ("trace", "to_pack"),
+];
+
+// Prints a completion script for the shell. Pack names are read from the
+// packs in `package_paths` now, so the script needs regenerating when packs
+// are added or removed.
+pub(crate) fn completions(
+    configuration: &Configuration,
+    mut command: clap::Command,
+    shell: Shell,
+) -> Result<(), Box<dyn Error>> {
+    let pack_names: Vec<String> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| pack.name.clone())
+        .collect();
+
+    for (subcommand, argument) in PACK_NAME_ARGUMENTS {
+        let pack_names = pack_names.clone();
+        command = command.mut_subcommand(subcommand, |subcommand| {
+            subcommand.mut_arg(argument, |argument| {
+                argument.value_parser(PossibleValuesParser::new(pack_names))
+            })
+        });
+    }
+
+    // The binary is installed as both `pks` and `packs`
+    let bin_name = std::env::args()
+        .next()
+        .and_then(|arg| {
+            Path::new(&arg)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| String::from("pks"));
+
+    generate(shell, &mut command, bin_name, &mut io::stdout());
+    Ok(())
+}
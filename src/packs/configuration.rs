@@ -1,30 +1,51 @@
 use super::caching::cache::Cache;
-use super::caching::create_cache_dir_idempotently;
 use super::caching::noop_cache::NoopCache;
-use super::caching::per_file_cache::PerFileCache;
+use super::caching::per_file_cache::{PerFileCache, BINCODE_CACHE_VERSION};
+use super::caching::{create_cache_dir_idempotently, is_read_only_error};
 use super::checker::architecture::Layers;
+use super::checker::{FailOn, OutputFormat, PathDisplay, ViolationSort};
+use super::file_provider::{FileProvider, WorkingTree};
 use super::file_utils::user_inputted_paths_to_absolute_filepaths;
-use super::raw_configuration::RawConfiguration;
+use super::load_paths;
+use super::pack::{InheritedEnforcement, Pack};
+use super::package_todo;
+use super::parsing::prescan::SkippedFiles;
+use super::raw_configuration::{
+    CacheFormat, DefinitionScope, PackBudgets, RawConfiguration,
+    ReferenceFilterSettings, ReopenedConstantsSettings, TodoFormat,
+    TodoStorage,
+};
 use super::PackSet;
+use super::ReferenceConfidence;
+use super::Warning;
 
 use crate::packs::raw_configuration;
-use crate::packs::walk_directory::WalkDirectoryResult;
+use crate::packs::walk_directory::{WalkDirectoryResult, WalkTimings};
 
 use crate::packs::walk_directory;
 
 use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
 use std::{
     collections::HashSet,
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tracing::debug;
 use walk_directory::walk_directory;
 
+#[derive(Clone)]
 pub struct Configuration {
     pub included_files: HashSet<PathBuf>,
     pub absolute_root: PathBuf,
+    // Where the files of the project are read from
+    pub file_provider: Arc<dyn FileProvider>,
     pub cache_enabled: bool,
     pub cache_directory: PathBuf,
+    pub cache_format: CacheFormat,
+    // Read from the cache, but never write to it
+    pub cache_read_only: bool,
     pub pack_set: PackSet,
     pub layers: Layers,
     pub experimental_parser: bool,
@@ -35,8 +56,52 @@ pub struct Configuration {
     // and configure logging in one place. As the complexity of how/why we want to see different logs
     // grows, we can refactor this.
     pub print_files: bool,
+    pub timing: bool,
+    // How long finding files and packs took, for `timing`
+    pub walk_timings: WalkTimings,
     pub packs_first_mode: bool,
     pub ignore_recorded_violations: bool,
+    pub fail_on: FailOn,
+    pub warn_on_stale: bool,
+    pub output_format: OutputFormat,
+    // The Handlebars template for `OutputFormat::Template`
+    pub template_path: Option<PathBuf>,
+    pub output_path: Option<PathBuf>,
+    pub path_display: PathDisplay,
+    pub sort: ViolationSort,
+    // Whether violation locations are OSC 8 terminal hyperlinks to the file
+    pub hyperlinks: bool,
+    // References the parser is less sure of are not checked
+    pub min_confidence: Option<ReferenceConfidence>,
+    pub enforce_from_root: bool,
+    pub pack_budgets: PackBudgets,
+    pub todo_storage: TodoStorage,
+    pub todo_format: TodoFormat,
+    pub reference_filter: ReferenceFilterSettings,
+    pub reopened_constants: Option<ReopenedConstantsSettings>,
+    pub definition_scope: DefinitionScope,
+    pub follow_constant_aliases: bool,
+    pub parse_eval_strings: bool,
+    pub config_reference_keys: Vec<String>,
+    pub isolate_parse_crashes: bool,
+    // Files bigger than this many bytes are skipped instead of parsed
+    pub max_file_size: Option<u64>,
+    pub max_parse_duration: Option<Duration>,
+    pub root_namespace: Option<String>,
+    // The pack each namespace's constants belong to, whatever file defines
+    // them
+    pub namespace_pack_mapping: HashMap<String, String>,
+    pub load_paths_dump: Option<PathBuf>,
+    // The absolute paths read from `load_paths_dump`
+    pub load_paths: Option<HashSet<PathBuf>>,
+    pub violation_messages: HashMap<String, String>,
+    pub violation_code_urls: HashMap<String, String>,
+    // Reported by `check` apart from violations
+    pub warnings: Vec<Warning>,
+    // Whether warnings fail the check
+    pub deny_warnings: bool,
+    // The files that weren't parsed since they can't reference a constant
+    pub skipped_files: SkippedFiles,
 }
 
 impl Configuration {
@@ -47,10 +112,21 @@ impl Configuration {
         if input_files.is_empty() {
             self.included_files.clone()
         } else {
-            let absolute_filepaths = user_inputted_paths_to_absolute_filepaths(
-                &self.absolute_root,
-                input_files,
-            );
+            // A path through a symlink to an included file is that file
+            let absolute_filepaths: HashSet<PathBuf> =
+                user_inputted_paths_to_absolute_filepaths(
+                    &self.absolute_root,
+                    input_files,
+                )
+                .into_iter()
+                .map(|path| {
+                    if self.included_files.contains(&path) {
+                        path
+                    } else {
+                        dunce::canonicalize(&path).unwrap_or(path)
+                    }
+                })
+                .collect();
             self.included_files
                 .intersection(&absolute_filepaths)
                 .cloned()
@@ -58,6 +134,80 @@ impl Configuration {
         }
     }
 
+    // The configuration with the packs, todo files and included files as
+    // they are on disk now, and the settings of this run
+    pub(crate) fn reload(&self) -> Result<Configuration, Box<dyn Error>> {
+        let reloaded =
+            load(&self.absolute_root, true, self.file_provider.clone())?;
+        Ok(Configuration {
+            pack_set: reloaded.pack_set,
+            included_files: reloaded.included_files,
+            ..self.clone()
+        })
+    }
+
+    pub(crate) fn included_files_in_pack(
+        &self,
+        pack_name: &str,
+    ) -> HashSet<PathBuf> {
+        self.included_files
+            .iter()
+            .filter(|file| {
+                self.pack_set
+                    .for_file(file)
+                    .is_some_and(|pack| pack.name == pack_name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    // The pack `namespace_pack_mapping` gives the constant to, going by the
+    // most nested of its namespaces that is mapped, e.g. `packs/billing` for
+    // `::Billing::Invoice` with `Billing: packs/billing`
+    pub(crate) fn mapped_pack(&self, constant_name: &str) -> Option<&str> {
+        let constant_name = constant_name.trim_start_matches("::");
+        self.namespace_pack_mapping
+            .iter()
+            .map(|(namespace, pack_name)| {
+                (namespace.trim_start_matches("::"), pack_name)
+            })
+            .filter(|(namespace, pack_name)| {
+                constant_name.strip_prefix(namespace).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with("::")
+                }) && self.pack_set.for_pack(pack_name).is_ok()
+            })
+            .max_by_key(|(namespace, _)| namespace.len())
+            .map(|(_, pack_name)| pack_name.as_str())
+    }
+
+    // Each pack `namespace_pack_mapping` maps to has to exist
+    pub(crate) fn namespace_pack_mapping_errors(&self) -> Vec<String> {
+        let mut errors: Vec<String> = self
+            .namespace_pack_mapping
+            .iter()
+            .filter(|(_, pack_name)| self.pack_set.for_pack(pack_name).is_err())
+            .map(|(namespace, pack_name)| {
+                format!(
+                    "`namespace_pack_mapping` in packwerk.yml maps `{}` to `{}`, which is not a pack",
+                    namespace, pack_name
+                )
+            })
+            .collect();
+        errors.sort();
+        errors
+    }
+
+    // Whether caches, e.g. the constant resolver's, are read
+    pub(crate) fn reads_cache(&self) -> bool {
+        self.cache_enabled
+    }
+
+    // Whether caches are written, which `--no-cache-write` turns off while
+    // still reading them
+    pub(crate) fn writes_cache(&self) -> bool {
+        self.cache_enabled && !self.cache_read_only
+    }
+
     pub(crate) fn get_cache(&self) -> Box<dyn Cache + Send + Sync> {
         if self.cache_enabled {
             let cache_dir = if self.experimental_parser {
@@ -66,41 +216,172 @@ impl Configuration {
                 self.cache_directory.join("zeitwerk")
             };
 
-            create_cache_dir_idempotently(&cache_dir);
+            // Entries in different formats can't be read by one another,
+            // so each format, and each version of bincode entries, gets its
+            // own directory
+            let cache_dir = match self.cache_format {
+                CacheFormat::Json => cache_dir,
+                CacheFormat::Bincode => cache_dir
+                    .join(format!("bincode-v{}", BINCODE_CACHE_VERSION)),
+            };
 
-            Box::new(PerFileCache { cache_dir })
+            let mut read_only = self.cache_read_only;
+            if !read_only {
+                match create_cache_dir_idempotently(&cache_dir) {
+                    Ok(()) => {}
+                    Err(e) if is_read_only_error(&e) => {
+                        debug!(
+                            "Cache directory {:?} is not writable, using the cache read-only",
+                            cache_dir
+                        );
+                        read_only = true;
+                    }
+                    Err(e) => {
+                        panic!("Failed to create cache directory: {}", e)
+                    }
+                }
+            }
+
+            Box::new(PerFileCache {
+                cache_dir,
+                cache_format: self.cache_format,
+                read_only,
+            })
         } else {
             Box::new(NoopCache {})
         }
     }
 }
 
-pub(crate) fn get(absolute_root: &Path) -> Configuration {
+pub(crate) fn get(
+    absolute_root: &Path,
+) -> Result<Configuration, Box<dyn Error>> {
+    load(absolute_root, false, Arc::new(WorkingTree))
+}
+
+// Builds the configuration from the files `file_provider` reads. Without a
+// root `package.yml` this fails with how to create one, unless
+// `allow_no_root` is set, in which case the root pack is an implicit one
+// with the default settings.
+pub(crate) fn load(
+    absolute_root: &Path,
+    allow_no_root: bool,
+    file_provider: Arc<dyn FileProvider>,
+) -> Result<Configuration, Box<dyn Error>> {
     debug!("Beginning to build configuration");
 
-    let raw_config = raw_configuration::get(absolute_root);
-    let walk_directory_result =
-        walk_directory(absolute_root.to_path_buf(), &raw_config);
+    let raw_config =
+        raw_configuration::get(absolute_root, file_provider.as_ref())?;
+    let mut walk_directory_result = walk_directory(
+        absolute_root.to_path_buf(),
+        &raw_config,
+        file_provider.as_ref(),
+    );
+
+    let has_root_pack = walk_directory_result
+        .included_packs
+        .iter()
+        .any(|pack| pack.name == ".");
+    if !has_root_pack {
+        if !allow_no_root {
+            return Err(format!(
+                "No root pack found: there is no package.yml in {}. The root pack owns every file outside of other packs, so packs needs one. \
+                 Run `pks init` to create it (along with a packwerk.yml, if there isn't one), or pass `--allow-no-root` to use an implicit root pack with the default settings.",
+                absolute_root.display()
+            )
+            .into());
+        }
+        walk_directory_result
+            .included_packs
+            .insert(Pack::from_contents(
+                &absolute_root.join("package.yml"),
+                absolute_root,
+                "{}",
+                package_todo::read(
+                    file_provider.as_ref(),
+                    &absolute_root.join("package_todo.yml"),
+                ),
+            ));
+    }
 
-    from_raw(absolute_root, raw_config, walk_directory_result)
+    let mut configuration = from_raw(
+        absolute_root,
+        raw_config,
+        walk_directory_result,
+        file_provider,
+    );
+    if let Some(load_paths_dump) = &configuration.load_paths_dump {
+        configuration.load_paths = Some(load_paths::read_dump(
+            &configuration.absolute_root,
+            load_paths_dump,
+        )?);
+    }
+
+    Ok(configuration)
 }
 
 pub(crate) fn from_raw(
     absolute_root: &Path,
     raw_config: RawConfiguration,
     walk_directory_result: WalkDirectoryResult,
+    file_provider: Arc<dyn FileProvider>,
 ) -> Configuration {
     let WalkDirectoryResult {
         included_files,
-        included_packs,
+        mut included_packs,
         owning_package_yml_for_file,
+        timings: walk_timings,
     } = walk_directory_result;
 
     let absolute_root = absolute_root.to_path_buf();
+    let todo_storage = raw_config.todo_storage;
+    let todo_format = raw_config.todo_format;
+    if todo_storage == TodoStorage::Consolidated {
+        let mut package_todos_by_pack_name = package_todo::read_consolidated(
+            file_provider.as_ref(),
+            &absolute_root,
+        );
+        included_packs = included_packs
+            .into_iter()
+            .map(|pack| Pack {
+                package_todo: package_todos_by_pack_name
+                    .remove(&pack.name)
+                    .unwrap_or_default(),
+                ..pack
+            })
+            .collect();
+    }
+    if raw_config.inherit_enforcement_from_root {
+        let inherited_enforcement = included_packs
+            .iter()
+            .find(|pack| pack.name == ".")
+            .map(InheritedEnforcement::from_pack)
+            .unwrap_or_default();
+        included_packs = included_packs
+            .into_iter()
+            .map(|pack| Pack {
+                inherited_enforcement: inherited_enforcement.clone(),
+                ..pack
+            })
+            .collect();
+    }
+    if !raw_config.sub_app_dirs.is_empty() {
+        included_packs = qualify_sub_app_pack_names(
+            included_packs,
+            &raw_config.sub_app_dirs,
+        );
+    }
     let pack_set = PackSet::build(included_packs, owning_package_yml_for_file);
 
-    let cache_directory = absolute_root.join(raw_config.cache_directory);
+    // PACKS_CACHE_DIR moves the cache out of the source tree, e.g. for
+    // sandboxed builds that cannot write to it
+    let cache_directory = match std::env::var_os("PACKS_CACHE_DIR") {
+        Some(cache_directory) => absolute_root.join(cache_directory),
+        None => absolute_root.join(raw_config.cache_directory),
+    };
     let cache_enabled = raw_config.cache;
+    let cache_format = raw_config.cache_format;
+    let cache_read_only = false;
     let experimental_parser = raw_config.experimental_parser;
 
     let layers = Layers {
@@ -109,6 +390,25 @@ pub(crate) fn from_raw(
 
     let ignored_definitions = raw_config.ignored_definitions;
     let packs_first_mode = raw_config.packs_first_mode;
+    let enforce_from_root = raw_config.enforce_from_root;
+    let pack_budgets = raw_config.pack_budgets;
+    let reference_filter = raw_config.reference_filter;
+    let reopened_constants = raw_config.reopened_constants;
+    let definition_scope = raw_config.definition_scope;
+    let follow_constant_aliases = raw_config.follow_constant_aliases;
+    let parse_eval_strings = raw_config.parse_eval_strings;
+    let config_reference_keys = raw_config.config_reference_keys;
+    let isolate_parse_crashes = raw_config.isolate_parse_crashes;
+    let max_file_size = raw_config.max_file_size;
+    let max_parse_duration =
+        raw_config.max_parse_duration.map(Duration::from_secs_f64);
+    let root_namespace = raw_config.root_namespace;
+    let namespace_pack_mapping = raw_config.namespace_pack_mapping;
+    let violation_messages = raw_config.violation_messages;
+    let violation_code_urls = raw_config.violation_code_urls;
+    let load_paths_dump = raw_config
+        .load_paths_dump
+        .map(|load_paths_dump| absolute_root.join(load_paths_dump));
 
     let custom_associations = raw_config
         .custom_associations
@@ -121,13 +421,34 @@ pub(crate) fn from_raw(
 
     let stdin_file_path: Option<PathBuf> = None;
     let print_files = false;
+    let timing = false;
     let ignore_recorded_violations = false;
+    let fail_on = FailOn::default();
+    let warn_on_stale = false;
+    let output_format = OutputFormat::default();
+    let template_path = None;
+    let path_display = PathDisplay::default();
+    let sort = ViolationSort::default();
+    let hyperlinks = false;
+    let output_path = None;
+    let min_confidence = None;
+    let load_paths = None;
+    let warnings = raw_config
+        .warnings
+        .into_iter()
+        .map(Warning::configuration)
+        .collect();
+    let deny_warnings = false;
+    let skipped_files = SkippedFiles::default();
 
     Configuration {
         included_files,
         absolute_root,
+        file_provider,
         cache_enabled,
         cache_directory,
+        cache_format,
+        cache_read_only,
         pack_set,
         layers,
         experimental_parser,
@@ -135,18 +456,98 @@ pub(crate) fn from_raw(
         custom_associations,
         stdin_file_path,
         print_files,
+        timing,
+        walk_timings,
         packs_first_mode,
         ignore_recorded_violations,
+        fail_on,
+        warn_on_stale,
+        output_format,
+        template_path,
+        path_display,
+        sort,
+        hyperlinks,
+        output_path,
+        min_confidence,
+        enforce_from_root,
+        pack_budgets,
+        todo_storage,
+        todo_format,
+        reference_filter,
+        reopened_constants,
+        definition_scope,
+        follow_constant_aliases,
+        parse_eval_strings,
+        config_reference_keys,
+        isolate_parse_crashes,
+        max_file_size,
+        max_parse_duration,
+        root_namespace,
+        namespace_pack_mapping,
+        load_paths_dump,
+        load_paths,
+        violation_messages,
+        violation_code_urls,
+        warnings,
+        deny_warnings,
+        skipped_files,
     }
 }
 
+// Packs in a sub-app refer to each other by names relative to the sub-app,
+// e.g. `packs/bar` for `gems/shared/packs/bar`. A name is read as one of the
+// sub-app's packs when the sub-app has it, and as one of the app's otherwise.
+fn qualify_sub_app_pack_names(
+    packs: HashSet<Pack>,
+    sub_app_dirs: &[String],
+) -> HashSet<Pack> {
+    let pack_names: HashSet<String> =
+        packs.iter().map(|pack| pack.name.clone()).collect();
+
+    packs
+        .into_iter()
+        .map(|pack| {
+            let Some(dir) = sub_app_dirs
+                .iter()
+                .filter(|dir| {
+                    pack.name == **dir
+                        || pack.name.starts_with(&format!("{}/", dir))
+                })
+                .max_by_key(|dir| dir.len())
+            else {
+                return pack;
+            };
+            let qualify = |names: &HashSet<String>| -> HashSet<String> {
+                names
+                    .iter()
+                    .map(|name| {
+                        let qualified = format!("{}/{}", dir, name);
+                        if pack_names.contains(&qualified) {
+                            qualified
+                        } else {
+                            name.clone()
+                        }
+                    })
+                    .collect()
+            };
+
+            Pack {
+                dependencies: qualify(&pack.dependencies),
+                ignored_dependencies: qualify(&pack.ignored_dependencies),
+                visible_to: pack.visible_to.as_ref().map(qualify),
+                ..pack
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use crate::packs::{
         configuration,
-        pack::{CheckerSetting, Pack},
+        pack::{EnforcementSetting, Pack},
         PackageTodo,
     };
 
@@ -155,7 +556,7 @@ mod tests {
     #[test]
     fn default_options() {
         let absolute_root = PathBuf::from("tests/fixtures/simple_app");
-        let actual = configuration::get(&absolute_root);
+        let actual = configuration::get(&absolute_root).unwrap();
         assert_eq!(actual.absolute_root, absolute_root);
 
         let expected_included_files = vec![
@@ -174,9 +575,12 @@ mod tests {
         let expected_packs = vec![
             Pack {
                 enforce_dependencies: None,
-                enforce_privacy: Some(CheckerSetting::True),
+                enforce_privacy: Some(EnforcementSetting::On),
                 enforce_visibility: None,
                 enforce_architecture: None,
+                enforce_upward_references: None,
+                enforce_privacy_from: None,
+                inherited_enforcement: Default::default(),
                 owner: None,
                 yml: absolute_root.join("packs/bar/package.yml"),
                 name: String::from("packs/bar"),
@@ -184,11 +588,17 @@ mod tests {
                 dependencies: HashSet::new(),
                 visible_to: None,
                 package_todo: PackageTodo::default(),
+                public_api: None,
+                todo_expires_on: None,
                 ignored_dependencies: HashSet::new(),
                 ignored_private_constants: HashSet::new(),
                 private_constants: HashSet::new(),
+                public_constants: HashSet::new(),
                 public_folder: None,
                 layer: None,
+                root_namespace: None,
+                autoload_paths: vec![],
+                facade: None,
                 client_keys: HashMap::new(),
             },
             Pack {
@@ -196,6 +606,9 @@ mod tests {
                 enforce_privacy: None,
                 enforce_visibility: None,
                 enforce_architecture: None,
+                enforce_upward_references: None,
+                enforce_privacy_from: None,
+                inherited_enforcement: Default::default(),
                 owner: None,
                 yml: absolute_root.join("packs/baz/package.yml"),
                 name: String::from("packs/baz"),
@@ -203,18 +616,27 @@ mod tests {
                 dependencies: HashSet::new(),
                 visible_to: None,
                 package_todo: PackageTodo::default(),
+                public_api: None,
+                todo_expires_on: None,
                 ignored_dependencies: HashSet::new(),
                 ignored_private_constants: HashSet::new(),
                 private_constants: HashSet::new(),
+                public_constants: HashSet::new(),
                 public_folder: None,
                 layer: None,
+                root_namespace: None,
+                autoload_paths: vec![],
+                facade: None,
                 client_keys: HashMap::new(),
             },
             Pack {
-                enforce_dependencies: Some(CheckerSetting::True),
-                enforce_privacy: Some(CheckerSetting::True),
+                enforce_dependencies: Some(EnforcementSetting::On),
+                enforce_privacy: Some(EnforcementSetting::On),
                 enforce_visibility: None,
                 enforce_architecture: None,
+                enforce_upward_references: None,
+                enforce_privacy_from: None,
+                inherited_enforcement: Default::default(),
                 owner: None,
                 yml: absolute_root.join("packs/foo/package.yml"),
                 name: String::from("packs/foo"),
@@ -224,11 +646,17 @@ mod tests {
                 )]),
                 visible_to: None,
                 package_todo: PackageTodo::default(),
+                public_api: None,
+                todo_expires_on: None,
                 ignored_dependencies: HashSet::new(),
                 ignored_private_constants: HashSet::new(),
                 private_constants: HashSet::new(),
+                public_constants: HashSet::new(),
                 public_folder: None,
                 layer: None,
+                root_namespace: None,
+                autoload_paths: vec![],
+                facade: None,
                 client_keys: HashMap::new(),
             },
             Pack {
@@ -236,6 +664,9 @@ mod tests {
                 enforce_privacy: None,
                 enforce_visibility: None,
                 enforce_architecture: None,
+                enforce_upward_references: None,
+                enforce_privacy_from: None,
+                inherited_enforcement: Default::default(),
                 owner: None,
                 yml: absolute_root.join("package.yml"),
                 name: String::from("."),
@@ -243,11 +674,17 @@ mod tests {
                 dependencies: HashSet::new(),
                 visible_to: None,
                 package_todo: PackageTodo::default(),
+                public_api: None,
+                todo_expires_on: None,
                 ignored_dependencies: HashSet::new(),
                 ignored_private_constants: HashSet::new(),
                 private_constants: HashSet::new(),
+                public_constants: HashSet::new(),
                 public_folder: None,
                 layer: None,
+                root_namespace: None,
+                autoload_paths: vec![],
+                facade: None,
                 client_keys: HashMap::new(),
             },
         ];
@@ -260,7 +697,7 @@ mod tests {
     #[test]
     fn filtered_absolute_paths_with_nonempty_input_paths() {
         let absolute_root = PathBuf::from("tests/fixtures/simple_app");
-        let configuration = configuration::get(&absolute_root);
+        let configuration = configuration::get(&absolute_root).unwrap();
         let actual_paths = configuration.intersect_files(vec![
             String::from("packs/foo/app/services/foo.rb"),
             String::from("scripts/my_script.rb"),
@@ -279,7 +716,7 @@ mod tests {
     #[test]
     fn filtered_absolute_paths_with_empty_input_paths() {
         let absolute_root = PathBuf::from("tests/fixtures/simple_app");
-        let configuration = configuration::get(&absolute_root);
+        let configuration = configuration::get(&absolute_root).unwrap();
         let actual_paths = configuration.intersect_files(vec![]);
         let expected_paths = vec![
             absolute_root.join("packs/bar/app/services/bar.rb"),
@@ -298,7 +735,7 @@ mod tests {
     #[test]
     fn filtered_absolute_paths_with_directory_input_paths() {
         let absolute_root = PathBuf::from("tests/fixtures/simple_app");
-        let configuration = configuration::get(&absolute_root);
+        let configuration = configuration::get(&absolute_root).unwrap();
         let actual_paths =
             configuration.intersect_files(vec![String::from("packs/bar")]);
         let expected_paths = vec![
@@ -328,13 +765,165 @@ mod tests {
             included_files: Default::default(),
             included_packs,
             owning_package_yml_for_file: Default::default(),
+            timings: Default::default(),
         };
 
-        let configuration =
-            configuration::from_raw(&absolute_root, raw, walk_directory_result);
+        let configuration = configuration::from_raw(
+            &absolute_root,
+            raw,
+            walk_directory_result,
+            Arc::new(WorkingTree),
+        );
         let actual_associations = configuration.custom_associations;
         let expected_paths = vec!["my_association".to_owned()];
 
         assert_eq!(actual_associations, expected_paths);
     }
+
+    #[test]
+    fn with_enforcement_inherited_from_root() {
+        let absolute_root = PathBuf::from("tests/fixtures/simple_app");
+        let raw = RawConfiguration {
+            inherit_enforcement_from_root: true,
+            ..RawConfiguration::default()
+        };
+
+        let included_packs: HashSet<Pack> = vec![
+            Pack {
+                name: String::from("."),
+                enforce_privacy: Some(EnforcementSetting::Strict),
+                enforce_dependencies: Some(EnforcementSetting::On),
+                ..Pack::default()
+            },
+            Pack {
+                name: String::from("packs/foo"),
+                enforce_dependencies: Some(EnforcementSetting::Off),
+                ..Pack::default()
+            },
+        ]
+        .into_iter()
+        .collect();
+        let walk_directory_result = WalkDirectoryResult {
+            included_files: Default::default(),
+            included_packs,
+            owning_package_yml_for_file: Default::default(),
+            timings: Default::default(),
+        };
+
+        let configuration = configuration::from_raw(
+            &absolute_root,
+            raw,
+            walk_directory_result,
+            Arc::new(WorkingTree),
+        );
+        let foo = configuration.pack_set.for_pack("packs/foo").unwrap();
+
+        assert_eq!(&EnforcementSetting::Strict, foo.enforce_privacy());
+        assert_eq!(&EnforcementSetting::Off, foo.enforce_dependencies());
+        assert_eq!(&EnforcementSetting::Off, foo.enforce_visibility());
+        assert_eq!(
+            "enforce_dependencies: false\n",
+            crate::packs::pack::serialize_pack(foo)
+        );
+    }
+
+    #[test]
+    fn with_sub_app_pack_names() {
+        let absolute_root = PathBuf::from("tests/fixtures/simple_app");
+        let raw = RawConfiguration {
+            sub_app_dirs: vec![String::from("gems/shared")],
+            ..RawConfiguration::default()
+        };
+
+        let pack = |name: &str, dependencies: &[&str]| Pack {
+            name: String::from(name),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Pack::default()
+        };
+        let included_packs: HashSet<Pack> = vec![
+            pack(".", &[]),
+            pack("packs/parts", &[]),
+            pack("packs/billing", &[]),
+            pack("gems/shared/packs/parts", &[]),
+            pack(
+                "gems/shared/packs/widgets",
+                &["packs/parts", "packs/billing"],
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let walk_directory_result = WalkDirectoryResult {
+            included_files: Default::default(),
+            included_packs,
+            owning_package_yml_for_file: Default::default(),
+            timings: Default::default(),
+        };
+
+        let configuration = configuration::from_raw(
+            &absolute_root,
+            raw,
+            walk_directory_result,
+            Arc::new(WorkingTree),
+        );
+        let widgets = configuration
+            .pack_set
+            .for_pack("gems/shared/packs/widgets")
+            .unwrap();
+
+        let expected: HashSet<String> = vec![
+            String::from("gems/shared/packs/parts"),
+            String::from("packs/billing"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(expected, widgets.dependencies);
+    }
+
+    #[test]
+    fn test_mapped_pack() {
+        let pack = |name: &str| Pack {
+            name: String::from(name),
+            ..Pack::default()
+        };
+        let configuration = Configuration {
+            pack_set: PackSet::build(
+                HashSet::from([
+                    pack("."),
+                    pack("packs/billing"),
+                    pack("packs/refunds"),
+                ]),
+                HashMap::new(),
+            ),
+            namespace_pack_mapping: HashMap::from([
+                (String::from("Billing"), String::from("packs/billing")),
+                (
+                    String::from("::Billing::Refunds"),
+                    String::from("packs/refunds"),
+                ),
+                (String::from("Shipping"), String::from("packs/shipping")),
+            ]),
+            ..Configuration::default()
+        };
+
+        assert_eq!(
+            Some("packs/billing"),
+            configuration.mapped_pack("::Billing")
+        );
+        assert_eq!(
+            Some("packs/billing"),
+            configuration.mapped_pack("::Billing::Invoice")
+        );
+        assert_eq!(
+            Some("packs/refunds"),
+            configuration.mapped_pack("::Billing::Refunds::Refund")
+        );
+        assert_eq!(None, configuration.mapped_pack("::BillingAddress"));
+        assert_eq!(None, configuration.mapped_pack("::Shipping::Label"));
+        assert_eq!(
+            vec![String::from(
+                "`namespace_pack_mapping` in packwerk.yml maps `Shipping` to `packs/shipping`, which is not a pack"
+            )],
+            configuration.namespace_pack_mapping_errors()
+        );
+    }
 }
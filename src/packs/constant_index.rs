@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use super::constant_resolver::{ConstantDefinition, ConstantResolver};
+use super::file_utils::to_slash_path;
+use super::{
+    get_experimental_constant_resolver, get_zeitwerk_constant_resolver,
+    process_files_with_cache, Configuration, ProcessedFile,
+};
+
+// A constant definition, attributed to the pack its file belongs to
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Constant {
+    // Fully qualified, e.g. `::Foo::Bar`
+    pub name: String,
+    // Relative to the project root, with forward slashes
+    pub file: String,
+    pub pack_name: Option<String>,
+}
+
+// Every constant defined in the project, resolved the same way `check`
+// resolves references. Constants defined in more than one file appear once
+// per file.
+#[derive(Debug, Default)]
+pub struct ConstantIndex {
+    // Sorted by name, then file, so lookups and namespaces are ranges
+    constants: Vec<Constant>,
+    constants_by_file: BTreeMap<String, Vec<usize>>,
+}
+
+impl ConstantIndex {
+    pub fn new(configuration: &Configuration) -> ConstantIndex {
+        let constant_resolver = constant_resolver(configuration);
+        let definitions = constant_resolver
+            .fully_qualified_constant_name_to_constant_definition_map();
+        ConstantIndex::from_definitions(
+            configuration,
+            definitions.values().flatten(),
+        )
+    }
+
+    pub(crate) fn from_definitions<'a>(
+        configuration: &Configuration,
+        definitions: impl Iterator<Item = &'a ConstantDefinition>,
+    ) -> ConstantIndex {
+        let mut constants: Vec<Constant> = definitions
+            .map(|definition| {
+                let path = &definition.absolute_path_of_definition;
+                Constant {
+                    name: qualified(&definition.fully_qualified_name),
+                    file: to_slash_path(
+                        path.strip_prefix(&configuration.absolute_root)
+                            .unwrap_or(path),
+                    ),
+                    pack_name: configuration
+                        .pack_set
+                        .for_file(path)
+                        .map(|pack| pack.name.clone()),
+                }
+            })
+            .collect();
+        constants.sort();
+        constants.dedup();
+
+        let mut constants_by_file: BTreeMap<String, Vec<usize>> =
+            BTreeMap::new();
+        for (index, constant) in constants.iter().enumerate() {
+            constants_by_file
+                .entry(constant.file.clone())
+                .or_default()
+                .push(index);
+        }
+
+        ConstantIndex {
+            constants,
+            constants_by_file,
+        }
+    }
+
+    // The definitions of a constant, by its fully qualified name. The leading
+    // `::` is optional.
+    pub fn get(&self, name: &str) -> &[Constant] {
+        let name = qualified(name);
+        &self.constants[self.range(
+            |constant| constant.name < name,
+            |constant| constant.name <= name,
+        )]
+    }
+
+    // The constants nested in `namespace` at any depth, not including the
+    // namespace itself
+    pub fn in_namespace(&self, namespace: &str) -> &[Constant] {
+        let prefix = format!("{}::", qualified(namespace));
+        &self.constants[self.range(
+            |constant| constant.name < prefix,
+            |constant| {
+                constant.name < prefix || constant.name.starts_with(&prefix)
+            },
+        )]
+    }
+
+    // The constants defined in a file, by its path relative to the root
+    pub fn defined_in(&self, file: &str) -> Vec<&Constant> {
+        self.constants_by_file
+            .get(file)
+            .map(|indexes| {
+                indexes
+                    .iter()
+                    .map(|index| &self.constants[*index])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Every definition, sorted by name then file
+    pub fn iter(&self) -> impl Iterator<Item = &Constant> {
+        self.constants.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.constants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constants.is_empty()
+    }
+
+    fn range(
+        &self,
+        before: impl Fn(&Constant) -> bool,
+        before_end: impl Fn(&Constant) -> bool,
+    ) -> Range<usize> {
+        let start = self.constants.partition_point(before);
+        let end = self.constants.partition_point(before_end);
+        start..end
+    }
+}
+
+fn qualified(name: &str) -> String {
+    format!("::{}", name.trim_start_matches("::"))
+}
+
+pub(crate) fn constant_resolver(
+    configuration: &Configuration,
+) -> Box<dyn ConstantResolver + Send + Sync> {
+    if configuration.experimental_parser {
+        let processed_files: Vec<ProcessedFile> = process_files_with_cache(
+            &configuration.included_files,
+            configuration.get_cache(),
+            configuration,
+        );
+
+        get_experimental_constant_resolver(
+            &configuration.absolute_root,
+            &processed_files,
+            &configuration.ignored_definitions,
+        )
+    } else {
+        get_zeitwerk_constant_resolver(configuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::configuration;
+    use crate::packs::DefinitionKind;
+
+    fn constant_index() -> ConstantIndex {
+        let configuration = configuration::get(
+            PathBuf::from("tests/fixtures/simple_app")
+                .canonicalize()
+                .expect("Could not canonicalize path")
+                .as_path(),
+        )
+        .unwrap();
+        let root = &configuration.absolute_root;
+        let definitions = [
+            ("::Foo", "packs/foo/app/services/foo.rb"),
+            ("::Foo::Bar", "packs/foo/app/services/foo/bar.rb"),
+            ("::Foo::Bar::Baz", "packs/foo/app/services/foo/bar/baz.rb"),
+            ("::FooBar", "packs/baz/app/services/baz.rb"),
+            ("::Bar", "packs/bar/app/services/bar.rb"),
+            ("::Bar", "app/services/some_root_class.rb"),
+        ]
+        .map(|(name, file)| ConstantDefinition {
+            fully_qualified_name: String::from(name),
+            absolute_path_of_definition: root.join(file),
+            kind: DefinitionKind::Constant,
+        });
+        ConstantIndex::from_definitions(&configuration, definitions.iter())
+    }
+
+    fn names(constants: &[Constant]) -> Vec<&str> {
+        constants
+            .iter()
+            .map(|constant| constant.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_get() {
+        let index = constant_index();
+
+        assert_eq!(
+            vec![
+                Constant {
+                    name: String::from("::Bar"),
+                    file: String::from("app/services/some_root_class.rb"),
+                    pack_name: Some(String::from(".")),
+                },
+                Constant {
+                    name: String::from("::Bar"),
+                    file: String::from("packs/bar/app/services/bar.rb"),
+                    pack_name: Some(String::from("packs/bar")),
+                },
+            ],
+            index.get("Bar")
+        );
+        assert_eq!(vec!["::Foo"], names(index.get("::Foo")));
+        assert!(index.get("::Qux").is_empty());
+    }
+
+    #[test]
+    fn test_in_namespace() {
+        let index = constant_index();
+
+        assert_eq!(
+            vec!["::Foo::Bar", "::Foo::Bar::Baz"],
+            names(index.in_namespace("Foo"))
+        );
+        assert!(index.in_namespace("::Foo::Bar::Baz").is_empty());
+    }
+
+    #[test]
+    fn test_defined_in() {
+        let index = constant_index();
+
+        assert_eq!(
+            vec!["::FooBar"],
+            index
+                .defined_in("packs/baz/app/services/baz.rb")
+                .iter()
+                .map(|constant| constant.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(6, index.iter().count());
+    }
+}
@@ -2,10 +2,14 @@ use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use super::DefinitionKind;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ConstantDefinition {
     pub fully_qualified_name: String,
     pub absolute_path_of_definition: PathBuf,
+    #[serde(default)]
+    pub kind: DefinitionKind,
 }
 
 pub trait ConstantResolver {
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use super::reference_extractor::get_all_references_and_resolver;
+use super::Configuration;
+
+// Lists constants that nothing references. With `public_only`, lists
+// constants in public folders that no *other* pack references.
+pub(crate) fn dead_code(
+    configuration: &Configuration,
+    public_only: bool,
+) -> Result<(), Box<dyn Error>> {
+    let (references, constant_resolver) = get_all_references_and_resolver(
+        configuration,
+        &configuration.included_files,
+    );
+
+    // A namespace counts as used when something inside it is referenced, so
+    // each referenced constant marks its namespaces as referenced too.
+    // References from the file defining the constant, like the name of the
+    // class it opens, don't count as using it.
+    let mut referenced_constants: HashSet<&str> = HashSet::new();
+    for reference in &references {
+        if public_only
+            && reference.defining_pack_name.as_ref()
+                == Some(&reference.referencing_pack_name)
+        {
+            continue;
+        }
+        if reference.relative_defining_file.as_ref()
+            == Some(&reference.relative_referencing_file)
+        {
+            continue;
+        }
+        let constant_name = reference.constant_name.as_str();
+        referenced_constants.extend(
+            constant_name
+                .match_indices("::")
+                .map(|(index, _)| &constant_name[..index])
+                .filter(|namespace| !namespace.is_empty()),
+        );
+        referenced_constants.insert(constant_name);
+    }
+
+    let mut dead_constants: Vec<(String, &str)> = Vec::new();
+    for (constant_name, definitions) in constant_resolver
+        .fully_qualified_constant_name_to_constant_definition_map()
+    {
+        if referenced_constants.contains(constant_name.as_str()) {
+            continue;
+        }
+
+        for definition in definitions {
+            let absolute_path = &definition.absolute_path_of_definition;
+            let Some(pack) = configuration.pack_set.for_file(absolute_path)
+            else {
+                continue;
+            };
+            let relative_path = absolute_path
+                .strip_prefix(&configuration.absolute_root)
+                .unwrap();
+
+            if public_only && !relative_path.starts_with(pack.public_folder()) {
+                continue;
+            }
+
+            dead_constants.push((
+                relative_path.to_string_lossy().into_owned(),
+                constant_name,
+            ));
+        }
+    }
+
+    let kind = if public_only {
+        "public constant"
+    } else {
+        "constant"
+    };
+    if dead_constants.is_empty() {
+        println!("No unreferenced {}s found", kind);
+        return Ok(());
+    }
+
+    dead_constants.sort();
+    for (relative_path, constant_name) in &dead_constants {
+        println!("{} {}", relative_path, constant_name);
+    }
+    println!("\n{} unreferenced {}(s) found", dead_constants.len(), kind);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::thread;
+
+use rayon::ThreadPoolBuilder;
+
+use super::checker::reference::Reference;
+use super::pipeline;
+use super::Configuration;
+
+// What a run finds, serialized so two runs can be compared byte for byte
+#[derive(Debug, PartialEq, Eq)]
+struct RunResult {
+    references: Vec<String>,
+    violations: String,
+}
+
+// Runs the check twice, once on one thread in the usual file order, then on
+// every core with the files and parsed files in reverse order, and errors
+// if the references or violations found differ. The cache is skipped so
+// every file is parsed both times.
+pub(crate) fn verify_determinism(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    let configuration = Configuration {
+        cache_enabled: false,
+        ..configuration.clone()
+    };
+    let threads = thread::available_parallelism()
+        .map(|threads| threads.get())
+        .unwrap_or(1)
+        .max(2);
+
+    let first = run(&configuration, 1, false)?;
+    let second = run(&configuration, threads, true)?;
+
+    if let Some(difference) = first_difference(&first, &second) {
+        return Err(format!(
+            "Results differ between a run on 1 thread and a run on {} threads in reverse order:\n{}",
+            threads, difference
+        )
+        .into());
+    }
+
+    println!(
+        "Results are identical on 1 thread and on {} threads in reverse order ({} references, {} violations)",
+        threads,
+        first.references.len(),
+        serde_json::from_str::<Vec<serde_json::Value>>(&first.violations)?
+            .len()
+    );
+    Ok(())
+}
+
+fn run(
+    configuration: &Configuration,
+    threads: usize,
+    reverse: bool,
+) -> Result<RunResult, Box<dyn Error>> {
+    let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+    let result = pool.install(|| {
+        let mut files = pipeline::discover_files(configuration, vec![]);
+        if reverse {
+            files.files.reverse();
+        }
+        let mut processed_files = pipeline::parse(configuration, &files);
+        if reverse {
+            processed_files.reverse();
+        }
+        let resolver = pipeline::default_resolver(configuration);
+        let references = pipeline::resolve(
+            configuration,
+            resolver.as_ref(),
+            &processed_files,
+        );
+        let serialized_references = serialize_each(&references)?;
+        let violations = pipeline::check(configuration, references);
+
+        Ok::<_, serde_json::Error>(RunResult {
+            references: serialized_references,
+            violations: serde_json::to_string_pretty(&violations)?,
+        })
+    })?;
+    Ok(result)
+}
+
+// References are found file by file, so they come in the order of the
+// files; it's what's in them that has to be the same
+fn serialize_each(
+    references: &[Reference],
+) -> Result<Vec<String>, serde_json::Error> {
+    let mut serialized = references
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<String>, _>>()?;
+    serialized.sort();
+    Ok(serialized)
+}
+
+fn first_difference(first: &RunResult, second: &RunResult) -> Option<String> {
+    if first.references != second.references {
+        let difference = first
+            .references
+            .iter()
+            .zip(&second.references)
+            .find(|(first, second)| first != second)
+            .map(|(first, second)| format!("- {}\n+ {}", first, second))
+            .unwrap_or_else(|| {
+                format!(
+                    "{} references, then {}",
+                    first.references.len(),
+                    second.references.len()
+                )
+            });
+        return Some(format!("References differ:\n{}", difference));
+    }
+
+    first
+        .violations
+        .lines()
+        .zip(second.violations.lines())
+        .find(|(first, second)| first != second)
+        .map(|(first, second)| {
+            format!("Violations differ:\n- {}\n+ {}", first, second)
+        })
+        .or_else(|| {
+            (first.violations != second.violations)
+                .then(|| String::from("Violations differ in length"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_difference() {
+        let result = |violations: &str| RunResult {
+            references: vec![String::from("{\"constant_name\":\"::Bar\"}")],
+            violations: violations.to_owned(),
+        };
+
+        assert_eq!(None, first_difference(&result("[1]"), &result("[1]")));
+        assert_eq!(
+            Some(String::from("Violations differ:\n- [1]\n+ [2]")),
+            first_difference(&result("[1]"), &result("[2]"))
+        );
+        assert_eq!(
+            Some(String::from("Violations differ in length")),
+            first_difference(&result("[\n1"), &result("[\n1\n]"))
+        );
+    }
+}
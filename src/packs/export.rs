@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+use super::constant_index::ConstantIndex;
+use super::pack::Pack;
+use super::parsing::ruby::inflector_shim::camelize;
+use super::parsing::ruby::rails_utils::get_acronyms;
+use super::Configuration;
+
+pub(crate) const SORBET_PACKAGE_FILE_NAME: &str = "__package.rb";
+
+// A Sorbet package, as declared in a `__package.rb` file
+#[derive(Debug, PartialEq, Eq)]
+struct SorbetPackage {
+    name: String,
+    imports: BTreeSet<String>,
+    exports: BTreeSet<String>,
+}
+
+// Writes a Sorbet `__package.rb` next to each package.yml, except the root
+// one, so package.yml stays the one source of truth. Each package imports
+// the packs its pack depends on and exports the constants in its public
+// folders.
+pub(crate) fn sorbet_packages(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    let acronyms = get_acronyms(
+        configuration.file_provider.as_ref(),
+        &configuration.absolute_root,
+    );
+    let package_name = |pack: &Pack| camelize(&pack.name, &acronyms);
+    let constant_index = ConstantIndex::new(configuration);
+
+    let mut written = 0;
+    for pack in &configuration.pack_set.packs {
+        if pack.name == "." {
+            continue;
+        }
+        let imports = pack
+            .dependencies
+            .iter()
+            .filter(|dependency| *dependency != ".")
+            .filter_map(|dependency| {
+                configuration.pack_set.for_pack(dependency).ok()
+            })
+            .map(package_name)
+            .collect();
+        let package = SorbetPackage {
+            name: package_name(pack),
+            imports,
+            exports: exports(pack, &constant_index),
+        };
+        let package_rb = pack.yml.with_file_name(SORBET_PACKAGE_FILE_NAME);
+        std::fs::write(&package_rb, to_ruby(&package))?;
+        written += 1;
+    }
+
+    println!("Wrote {} {} file(s)", written, SORBET_PACKAGE_FILE_NAME);
+    Ok(())
+}
+
+// The public constants of the pack, leaving out those nested in another
+// exported constant, since exporting a namespace exports what it contains
+fn exports(pack: &Pack, constant_index: &ConstantIndex) -> BTreeSet<String> {
+    let public_folders = pack.public_folders();
+    let public_constants: BTreeSet<&str> = constant_index
+        .iter()
+        .filter(|constant| {
+            constant.pack_name.as_deref() == Some(pack.name.as_str())
+                && public_folders
+                    .iter()
+                    .any(|folder| Path::new(&constant.file).starts_with(folder))
+        })
+        .map(|constant| constant.name.trim_start_matches("::"))
+        .collect();
+
+    public_constants
+        .iter()
+        .filter(|name| {
+            !public_constants
+                .iter()
+                .any(|namespace| name.starts_with(&format!("{}::", namespace)))
+        })
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn to_ruby(package: &SorbetPackage) -> String {
+    let mut ruby = String::from(
+        "# typed: strict\n\
+         # Generated from package.yml by `pks export sorbet-packages`. Edit package.yml instead.\n\n",
+    );
+    writeln!(ruby, "class {} < PackageSpec", package.name).unwrap();
+    for import in &package.imports {
+        writeln!(ruby, "  import {}", import).unwrap();
+    }
+    if !package.imports.is_empty() && !package.exports.is_empty() {
+        ruby.push('\n');
+    }
+    for export in &package.exports {
+        writeln!(ruby, "  export {}", export).unwrap();
+    }
+    ruby.push_str("end\n");
+    ruby
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_to_ruby() {
+        let package = SorbetPackage {
+            name: String::from("Packs::Foo"),
+            imports: BTreeSet::from([
+                String::from("Packs::Bar"),
+                String::from("Packs::Baz"),
+            ]),
+            exports: BTreeSet::from([String::from("Foo::Api")]),
+        };
+
+        assert_eq!(
+            "\
+# typed: strict
+# Generated from package.yml by `pks export sorbet-packages`. Edit package.yml instead.
+
+class Packs::Foo < PackageSpec
+  import Packs::Bar
+  import Packs::Baz
+
+  export Foo::Api
+end
+",
+            to_ruby(&package)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use super::constant_index::ConstantIndex;
+use super::pack::{Facade, Pack};
+use super::parsing::ruby::inflector_shim::underscore;
+use super::Configuration;
+
+// Writes the pack's facade, a module in its public folder re-exporting the
+// private constants listed under `facade` in its package.yml, e.g.
+// `Billing::Api::Invoice = ::Billing::Invoice`, so other packs can reference
+// those constants through the public module. The file is rewritten from
+// package.yml each time.
+pub(crate) fn generate_facade(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    let pack = configuration.pack_set.for_pack(pack_name)?;
+    let Some(facade) = &pack.facade else {
+        return Err(format!(
+            "{} has no `facade` in its package.yml",
+            pack.name
+        )
+        .into());
+    };
+
+    let constant_index = ConstantIndex::new(configuration);
+    let mut aliases: Vec<(String, String)> = vec![];
+    let mut constants_by_alias: HashMap<String, String> = HashMap::new();
+    for constant in &facade.constants {
+        let constant = format!("::{}", constant.trim_start_matches("::"));
+        let defined_in_pack =
+            constant_index.get(&constant).iter().any(|definition| {
+                definition.pack_name.as_deref() == Some(pack.name.as_str())
+            });
+        if !defined_in_pack {
+            return Err(format!(
+                "`{}` is not defined in {}",
+                constant, pack.name
+            )
+            .into());
+        }
+
+        let alias = constant.rsplit("::").next().unwrap_or_default();
+        if let Some(other) =
+            constants_by_alias.insert(alias.to_owned(), constant.clone())
+        {
+            return Err(format!(
+                "`{}` and `{}` would both be re-exported as `{}`",
+                other, constant, alias
+            )
+            .into());
+        }
+        aliases.push((alias.to_owned(), constant));
+    }
+
+    let path = facade_path(pack, facade);
+    let absolute_path = configuration.absolute_root.join(&path);
+    if let Some(parent) = absolute_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&absolute_path, to_ruby(pack, facade, &aliases))?;
+
+    println!(
+        "Wrote {} re-exporting {} constant(s)",
+        path.display(),
+        aliases.len()
+    );
+    Ok(())
+}
+
+// Where Zeitwerk expects the module, relative to the root
+fn facade_path(pack: &Pack, facade: &Facade) -> PathBuf {
+    let module = facade.module.trim_start_matches("::");
+    pack.public_folder()
+        .join(format!("{}.rb", underscore(module)))
+}
+
+fn to_ruby(
+    pack: &Pack,
+    facade: &Facade,
+    aliases: &[(String, String)],
+) -> String {
+    let mut ruby = format!(
+        "# typed: true\n\
+         # Generated from package.yml by `pks generate-facade {}`. Edit package.yml instead.\n\n",
+        pack.name
+    );
+    writeln!(ruby, "module {}", facade.module.trim_start_matches("::"))
+        .unwrap();
+    for (alias, constant) in aliases {
+        writeln!(ruby, "  {} = {}", alias, constant).unwrap();
+    }
+    ruby.push_str("end\n");
+    ruby
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pack_with_facade() -> (Pack, Facade) {
+        let facade = Facade {
+            module: String::from("Billing::Api"),
+            constants: vec![String::from("Billing::Invoice")],
+        };
+        let pack = Pack {
+            name: String::from("packs/billing"),
+            relative_path: PathBuf::from("packs/billing"),
+            facade: Some(facade.clone()),
+            ..Pack::default()
+        };
+        (pack, facade)
+    }
+
+    #[test]
+    fn test_facade_path() {
+        let (pack, facade) = pack_with_facade();
+
+        assert_eq!(
+            PathBuf::from("packs/billing/app/public/billing/api.rb"),
+            facade_path(&pack, &facade)
+        );
+    }
+
+    #[test]
+    fn test_to_ruby() {
+        let (pack, facade) = pack_with_facade();
+        let aliases =
+            vec![(String::from("Invoice"), String::from("::Billing::Invoice"))];
+
+        assert_eq!(
+            "\
+# typed: true
+# Generated from package.yml by `pks generate-facade packs/billing`. Edit package.yml instead.
+
+module Billing::Api
+  Invoice = ::Billing::Invoice
+end
+",
+            to_ruby(&pack, &facade, &aliases)
+        );
+    }
+}
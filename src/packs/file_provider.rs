@@ -0,0 +1,109 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::packs::file_utils::build_glob_set;
+
+// Where the files of the project are read from. Discovery and the parsers
+// read through it, so `--git-ref` can read the files of a commit straight
+// from the git object database, without a working tree.
+pub trait FileProvider: Send + Sync {
+    // Every file of the project, as absolute paths, if they are known up
+    // front. The working tree has no such list, and is walked instead, so
+    // excluded directories can be skipped without being read.
+    fn files(&self) -> Option<&[PathBuf]>;
+
+    fn read(&self, absolute_path: &Path) -> io::Result<Vec<u8>>;
+
+    fn read_to_string(&self, absolute_path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(absolute_path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn is_file(&self, absolute_path: &Path) -> bool;
+
+    // The files and directories matching an absolute glob pattern, like
+    // `glob::glob` finds them on disk
+    fn glob(&self, pattern: &str) -> Vec<PathBuf>;
+}
+
+// The files on disk
+pub struct WorkingTree;
+
+impl FileProvider for WorkingTree {
+    fn files(&self) -> Option<&[PathBuf]> {
+        None
+    }
+
+    fn read(&self, absolute_path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(absolute_path)
+    }
+
+    fn is_file(&self, absolute_path: &Path) -> bool {
+        absolute_path.is_file()
+    }
+
+    fn glob(&self, pattern: &str) -> Vec<PathBuf> {
+        glob::glob(pattern)
+            .expect("Failed to read glob pattern")
+            .flatten()
+            .collect()
+    }
+}
+
+// The files and directories of `files` matching an absolute glob pattern,
+// for providers that list their files up front. Directories are the
+// ancestors of the files, up to and including `absolute_root`.
+pub(crate) fn glob_listed_files(
+    files: &[PathBuf],
+    absolute_root: &Path,
+    pattern: &str,
+) -> Vec<PathBuf> {
+    let glob_set = build_glob_set(&[pattern.to_owned()]);
+    let mut matches: Vec<PathBuf> = files
+        .iter()
+        .flat_map(|file| {
+            file.ancestors()
+                .take_while(|ancestor| ancestor.starts_with(absolute_root))
+        })
+        .filter(|path| glob_set.is_match(path))
+        .map(Path::to_path_buf)
+        .collect();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_listed_files() {
+        let root = PathBuf::from("/app");
+        let files = vec![
+            root.join("packs/foo/app/services/foo.rb"),
+            root.join("packs/foo/app/models/concerns/bar.rb"),
+            root.join("packs/foo/package.yml"),
+        ];
+
+        assert_eq!(
+            vec![
+                root.join("packs/foo/app/models"),
+                root.join("packs/foo/app/services"),
+            ],
+            glob_listed_files(&files, &root, "/app/packs/foo/app/*")
+        );
+        assert_eq!(
+            vec![root.join("packs/foo/app/models/concerns")],
+            glob_listed_files(&files, &root, "/app/packs/foo/app/*/concerns")
+        );
+        assert_eq!(
+            vec![
+                root.join("packs/foo/app/models/concerns/bar.rb"),
+                root.join("packs/foo/app/services/foo.rb"),
+            ],
+            glob_listed_files(&files, &root, "/app/packs/foo/app/**/*.rb")
+        );
+    }
+}
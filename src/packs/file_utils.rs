@@ -2,9 +2,10 @@ use std::{
     collections::HashSet,
     fs, io,
     io::Read,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
+use crate::packs::parsing::FileSigils;
 use crate::packs::Configuration;
 use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use regex::Regex;
@@ -13,11 +14,13 @@ use regex::Regex;
 pub enum SupportedFileType {
     Ruby,
     Erb,
+    // Only config files, see `config_reference_files` in packwerk.yml
+    Yaml,
 }
 
 pub fn get_file_type(path: &Path) -> Option<SupportedFileType> {
     let ruby_special_files = ["Gemfile", "Rakefile"];
-    let ruby_extensions = vec!["rb", "rake", "builder", "gemspec", "ru"];
+    let ruby_extensions = vec!["rb", "rake", "builder", "gemspec", "ru", "rbi"];
 
     let extension = path.extension();
     // Eventually, we can have packs::parsing::ruby, packs::parsing::erb, etc.
@@ -30,11 +33,17 @@ pub fn get_file_type(path: &Path) -> Option<SupportedFileType> {
         || ruby_special_files.iter().any(|file| path.ends_with(file));
 
     let is_erb_file = path.extension().map_or(false, |ext| ext == "erb");
+    // JSON is YAML too
+    let is_yaml_file = ["yml", "yaml", "json"]
+        .into_iter()
+        .any(|ext| extension.is_some_and(|e| e == ext));
 
     if is_ruby_file {
         Some(SupportedFileType::Ruby)
     } else if is_erb_file {
         Some(SupportedFileType::Erb)
+    } else if is_yaml_file {
+        Some(SupportedFileType::Yaml)
     } else {
         None
     }
@@ -55,15 +64,6 @@ pub fn build_glob_set(globs: &[String]) -> GlobSet {
     builder.build().unwrap()
 }
 
-pub fn process_glob_pattern(pattern: &str, paths: &mut Vec<PathBuf>) {
-    for path in glob::glob(pattern)
-        .expect("Failed to read glob pattern")
-        .flatten()
-    {
-        paths.push(path);
-    }
-}
-
 pub fn glob_ruby_files_in_dirs(dirs: Vec<&PathBuf>) -> Vec<PathBuf> {
     let mut paths = Vec::new();
     for dir in dirs {
@@ -145,16 +145,66 @@ pub fn file_read_contents(
             )
         })
     } else {
-        fs::read_to_string(path).unwrap_or_else(|_| {
-            println!(
-                "Failed to read contents of {} – skipping this file",
-                path.to_string_lossy()
+        let bytes =
+            configuration.file_provider.read(path).unwrap_or_else(|_| {
+                println!(
+                    "Failed to read contents of {} – skipping this file",
+                    path.to_string_lossy()
+                );
+                vec![]
+            });
+        decode_source(bytes).unwrap_or_else(|problem| {
+            eprintln!(
+                "Could not parse {}: {} – skipping this file",
+                path.to_string_lossy(),
+                problem
             );
             "".to_string()
         })
     }
 }
 
+// Source files as UTF-8, without a byte order mark. Files that aren't UTF-8
+// are decoded from the encoding their magic comment names, e.g.
+// `# encoding: iso-8859-1`, if it is one that maps bytes to characters one to
+// one.
+fn decode_source(mut bytes: Vec<u8>) -> Result<String, String> {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        bytes.drain(..3);
+    }
+    let bytes = match String::from_utf8(bytes) {
+        Ok(contents) => return Ok(contents),
+        Err(error) => error.into_bytes(),
+    };
+
+    match magic_encoding(&bytes).as_deref() {
+        Some("iso-8859-1" | "latin1" | "binary" | "ascii-8bit") => {
+            Ok(bytes.iter().map(|byte| char::from(*byte)).collect())
+        }
+        Some(encoding) => {
+            Err(format!("the `{}` encoding is not supported", encoding))
+        }
+        None => Err(String::from(
+            "it is not valid UTF-8 and has no encoding magic comment",
+        )),
+    }
+}
+
+// The encoding named by an `encoding:` or `coding:` magic comment, which
+// Ruby only reads from the first line, or the second after a shebang
+fn magic_encoding(bytes: &[u8]) -> Option<String> {
+    let encoding = Regex::new(r"^#.*?coding[:=]\s*([\w.-]+)").unwrap();
+    bytes
+        .split(|byte| *byte == b'\n')
+        .take(2)
+        .map(String::from_utf8_lossy)
+        .find_map(|line| {
+            encoding
+                .captures(&line)
+                .map(|captures| captures[1].to_lowercase())
+        })
+}
+
 pub fn is_stdin_file(path: &Path, configuration: &Configuration) -> bool {
     match &configuration.stdin_file_path {
         Some(stdin_path) => path == stdin_path.as_path(),
@@ -162,6 +212,57 @@ pub fn is_stdin_file(path: &Path, configuration: &Configuration) -> bool {
     }
 }
 
+// The sigils among the comments at the top of a Ruby file, before any code
+pub(crate) fn file_sigils(contents: &str) -> FileSigils {
+    let header: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .collect();
+    FileSigils {
+        pack_public: header.iter().any(|line| is_sigil(line, "pack_public")),
+        packs_strict: header.iter().any(|line| is_sigil(line, "packs_strict")),
+    }
+}
+
+// `# <name>: true`
+fn is_sigil(line: &str, name: &str) -> bool {
+    line.trim()
+        .strip_prefix('#')
+        .and_then(|comment| comment.trim().strip_prefix(name))
+        .and_then(|rest| rest.strip_prefix(':'))
+        .is_some_and(|value| value.trim() == "true")
+}
+
+// Paths written to todo files, caches and output always use `/`, so they are
+// the same for developers on Windows and everyone else
+// `path` relative to `base`, going up with `..` where needed. Both are
+// absolute.
+pub(crate) fn relative_path_from(path: &Path, base: &Path) -> PathBuf {
+    let path_components: Vec<_> = path.components().collect();
+    let base_components: Vec<_> = base.components().collect();
+    let common = path_components
+        .iter()
+        .zip(&base_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative: PathBuf = base_components[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .collect();
+    relative.extend(&path_components[common..]);
+    relative
+}
+
+pub(crate) fn to_slash_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if std::path::MAIN_SEPARATOR == '\\' {
+        path.replace('\\', "/")
+    } else {
+        path.into_owned()
+    }
+}
+
 pub fn get_absolute_path(
     path: String,
     configuration: &Configuration,
@@ -174,3 +275,88 @@ pub fn get_absolute_path(
         configuration.absolute_root.join(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path_from() {
+        assert_eq!(
+            PathBuf::from("packs/foo/app/services/foo.rb"),
+            relative_path_from(
+                Path::new("/app/packs/foo/app/services/foo.rb"),
+                Path::new("/app")
+            )
+        );
+        assert_eq!(
+            PathBuf::from("../foo/app/services/foo.rb"),
+            relative_path_from(
+                Path::new("/app/packs/foo/app/services/foo.rb"),
+                Path::new("/app/packs/bar")
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_source() {
+        assert_eq!(
+            Ok(String::from("class Foo; end")),
+            decode_source(b"\xEF\xBB\xBFclass Foo; end".to_vec())
+        );
+        assert_eq!(
+            Ok(String::from("# encoding: utf-8\nFoo = \"é\"")),
+            decode_source("# encoding: utf-8\nFoo = \"é\"".as_bytes().to_vec())
+        );
+        assert_eq!(
+            Ok(String::from("# -*- coding: ISO-8859-1 -*-\nFoo = \"é\"")),
+            decode_source(
+                b"# -*- coding: ISO-8859-1 -*-\nFoo = \"\xE9\"".to_vec()
+            )
+        );
+        assert_eq!(
+            Err(String::from("the `shift_jis` encoding is not supported")),
+            decode_source(
+                b"# encoding: Shift_JIS\nFoo = \"\x82\xA0\"".to_vec()
+            )
+        );
+        assert!(decode_source(b"Foo = \"\xE9\"".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_is_pack_public_sigil() {
+        assert!(is_sigil("# pack_public: true", "pack_public"));
+        assert!(is_sigil("#pack_public:true", "pack_public"));
+        assert!(!is_sigil("# pack_public: false", "pack_public"));
+        assert!(!is_sigil("# typed: strict", "pack_public"));
+    }
+
+    #[test]
+    fn test_file_sigils() {
+        assert_eq!(
+            FileSigils {
+                pack_public: true,
+                packs_strict: false,
+            },
+            file_sigils("# frozen_string_literal: true\n\n# pack_public: true\nmodule Bar\nend\n")
+        );
+        // Only the comments before any code count
+        assert_eq!(
+            FileSigils::default(),
+            file_sigils("module Bar\n  # packs_strict: true\nend\n")
+        );
+    }
+
+    #[test]
+    fn test_is_packs_strict_sigil() {
+        assert!(is_sigil("# packs_strict: true", "packs_strict"));
+        assert!(!is_sigil("# packs_strict: true", "pack_public"));
+        assert!(!is_sigil("# packs_strict_mode: true", "packs_strict"));
+    }
+
+    #[test]
+    fn test_to_slash_path() {
+        let path = Path::new("packs").join("foo").join("app/services/foo.rb");
+        assert_eq!("packs/foo/app/services/foo.rb", to_slash_path(&path));
+    }
+}
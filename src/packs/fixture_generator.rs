@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// How big a generated app is. Violations are dependency violations, each on
+// a different constant or file, so `check` reports exactly this many.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixtureSize {
+    pub packs: usize,
+    pub files_per_pack: usize,
+    pub violations: usize,
+}
+
+impl FixtureSize {
+    // Only packs that come before another pack can reference a constant of
+    // the next pack without declaring it
+    fn max_violations(&self) -> usize {
+        self.packs.saturating_sub(1) * self.files_per_pack * self.files_per_pack
+    }
+}
+
+// Writes a synthetic app to `path`: a chain of packs where each pack depends
+// on the one before it, with references to the next pack for violations.
+// Used to reproduce performance issues without sharing a real codebase.
+pub(crate) fn generate_fixture(
+    path: &Path,
+    size: FixtureSize,
+) -> Result<(), Box<dyn Error>> {
+    if size.packs == 0 || size.files_per_pack == 0 {
+        return Err("A fixture needs at least one pack and one file".into());
+    }
+    if size.violations > size.max_violations() {
+        return Err(format!(
+            "{} pack(s) with {} file(s) each can have at most {} violation(s)",
+            size.packs,
+            size.files_per_pack,
+            size.max_violations()
+        )
+        .into());
+    }
+    if path.exists() && fs::read_dir(path)?.next().is_some() {
+        return Err(format!("`{}` is not empty", path.display()).into());
+    }
+
+    fs::create_dir_all(path)?;
+    fs::write(
+        path.join("packwerk.yml"),
+        "# Generated by `pks generate-fixture`\ninclude:\n- \"**/*.rb\"\n",
+    )?;
+    fs::write(path.join("package.yml"), "enforce_dependencies: true\n")?;
+
+    for pack in 0..size.packs {
+        let pack_path = path.join(format!("packs/pack_{}", pack));
+        let services_path =
+            pack_path.join(format!("app/services/pack_{}", pack));
+        fs::create_dir_all(&services_path)?;
+        fs::write(pack_path.join("package.yml"), package_yml(pack))?;
+
+        for file in 0..size.files_per_pack {
+            fs::write(
+                services_path.join(format!("service_{}.rb", file)),
+                service(pack, file, size),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn package_yml(pack: usize) -> String {
+    if pack == 0 {
+        String::from("enforce_dependencies: true\n")
+    } else {
+        format!(
+            "enforce_dependencies: true\ndependencies:\n- packs/pack_{}\n",
+            pack - 1
+        )
+    }
+}
+
+fn service(pack: usize, file: usize, size: FixtureSize) -> String {
+    let mut references = vec![];
+    if pack > 0 {
+        references.push(format!("Pack{}::Service0", pack - 1));
+    }
+    references.extend(
+        violating_constants(pack, file, size)
+            .map(|service| format!("Pack{}::Service{}", pack + 1, service)),
+    );
+
+    let body: String = references
+        .iter()
+        .map(|reference| format!("      {}.new\n", reference))
+        .collect();
+    format!(
+        "module Pack{}\n  class Service{}\n    def call\n{}    end\n  end\nend\n",
+        pack, file, body
+    )
+}
+
+// Violation `n` is made from pack `n % (packs - 1)`, in the file and on the
+// constant of the next pack picked by the rest of `n`
+fn violating_constants(
+    pack: usize,
+    file: usize,
+    size: FixtureSize,
+) -> impl Iterator<Item = usize> {
+    let referencing_packs = size.packs.saturating_sub(1).max(1);
+    (0..size.violations).filter_map(move |violation| {
+        let rest = violation / referencing_packs;
+        (violation % referencing_packs == pack
+            && rest % size.files_per_pack == file)
+            .then_some(rest / size.files_per_pack)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violating_constants() {
+        let size = FixtureSize {
+            packs: 3,
+            files_per_pack: 2,
+            violations: 8,
+        };
+
+        let all: Vec<(usize, usize, usize)> = (0..size.packs)
+            .flat_map(|pack| {
+                (0..size.files_per_pack).flat_map(move |file| {
+                    violating_constants(pack, file, size)
+                        .map(move |service| (pack, file, service))
+                })
+            })
+            .collect();
+
+        assert_eq!(size.violations, all.len());
+        assert!(all.iter().all(|(pack, _, service)| {
+            *pack < size.packs - 1 && *service < size.files_per_pack
+        }));
+        assert_eq!(8, size.max_violations());
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use crate::packs::file_provider::{glob_listed_files, FileProvider};
+use crate::packs::package_todo::date_from_days;
+
+// The abbreviated hash of the commit checked out at the root, if it is a git
+// repository
+pub(crate) fn current_commit(absolute_root: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(absolute_root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?;
+    Some(commit.trim().to_owned()).filter(|commit| !commit.is_empty())
+}
+
+// How many commits touched each file in the last `months` months, keyed by
+// the file's path relative to the root
+pub(crate) fn commits_per_file(
+    absolute_root: &Path,
+    months: u32,
+) -> Result<HashMap<String, usize>, Box<dyn Error>> {
+    let since = format!("--since={} months ago", months);
+    let output = Command::new("git")
+        .args(["log", &since, "--name-only", "--format=", "--relative"])
+        .current_dir(absolute_root)
+        .output()
+        .map_err(|error| format!("Could not run `git log`: {}", error))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`git log` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(count_files(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// The commit that last changed a line, and the day it was committed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct LineOrigin {
+    pub commit: String,
+    pub date: String,
+}
+
+// The origin of each line of a file, by `git blame`, indexed by line number
+// minus one. Lines that aren't committed yet have no origin.
+pub(crate) fn blame(
+    absolute_root: &Path,
+    relative_file: &str,
+) -> Option<Vec<Option<LineOrigin>>> {
+    let output = Command::new("git")
+        .args(["blame", "--line-porcelain", "--", relative_file])
+        .current_dir(absolute_root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(line_origins(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Whether `commit` is `other` or one of its ancestors
+pub(crate) fn is_ancestor(
+    absolute_root: &Path,
+    commit: &str,
+    other: &str,
+) -> bool {
+    Command::new("git")
+        .args(["merge-base", "--is-ancestor", commit, other])
+        .current_dir(absolute_root)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+// The last commit on the current branch made before `date` (YYYY-MM-DD), if
+// any
+pub(crate) fn last_commit_before(
+    absolute_root: &Path,
+    date: &str,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let before = format!("--before={} 00:00:00", date);
+    let commit = run(absolute_root, &["rev-list", "-1", &before, "HEAD"])?;
+    Ok(Some(commit.trim().to_owned()).filter(|commit| !commit.is_empty()))
+}
+
+// The files in `commit`, relative to the root
+pub(crate) fn files_at(
+    absolute_root: &Path,
+    commit: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let files = run(absolute_root, &["ls-tree", "-r", "--name-only", commit])?;
+    Ok(files.lines().map(str::to_owned).collect())
+}
+
+// The contents of a file, relative to the root, as of `commit`
+pub(crate) fn file_at(
+    absolute_root: &Path,
+    commit: &str,
+    relative_file: &str,
+) -> Result<String, Box<dyn Error>> {
+    run(
+        absolute_root,
+        &["show", &format!("{}:./{}", commit, relative_file)],
+    )
+}
+
+// The files that differ between `commit` and the working tree, relative to
+// the root, including the ones that are deleted
+pub(crate) fn changed_files(
+    absolute_root: &Path,
+    commit: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let files = run(
+        absolute_root,
+        &["diff", "--name-only", "--relative", commit],
+    )?;
+    Ok(files.lines().map(str::to_owned).collect())
+}
+
+// The files of a commit, read from the git object database by one
+// `git cat-file --batch` process, so neither a working tree nor a checkout
+// is needed, and bare repositories work too
+pub(crate) struct GitObjects {
+    absolute_root: PathBuf,
+    files: Vec<PathBuf>,
+    // The blob of each file, by its absolute path
+    blobs: HashMap<PathBuf, String>,
+    // Files are parsed in parallel, but the process answers one request at
+    // a time
+    batch: Mutex<CatFileBatch>,
+}
+
+struct CatFileBatch {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Drop for CatFileBatch {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+impl GitObjects {
+    pub(crate) fn new(
+        absolute_root: &Path,
+        git_ref: &str,
+    ) -> Result<GitObjects, Box<dyn Error>> {
+        let commit = run(
+            absolute_root,
+            &["rev-parse", "--verify", &format!("{}^{{commit}}", git_ref)],
+        )
+        .map_err(|_| format!("`{}` is not a commit", git_ref))?
+        .trim()
+        .to_owned();
+
+        // `ls-tree` lists the files under the current directory, relative
+        // to it, so they are relative to the root even if it isn't the top
+        // of the repository. Each entry is `<mode> <type> <object>\t<path>`.
+        let tree = run(absolute_root, &["ls-tree", "-r", "-z", &commit])?;
+        let mut blobs = HashMap::new();
+        for entry in tree.split('\0').filter(|entry| !entry.is_empty()) {
+            let Some((metadata, path)) = entry.split_once('\t') else {
+                continue;
+            };
+            // Symlinks and submodules have no contents of their own
+            let mut metadata = metadata.split(' ');
+            let (Some(mode), Some("blob"), Some(object)) =
+                (metadata.next(), metadata.next(), metadata.next())
+            else {
+                continue;
+            };
+            if mode == "120000" {
+                continue;
+            }
+            blobs.insert(absolute_root.join(path), object.to_owned());
+        }
+        let mut files: Vec<PathBuf> = blobs.keys().cloned().collect();
+        files.sort();
+
+        let mut process = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .current_dir(absolute_root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| {
+                format!("Could not run `git cat-file`: {}", error)
+            })?;
+        let stdin = process.stdin.take().expect("stdin is piped");
+        let stdout =
+            BufReader::new(process.stdout.take().expect("stdout is piped"));
+
+        Ok(GitObjects {
+            absolute_root: absolute_root.to_path_buf(),
+            files,
+            blobs,
+            batch: Mutex::new(CatFileBatch {
+                process,
+                stdin,
+                stdout,
+            }),
+        })
+    }
+}
+
+impl FileProvider for GitObjects {
+    fn files(&self) -> Option<&[PathBuf]> {
+        Some(&self.files)
+    }
+
+    fn read(&self, absolute_path: &Path) -> io::Result<Vec<u8>> {
+        let object = self.blobs.get(absolute_path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not in the commit", absolute_path.display()),
+            )
+        })?;
+
+        // Each object is printed as `<object> blob <size>`, then its
+        // contents and a newline
+        let mut batch = self.batch.lock().unwrap();
+        writeln!(batch.stdin, "{}", object)?;
+        batch.stdin.flush()?;
+        let mut header = String::new();
+        batch.stdout.read_line(&mut header)?;
+        let size: usize = header
+            .split(' ')
+            .nth(2)
+            .and_then(|size| size.trim().parse().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unexpected `git cat-file` output: {}", header),
+                )
+            })?;
+        let mut contents = vec![0; size + 1];
+        batch.stdout.read_exact(&mut contents)?;
+        contents.pop();
+        Ok(contents)
+    }
+
+    fn is_file(&self, absolute_path: &Path) -> bool {
+        self.blobs.contains_key(absolute_path)
+    }
+
+    fn glob(&self, pattern: &str) -> Vec<PathBuf> {
+        glob_listed_files(&self.files, &self.absolute_root, pattern)
+    }
+}
+
+fn run(absolute_root: &Path, args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(absolute_root)
+        .output()
+        .map_err(|error| {
+            format!("Could not run `git {}`: {}", args[0], error)
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "`git {}` failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// `--line-porcelain` prints a header for every line: the commit, then
+// fields like `committer-time`, then the line itself after a tab
+fn line_origins(blame: &str) -> Vec<Option<LineOrigin>> {
+    let mut origins = vec![];
+    let mut commit: Option<&str> = None;
+    let mut date = String::new();
+    for line in blame.lines() {
+        if line.starts_with('\t') {
+            let origin = commit
+                .filter(|commit| commit.chars().any(|c| c != '0'))
+                .map(|commit| LineOrigin {
+                    commit: commit.to_owned(),
+                    date: date.clone(),
+                });
+            origins.push(origin);
+            commit = None;
+        } else if commit.is_none() {
+            commit = line.split(' ').next();
+        } else if let Some(time) = line.strip_prefix("committer-time ") {
+            let seconds: u64 = time.trim().parse().unwrap_or_default();
+            date = date_from_days(seconds / 86_400);
+        }
+    }
+    origins
+}
+
+// `git log --name-only --format=` prints the files of each commit, one per
+// line, with a blank line between commits
+fn count_files(log: &str) -> HashMap<String, usize> {
+    let mut commits: HashMap<String, usize> = HashMap::new();
+    for file in log.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        *commits.entry(file.to_owned()).or_default() += 1;
+    }
+    commits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_files() {
+        let log = "\npacks/foo/app/services/foo.rb\npacks/bar/app/services/bar.rb\n\npacks/foo/app/services/foo.rb\n";
+        let commits = count_files(log);
+
+        assert_eq!(Some(&2), commits.get("packs/foo/app/services/foo.rb"));
+        assert_eq!(Some(&1), commits.get("packs/bar/app/services/bar.rb"));
+        assert_eq!(2, commits.len());
+    }
+
+    #[test]
+    fn test_line_origins() {
+        let blame = "\
+4a0c1f7e0b6a4c1d9e3f5b7a8c9d0e1f2a3b4c5d 1 1 2
+author Jane
+committer-time 1704067200
+filename foo.rb
+\tclass Foo
+4a0c1f7e0b6a4c1d9e3f5b7a8c9d0e1f2a3b4c5d 2 2
+author Jane
+committer-time 1704067200
+filename foo.rb
+\t  Bar
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+committer-time 1718000000
+filename foo.rb
+\tend
+";
+
+        assert_eq!(
+            vec![
+                Some(LineOrigin {
+                    commit: String::from(
+                        "4a0c1f7e0b6a4c1d9e3f5b7a8c9d0e1f2a3b4c5d"
+                    ),
+                    date: String::from("2024-01-01"),
+                }),
+                Some(LineOrigin {
+                    commit: String::from(
+                        "4a0c1f7e0b6a4c1d9e3f5b7a8c9d0e1f2a3b4c5d"
+                    ),
+                    date: String::from("2024-01-01"),
+                }),
+                None,
+            ],
+            line_origins(blame)
+        );
+    }
+}
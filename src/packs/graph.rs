@@ -0,0 +1,384 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::Write;
+
+use petgraph::algo::tarjan_scc;
+use petgraph::prelude::DiGraphMap;
+
+use super::reference_extractor::get_all_references;
+use super::Configuration;
+
+// What `graph` colors the packs by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorBy {
+    Layer,
+    Owner,
+}
+
+#[derive(Debug, Default)]
+pub struct GraphOptions {
+    pub cycles_only: bool,
+    pub min_references: usize,
+    pub focus: Option<String>,
+    pub max_depth: Option<usize>,
+    pub color_by: Option<ColorBy>,
+}
+
+// The number of references from one pack to another
+type Edges = BTreeMap<(String, String), usize>;
+
+const PALETTE: [&str; 12] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462",
+    "#b3de69", "#fccde5", "#d9d9d9", "#bc80bd", "#ccebc5", "#ffed6f",
+];
+
+// Prints the packs and the references between them in the DOT format, e.g.
+// for `dot -Tsvg`. Each edge is labeled with its number of references.
+pub(crate) fn graph(
+    configuration: &Configuration,
+    options: &GraphOptions,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(focus) = &options.focus {
+        configuration.pack_set.for_pack(focus).map_err(|_| {
+            format!("`{}` not found. Try `packs list-packs` to debug.", focus)
+        })?;
+    }
+
+    let mut edges: Edges = BTreeMap::new();
+    for reference in
+        get_all_references(configuration, &configuration.included_files)
+    {
+        if let Some(defining_pack_name) = reference.defining_pack_name {
+            if defining_pack_name != reference.referencing_pack_name {
+                *edges
+                    .entry((
+                        reference.referencing_pack_name,
+                        defining_pack_name,
+                    ))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let edges = filter_edges(edges, options);
+    let colors: HashMap<String, String> = match options.color_by {
+        Some(color_by) => node_colors(configuration, &edges, options, color_by),
+        None => HashMap::new(),
+    };
+
+    print!("{}", to_dot(&edges, options.focus.as_deref(), &colors));
+    Ok(())
+}
+
+// Packs that depend on each other, which have to move together, or a single
+// pack
+#[derive(Debug, PartialEq, Eq)]
+struct PackGroup {
+    depth: usize,
+    packs: Vec<String>,
+}
+
+// Prints the packs by their declared dependencies, from the packs that
+// depend on nothing up, e.g. to plan in which order to extract them. Each
+// pack is prefixed with its depth, the length of its longest dependency
+// chain. Packs in a cycle share a line.
+pub(crate) fn order(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    let pack_names: BTreeSet<&str> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| pack.name.as_str())
+        .collect();
+    let dependencies: BTreeMap<&str, BTreeSet<&str>> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| {
+            let dependencies = pack
+                .dependencies
+                .iter()
+                .map(String::as_str)
+                .filter(|dependency| pack_names.contains(dependency))
+                .collect();
+            (pack.name.as_str(), dependencies)
+        })
+        .collect();
+
+    for group in pack_order(&dependencies) {
+        if group.packs.len() > 1 {
+            println!("{} {} (cycle)", group.depth, group.packs.join(", "));
+        } else {
+            println!("{} {}", group.depth, group.packs.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn pack_order(dependencies: &BTreeMap<&str, BTreeSet<&str>>) -> Vec<PackGroup> {
+    let mut graph = DiGraphMap::<&str, ()>::new();
+    for (pack, pack_dependencies) in dependencies {
+        graph.add_node(pack);
+        for dependency in pack_dependencies {
+            graph.add_edge(pack, dependency, ());
+        }
+    }
+
+    // Components come out of `tarjan_scc` dependencies first, so the depth
+    // of every dependency is known by the time a component is reached
+    let mut component_for_pack: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<PackGroup> = vec![];
+    for (index, component) in tarjan_scc(&graph).into_iter().enumerate() {
+        for pack in &component {
+            component_for_pack.insert(pack, index);
+        }
+        let depth = component
+            .iter()
+            .flat_map(|pack| graph.neighbors(pack))
+            .map(|dependency| component_for_pack[dependency])
+            .filter(|dependency_component| *dependency_component != index)
+            .map(|dependency_component| groups[dependency_component].depth + 1)
+            .max()
+            .unwrap_or(0);
+        let mut packs: Vec<String> =
+            component.into_iter().map(String::from).collect();
+        packs.sort();
+        groups.push(PackGroup { depth, packs });
+    }
+
+    groups.sort_by(|a, b| (a.depth, &a.packs).cmp(&(b.depth, &b.packs)));
+    groups
+}
+
+fn filter_edges(edges: Edges, options: &GraphOptions) -> Edges {
+    let mut edges: Edges = edges
+        .into_iter()
+        .filter(|(_, count)| *count >= options.min_references)
+        .collect();
+
+    if options.cycles_only {
+        let cyclic = cyclic_edges(&edges);
+        edges.retain(|edge, _| cyclic.contains(edge));
+    }
+
+    if let Some(focus) = &options.focus {
+        let depths = depths_from(&edges, focus);
+        let within_depth = |pack: &String| {
+            depths.get(pack).is_some_and(|depth| {
+                options
+                    .max_depth
+                    .map_or(true, |max_depth| *depth <= max_depth)
+            })
+        };
+        edges.retain(|(from, to), _| within_depth(from) && within_depth(to));
+    }
+
+    edges
+}
+
+// Edges between packs in the same strongly connected component, i.e. those
+// that are part of at least one cycle
+fn cyclic_edges(edges: &Edges) -> BTreeSet<(String, String)> {
+    let mut graph = DiGraphMap::<&str, ()>::new();
+    for (from, to) in edges.keys() {
+        graph.add_edge(from.as_str(), to.as_str(), ());
+    }
+
+    let mut component_for_pack: HashMap<&str, usize> = HashMap::new();
+    for (index, component) in tarjan_scc(&graph).into_iter().enumerate() {
+        if component.len() > 1 {
+            for pack in component {
+                component_for_pack.insert(pack, index);
+            }
+        }
+    }
+
+    edges
+        .keys()
+        .filter(|(from, to)| {
+            let from_component = component_for_pack.get(from.as_str());
+            from_component.is_some()
+                && from_component == component_for_pack.get(to.as_str())
+        })
+        .cloned()
+        .collect()
+}
+
+// How many edges away each pack is from the focus pack, in either direction
+fn depths_from(edges: &Edges, focus: &str) -> HashMap<String, usize> {
+    let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (from, to) in edges.keys() {
+        neighbors.entry(from).or_default().push(to);
+        neighbors.entry(to).or_default().push(from);
+    }
+
+    let mut depths: HashMap<String, usize> =
+        HashMap::from([(focus.to_owned(), 0)]);
+    let mut queue = VecDeque::from([(focus, 0)]);
+    while let Some((pack, depth)) = queue.pop_front() {
+        for neighbor in neighbors.get(pack).into_iter().flatten() {
+            if !depths.contains_key(*neighbor) {
+                depths.insert(neighbor.to_string(), depth + 1);
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+    }
+    depths
+}
+
+fn node_colors(
+    configuration: &Configuration,
+    edges: &Edges,
+    options: &GraphOptions,
+    color_by: ColorBy,
+) -> HashMap<String, String> {
+    let values: BTreeMap<String, String> =
+        nodes(edges, options.focus.as_deref())
+            .into_iter()
+            .filter_map(|name| {
+                let pack = configuration.pack_set.for_pack(&name).ok()?;
+                let value = match color_by {
+                    ColorBy::Layer => pack.layer.clone(),
+                    ColorBy::Owner => pack.owner_name().map(String::from),
+                }?;
+                Some((name, value))
+            })
+            .collect();
+
+    let distinct_values: BTreeSet<&String> = values.values().collect();
+    let color_for_value: HashMap<&String, &str> = distinct_values
+        .into_iter()
+        .zip(PALETTE.iter().cycle())
+        .map(|(value, color)| (value, *color))
+        .collect();
+
+    values
+        .iter()
+        .map(|(name, value)| (name.clone(), color_for_value[value].to_owned()))
+        .collect()
+}
+
+fn nodes(edges: &Edges, focus: Option<&str>) -> BTreeSet<String> {
+    edges
+        .keys()
+        .flat_map(|(from, to)| [from.clone(), to.clone()])
+        .chain(focus.map(String::from))
+        .collect()
+}
+
+fn to_dot(
+    edges: &Edges,
+    focus: Option<&str>,
+    colors: &HashMap<String, String>,
+) -> String {
+    let mut dot = String::from("digraph packs {\n");
+    for node in nodes(edges, focus) {
+        match colors.get(&node) {
+            Some(color) => writeln!(
+                dot,
+                "  \"{}\" [style=filled, fillcolor=\"{}\"];",
+                node, color
+            ),
+            None => writeln!(dot, "  \"{}\";", node),
+        }
+        .unwrap();
+    }
+    for ((from, to), count) in edges {
+        writeln!(dot, "  \"{}\" -> \"{}\" [label=\"{}\"];", from, to, count)
+            .unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn edges(edges: &[(&str, &str, usize)]) -> Edges {
+        edges
+            .iter()
+            .map(|(from, to, count)| {
+                ((from.to_string(), to.to_string()), *count)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_order() {
+        let dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::from([
+            ("packs/a", BTreeSet::from(["packs/b", "packs/d"])),
+            ("packs/b", BTreeSet::from(["packs/c"])),
+            ("packs/c", BTreeSet::from(["packs/b", "packs/d"])),
+            ("packs/d", BTreeSet::new()),
+            ("packs/e", BTreeSet::new()),
+        ]);
+        let group = |depth: usize, packs: &[&str]| PackGroup {
+            depth,
+            packs: packs.iter().map(|pack| pack.to_string()).collect(),
+        };
+
+        assert_eq!(
+            vec![
+                group(0, &["packs/d"]),
+                group(0, &["packs/e"]),
+                group(1, &["packs/b", "packs/c"]),
+                group(2, &["packs/a"]),
+            ],
+            pack_order(&dependencies)
+        );
+    }
+
+    #[test]
+    fn test_filter_edges() {
+        let all_edges = edges(&[
+            ("packs/a", "packs/b", 3),
+            ("packs/b", "packs/a", 1),
+            ("packs/b", "packs/c", 5),
+            ("packs/c", "packs/d", 2),
+        ]);
+
+        let cycles_only = GraphOptions {
+            cycles_only: true,
+            ..GraphOptions::default()
+        };
+        assert_eq!(
+            edges(&[("packs/a", "packs/b", 3), ("packs/b", "packs/a", 1)]),
+            filter_edges(all_edges.clone(), &cycles_only)
+        );
+
+        let min_references = GraphOptions {
+            min_references: 3,
+            ..GraphOptions::default()
+        };
+        assert_eq!(
+            edges(&[("packs/a", "packs/b", 3), ("packs/b", "packs/c", 5)]),
+            filter_edges(all_edges.clone(), &min_references)
+        );
+
+        let focus = GraphOptions {
+            focus: Some(String::from("packs/a")),
+            max_depth: Some(1),
+            ..GraphOptions::default()
+        };
+        assert_eq!(
+            edges(&[("packs/a", "packs/b", 3), ("packs/b", "packs/a", 1)]),
+            filter_edges(all_edges, &focus)
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let colors = HashMap::from([(
+            String::from("packs/a"),
+            String::from(PALETTE[0]),
+        )]);
+
+        assert_eq!(
+            "digraph packs {\n  \"packs/a\" [style=filled, fillcolor=\"#8dd3c7\"];\n  \"packs/b\";\n  \"packs/a\" -> \"packs/b\" [label=\"3\"];\n}\n",
+            to_dot(&edges(&[("packs/a", "packs/b", 3)]), None, &colors)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::json;
+
+use super::checker::{get_all_violations, get_checkers, ViolationIdentifier};
+use crate::packs::Configuration;
+
+const STATE_FILE_NAME: &str = "health_report.json";
+
+// Where `report` sends its summary
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WebhookFormat {
+    // The summary as it is
+    #[default]
+    Json,
+    // A message with Slack blocks, for an incoming webhook
+    Slack,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct OffenderPack {
+    pack: String,
+    violations_count: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct HealthReport {
+    packs_count: usize,
+    violations_count: usize,
+    recorded_violations_count: usize,
+    // Compared to the previous report, if there is one
+    new_violations_count: Option<usize>,
+    resolved_violations_count: Option<usize>,
+    top_offender_packs: Vec<OffenderPack>,
+}
+
+// Summarizes the health of the architecture for scheduled runs, e.g. a
+// nightly job, and posts it to a webhook if there is one. The violations
+// are kept in `state_file` to tell what changed since the previous run.
+pub(crate) fn health_report(
+    configuration: &Configuration,
+    webhook: Option<&str>,
+    format: WebhookFormat,
+    state_file: Option<PathBuf>,
+    top: usize,
+) -> Result<(), Box<dyn Error>> {
+    let checkers = get_checkers(configuration);
+    let violations: BTreeSet<ViolationIdentifier> = get_all_violations(
+        configuration,
+        &configuration.included_files,
+        &checkers,
+    )
+    .into_iter()
+    .map(|violation| violation.identifier)
+    .collect();
+
+    let state_file = state_file
+        .unwrap_or_else(|| configuration.cache_directory.join(STATE_FILE_NAME));
+    let previous_violations = read_state(&state_file)?;
+    let report = summarize(
+        configuration,
+        &violations,
+        previous_violations.as_ref(),
+        top,
+    );
+
+    let payload = match format {
+        WebhookFormat::Json => serde_json::to_string_pretty(&report)?,
+        WebhookFormat::Slack => {
+            serde_json::to_string_pretty(&slack_message(&report))?
+        }
+    };
+    match webhook {
+        Some(url) => post(url, &payload)?,
+        None => println!("{}", payload),
+    }
+
+    if !configuration.cache_read_only {
+        write_state(&state_file, &violations)?;
+    }
+    Ok(())
+}
+
+fn summarize(
+    configuration: &Configuration,
+    violations: &BTreeSet<ViolationIdentifier>,
+    previous_violations: Option<&BTreeSet<ViolationIdentifier>>,
+    top: usize,
+) -> HealthReport {
+    let recorded_violations = &configuration.pack_set.all_violations;
+    let mut counts_by_pack: BTreeMap<&str, usize> = BTreeMap::new();
+    for violation in violations {
+        *counts_by_pack
+            .entry(&violation.referencing_pack_name)
+            .or_default() += 1;
+    }
+    let mut top_offender_packs: Vec<OffenderPack> = counts_by_pack
+        .into_iter()
+        .map(|(pack, violations_count)| OffenderPack {
+            pack: pack.to_owned(),
+            violations_count,
+        })
+        .collect();
+    // Stable, so packs with as many violations stay sorted by name
+    top_offender_packs
+        .sort_by_key(|offender| Reverse(offender.violations_count));
+    top_offender_packs.truncate(top);
+
+    HealthReport {
+        packs_count: configuration.pack_set.packs.len(),
+        violations_count: violations.len(),
+        recorded_violations_count: violations
+            .iter()
+            .filter(|violation| recorded_violations.contains(violation))
+            .count(),
+        new_violations_count: previous_violations
+            .map(|previous| violations.difference(previous).count()),
+        resolved_violations_count: previous_violations
+            .map(|previous| previous.difference(violations).count()),
+        top_offender_packs,
+    }
+}
+
+fn slack_message(report: &HealthReport) -> serde_json::Value {
+    let mut summary = format!(
+        "*{} violation(s)* across {} pack(s), {} of them recorded in todo files",
+        report.violations_count,
+        report.packs_count,
+        report.recorded_violations_count
+    );
+    if let (Some(new), Some(resolved)) = (
+        report.new_violations_count,
+        report.resolved_violations_count,
+    ) {
+        summary.push_str(&format!(
+            "\n{} new and {} resolved since the previous report",
+            new, resolved
+        ));
+    }
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": summary },
+    })];
+    if !report.top_offender_packs.is_empty() {
+        let offenders: Vec<String> = report
+            .top_offender_packs
+            .iter()
+            .map(|offender| {
+                format!("• `{}`: {}", offender.pack, offender.violations_count)
+            })
+            .collect();
+        blocks.push(json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!("*Top offender packs*\n{}", offenders.join("\n")),
+            },
+        }));
+    }
+
+    json!({ "text": summary, "blocks": blocks })
+}
+
+fn post(url: &str, payload: &str) -> Result<(), Box<dyn Error>> {
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .send_string(payload)
+        // The errors of ureq include the URL, which is usually a secret
+        .map_err(|e| {
+            let reason = match e {
+                ureq::Error::Status(status, _) => {
+                    format!("it responded with status {}", status)
+                }
+                ureq::Error::Transport(transport) => {
+                    transport.kind().to_string()
+                }
+            };
+            format!("Failed to post the report to the webhook: {}", reason)
+        })?;
+    Ok(())
+}
+
+fn read_state(
+    path: &Path,
+) -> Result<Option<BTreeSet<ViolationIdentifier>>, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(path)?;
+    let violations = serde_json::from_str(&contents).map_err(|e| {
+        format!("Failed to parse the report at {}: {}", path.display(), e)
+    })?;
+    Ok(Some(violations))
+}
+
+fn write_state(
+    path: &Path,
+    violations: &BTreeSet<ViolationIdentifier>,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string(violations)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn violation(
+        file: &str,
+        referencing_pack_name: &str,
+    ) -> ViolationIdentifier {
+        ViolationIdentifier {
+            violation_type: String::from("dependency"),
+            file: String::from(file),
+            constant_name: String::from("::Bar"),
+            referencing_pack_name: String::from(referencing_pack_name),
+            defining_pack_name: String::from("packs/bar"),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let configuration = Configuration::default();
+        let violations = BTreeSet::from([
+            violation("packs/foo/a.rb", "packs/foo"),
+            violation("packs/foo/b.rb", "packs/foo"),
+            violation("packs/baz/c.rb", "packs/baz"),
+        ]);
+        let previous_violations = BTreeSet::from([
+            violation("packs/foo/a.rb", "packs/foo"),
+            violation("packs/qux/d.rb", "packs/qux"),
+        ]);
+
+        assert_eq!(
+            HealthReport {
+                packs_count: 1,
+                violations_count: 3,
+                recorded_violations_count: 0,
+                new_violations_count: Some(2),
+                resolved_violations_count: Some(1),
+                top_offender_packs: vec![OffenderPack {
+                    pack: String::from("packs/foo"),
+                    violations_count: 2,
+                }],
+            },
+            summarize(
+                &configuration,
+                &violations,
+                Some(&previous_violations),
+                1
+            )
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use super::reference_extractor::get_all_references;
+use super::Configuration;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HeatmapFormat {
+    // One row per referencing pack and one column per defining pack
+    #[default]
+    Csv,
+    Json,
+}
+
+// The number of references from each pack (rows) to each pack (columns),
+// both in the order of `packs`
+#[derive(Debug, PartialEq, Serialize)]
+struct Heatmap {
+    packs: Vec<String>,
+    references: Vec<Vec<f64>>,
+}
+
+// Prints how many references each pack makes to constants of each other
+// pack, as a matrix for heatmap tools. With `normalize`, the
+// counts are divided by the number of files in the referencing pack, so big
+// packs don't outshine small ones.
+pub(crate) fn heatmap(
+    configuration: &Configuration,
+    format: HeatmapFormat,
+    normalize: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut packs: Vec<String> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| pack.name.clone())
+        .collect();
+    packs.sort();
+
+    let references: Vec<(String, String)> =
+        get_all_references(configuration, &configuration.included_files)
+            .into_iter()
+            .filter_map(|reference| {
+                Some((
+                    reference.referencing_pack_name,
+                    reference.defining_pack_name?,
+                ))
+            })
+            .collect();
+    let mut heatmap = count_references(packs, &references);
+
+    if normalize {
+        let mut file_counts: HashMap<&str, usize> = HashMap::new();
+        for file in &configuration.included_files {
+            if let Some(pack) = configuration.pack_set.for_file(file) {
+                *file_counts.entry(pack.name.as_str()).or_default() += 1;
+            }
+        }
+        normalize_by_file_count(&mut heatmap, &file_counts);
+    }
+
+    match format {
+        HeatmapFormat::Csv => print!("{}", to_csv(&heatmap)),
+        HeatmapFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&heatmap)?)
+        }
+    }
+    Ok(())
+}
+
+fn count_references(
+    packs: Vec<String>,
+    references: &[(String, String)],
+) -> Heatmap {
+    let index: HashMap<&str, usize> = packs
+        .iter()
+        .enumerate()
+        .map(|(index, pack)| (pack.as_str(), index))
+        .collect();
+    let mut counts = vec![vec![0.0; packs.len()]; packs.len()];
+    for (referencing_pack, defining_pack) in references {
+        // References within a pack would only fill the diagonal
+        if referencing_pack == defining_pack {
+            continue;
+        }
+        if let (Some(row), Some(column)) = (
+            index.get(referencing_pack.as_str()),
+            index.get(defining_pack.as_str()),
+        ) {
+            counts[*row][*column] += 1.0;
+        }
+    }
+    Heatmap {
+        packs,
+        references: counts,
+    }
+}
+
+// References per file of the referencing pack, to 4 decimal places
+fn normalize_by_file_count(
+    heatmap: &mut Heatmap,
+    file_counts: &HashMap<&str, usize>,
+) {
+    for (pack, row) in heatmap.packs.iter().zip(&mut heatmap.references) {
+        let file_count = file_counts.get(pack.as_str()).copied().unwrap_or(0);
+        for count in row.iter_mut() {
+            *count = if file_count == 0 {
+                0.0
+            } else {
+                (*count / file_count as f64 * 10000.0).round() / 10000.0
+            };
+        }
+    }
+}
+
+fn to_csv(heatmap: &Heatmap) -> String {
+    let mut csv = String::from("referencing_pack");
+    for pack in &heatmap.packs {
+        write!(csv, ",{}", pack).unwrap();
+    }
+    csv.push('\n');
+    for (pack, row) in heatmap.packs.iter().zip(&heatmap.references) {
+        csv.push_str(pack);
+        for count in row {
+            write!(csv, ",{}", count).unwrap();
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn heatmap() -> Heatmap {
+        let packs = vec![String::from("packs/bar"), String::from("packs/foo")];
+        let reference = |from: &str, to: &str| (from.to_owned(), to.to_owned());
+        count_references(
+            packs,
+            &[
+                reference("packs/foo", "packs/bar"),
+                reference("packs/foo", "packs/bar"),
+                reference("packs/foo", "packs/foo"),
+                reference("packs/bar", "packs/baz"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            "\
+referencing_pack,packs/bar,packs/foo
+packs/bar,0,0
+packs/foo,2,0
+",
+            to_csv(&heatmap())
+        );
+    }
+
+    #[test]
+    fn test_normalize_by_file_count() {
+        let mut heatmap = heatmap();
+        normalize_by_file_count(
+            &mut heatmap,
+            &HashMap::from([("packs/foo", 3)]),
+        );
+
+        assert_eq!(vec![vec![0.0, 0.0], vec![0.6667, 0.0]], heatmap.references);
+    }
+}
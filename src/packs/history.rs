@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::git;
+use super::package_todo::{today, CONSOLIDATED_TODO_FILE_NAME};
+use super::{Configuration, PackageTodo};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistoryFormat {
+    // One `month,pack,violation_type,count` row per count
+    #[default]
+    Csv,
+    Json,
+}
+
+// The violations recorded in todo files as of the end of a month
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct MonthlyCounts {
+    // YYYY-MM
+    month: String,
+    // The last commit of the month
+    commit: String,
+    total: usize,
+    // By referencing pack, then violation type
+    counts: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+// Counts the violations recorded in todo files at the end of each of the
+// last `months` months, including this one, by reading the todo files of
+// the last commit of each month. Months before the first commit are left
+// out.
+pub(crate) fn history(
+    configuration: &Configuration,
+    months: u32,
+    since: Option<&str>,
+    format: HistoryFormat,
+) -> Result<(), Box<dyn Error>> {
+    let today = today();
+    let months = match since {
+        Some(since) => months_since(&today, since)?,
+        None => months,
+    };
+    let absolute_root = &configuration.absolute_root;
+    let mut history = vec![];
+    for (month, next_month) in month_starts(&today, months) {
+        let Some(commit) = git::last_commit_before(absolute_root, &next_month)?
+        else {
+            continue;
+        };
+        let mut counts: BTreeMap<String, BTreeMap<String, usize>> =
+            BTreeMap::new();
+        for file in git::files_at(absolute_root, &commit)? {
+            if !is_todo_file(&file) {
+                continue;
+            }
+            let contents = git::file_at(absolute_root, &commit, &file)?;
+            for (pack_name, package_todo) in package_todos(&file, &contents) {
+                add_counts(&mut counts, pack_name, &package_todo);
+            }
+        }
+        history.push(MonthlyCounts {
+            month,
+            commit,
+            total: counts.values().flat_map(BTreeMap::values).sum(),
+            counts,
+        });
+    }
+
+    match format {
+        HistoryFormat::Csv => print!("{}", to_csv(&history)),
+        HistoryFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&history)?)
+        }
+    }
+    Ok(())
+}
+
+// How many months there are from `since` (YYYY-MM) to this one, including
+// both
+fn months_since(today: &str, since: &str) -> Result<u32, Box<dyn Error>> {
+    let invalid = || format!("`{}` is not a month like 2024-01", since);
+    let (year, month) = since.split_once('-').ok_or_else(invalid)?;
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month: i32 = month.parse().map_err(|_| invalid())?;
+    if year.to_string().len() != 4 || !(1..=12).contains(&month) {
+        return Err(invalid().into());
+    }
+    let current = month_index(today);
+    let since_index = year * 12 + month - 1;
+    if since_index > current {
+        return Err(format!("`{}` is in the future", since).into());
+    }
+    Ok((current - since_index + 1) as u32)
+}
+
+// Months since the start of year 0, for YYYY-MM(-DD)
+fn month_index(date: &str) -> i32 {
+    let year: i32 = date[0..4].parse().unwrap_or_default();
+    let month: i32 = date[5..7].parse().unwrap_or(1);
+    year * 12 + month - 1
+}
+
+// Each of the last `months` months (YYYY-MM), oldest first, with the first
+// day of the month after it (YYYY-MM-DD)
+fn month_starts(today: &str, months: u32) -> Vec<(String, String)> {
+    let current = month_index(today);
+    (0..months as i32)
+        .rev()
+        .map(|months_ago| {
+            let index = current - months_ago;
+            let next = index + 1;
+            (
+                format!("{:04}-{:02}", index / 12, index % 12 + 1),
+                format!("{:04}-{:02}-01", next / 12, next % 12 + 1),
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn is_todo_file(relative_file: &str) -> bool {
+    relative_file == CONSOLIDATED_TODO_FILE_NAME
+        || Path::new(relative_file).ends_with("package_todo.yml")
+}
+
+// The todo of each referencing pack in a todo file. Todo files that no
+// longer parse are skipped, since history can't be fixed.
+pub(crate) fn package_todos(
+    relative_file: &str,
+    contents: &str,
+) -> Vec<(String, PackageTodo)> {
+    if relative_file == CONSOLIDATED_TODO_FILE_NAME {
+        let package_todos: Option<BTreeMap<String, PackageTodo>> =
+            serde_yaml::from_str(contents).unwrap_or_default();
+        return package_todos.unwrap_or_default().into_iter().collect();
+    }
+
+    let pack_name = match relative_file.rsplit_once('/') {
+        Some((pack_name, _)) => pack_name.to_owned(),
+        None => String::from("."),
+    };
+    serde_yaml::from_str::<PackageTodo>(contents)
+        .map(|package_todo| vec![(pack_name, package_todo)])
+        .unwrap_or_default()
+}
+
+// A violation is recorded once per file it is in
+fn add_counts(
+    counts: &mut BTreeMap<String, BTreeMap<String, usize>>,
+    pack_name: String,
+    package_todo: &PackageTodo,
+) {
+    let pack_counts = counts.entry(pack_name).or_default();
+    for violation_group in package_todo
+        .violations_by_defining_pack
+        .values()
+        .flat_map(BTreeMap::values)
+    {
+        for violation_type in &violation_group.violation_types {
+            *pack_counts.entry(violation_type.clone()).or_default() +=
+                violation_group.files.len();
+        }
+    }
+}
+
+fn to_csv(history: &[MonthlyCounts]) -> String {
+    let mut csv = String::from("month,pack,violation_type,count\n");
+    for monthly_counts in history {
+        for (pack_name, pack_counts) in &monthly_counts.counts {
+            for (violation_type, count) in pack_counts {
+                writeln!(
+                    csv,
+                    "{},{},{},{}",
+                    monthly_counts.month, pack_name, violation_type, count
+                )
+                .unwrap();
+            }
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_month_starts() {
+        assert_eq!(
+            vec![
+                (String::from("2023-11"), String::from("2023-12-01")),
+                (String::from("2023-12"), String::from("2024-01-01")),
+                (String::from("2024-01"), String::from("2024-02-01")),
+            ],
+            month_starts("2024-01-15", 3)
+        );
+    }
+
+    #[test]
+    fn test_months_since() {
+        assert_eq!(3, months_since("2024-01-15", "2023-11").unwrap());
+        assert_eq!(1, months_since("2024-01-15", "2024-01").unwrap());
+        assert!(months_since("2024-01-15", "2024-02").is_err());
+        assert!(months_since("2024-01-15", "2024-13").is_err());
+        assert!(months_since("2024-01-15", "January").is_err());
+    }
+
+    #[test]
+    fn test_counts_and_csv() {
+        let contents = "\
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+    - packs/foo/app/services/foo/thing.rb
+  \"::Bar::Baz\":
+    violations:
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+";
+        let mut counts = BTreeMap::new();
+        for (pack_name, package_todo) in
+            package_todos("packs/foo/package_todo.yml", contents)
+        {
+            add_counts(&mut counts, pack_name, &package_todo);
+        }
+        let history = vec![MonthlyCounts {
+            month: String::from("2024-01"),
+            commit: String::from("abc123"),
+            total: 5,
+            counts,
+        }];
+
+        assert_eq!(
+            "\
+month,pack,violation_type,count
+2024-01,packs/foo,dependency,2
+2024-01,packs/foo,privacy,3
+",
+            to_csv(&history)
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::error::Error;
+use std::path::Path;
+
+use super::file_utils::{
+    relative_path_from, to_slash_path,
+    user_inputted_paths_to_absolute_filepaths,
+};
+use super::pack::Pack;
+use super::Configuration;
+
+// Prints the spec files of the packs containing the given files, and of every
+// pack that depends on them, directly or through other packs, one per line
+// so they can be passed to rspec. Files that were deleted still count
+// towards the pack they were in.
+pub(crate) fn impacted_tests(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    if files.is_empty() {
+        return Err(
+            "Pass the files that changed, e.g. from `git diff --name-only`"
+                .into(),
+        );
+    }
+
+    let changed_packs: BTreeSet<&str> =
+        user_inputted_paths_to_absolute_filepaths(
+            &configuration.absolute_root,
+            files,
+        )
+        .iter()
+        .filter_map(|path| owning_pack(configuration, path))
+        .map(|pack| pack.name.as_str())
+        .collect();
+
+    let mut dependents: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for pack in &configuration.pack_set.packs {
+        for dependency in &pack.dependencies {
+            dependents
+                .entry(dependency.as_str())
+                .or_default()
+                .insert(pack.name.as_str());
+        }
+    }
+
+    let mut spec_files = BTreeSet::new();
+    for pack_name in impacted_packs(&changed_packs, &dependents) {
+        let pack = configuration.pack_set.for_pack(pack_name)?;
+        let pattern = configuration
+            .absolute_root
+            .join(pack.spec_folder())
+            .join("**/*_spec.rb");
+        for spec_file in glob::glob(&pattern.to_string_lossy())?.flatten() {
+            // The root pack's spec folder doesn't contain other packs', but
+            // a pack's can contain the packs nested in it
+            if owning_pack(configuration, &spec_file)
+                .is_some_and(|owner| owner.name == pack.name)
+            {
+                spec_files.insert(to_slash_path(&relative_path_from(
+                    &spec_file,
+                    &configuration.absolute_root,
+                )));
+            }
+        }
+    }
+
+    for spec_file in spec_files {
+        println!("{}", spec_file);
+    }
+    Ok(())
+}
+
+// The pack with the longest path containing the file. Unlike
+// `PackSet::for_file`, this works for files that aren't included, like
+// specs, or no longer exist.
+fn owning_pack<'a>(
+    configuration: &'a Configuration,
+    absolute_path: &Path,
+) -> Option<&'a Pack> {
+    let relative_path = absolute_path
+        .strip_prefix(&configuration.absolute_root)
+        .ok()?;
+    configuration
+        .pack_set
+        .packs
+        .iter()
+        .filter(|pack| {
+            pack.name == "." || relative_path.starts_with(&pack.relative_path)
+        })
+        .max_by_key(|pack| {
+            if pack.name == "." {
+                0
+            } else {
+                pack.relative_path.components().count()
+            }
+        })
+}
+
+// The changed packs, and the packs depending on them through any number of
+// declared dependencies
+fn impacted_packs<'a>(
+    changed_packs: &BTreeSet<&'a str>,
+    dependents: &BTreeMap<&'a str, BTreeSet<&'a str>>,
+) -> BTreeSet<&'a str> {
+    let mut impacted = changed_packs.clone();
+    let mut queue: VecDeque<&str> = changed_packs.iter().copied().collect();
+    while let Some(pack) = queue.pop_front() {
+        for dependent in dependents.get(pack).into_iter().flatten() {
+            if impacted.insert(dependent) {
+                queue.push_back(dependent);
+            }
+        }
+    }
+    impacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_impacted_packs() {
+        let dependents = BTreeMap::from([
+            ("packs/bar", BTreeSet::from(["packs/foo"])),
+            ("packs/foo", BTreeSet::from(["packs/baz", "packs/bar"])),
+            ("packs/qux", BTreeSet::from(["packs/quux"])),
+        ]);
+
+        assert_eq!(
+            BTreeSet::from(["packs/bar", "packs/baz", "packs/foo"]),
+            impacted_packs(&BTreeSet::from(["packs/bar"]), &dependents)
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use super::export::SORBET_PACKAGE_FILE_NAME;
+use super::file_provider::WorkingTree;
+use super::file_utils::to_slash_path;
+use super::pack::{write_pack_to_disk, Pack};
+use super::PackageTodo;
+
+// Directories that hold other people's code, never packages of the app
+const SKIPPED_DIRECTORIES: [&str; 4] =
+    ["node_modules", "vendor", "tmp", ".git"];
+
+// What a Sorbet `__package.rb` declares, with names not starting with `::`
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SorbetPackage {
+    name: String,
+    imports: BTreeSet<String>,
+    visible_to: BTreeSet<String>,
+    exports_count: usize,
+}
+
+// Writes a package.yml next to each Sorbet `__package.rb`, with the packages
+// it imports as dependencies and the packages it is visible to as
+// `visible_to`. Existing package.yml files keep their settings and gain the
+// missing dependencies.
+pub(crate) fn sorbet_packages(
+    absolute_root: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut packages: BTreeMap<String, SorbetPackage> = BTreeMap::new();
+    for package_rb in package_rb_files(absolute_root)? {
+        let pack_directory = package_rb.parent().unwrap_or(absolute_root);
+        let pack_name = match pack_directory.strip_prefix(absolute_root) {
+            Ok(relative) if relative.as_os_str().is_empty() => {
+                String::from(".")
+            }
+            Ok(relative) => to_slash_path(relative),
+            Err(_) => continue,
+        };
+        let package = parse(&std::fs::read_to_string(&package_rb)?)
+            .ok_or_else(|| {
+                format!(
+                    "{} doesn't declare a `class ... < PackageSpec`",
+                    package_rb.display()
+                )
+            })?;
+        packages.insert(pack_name, package);
+    }
+    if packages.is_empty() {
+        println!("No {} files found", SORBET_PACKAGE_FILE_NAME);
+        return Ok(());
+    }
+
+    let pack_names: BTreeMap<&str, &str> = packages
+        .iter()
+        .map(|(pack_name, package)| (package.name.as_str(), pack_name.as_str()))
+        .collect();
+    let mut unknown_packages: BTreeSet<String> = BTreeSet::new();
+    let mut to_pack_names = |names: &BTreeSet<String>| -> HashSet<String> {
+        names
+            .iter()
+            .filter_map(|name| match pack_names.get(name.as_str()) {
+                Some(pack_name) => Some(pack_name.to_string()),
+                None => {
+                    unknown_packages.insert(name.clone());
+                    None
+                }
+            })
+            .collect()
+    };
+
+    let mut exports_count = 0;
+    for (pack_name, package) in &packages {
+        let dependencies = to_pack_names(&package.imports);
+        let visible_to = to_pack_names(&package.visible_to);
+        let package_yml = absolute_root.join(pack_name).join("package.yml");
+        let pack = if package_yml.exists() {
+            let pack =
+                Pack::from_path(&package_yml, absolute_root, &WorkingTree);
+            Pack {
+                dependencies: pack
+                    .dependencies
+                    .union(&dependencies)
+                    .cloned()
+                    .collect(),
+                ..pack
+            }
+        } else {
+            let contents = if visible_to.is_empty() {
+                "enforce_dependencies: true"
+            } else {
+                "enforce_dependencies: true\nenforce_visibility: true"
+            };
+            let pack = Pack::from_contents(
+                &package_yml,
+                absolute_root,
+                contents,
+                PackageTodo::default(),
+            );
+            Pack {
+                dependencies,
+                visible_to: Some(visible_to).filter(|v| !v.is_empty()),
+                ..pack
+            }
+        };
+        write_pack_to_disk(&pack);
+        exports_count += package.exports_count;
+    }
+
+    println!(
+        "Wrote package.yml files for {} Sorbet package(s)",
+        packages.len()
+    );
+    if !unknown_packages.is_empty() {
+        println!(
+            "These imported packages have no {} in the project, so they were left out: {}",
+            SORBET_PACKAGE_FILE_NAME,
+            unknown_packages.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+    if exports_count > 0 {
+        println!(
+            "{} export(s) were not imported: packs makes constants public by moving them into the pack's public folder",
+            exports_count
+        );
+    }
+    Ok(())
+}
+
+fn package_rb_files(
+    absolute_root: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let pattern = absolute_root.join("**").join(SORBET_PACKAGE_FILE_NAME);
+    let mut files = vec![];
+    for path in glob::glob(&pattern.to_string_lossy())? {
+        let path = path?;
+        let relative = path.strip_prefix(absolute_root).unwrap_or(&path);
+        let is_skipped = relative.components().any(|component| {
+            SKIPPED_DIRECTORIES
+                .iter()
+                .any(|directory| component.as_os_str() == *directory)
+        });
+        if !is_skipped {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+// Reads the declarations of a `__package.rb`, which are plain enough to not
+// need a full parse. None if it declares no package.
+fn parse(contents: &str) -> Option<SorbetPackage> {
+    let declaration = Regex::new(
+        r"^\s*(class|import|visible_to|export)\s+(?:::)?([A-Z][\w:]*)",
+    )
+    .unwrap();
+    let mut package = SorbetPackage::default();
+    for line in contents.lines() {
+        let Some(captures) = declaration.captures(line) else {
+            continue;
+        };
+        let name = captures[2].to_owned();
+        match &captures[1] {
+            "class" if line.contains("PackageSpec") => package.name = name,
+            "import" => {
+                package.imports.insert(name);
+            }
+            "visible_to" => {
+                package.visible_to.insert(name);
+            }
+            "export" => package.exports_count += 1,
+            _ => {}
+        }
+    }
+    Some(package).filter(|package| !package.name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        let contents = "\
+# typed: strict
+
+class Packs::Foo < PackageSpec
+  import Packs::Bar
+  import ::Packs::Baz
+  test_import Packs::TestHelpers
+
+  visible_to Packs::Qux
+
+  export Foo::Api
+  export Foo::Types
+end
+";
+
+        assert_eq!(
+            Some(SorbetPackage {
+                name: String::from("Packs::Foo"),
+                imports: BTreeSet::from([
+                    String::from("Packs::Bar"),
+                    String::from("Packs::Baz"),
+                ]),
+                visible_to: BTreeSet::from([String::from("Packs::Qux")]),
+                exports_count: 2,
+            }),
+            parse(contents)
+        );
+        assert_eq!(None, parse("# typed: strict\nclass Foo\nend\n"));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{error::Error, path::Path};
+
+use super::file_provider::WorkingTree;
+use super::file_utils::build_glob_set;
+use super::pack::{write_pack_to_disk, Pack};
+use super::raw_configuration;
+use super::PackageTodo;
+
+// `{starter_pack_path}` is filled in with the starter pack's path if
+// `packs/*` doesn't cover it
+const PACKWERK_YML: &str = "\
+# See: Setting up the configuration file
+# https://github.com/Shopify/packwerk/blob/main/USAGE.md#setting-up-the-configuration-file
+
+# List of patterns for folder paths to include
+include:
+- \"**/*.rb\"
+- \"**/*.rake\"
+- \"**/*.erb\"
+
+# List of patterns for folder paths to exclude
+exclude:
+- \"{bin,node_modules,script,tmp,vendor}/**/*\"
+
+# Patterns to find package configuration files
+package_paths:
+- \"packs/*\"{starter_pack_path}
+
+# Whether or not you want the cache enabled
+cache: true
+
+# Where you want the cache to be stored
+cache_directory: tmp/cache/packwerk
+";
+
+const ROOT_PACKAGE_YML: &str = "enforce_dependencies: true";
+
+// Writes the files a packwerk application needs to be checked:
+// a `packwerk.yml`, a root `package.yml`, and (optionally) a starter pack
+// that takes over the existing top-level `app/` directory.
+// Files that already exist are left untouched, so this is safe to re-run.
+pub(crate) fn init(
+    absolute_root: &Path,
+    starter_pack: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let starter_pack =
+        starter_pack.map(|starter_pack| starter_pack.trim_end_matches('/'));
+    let packwerk_yml = absolute_root.join("packwerk.yml");
+    if packwerk_yml.exists() {
+        println!("`packwerk.yml` already exists, leaving it as is");
+        if let Some(starter_pack) = starter_pack {
+            let package_paths =
+                raw_configuration::get(absolute_root, &WorkingTree)
+                    .map_err(|err| err.to_string())?
+                    .package_paths;
+            if !build_glob_set(&package_paths).is_match(starter_pack) {
+                return Err(format!(
+                    "`{}` is not covered by `package_paths` in packwerk.yml, so packs would not find the starter pack. Add it to `package_paths`, or choose a path that is covered",
+                    starter_pack
+                )
+                .into());
+            }
+        }
+    } else {
+        std::fs::write(&packwerk_yml, packwerk_yml_contents(starter_pack))?;
+        println!("Created `packwerk.yml`");
+    }
+
+    let root_package_yml = absolute_root.join("package.yml");
+    if root_package_yml.exists() {
+        println!("`package.yml` already exists, leaving it as is");
+    } else {
+        let root_pack = Pack::from_contents(
+            &root_package_yml,
+            absolute_root,
+            ROOT_PACKAGE_YML,
+            PackageTodo::default(),
+        );
+        write_pack_to_disk(&root_pack);
+        println!("Created root `package.yml`");
+    }
+
+    if let Some(starter_pack) = starter_pack {
+        wrap_app_directory(absolute_root, starter_pack)?;
+    }
+
+    Ok(())
+}
+
+fn packwerk_yml_contents(starter_pack: Option<&str>) -> String {
+    let starter_pack_path = match starter_pack {
+        Some(starter_pack)
+            if !build_glob_set(&[String::from("packs/*")])
+                .is_match(starter_pack) =>
+        {
+            format!("\n- \"{}\"", starter_pack)
+        }
+        _ => String::new(),
+    };
+    PACKWERK_YML.replace("{starter_pack_path}", &starter_pack_path)
+}
+
+fn wrap_app_directory(
+    absolute_root: &Path,
+    starter_pack: &str,
+) -> Result<(), Box<dyn Error>> {
+    let app_directory = absolute_root.join("app");
+    let pack_directory = absolute_root.join(starter_pack);
+    let package_yml = pack_directory.join("package.yml");
+
+    if package_yml.exists() {
+        println!("`{}` already exists, leaving it as is", starter_pack);
+        return Ok(());
+    }
+
+    if !app_directory.is_dir() {
+        return Err(format!(
+            "Cannot create starter pack `{}`: there is no `app/` directory to move into it",
+            starter_pack
+        )
+        .into());
+    }
+
+    let starter = Pack::from_contents(
+        &package_yml,
+        absolute_root,
+        "enforce_dependencies: true",
+        PackageTodo::default(),
+    );
+    write_pack_to_disk(&starter);
+
+    std::fs::rename(&app_directory, pack_directory.join("app"))?;
+    println!("Moved `app/` into starter pack `{}`", starter_pack);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packwerk_yml_contents() {
+        assert!(packwerk_yml_contents(Some("packs/app"))
+            .contains("package_paths:\n- \"packs/*\"\n\n"));
+        assert!(packwerk_yml_contents(Some("components/app")).contains(
+            "package_paths:\n- \"packs/*\"\n- \"components/app\"\n\n"
+        ));
+        assert!(!packwerk_yml_contents(None).contains("{starter_pack_path}"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+
+use serde::Serialize;
+
+use super::file_utils::to_slash_path;
+use super::pack::{EnforcementSetting, Pack};
+use super::Configuration;
+
+#[derive(Debug, PartialEq, Serialize)]
+struct PackListing<'a> {
+    name: &'a str,
+    package_yml: String,
+    enforcement: Enforcement<'a>,
+    dependencies: BTreeSet<&'a str>,
+    owner: Option<&'a str>,
+    metadata: Option<&'a serde_yaml::Value>,
+    public_path: String,
+    file_count: usize,
+    has_todo: bool,
+}
+
+// The settings each checker uses, including the ones packs inherit
+#[derive(Debug, PartialEq, Serialize)]
+struct Enforcement<'a> {
+    dependencies: &'a EnforcementSetting,
+    privacy: &'a EnforcementSetting,
+    visibility: &'a EnforcementSetting,
+    architecture: &'a EnforcementSetting,
+    upward_references: &'a EnforcementSetting,
+}
+
+pub(crate) fn list_packs(
+    configuration: &Configuration,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    if !json {
+        for pack in &configuration.pack_set.packs {
+            println!("{}", pack.yml.display())
+        }
+        return Ok(());
+    }
+
+    println!("{}", to_json(configuration)?);
+    Ok(())
+}
+
+// The packs as `list-packs --json` prints them
+pub(crate) fn to_json(
+    configuration: &Configuration,
+) -> serde_json::Result<String> {
+    let listings: Vec<PackListing> = configuration
+        .pack_set
+        .packs
+        .iter()
+        .map(|pack| listing(configuration, pack))
+        .collect();
+    serde_json::to_string_pretty(&listings)
+}
+
+fn listing<'a>(
+    configuration: &Configuration,
+    pack: &'a Pack,
+) -> PackListing<'a> {
+    let package_yml = pack
+        .yml
+        .strip_prefix(&configuration.absolute_root)
+        .unwrap_or(&pack.yml);
+    PackListing {
+        name: &pack.name,
+        package_yml: to_slash_path(package_yml),
+        enforcement: Enforcement {
+            dependencies: pack.enforce_dependencies(),
+            privacy: pack.enforce_privacy(),
+            visibility: pack.enforce_visibility(),
+            architecture: pack.enforce_architecture(),
+            upward_references: pack.enforce_upward_references(),
+        },
+        dependencies: pack.dependencies.iter().map(String::as_str).collect(),
+        owner: pack.owner_name(),
+        metadata: pack.client_keys.get("metadata"),
+        public_path: to_slash_path(&pack.public_folder()),
+        file_count: configuration.included_files_in_pack(&pack.name).len(),
+        has_todo: pack.yml.with_file_name("package_todo.yml").is_file(),
+    }
+}
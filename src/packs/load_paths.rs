@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::parsing::ruby::zeitwerk::get_autoload_paths;
+use super::Configuration;
+
+// The JSON written by the app, e.g.
+// { "autoload_paths": ["packs/foo/app/models"], "eager_load_paths": [...] }
+#[derive(Deserialize)]
+struct LoadPathsDump {
+    #[serde(default)]
+    autoload_paths: Vec<PathBuf>,
+    #[serde(default)]
+    eager_load_paths: Vec<PathBuf>,
+}
+
+// Reads the absolute load paths in a dump. Paths may be relative to the
+// root or absolute; those outside of the root, like gems, are skipped.
+pub(crate) fn read_dump(
+    absolute_root: &Path,
+    load_paths_dump: &Path,
+) -> Result<HashSet<PathBuf>, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(load_paths_dump).map_err(|e| {
+        format!(
+            "Failed to read the load paths dump at {}: {}",
+            load_paths_dump.display(),
+            e
+        )
+    })?;
+    let dump: LoadPathsDump = serde_json::from_str(&contents).map_err(|e| {
+        format!(
+            "Failed to parse the load paths dump at {}: {}",
+            load_paths_dump.display(),
+            e
+        )
+    })?;
+
+    let load_paths = dump
+        .autoload_paths
+        .into_iter()
+        .chain(dump.eager_load_paths)
+        .filter_map(|load_path| {
+            let relative_path = match load_path.strip_prefix(absolute_root) {
+                Ok(relative_path) => relative_path.to_owned(),
+                Err(_) if load_path.is_absolute() => return None,
+                Err(_) => load_path,
+            };
+            if relative_path
+                .components()
+                .any(|component| component == Component::ParentDir)
+            {
+                return None;
+            }
+            Some(absolute_root.join(relative_path))
+        })
+        .collect();
+
+    Ok(load_paths)
+}
+
+// Compares the load paths in the dump against the ones inferred from each
+// pack, so the dump can be regenerated (or the packs fixed) when they drift
+pub(crate) fn verify_load_paths(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    let (Some(load_paths_dump), Some(dumped)) =
+        (&configuration.load_paths_dump, &configuration.load_paths)
+    else {
+        return Err("No `load_paths_dump` is configured in packwerk.yml".into());
+    };
+    let relative_dump = load_paths_dump
+        .strip_prefix(&configuration.absolute_root)
+        .unwrap_or(load_paths_dump)
+        .display();
+
+    let dumped: HashSet<PathBuf> = dumped
+        .iter()
+        .filter(|load_path| load_path.is_dir())
+        .cloned()
+        .collect();
+    let inferred: HashSet<PathBuf> = get_autoload_paths(
+        configuration.file_provider.as_ref(),
+        &configuration.pack_set.packs,
+        None,
+    )
+    .into_keys()
+    .collect();
+
+    let only_dumped = relative_load_paths(configuration, &dumped, &inferred);
+    let only_inferred = relative_load_paths(configuration, &inferred, &dumped);
+
+    if only_dumped.is_empty() && only_inferred.is_empty() {
+        println!(
+            "The load paths in `{}` match the ones inferred from packs",
+            relative_dump
+        );
+        return Ok(());
+    }
+
+    if !only_dumped.is_empty() {
+        println!(
+            "Load paths in `{}` that packs does not infer:",
+            relative_dump
+        );
+        for load_path in &only_dumped {
+            println!("- {}", load_path);
+        }
+    }
+    if !only_inferred.is_empty() {
+        println!(
+            "Load paths inferred by packs that are not in `{}`:",
+            relative_dump
+        );
+        for load_path in &only_inferred {
+            println!("- {}", load_path);
+        }
+    }
+
+    Err(format!(
+        "{} load path(s) differ between `{}` and packs",
+        only_dumped.len() + only_inferred.len(),
+        relative_dump
+    )
+    .into())
+}
+
+fn relative_load_paths(
+    configuration: &Configuration,
+    load_paths: &HashSet<PathBuf>,
+    other_load_paths: &HashSet<PathBuf>,
+) -> Vec<String> {
+    let mut relative_load_paths: Vec<String> = load_paths
+        .difference(other_load_paths)
+        .map(|load_path| {
+            load_path
+                .strip_prefix(&configuration.absolute_root)
+                .unwrap_or(load_path)
+                .display()
+                .to_string()
+        })
+        .collect();
+    relative_load_paths.sort();
+    relative_load_paths
+}
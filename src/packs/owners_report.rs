@@ -0,0 +1,330 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use super::git;
+use super::history::{is_todo_file, package_todos};
+use super::package_todo::days_ago;
+use super::{Configuration, PackageTodo};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OwnersReportFormat {
+    // A table to post in a team channel
+    #[default]
+    Markdown,
+    Json,
+}
+
+// How far back the counts are compared to
+const COMPARED_DAYS_AGO: u64 = 7;
+
+#[derive(Debug, Serialize)]
+struct OwnersReport {
+    // The commit the counts are compared to, if there was one a week ago
+    compared_to: Option<String>,
+    teams: Vec<TeamReport>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct TeamReport {
+    team: String,
+    packs: Vec<String>,
+    // Recorded violations in the team's packs
+    committed: Counts,
+    // Recorded violations on constants of the team's packs
+    committed_against: Counts,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct Counts {
+    total: usize,
+    // Since the commit the report is compared to
+    change: Option<i64>,
+    by_violation_type: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TeamCounts {
+    committed: BTreeMap<String, usize>,
+    committed_against: BTreeMap<String, usize>,
+}
+
+// Counts the violations recorded in todo files by the team owning each
+// pack, both the ones their packs commit and the ones committed against
+// their packs, and compares them to the todo files of the last commit from
+// a week ago. Packs without an owner are left out.
+pub(crate) fn owners_report(
+    configuration: &Configuration,
+    format: OwnersReportFormat,
+) -> Result<(), Box<dyn Error>> {
+    let packs = &configuration.pack_set.packs;
+    let owners: HashMap<&str, &str> = packs
+        .iter()
+        .filter_map(|pack| Some((pack.name.as_str(), pack.owner_name()?)))
+        .collect();
+
+    let current = team_counts(
+        &owners,
+        packs
+            .iter()
+            .map(|pack| (pack.name.clone(), &pack.package_todo)),
+    );
+
+    let absolute_root = &configuration.absolute_root;
+    let compared_to =
+        git::last_commit_before(absolute_root, &days_ago(COMPARED_DAYS_AGO))
+            .ok()
+            .flatten();
+    let previous = match &compared_to {
+        Some(commit) => {
+            let mut previous_todos = vec![];
+            for file in git::files_at(absolute_root, commit)? {
+                if is_todo_file(&file) {
+                    let contents = git::file_at(absolute_root, commit, &file)?;
+                    previous_todos.extend(package_todos(&file, &contents));
+                }
+            }
+            Some(team_counts(
+                &owners,
+                previous_todos
+                    .iter()
+                    .map(|(pack_name, todo)| (pack_name.clone(), todo)),
+            ))
+        }
+        None => None,
+    };
+
+    let mut packs_by_team: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (pack_name, owner) in &owners {
+        packs_by_team
+            .entry(owner)
+            .or_default()
+            .push(pack_name.to_string());
+    }
+
+    let empty = TeamCounts::default();
+    let teams = packs_by_team
+        .into_iter()
+        .map(|(team, mut packs)| {
+            packs.sort();
+            let counts = current.get(team).unwrap_or(&empty);
+            let previous_counts = previous
+                .as_ref()
+                .map(|previous| previous.get(team).unwrap_or(&empty));
+            TeamReport {
+                team: team.to_owned(),
+                packs,
+                committed: Counts::new(
+                    &counts.committed,
+                    previous_counts.map(|previous| &previous.committed),
+                ),
+                committed_against: Counts::new(
+                    &counts.committed_against,
+                    previous_counts.map(|previous| &previous.committed_against),
+                ),
+            }
+        })
+        .collect();
+
+    let report = OwnersReport { compared_to, teams };
+    match format {
+        OwnersReportFormat::Markdown => print!("{}", to_markdown(&report)),
+        OwnersReportFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?)
+        }
+    }
+    Ok(())
+}
+
+impl Counts {
+    fn new(
+        by_violation_type: &BTreeMap<String, usize>,
+        previous: Option<&BTreeMap<String, usize>>,
+    ) -> Counts {
+        let total: usize = by_violation_type.values().sum();
+        let change = previous.map(|previous| {
+            total as i64 - previous.values().sum::<usize>() as i64
+        });
+        Counts {
+            total,
+            change,
+            by_violation_type: by_violation_type.clone(),
+        }
+    }
+}
+
+// A violation is counted once per file it is recorded for, like `history`
+fn team_counts<'a>(
+    owners: &HashMap<&str, &str>,
+    package_todos: impl Iterator<Item = (String, &'a PackageTodo)>,
+) -> HashMap<String, TeamCounts> {
+    let mut counts: HashMap<String, TeamCounts> = HashMap::new();
+    for (referencing_pack_name, package_todo) in package_todos {
+        for (defining_pack_name, violation_groups) in
+            &package_todo.violations_by_defining_pack
+        {
+            for violation_group in violation_groups.values() {
+                let files = violation_group.files.len();
+                for violation_type in &violation_group.violation_types {
+                    if let Some(team) =
+                        owners.get(referencing_pack_name.as_str())
+                    {
+                        *counts
+                            .entry(team.to_string())
+                            .or_default()
+                            .committed
+                            .entry(violation_type.clone())
+                            .or_default() += files;
+                    }
+                    if let Some(team) = owners.get(defining_pack_name.as_str())
+                    {
+                        *counts
+                            .entry(team.to_string())
+                            .or_default()
+                            .committed_against
+                            .entry(violation_type.clone())
+                            .or_default() += files;
+                    }
+                }
+            }
+        }
+    }
+    counts
+}
+
+fn to_markdown(report: &OwnersReport) -> String {
+    let mut markdown = String::from("# Violations by team\n\n");
+    match &report.compared_to {
+        Some(commit) => writeln!(
+            markdown,
+            "Changes are since {}, the last commit from {} days ago.\n",
+            &commit[..commit.len().min(8)],
+            COMPARED_DAYS_AGO
+        )
+        .unwrap(),
+        None => writeln!(
+            markdown,
+            "There is no commit from {} days ago to compare to.\n",
+            COMPARED_DAYS_AGO
+        )
+        .unwrap(),
+    }
+
+    markdown.push_str(
+        "| Team | Packs | Committed | Change | Committed against | Change |\n",
+    );
+    markdown.push_str("| --- | ---: | ---: | ---: | ---: | ---: |\n");
+    for team in &report.teams {
+        writeln!(
+            markdown,
+            "| {} | {} | {} | {} | {} | {} |",
+            team.team,
+            team.packs.len(),
+            team.committed.total,
+            format_change(team.committed.change),
+            team.committed_against.total,
+            format_change(team.committed_against.change)
+        )
+        .unwrap();
+    }
+
+    for team in &report.teams {
+        let violation_types: BTreeSet<&String> = team
+            .committed
+            .by_violation_type
+            .keys()
+            .chain(team.committed_against.by_violation_type.keys())
+            .collect();
+        if violation_types.is_empty() {
+            continue;
+        }
+        writeln!(markdown, "\n## {}\n", team.team).unwrap();
+        for violation_type in violation_types {
+            writeln!(
+                markdown,
+                "- {}: {} committed, {} committed against",
+                violation_type,
+                team.committed
+                    .by_violation_type
+                    .get(violation_type)
+                    .unwrap_or(&0),
+                team.committed_against
+                    .by_violation_type
+                    .get(violation_type)
+                    .unwrap_or(&0)
+            )
+            .unwrap();
+        }
+    }
+    markdown
+}
+
+fn format_change(change: Option<i64>) -> String {
+    match change {
+        Some(change) if change > 0 => format!("+{}", change),
+        Some(change) => change.to_string(),
+        None => String::from("n/a"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_team_counts() {
+        let package_todo: PackageTodo = serde_yaml::from_str(
+            "\
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+    - packs/foo/app/services/foo/thing.rb
+",
+        )
+        .unwrap();
+        let owners = HashMap::from([
+            ("packs/foo", "Foo Team"),
+            ("packs/bar", "Bar Team"),
+        ]);
+
+        let counts = team_counts(
+            &owners,
+            vec![(String::from("packs/foo"), &package_todo)].into_iter(),
+        );
+
+        let by_type = BTreeMap::from([
+            (String::from("dependency"), 2),
+            (String::from("privacy"), 2),
+        ]);
+        assert_eq!(
+            Some(&TeamCounts {
+                committed: by_type.clone(),
+                committed_against: BTreeMap::new(),
+            }),
+            counts.get("Foo Team")
+        );
+        assert_eq!(
+            Some(&TeamCounts {
+                committed: BTreeMap::new(),
+                committed_against: by_type,
+            }),
+            counts.get("Bar Team")
+        );
+    }
+
+    #[test]
+    fn test_format_change() {
+        assert_eq!("+2", format_change(Some(2)));
+        assert_eq!("-1", format_change(Some(-1)));
+        assert_eq!("0", format_change(Some(0)));
+        assert_eq!("n/a", format_change(None));
+    }
+}
@@ -1,8 +1,10 @@
+mod yml_editor;
+
 use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    error::Error,
+    fmt,
     hash::Hasher,
-    io::Read,
     path::{Path, PathBuf},
 };
 
@@ -10,7 +12,15 @@ use core::hash::Hash;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_yaml::Value;
 
-use super::{checker::ViolationIdentifier, PackageTodo};
+use yml_editor::YmlEditor;
+
+use super::{
+    checker::ViolationIdentifier,
+    file_provider::FileProvider,
+    file_utils::to_slash_path,
+    package_todo::{self, ViolationGroup},
+    PackageTodo,
+};
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct Pack {
@@ -23,37 +33,31 @@ pub struct Pack {
     #[serde(skip)]
     pub relative_path: PathBuf,
 
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "serialize_checker_setting",
-        deserialize_with = "deserialize_checker_setting"
-    )]
-    pub enforce_dependencies: Option<CheckerSetting>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_dependencies: Option<EnforcementSetting>,
 
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "serialize_checker_setting",
-        deserialize_with = "deserialize_checker_setting"
-    )]
-    pub enforce_privacy: Option<CheckerSetting>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_privacy: Option<EnforcementSetting>,
 
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "serialize_checker_setting",
-        deserialize_with = "deserialize_checker_setting"
-    )]
-    pub enforce_visibility: Option<CheckerSetting>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_visibility: Option<EnforcementSetting>,
 
-    #[serde(
-        default,
-        skip_serializing_if = "Option::is_none",
-        serialize_with = "serialize_checker_setting",
-        deserialize_with = "deserialize_checker_setting"
-    )]
-    pub enforce_architecture: Option<CheckerSetting>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_architecture: Option<EnforcementSetting>,
+
+    // Keeps packs nested in this one from referencing its private constants,
+    // even if they declare a dependency on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_upward_references: Option<EnforcementSetting>,
+
+    // Only references on lines committed after this date (YYYY-MM-DD) or
+    // commit are held to `enforce_privacy` even when recorded in a todo file,
+    // so new code is checked while older references stay recorded debt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforce_privacy_from: Option<String>,
+
+    #[serde(skip)]
+    pub inherited_enforcement: InheritedEnforcement,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub owner: Option<String>,
@@ -61,6 +65,17 @@ pub struct Pack {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layer: Option<String>,
 
+    // A module every constant in the pack is nested in without a matching
+    // directory, e.g. `MyCo` for `app/models/billing/invoice.rb` defining
+    // `MyCo::Billing::Invoice`. Overrides `root_namespace` in packwerk.yml.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_namespace: Option<String>,
+
+    // Extra autoload roots relative to the pack, for code that lives outside
+    // of `app/*`, e.g. `lib` or `domain/*`. Globs are expanded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub autoload_paths: Vec<String>,
+
     #[serde(
         default,
         skip_serializing_if = "HashSet::is_empty",
@@ -89,9 +104,27 @@ pub struct Pack {
     )]
     pub private_constants: HashSet<String>,
 
+    // With `enforce_privacy: explicit`, the only constants other packs may
+    // reference, wherever they're defined
+    #[serde(
+        default,
+        skip_serializing_if = "HashSet::is_empty",
+        serialize_with = "serialize_sorted_hashset_of_strings"
+    )]
+    pub public_constants: HashSet<String>,
+
     #[serde(skip)]
     pub package_todo: PackageTodo,
 
+    // Read from the public_api.yml next to package.yml, if there is one
+    #[serde(skip)]
+    pub public_api: Option<PublicApi>,
+
+    // After this date (YYYY-MM-DD), `check` fails on every violation
+    // recorded in the pack's todo that doesn't set its own `expires_on`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todo_expires_on: Option<String>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -102,6 +135,11 @@ pub struct Pack {
     #[serde(skip_serializing_if = "is_default_public_folder")]
     pub public_folder: Option<PathBuf>,
 
+    // The public module `generate-facade` writes to re-export some of the
+    // pack's private constants
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facade: Option<Facade>,
+
     #[serde(flatten)]
     pub client_keys: HashMap<String, Value>,
 }
@@ -112,30 +150,249 @@ impl Hash for Pack {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Deserialize, Serialize, Clone)]
-pub enum CheckerSetting {
+// A public module re-exporting private constants of the pack, e.g.
+//
+//     facade:
+//       module: Billing::Api
+//       constants:
+//         - Billing::Invoice
+//
+// generates `Billing::Api::Invoice = ::Billing::Invoice` in the public folder
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct Facade {
+    pub module: String,
+
+    #[serde(default)]
+    pub constants: Vec<String>,
+}
+
+// The exact constants other packs may reference, and optionally the methods
+// on them they may call, e.g.
+//
+//     constants:
+//       - Billing::Invoice
+//     methods:
+//       Billing::Invoice:
+//         - total
+//
+// When a pack has one, the privacy checker uses it instead of the public
+// folder to tell which constants are public
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct PublicApi {
+    #[serde(default)]
+    pub constants: BTreeSet<String>,
+
+    #[serde(default)]
+    pub methods: BTreeMap<String, Vec<String>>,
+}
+
+impl PublicApi {
+    fn read(
+        files: &dyn FileProvider,
+        public_api_path: &Path,
+    ) -> Option<PublicApi> {
+        let contents = files.read_to_string(public_api_path).ok()?;
+        let public_api: PublicApi = serde_yaml::from_str(&contents)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Failed to deserialize the public_api.yml file at {} with error {}",
+                    public_api_path.display(),
+                    e
+                )
+            });
+        Some(public_api.qualified())
+    }
+
+    // Constant names as references have them, e.g. `::Billing::Invoice`
+    fn qualified(self) -> PublicApi {
+        let qualify = |constant: String| {
+            format!("::{}", constant.trim_start_matches("::"))
+        };
+        PublicApi {
+            constants: self.constants.into_iter().map(qualify).collect(),
+            methods: self
+                .methods
+                .into_iter()
+                .map(|(constant, methods)| (qualify(constant), methods))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn includes(&self, constant_name: &str) -> bool {
+        self.constants.contains(constant_name)
+    }
+}
+
+// How a pack enforces one of the checkers. Written as `false`, `true` or
+// `strict` in package.yml, or `explicit` for `enforce_privacy`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub enum EnforcementSetting {
     #[default]
-    False,
-    True,
+    Off,
+    On,
     Strict,
+    // Only the constants listed in `public_constants` are public, the public
+    // folder is ignored
+    Explicit,
 }
 
-impl CheckerSetting {
-    pub fn is_false(&self) -> bool {
-        matches!(self, Self::False)
+impl EnforcementSetting {
+    pub fn is_enforced(&self) -> bool {
+        !matches!(self, Self::Off)
     }
 
     pub fn is_strict(&self) -> bool {
         matches!(self, Self::Strict)
     }
+
+    pub fn is_explicit(&self) -> bool {
+        matches!(self, Self::Explicit)
+    }
+}
+
+impl fmt::Display for EnforcementSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "false"),
+            Self::On => write!(f, "true"),
+            Self::Strict => write!(f, "strict"),
+            Self::Explicit => write!(f, "explicit"),
+        }
+    }
+}
+
+impl Serialize for EnforcementSetting {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Off => serializer.serialize_bool(false),
+            Self::On => serializer.serialize_bool(true),
+            Self::Strict => serializer.serialize_str("strict"),
+            Self::Explicit => serializer.serialize_str("explicit"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EnforcementSetting {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match String::deserialize(deserializer)?.as_str() {
+            "false" => Ok(Self::Off),
+            "true" => Ok(Self::On),
+            "strict" => Ok(Self::Strict),
+            "explicit" => Ok(Self::Explicit),
+            _ => Err(serde::de::Error::custom(
+                "expected one of: false, true, strict, explicit",
+            )),
+        }
+    }
+}
+
+// The enforcement settings of a pack, by the checker they're for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Enforcement {
+    Dependencies,
+    Privacy,
+    Visibility,
+    Architecture,
+    UpwardReferences,
+}
+
+impl Enforcement {
+    const ALL: [Enforcement; 5] = [
+        Self::Dependencies,
+        Self::Privacy,
+        Self::Visibility,
+        Self::Architecture,
+        Self::UpwardReferences,
+    ];
+
+    // The key of the setting in package.yml
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Dependencies => "enforce_dependencies",
+            Self::Privacy => "enforce_privacy",
+            Self::Visibility => "enforce_visibility",
+            Self::Architecture => "enforce_architecture",
+            Self::UpwardReferences => "enforce_upward_references",
+        }
+    }
+}
+
+// The enforcement settings of the root pack, which packs that leave a
+// setting out use when `inherit_enforcement_from_root` is set
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct InheritedEnforcement {
+    pub dependencies: Option<EnforcementSetting>,
+    pub privacy: Option<EnforcementSetting>,
+    pub visibility: Option<EnforcementSetting>,
+    pub architecture: Option<EnforcementSetting>,
+    pub upward_references: Option<EnforcementSetting>,
+}
+
+impl InheritedEnforcement {
+    pub(crate) fn from_pack(pack: &Pack) -> InheritedEnforcement {
+        InheritedEnforcement {
+            dependencies: pack.enforce_dependencies.clone(),
+            privacy: pack.enforce_privacy.clone(),
+            visibility: pack.enforce_visibility.clone(),
+            architecture: pack.enforce_architecture.clone(),
+            upward_references: pack.enforce_upward_references.clone(),
+        }
+    }
+}
+
+fn enforcement_setting<'a>(
+    setting: &'a Option<EnforcementSetting>,
+    inherited_setting: &'a Option<EnforcementSetting>,
+) -> &'a EnforcementSetting {
+    setting
+        .as_ref()
+        .or(inherited_setting.as_ref())
+        .unwrap_or(&EnforcementSetting::Off)
 }
 
 impl Pack {
-    pub fn all_violations(&self) -> Vec<ViolationIdentifier> {
+    // Recorded violations, leaving out those whose todo entry expired
+    pub fn all_violations(&self, today: &str) -> Vec<ViolationIdentifier> {
+        self.recorded_violations(|group| !self.is_expired(group, today))
+    }
+
+    // Recorded violations that `check` fails on again, because their todo
+    // entry expired
+    pub fn expired_violations(&self, today: &str) -> Vec<ViolationIdentifier> {
+        self.recorded_violations(|group| self.is_expired(group, today))
+    }
+
+    fn is_expired(
+        &self,
+        violation_group: &ViolationGroup,
+        today: &str,
+    ) -> bool {
+        violation_group
+            .expires_on
+            .as_ref()
+            .or(self.todo_expires_on.as_ref())
+            .is_some_and(|expires_on| {
+                package_todo::is_expired(expires_on, today)
+            })
+    }
+
+    fn recorded_violations(
+        &self,
+        include: impl Fn(&ViolationGroup) -> bool,
+    ) -> Vec<ViolationIdentifier> {
         let mut violations = Vec::new();
         let violations_by_pack = &self.package_todo.violations_by_defining_pack;
         for (defining_pack_name, violation_groups) in violations_by_pack {
             for (constant_name, violation_group) in violation_groups {
+                if !include(violation_group) {
+                    continue;
+                }
                 for violation_type in &violation_group.violation_types {
                     for file in &violation_group.files {
                         let identifier = ViolationIdentifier {
@@ -157,54 +414,42 @@ impl Pack {
     pub fn from_path(
         package_yml_absolute_path: &Path,
         absolute_root: &Path,
+        files: &dyn FileProvider,
     ) -> Pack {
-        let mut yaml_contents = String::new();
-        let mut file =
-            File::open(package_yml_absolute_path).unwrap_or_else(|e| {
+        let yaml_contents = files
+            .read_to_string(package_yml_absolute_path)
+            .unwrap_or_else(|e| {
                 panic!(
-                    "Failed to open the YAML file at {:?} with error: {:?}",
+                    "Failed to read the YAML file at {:?} with error: {:?}",
                     package_yml_absolute_path, e
                 )
             });
 
-        file.read_to_string(&mut yaml_contents).unwrap_or_else(|e| {
-            panic!(
-                "Failed to read the YAML file at {:?} with error: {:?}",
-                package_yml_absolute_path, e
-            )
-        });
-
         let absolute_path_to_package_todo = package_yml_absolute_path
             .parent()
             .unwrap()
             .join("package_todo.yml");
 
-        let package_todo: PackageTodo = if absolute_path_to_package_todo
-            .exists()
-        {
-            let mut package_todo_contents = String::new();
-            let mut file = File::open(&absolute_path_to_package_todo)
-                .expect("Failed to open the package_todo.yml file");
-            file.read_to_string(&mut package_todo_contents)
-                .expect("Could not read the package_todo.yml file");
-            serde_yaml::from_str(&package_todo_contents).unwrap_or_else(|e| {
-
-                panic!(
-                    "Failed to deserialize the package_todo.yml file at {} with error {}",
-                    absolute_path_to_package_todo.display(),
-                    e
-                )
-            })
-        } else {
-            PackageTodo::default()
-        };
-
-        Pack::from_contents(
-            package_yml_absolute_path,
-            absolute_root,
-            &yaml_contents,
-            package_todo,
-        )
+        let package_todo =
+            package_todo::read(files, &absolute_path_to_package_todo);
+
+        let public_api = PublicApi::read(
+            files,
+            &package_yml_absolute_path
+                .parent()
+                .unwrap()
+                .join("public_api.yml"),
+        );
+
+        Pack {
+            public_api,
+            ..Pack::from_contents(
+                package_yml_absolute_path,
+                absolute_root,
+                &yaml_contents,
+                package_todo,
+            )
+        }
     }
 
     pub fn from_contents(
@@ -232,10 +477,7 @@ impl Pack {
             .expect("Expected package to be in a parent directory")
             .to_owned();
 
-        let mut name = relative_path
-            .to_str()
-            .expect("Non-unicode characters?")
-            .to_owned();
+        let mut name = to_slash_path(&relative_path);
         let yml = package_yml_absolute_path;
 
         // Handle the root pack
@@ -259,45 +501,217 @@ impl Pack {
         self.relative_path.join("package.yml")
     }
 
-    pub(crate) fn enforce_architecture(&self) -> &CheckerSetting {
-        match &self.enforce_architecture {
-            Some(setting) => setting,
-            None => &CheckerSetting::False,
-        }
+    pub(crate) fn enforce_architecture(&self) -> &EnforcementSetting {
+        enforcement_setting(
+            &self.enforce_architecture,
+            &self.inherited_enforcement.architecture,
+        )
     }
 
-    pub(crate) fn enforce_dependencies(&self) -> &CheckerSetting {
-        match &self.enforce_dependencies {
-            Some(setting) => setting,
-            None => &CheckerSetting::False,
-        }
+    pub(crate) fn enforce_dependencies(&self) -> &EnforcementSetting {
+        enforcement_setting(
+            &self.enforce_dependencies,
+            &self.inherited_enforcement.dependencies,
+        )
     }
 
-    pub(crate) fn enforce_privacy(&self) -> &CheckerSetting {
-        match &self.enforce_privacy {
-            Some(setting) => setting,
-            None => &CheckerSetting::False,
-        }
+    pub(crate) fn enforce_privacy(&self) -> &EnforcementSetting {
+        enforcement_setting(
+            &self.enforce_privacy,
+            &self.inherited_enforcement.privacy,
+        )
     }
 
-    pub(crate) fn enforce_visibility(&self) -> &CheckerSetting {
-        match &self.enforce_visibility {
-            Some(setting) => setting,
-            None => &CheckerSetting::False,
-        }
+    // Whether `public_constants` lists the constant, e.g. `::Billing::Invoice`
+    // for `Billing::Invoice`
+    pub(crate) fn exports(&self, constant_name: &str) -> bool {
+        let constant_name = constant_name.trim_start_matches("::");
+        self.public_constants
+            .iter()
+            .any(|constant| constant.trim_start_matches("::") == constant_name)
+    }
+
+    pub(crate) fn enforce_visibility(&self) -> &EnforcementSetting {
+        enforcement_setting(
+            &self.enforce_visibility,
+            &self.inherited_enforcement.visibility,
+        )
+    }
+
+    pub(crate) fn enforce_upward_references(&self) -> &EnforcementSetting {
+        enforcement_setting(
+            &self.enforce_upward_references,
+            &self.inherited_enforcement.upward_references,
+        )
+    }
+
+    // Whether this pack's directory is inside the other pack's directory
+    pub(crate) fn is_nested_in(&self, other: &Pack) -> bool {
+        self.name != other.name
+            && (other.name == "."
+                || self.name.starts_with(&format!("{}/", other.name)))
+    }
+
+    // The `owner` key, or `metadata.owner` for packs that keep it there
+    pub(crate) fn owner_name(&self) -> Option<&str> {
+        self.owner
+            .as_deref()
+            .or_else(|| self.metadata_value("owner"))
+    }
+
+    pub(crate) fn docs_url(&self) -> Option<&str> {
+        self.metadata_value("docs_url")
     }
 
+    fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.client_keys.get("metadata")?.get(key)?.as_str()
+    }
+
+    // `app/public` in the pack unless package.yml says otherwise, e.g.
+    // `app/public` for the root pack and `packs/foo/app/public` for packs/foo
     pub(crate) fn public_folder(&self) -> PathBuf {
         match &self.public_folder {
             Some(folder) => folder.to_owned(),
-            None => self.relative_path.join("app/public"),
+            None => self.join_relative_path("app/public"),
+        }
+    }
+
+    // The public folder, plus any custom autoload path that is itself a
+    // `public` directory, e.g. `domain/public`
+    pub(crate) fn public_folders(&self) -> Vec<PathBuf> {
+        let mut public_folders = vec![self.public_folder()];
+        public_folders.extend(
+            self.autoload_paths
+                .iter()
+                .filter(|path| Path::new(path).ends_with("public"))
+                .map(|path| self.join_relative_path(path)),
+        );
+        public_folders
+    }
+
+    // Where the pack's specs are, e.g. `spec` for the root pack and
+    // `packs/foo/spec` for packs/foo
+    pub(crate) fn spec_folder(&self) -> PathBuf {
+        self.join_relative_path("spec")
+    }
+
+    // The root pack's path is `.`, which the paths of files relative to the
+    // root don't start with, so it's left out
+    fn join_relative_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.relative_path
+            .strip_prefix(".")
+            .unwrap_or(&self.relative_path)
+            .join(path)
+    }
+
+    // Adds a dependency on `pack_name`, returning whether the pack didn't
+    // have it yet. Like the other setters, this only changes the pack in
+    // memory until `write` is called.
+    pub fn add_dependency(&mut self, pack_name: &str) -> bool {
+        self.dependencies.insert(pack_name.to_owned())
+    }
+
+    // Removes the dependency on `pack_name`, returning whether there was one
+    pub fn remove_dependency(&mut self, pack_name: &str) -> bool {
+        self.dependencies.remove(pack_name)
+    }
+
+    pub fn set_enforcement(
+        &mut self,
+        enforcement: Enforcement,
+        setting: EnforcementSetting,
+    ) {
+        *self.enforcement_mut(enforcement) = Some(setting);
+    }
+
+    // The setting as written in package.yml, not inherited from the root
+    fn enforcement(
+        &self,
+        enforcement: Enforcement,
+    ) -> &Option<EnforcementSetting> {
+        match enforcement {
+            Enforcement::Dependencies => &self.enforce_dependencies,
+            Enforcement::Privacy => &self.enforce_privacy,
+            Enforcement::Visibility => &self.enforce_visibility,
+            Enforcement::Architecture => &self.enforce_architecture,
+            Enforcement::UpwardReferences => &self.enforce_upward_references,
+        }
+    }
+
+    fn enforcement_mut(
+        &mut self,
+        enforcement: Enforcement,
+    ) -> &mut Option<EnforcementSetting> {
+        match enforcement {
+            Enforcement::Dependencies => &mut self.enforce_dependencies,
+            Enforcement::Privacy => &mut self.enforce_privacy,
+            Enforcement::Visibility => &mut self.enforce_visibility,
+            Enforcement::Architecture => &mut self.enforce_architecture,
+            Enforcement::UpwardReferences => {
+                &mut self.enforce_upward_references
+            }
         }
     }
 
-    pub(crate) fn add_dependency(&self, to_pack: &Pack) -> Pack {
-        let mut new_pack = self.clone();
-        new_pack.dependencies.insert(to_pack.name.clone());
-        new_pack
+    // Writes the pack to its package.yml. Only the dependencies and
+    // enforcement settings that differ from the file are rewritten, so the
+    // file keeps its comments and the order of its keys. Other changes would
+    // need the whole pack serialized, losing the comments, so they're an
+    // error; `write_pack_to_disk` does that on purpose. A pack without a
+    // package.yml yet is serialized.
+    pub fn write(&self) -> Result<(), Box<dyn Error>> {
+        let contents = match std::fs::read_to_string(&self.yml) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                write_pack_to_disk(self);
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        std::fs::write(&self.yml, self.edit_yml(&contents)?)?;
+        Ok(())
+    }
+
+    fn edit_yml(&self, contents: &str) -> Result<String, Box<dyn Error>> {
+        let on_disk: Pack = serde_yaml::from_str(contents).map_err(|err| {
+            format!("Failed to parse {}: {}", self.yml.display(), err)
+        })?;
+        let mut unedited = Pack {
+            dependencies: on_disk.dependencies.clone(),
+            ..self.clone()
+        };
+        for enforcement in Enforcement::ALL {
+            *unedited.enforcement_mut(enforcement) =
+                on_disk.enforcement(enforcement).clone();
+        }
+        if serialize_pack(&unedited) != serialize_pack(&on_disk) {
+            return Err(format!(
+                "Only the dependencies and `enforce_*` settings of {} can be written without losing its comments, but other fields of the pack changed too",
+                self.yml.display()
+            )
+            .into());
+        }
+
+        let mut editor = YmlEditor::new(contents);
+        for enforcement in Enforcement::ALL {
+            let setting = self.enforcement(enforcement);
+            if setting != on_disk.enforcement(enforcement) {
+                editor.set_scalar(
+                    enforcement.key(),
+                    setting
+                        .as_ref()
+                        .map(|setting| setting.to_string())
+                        .as_deref(),
+                );
+            }
+        }
+        if self.dependencies != on_disk.dependencies {
+            editor.set_list(
+                "dependencies",
+                &self.dependencies.iter().map(String::as_str).collect(),
+            );
+        }
+        Ok(editor.contents())
     }
 }
 
@@ -365,43 +779,10 @@ pub fn write_pack_to_disk(pack: &Pack) {
     });
 }
 
-fn serialize_checker_setting<S>(
-    value: &Option<CheckerSetting>,
-    serializer: S,
-) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    match value {
-        Some(CheckerSetting::False) => serializer.serialize_bool(false),
-        Some(CheckerSetting::True) => serializer.serialize_bool(true),
-        Some(CheckerSetting::Strict) => serializer.serialize_str("strict"),
-        None => serializer.serialize_none(),
-    }
-}
-
-fn deserialize_checker_setting<'de, D>(
-    deserializer: D,
-) -> Result<Option<CheckerSetting>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    // Deserialize an optional String
-    let s = String::deserialize(deserializer);
-
-    match s.unwrap().as_str() {
-        "false" => Ok(Some(CheckerSetting::False)),
-        "true" => Ok(Some(CheckerSetting::True)),
-        "strict" => Ok(Some(CheckerSetting::Strict)),
-        _ => Err(serde::de::Error::custom(
-            "expected one of: false, true, strict",
-        )),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::packs::file_provider::WorkingTree;
     use pretty_assertions::assert_eq;
 
     fn reserialize_pack(pack_yml: &str) -> String {
@@ -432,6 +813,19 @@ dependencies:
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn test_serde_facade() {
+        let pack_yml = r#"
+facade:
+  module: Billing::Api
+  constants:
+  - Billing::Invoice
+"#
+        .trim_start();
+
+        assert_eq!(pack_yml, reserialize_pack(pack_yml))
+    }
+
     #[test]
     fn test_serde_with_enforcements() {
         let pack_yml = r#"
@@ -579,4 +973,81 @@ owner: Foobar
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn test_expired_violations() {
+        let package_todo: PackageTodo = serde_yaml::from_str(
+            r#"
+packs/bar:
+  "::Bar":
+    violations:
+    - dependency
+    files:
+    - packs/foo/bar.rb
+  "::Baz":
+    violations:
+    - dependency
+    files:
+    - packs/foo/baz.rb
+    expires_on: 2024-02-01
+"#,
+        )
+        .unwrap();
+        let pack = Pack {
+            name: String::from("packs/foo"),
+            package_todo,
+            todo_expires_on: Some(String::from("2024-01-01")),
+            ..Pack::default()
+        };
+
+        let files = |violations: Vec<ViolationIdentifier>| {
+            violations
+                .into_iter()
+                .map(|violation| violation.file)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            vec![String::from("packs/foo/bar.rb")],
+            files(pack.expired_violations("2024-01-15"))
+        );
+        assert_eq!(
+            vec![String::from("packs/foo/baz.rb")],
+            files(pack.all_violations("2024-01-15"))
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let root = std::env::temp_dir().join("packs_pack_write_test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("packs/foo")).unwrap();
+        let yml = root.join("packs/foo/package.yml");
+        std::fs::write(
+            &yml,
+            "# Owned by billing\nlayer: product\ndependencies:\n  - packs/c # for Money\n",
+        )
+        .unwrap();
+
+        let mut pack = Pack::from_path(&yml, &root, &WorkingTree);
+        pack.add_dependency("packs/a");
+        pack.remove_dependency("packs/b");
+        pack.set_enforcement(Enforcement::Privacy, EnforcementSetting::Strict);
+        pack.write().unwrap();
+        assert_eq!(
+            "# Owned by billing\nenforce_privacy: strict\nlayer: product\ndependencies:\n  - packs/a\n  - packs/c # for Money\n",
+            std::fs::read_to_string(&yml).unwrap()
+        );
+
+        // Other changes would lose the comments, so they aren't written
+        let written = std::fs::read_to_string(&yml).unwrap();
+        pack.owner = Some(String::from("Billing"));
+        assert!(pack
+            .write()
+            .unwrap_err()
+            .to_string()
+            .contains("other fields of the pack changed too"));
+        assert_eq!(written, std::fs::read_to_string(&yml).unwrap());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
 }
@@ -0,0 +1,320 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+// Edits top-level keys of a package.yml in its text, so the comments, order
+// and formatting of everything else in the file are kept. Only handles what
+// package.yml files use: scalars, and lists of strings.
+pub(super) struct YmlEditor {
+    lines: Vec<String>,
+}
+
+impl YmlEditor {
+    pub(super) fn new(contents: &str) -> YmlEditor {
+        YmlEditor {
+            lines: contents.lines().map(String::from).collect(),
+        }
+    }
+
+    pub(super) fn contents(&self) -> String {
+        if self.lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", self.lines.join("\n"))
+        }
+    }
+
+    // Sets `key: value`, keeping a comment at the end of the line, or
+    // removes the key if there's no value. A new key goes after the other
+    // `enforce_*` keys, or else at the top, as `serialize_pack` orders them.
+    pub(super) fn set_scalar(&mut self, key: &str, value: Option<&str>) {
+        let Some(value) = value else {
+            if let Some(range) = self.key_range(key) {
+                self.lines.drain(range);
+            }
+            return;
+        };
+
+        let line = format!("{}: {}", key, value);
+        match self.key_range(key) {
+            Some(range) => {
+                let line = match inline_comment(&self.lines[range.start]) {
+                    Some(comment) => format!("{} {}", line, comment),
+                    None => line,
+                };
+                self.lines.splice(range, [line]);
+            }
+            None => {
+                let index = self
+                    .top_level_keys()
+                    .filter(|(_, name)| name.starts_with("enforce_"))
+                    .last()
+                    .and_then(|(_, name)| self.key_range(&name))
+                    .map(|range| range.end)
+                    .or_else(|| self.top_level_keys().next().map(|(i, _)| i))
+                    .unwrap_or(self.lines.len());
+                self.lines.insert(index, line);
+            }
+        }
+    }
+
+    // Makes the list under `key` hold `items`, or removes the key if there
+    // are none. Items already in the list keep their place and comments,
+    // and new ones go before the first item that sorts after them.
+    pub(super) fn set_list(&mut self, key: &str, items: &BTreeSet<&str>) {
+        let range = self.key_range(key);
+        if items.is_empty() {
+            if let Some(range) = range {
+                self.lines.drain(range);
+            }
+            return;
+        }
+
+        let Some(range) = range
+            .filter(|range| value_of(&self.lines[range.start], key).is_empty())
+        else {
+            // A missing or flow style list, e.g. `dependencies: []`, is
+            // written out as a block
+            let mut block = vec![format!("{}:", key)];
+            block.extend(items.iter().map(|item| format!("  - {}", item)));
+            match self.key_range(key) {
+                Some(range) => {
+                    self.lines.splice(range, block);
+                }
+                None => self.lines.extend(block),
+            }
+            return;
+        };
+
+        let mut block: Vec<String> = self.lines[range.start + 1..range.end]
+            .iter()
+            .filter(|line| {
+                list_item(line).map_or(true, |item| items.contains(item))
+            })
+            .cloned()
+            .collect();
+        let prefix = block
+            .iter()
+            .find(|line| list_item(line).is_some())
+            .and_then(|line| line.find('-').map(|dash| &line[..dash]))
+            .unwrap_or("  ")
+            .to_owned();
+        for item in items {
+            if block.iter().any(|line| list_item(line) == Some(*item)) {
+                continue;
+            }
+            let index = block
+                .iter()
+                .position(|line| list_item(line).is_some_and(|it| it > *item))
+                .or_else(|| {
+                    block
+                        .iter()
+                        .rposition(|line| list_item(line).is_some())
+                        .map(|last| last + 1)
+                })
+                .unwrap_or(block.len());
+            block.insert(index, format!("{}- {}", prefix, item));
+        }
+        self.lines.splice(range.start + 1..range.end, block);
+    }
+
+    // The lines of a top-level key, from the key to the last line indented
+    // under it. Blank lines and comments after it are left to the next key.
+    fn key_range(&self, key: &str) -> Option<Range<usize>> {
+        let (start, _) = self.top_level_keys().find(|(_, name)| name == key)?;
+        let mut end = start + 1;
+        for (index, line) in self.lines.iter().enumerate().skip(start + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !line.starts_with([' ', '\t', '-']) {
+                break;
+            }
+            end = index + 1;
+        }
+        Some(start..end)
+    }
+
+    fn top_level_keys(&self) -> impl Iterator<Item = (usize, String)> + '_ {
+        self.lines.iter().enumerate().filter_map(|(index, line)| {
+            if line.starts_with([' ', '\t', '-', '#']) {
+                return None;
+            }
+            let (name, _) = line.split_once(':')?;
+            Some((index, name.trim().to_owned()))
+        })
+    }
+}
+
+// The value after `key:`, without a comment at the end of the line
+fn value_of<'a>(line: &'a str, key: &str) -> &'a str {
+    let value = line[key.len()..].trim_start_matches(':');
+    strip_comment(value).trim()
+}
+
+// The item of a line like `  - packs/foo # comment`, unquoted
+fn list_item(line: &str) -> Option<&str> {
+    let item = line.trim_start().strip_prefix('-')?;
+    let item = strip_comment(item).trim();
+    Some(
+        item.strip_prefix(['"', '\''])
+            .and_then(|item| item.strip_suffix(['"', '\'']))
+            .unwrap_or(item),
+    )
+}
+
+fn strip_comment(text: &str) -> &str {
+    comment_start(text).map_or(text, |comment| &text[..comment])
+}
+
+fn inline_comment(line: &str) -> Option<&str> {
+    comment_start(line).map(|comment| &line[comment..])
+}
+
+// Where a comment starts: a `#` after whitespace, outside of a quoted
+// scalar like `"packs/#1"` or `'it''s # not a comment'`
+fn comment_start(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let mut quote: Option<char> = None;
+    let mut previous: Option<char> = None;
+    while let Some((index, c)) = chars.next() {
+        match quote {
+            // `\"` in double quotes and `''` in single quotes are escapes
+            Some('"') if c == '\\' => {
+                chars.next();
+            }
+            Some('\'')
+                if c == '\'' && chars.peek().map(|(_, c)| *c) == Some('\'') =>
+            {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            // Quotes only matter at the start of a scalar
+            None if matches!(c, '"' | '\'')
+                && previous.map_or(true, |previous| {
+                    previous.is_whitespace() || matches!(previous, ':' | '-')
+                }) =>
+            {
+                quote = Some(c)
+            }
+            None if c == '#'
+                && previous
+                    .is_some_and(|previous| previous.is_whitespace()) =>
+            {
+                return Some(index)
+            }
+            None => {}
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PACKAGE_YML: &str = "\
+# Owned by the billing team
+enforce_dependencies: true # until the cycle is gone
+layer: product
+
+dependencies:
+- packs/a # for Money
+- packs/c
+metadata:
+  owner: billing
+";
+
+    #[test]
+    fn test_set_list() {
+        let mut editor = YmlEditor::new(PACKAGE_YML);
+        editor
+            .set_list("dependencies", &BTreeSet::from(["packs/a", "packs/b"]));
+
+        assert_eq!(
+            "\
+# Owned by the billing team
+enforce_dependencies: true # until the cycle is gone
+layer: product
+
+dependencies:
+- packs/a # for Money
+- packs/b
+metadata:
+  owner: billing
+",
+            editor.contents()
+        );
+
+        editor.set_list("dependencies", &BTreeSet::new());
+        editor.set_list("ignored_dependencies", &BTreeSet::from(["packs/d"]));
+        assert_eq!(
+            "\
+# Owned by the billing team
+enforce_dependencies: true # until the cycle is gone
+layer: product
+
+metadata:
+  owner: billing
+ignored_dependencies:
+  - packs/d
+",
+            editor.contents()
+        );
+    }
+
+    #[test]
+    fn test_comment_start() {
+        assert_eq!(Some(10), comment_start("- packs/a # for Money"));
+        assert_eq!(None, comment_start("- \"packs/#1\""));
+        assert_eq!(Some(13), comment_start("- \"packs/#1\" # one"));
+        assert_eq!(
+            Some(25),
+            comment_start("layer: 'it''s # a layer' # comment")
+        );
+        assert_eq!(Some(12), comment_start("owner: it's # comment"));
+        assert_eq!(None, comment_start("owner: \"a \\\" # b\""));
+        assert_eq!(None, comment_start("owner: team#1"));
+    }
+
+    #[test]
+    fn test_set_scalar() {
+        let mut editor = YmlEditor::new(PACKAGE_YML);
+        editor.set_scalar("enforce_dependencies", Some("strict"));
+        editor.set_scalar("enforce_privacy", Some("true"));
+        editor.set_scalar("layer", None);
+
+        assert_eq!(
+            "\
+# Owned by the billing team
+enforce_dependencies: strict # until the cycle is gone
+enforce_privacy: true
+
+dependencies:
+- packs/a # for Money
+- packs/c
+metadata:
+  owner: billing
+",
+            editor.contents()
+        );
+
+        let mut editor = YmlEditor::new(
+            "owner: \"Team #1\" # since 2023\nlayer: 'it''s # a layer'\n",
+        );
+        editor.set_scalar("owner", Some("\"Team #2\""));
+        assert_eq!(
+            "owner: \"Team #2\" # since 2023\nlayer: 'it''s # a layer'\n",
+            editor.contents()
+        );
+
+        let mut editor = YmlEditor::new("# A comment\nlayer: product\n");
+        editor.set_scalar("enforce_privacy", Some("true"));
+        assert_eq!(
+            "# A comment\nenforce_privacy: true\nlayer: product\n",
+            editor.contents()
+        );
+    }
+}
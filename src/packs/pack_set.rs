@@ -1,13 +1,13 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use itertools::Itertools;
 
-use super::{checker::ViolationIdentifier, pack::Pack};
+use super::{checker::ViolationIdentifier, pack::Pack, package_todo};
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct PackSet {
     pub packs: Vec<Pack>,
     indexed_packs: HashMap<String, Pack>,
@@ -19,6 +19,12 @@ pub struct PackSet {
     // We will also likely want to have an optimization that only rewrites the files
     // that have different violations.
     pub all_violations: HashSet<ViolationIdentifier>,
+    // Recorded violations whose todo entry expired, which are left out of
+    // `all_violations` so that `check` reports them again
+    pub expired_violations: HashSet<ViolationIdentifier>,
+    // The package.yml files of packs sharing a name, e.g. through a symlink,
+    // of which only the first is used
+    pub(crate) duplicate_packs: BTreeMap<String, Vec<PathBuf>>,
 }
 
 impl PackSet {
@@ -31,19 +37,34 @@ impl PackSet {
             .sorted_by(|packa, packb| {
                 Ord::cmp(&packb.name.len(), &packa.name.len())
                     .then_with(|| packa.name.cmp(&packb.name))
+                    .then_with(|| packa.yml.cmp(&packb.yml))
             })
             .collect();
         let mut indexed_packs_by_name: HashMap<String, Pack> = HashMap::new();
         let mut indexed_packs_by_yml: HashMap<PathBuf, String> = HashMap::new();
 
+        let today = package_todo::today();
         let mut all_violations = HashSet::new();
+        let mut expired_violations = HashSet::new();
+        let mut duplicate_packs: BTreeMap<String, Vec<PathBuf>> =
+            BTreeMap::new();
         for pack in &packs {
-            indexed_packs_by_name.insert(pack.name.clone(), pack.clone());
             indexed_packs_by_yml.insert(pack.yml.clone(), pack.name.clone());
-            for violation_identifier in pack.all_violations() {
-                all_violations.insert(violation_identifier);
+            if let Some(first) = indexed_packs_by_name.get(&pack.name) {
+                duplicate_packs
+                    .entry(pack.name.clone())
+                    .or_insert_with(|| vec![first.yml.clone()])
+                    .push(pack.yml.clone());
+                continue;
             }
+            indexed_packs_by_name.insert(pack.name.clone(), pack.clone());
+            all_violations.extend(pack.all_violations(&today));
+            expired_violations.extend(pack.expired_violations(&today));
         }
+        let packs: Vec<Pack> = packs
+            .into_iter()
+            .dedup_by(|packa, packb| packa.name == packb.name)
+            .collect();
 
         let mut owning_pack_name_for_file: HashMap<PathBuf, String> =
             HashMap::new();
@@ -64,10 +85,30 @@ impl PackSet {
             indexed_packs,
             packs,
             all_violations,
+            expired_violations,
             owning_pack_name_for_file,
+            duplicate_packs,
         }
     }
 
+    // One error per name shared by more than one package.yml, listing them
+    pub(crate) fn duplicate_pack_errors(&self) -> Vec<String> {
+        self.duplicate_packs
+            .iter()
+            .map(|(name, ymls)| {
+                let ymls: Vec<String> = ymls
+                    .iter()
+                    .map(|yml| format!("- {}", yml.display()))
+                    .collect();
+                format!(
+                    "These package.yml files are all for the pack `{}`, only the first is used. Look for symlinks, or `package_paths` in packwerk.yml reaching the same package.yml twice:\n{}",
+                    name,
+                    ymls.join("\n")
+                )
+            })
+            .collect()
+    }
+
     pub fn for_file(&self, absolute_file_path: &Path) -> Option<&Pack> {
         self.owning_pack_name_for_file.get(absolute_file_path).map(
             |pack_name| {
@@ -107,8 +148,10 @@ impl PackSet {
 #[cfg(test)]
 mod tests {
     use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
 
     use crate::packs::pack::Pack;
+    use pretty_assertions::assert_eq;
 
     use super::PackSet;
 
@@ -140,4 +183,38 @@ mod tests {
         let actual_pack = pack_set.for_pack("packs/foo/");
         assert!(actual_pack.is_ok());
     }
+
+    #[test]
+    fn duplicate_pack_names() {
+        let pack = |yml: &str| Pack {
+            name: "packs/foo".to_string(),
+            yml: PathBuf::from(yml),
+            ..Pack::default()
+        };
+        let root_pack = Pack {
+            name: ".".to_string(),
+            ..Pack::default()
+        };
+        let packs = HashSet::from([
+            pack("/app/packs/foo/package.yml"),
+            pack("/app/other/packs/foo/package.yml"),
+            root_pack,
+        ]);
+
+        let pack_set = PackSet::build(packs, HashMap::new());
+
+        assert_eq!(2, pack_set.packs.len());
+        assert_eq!(
+            PathBuf::from("/app/other/packs/foo/package.yml"),
+            pack_set.for_pack("packs/foo").unwrap().yml
+        );
+        assert_eq!(
+            vec![String::from(
+                "These package.yml files are all for the pack `packs/foo`, only the first is used. Look for symlinks, or `package_paths` in packwerk.yml reaching the same package.yml twice:
+- /app/other/packs/foo/package.yml
+- /app/packs/foo/package.yml"
+            )],
+            pack_set.duplicate_pack_errors()
+        );
+    }
 }
@@ -1,10 +1,18 @@
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::debug;
 
+use super::file_provider::FileProvider;
+use super::git;
+use super::raw_configuration::{TodoFormat, TodoStorage};
 use super::{pack::Pack, Configuration, Violation};
 
+pub(crate) const CONSOLIDATED_TODO_FILE_NAME: &str = "packwerk_todo.yml";
+
 #[derive(PartialEq, Debug, Eq, Deserialize, Serialize, Default, Clone)]
 pub struct ViolationGroup {
     // Use serde rename to parse the key as violations
@@ -12,6 +20,17 @@ pub struct ViolationGroup {
     pub violation_types: HashSet<String>,
     #[serde(serialize_with = "serialize_sorted_set")]
     pub files: HashSet<String>,
+    // The rest is only written with `todo_format: v2`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub occurrences: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_recorded: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_in: Option<String>,
+    // Added by hand: after this date (YYYY-MM-DD), `check` fails on the
+    // violations again. `update` keeps it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_on: Option<String>,
 }
 
 fn serialize_sorted_set<S>(
@@ -135,9 +154,81 @@ pub fn write_violations_to_disk(
     violations: HashSet<Violation>,
 ) {
     debug!("Starting writing violations to disk");
+    let package_todos_by_pack_name = package_todos(configuration, violations);
+
+    match configuration.todo_storage {
+        TodoStorage::PerPack => write_package_todos_to_disk(
+            configuration,
+            &package_todos_by_pack_name,
+        ),
+        TodoStorage::Consolidated => write_consolidated_todo_to_disk(
+            configuration,
+            package_todos_by_pack_name.into_iter().collect(),
+        ),
+    }
+
+    debug!("Finished writing violations to disk");
+}
+
+// Rewrites the todos of `pack_names` from `violations`, the violations found
+// in their files, and leaves the todos of the other packs as they're recorded
+pub(crate) fn write_pack_violations_to_disk(
+    configuration: &Configuration,
+    pack_names: &BTreeSet<String>,
+    violations: HashSet<Violation>,
+) {
+    let mut package_todos_by_pack_name =
+        package_todos(configuration, violations);
+    package_todos_by_pack_name
+        .retain(|pack_name, _| pack_names.contains(pack_name));
+
+    match configuration.todo_storage {
+        TodoStorage::PerPack => {
+            for pack in &configuration.pack_set.packs {
+                if !pack_names.contains(&pack.name) {
+                    continue;
+                }
+                match package_todos_by_pack_name.get(&pack.name) {
+                    Some(package_todo) => write_package_todo_to_disk(
+                        pack,
+                        package_todo,
+                        configuration.packs_first_mode,
+                    ),
+                    None => delete_package_todo_from_disk(pack),
+                }
+            }
+        }
+        TodoStorage::Consolidated => {
+            let mut all_package_todos: BTreeMap<String, PackageTodo> =
+                configuration
+                    .pack_set
+                    .packs
+                    .iter()
+                    .filter(|pack| {
+                        !pack_names.contains(&pack.name)
+                            && !pack
+                                .package_todo
+                                .violations_by_defining_pack
+                                .is_empty()
+                    })
+                    .map(|pack| (pack.name.clone(), pack.package_todo.clone()))
+                    .collect();
+            all_package_todos.extend(package_todos_by_pack_name);
+            write_consolidated_todo_to_disk(configuration, all_package_todos)
+        }
+    }
+}
+
+// The todo of each responsible pack, keeping what's recorded about the
+// violations that already were
+fn package_todos(
+    configuration: &Configuration,
+    violations: HashSet<Violation>,
+) -> HashMap<String, PackageTodo> {
     // First we need to group the violations by the repsonsible pack, which today is always the referencing pack
     // Later if we change where a violation shows up, we should delegate to the checker
     // to decide what pack it should be in.
+    let occurrences = count_occurrences(&violations);
     let mut violations_by_responsible_pack: HashMap<String, Vec<Violation>> =
         HashMap::new();
     for violation in violations {
@@ -149,9 +240,322 @@ pub fn write_violations_to_disk(
             .push(violation);
     }
 
-    let package_todos_by_pack_name =
+    let mut package_todos_by_pack_name =
         package_todos_for_pack_name(violations_by_responsible_pack);
+    keep_expiry_dates(configuration, &mut package_todos_by_pack_name);
+    if configuration.todo_format == TodoFormat::V2 {
+        add_todo_metadata(
+            configuration,
+            &mut package_todos_by_pack_name,
+            occurrences,
+        );
+    }
+    package_todos_by_pack_name
+}
+
+// (responsible pack, defining pack, constant) -> violation type -> count
+type Occurrences = HashMap<(String, String, String), BTreeMap<String, usize>>;
+
+fn count_occurrences(violations: &HashSet<Violation>) -> Occurrences {
+    let mut occurrences: Occurrences = HashMap::new();
+    for violation in violations {
+        let identifier = &violation.identifier;
+        let key = (
+            identifier.referencing_pack_name.clone(),
+            identifier.defining_pack_name.clone(),
+            identifier.constant_name.clone(),
+        );
+        *occurrences
+            .entry(key)
+            .or_default()
+            .entry(identifier.violation_type.clone())
+            .or_default() += 1;
+    }
+    occurrences
+}
+
+// Expiry dates are set by hand, so they're carried over from the todo files
+// for the constants that are still recorded
+fn keep_expiry_dates(
+    configuration: &Configuration,
+    package_todos_by_pack_name: &mut HashMap<String, PackageTodo>,
+) {
+    for (pack_name, package_todo) in package_todos_by_pack_name.iter_mut() {
+        let Ok(pack) = configuration.pack_set.for_pack(pack_name) else {
+            continue;
+        };
+        for (defining_pack_name, violations_by_constant) in
+            package_todo.violations_by_defining_pack.iter_mut()
+        {
+            for (constant_name, violation_group) in
+                violations_by_constant.iter_mut()
+            {
+                violation_group.expires_on = pack
+                    .package_todo
+                    .violations_by_defining_pack
+                    .get(defining_pack_name)
+                    .and_then(|groups| groups.get(constant_name))
+                    .and_then(|group| group.expires_on.clone());
+            }
+        }
+    }
+}
+
+// Constants that were already recorded keep the date and commit they were
+// first recorded at, so that the oldest debt can be found
+fn add_todo_metadata(
+    configuration: &Configuration,
+    package_todos_by_pack_name: &mut HashMap<String, PackageTodo>,
+    mut occurrences: Occurrences,
+) {
+    let today = today();
+    let commit = git::current_commit(&configuration.absolute_root);
+
+    for (pack_name, package_todo) in package_todos_by_pack_name.iter_mut() {
+        let recorded_todo = configuration
+            .pack_set
+            .for_pack(pack_name)
+            .ok()
+            .map(|pack| &pack.package_todo);
+
+        for (defining_pack_name, violations_by_constant) in
+            package_todo.violations_by_defining_pack.iter_mut()
+        {
+            for (constant_name, violation_group) in
+                violations_by_constant.iter_mut()
+            {
+                let recorded_group = recorded_todo
+                    .and_then(|todo| {
+                        todo.violations_by_defining_pack.get(defining_pack_name)
+                    })
+                    .and_then(|groups| groups.get(constant_name))
+                    .filter(|group| group.first_recorded.is_some());
+
+                violation_group.occurrences = occurrences
+                    .remove(&(
+                        pack_name.clone(),
+                        defining_pack_name.clone(),
+                        constant_name.clone(),
+                    ))
+                    .unwrap_or_default();
+                match recorded_group {
+                    Some(recorded_group) => {
+                        violation_group.first_recorded =
+                            recorded_group.first_recorded.clone();
+                        violation_group.recorded_in =
+                            recorded_group.recorded_in.clone();
+                    }
+                    None => {
+                        violation_group.first_recorded = Some(today.clone());
+                        violation_group.recorded_in = commit.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Today's date in UTC, as YYYY-MM-DD
+pub(crate) fn today() -> String {
+    days_ago(0)
+}
+
+// The date in UTC `days` days before today, as YYYY-MM-DD
+pub(crate) fn days_ago(days: u64) -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("The system clock is set before 1970")
+        .as_secs();
+    date_from_days((seconds / 86_400).saturating_sub(days))
+}
+
+// Whether `date` is a YYYY-MM-DD date, which sorts like the date it is
+pub(crate) fn is_date(date: &str) -> bool {
+    date.len() == 10
+        && date.char_indices().all(|(index, c)| match index {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+// An expiry date is inclusive: the violations fail again the day after
+pub(crate) fn is_expired(expires_on: &str, today: &str) -> bool {
+    is_date(expires_on) && expires_on < today
+}
+
+// Converts a number of days since 1970-01-01 into a YYYY-MM-DD date, see
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn date_from_days(days: u64) -> String {
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Lists recorded violations by when they were first recorded, oldest first
+pub(crate) fn list_oldest(
+    configuration: &Configuration,
+    limit: Option<usize>,
+) -> Result<(), Box<dyn Error>> {
+    let mut recorded: Vec<(&str, &str, &str, &str, &ViolationGroup)> = vec![];
+    for pack in &configuration.pack_set.packs {
+        for (defining_pack_name, violations_by_constant) in
+            &pack.package_todo.violations_by_defining_pack
+        {
+            for (constant_name, violation_group) in violations_by_constant {
+                if let Some(first_recorded) = &violation_group.first_recorded {
+                    recorded.push((
+                        first_recorded,
+                        constant_name,
+                        &pack.name,
+                        defining_pack_name,
+                        violation_group,
+                    ));
+                }
+            }
+        }
+    }
+
+    if recorded.is_empty() {
+        return Err("No recorded violation has a first recorded date. Set `todo_format: v2` in packwerk.yml and run `pks update` to add them.".into());
+    }
+
+    recorded.sort_by(|a, b| (a.0, a.1, a.2).cmp(&(b.0, b.1, b.2)));
+    for (
+        first_recorded,
+        constant_name,
+        referencing_pack_name,
+        defining_pack_name,
+        violation_group,
+    ) in recorded.into_iter().take(limit.unwrap_or(usize::MAX))
+    {
+        let mut violation_types: Vec<&String> =
+            violation_group.violation_types.iter().collect();
+        violation_types.sort();
+        let violation_types = violation_types
+            .into_iter()
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let occurrences: usize = violation_group.occurrences.values().sum();
+        let recorded_in = match &violation_group.recorded_in {
+            Some(commit) => format!(", recorded in {}", commit),
+            None => String::new(),
+        };
+        println!(
+            "{} {} ({}) from `{}` to `{}`, {} occurrence(s){}",
+            first_recorded,
+            constant_name,
+            violation_types,
+            referencing_pack_name,
+            defining_pack_name,
+            occurrences,
+            recorded_in
+        );
+    }
+
+    Ok(())
+}
+
+// Counts of recorded violations, most first. Each file referencing a
+// constant in a todo file is one violation.
+#[derive(Debug, Default, PartialEq)]
+struct TopOffenders {
+    constants: Vec<(String, usize)>,
+    defining_packs: Vec<(String, usize)>,
+    referencing_files: Vec<(String, usize)>,
+}
+
+fn top_offenders(packs: &[Pack]) -> TopOffenders {
+    let mut constants: HashMap<&str, usize> = HashMap::new();
+    let mut defining_packs: HashMap<&str, usize> = HashMap::new();
+    let mut referencing_files: HashMap<&str, usize> = HashMap::new();
+    for pack in packs {
+        for (defining_pack_name, violations_by_constant) in
+            &pack.package_todo.violations_by_defining_pack
+        {
+            for (constant_name, violation_group) in violations_by_constant {
+                let count = violation_group.files.len();
+                *constants.entry(constant_name).or_default() += count;
+                *defining_packs.entry(defining_pack_name).or_default() += count;
+                for file in &violation_group.files {
+                    *referencing_files.entry(file).or_default() += 1;
+                }
+            }
+        }
+    }
+
+    TopOffenders {
+        constants: ranked(constants),
+        defining_packs: ranked(defining_packs),
+        referencing_files: ranked(referencing_files),
+    }
+}
+
+// Most first, then by name
+fn ranked(counts: HashMap<&str, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(name, count)| (name.to_owned(), count))
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+// Lists the constants, defining packs and referencing files with the most
+// recorded violations, to see where paying down debt goes furthest
+pub(crate) fn list_top_offenders(
+    configuration: &Configuration,
+    limit: usize,
+) -> Result<(), Box<dyn Error>> {
+    let top_offenders = top_offenders(&configuration.pack_set.packs);
+    if top_offenders.constants.is_empty() {
+        return Err("No violations are recorded in todo files".into());
+    }
 
+    let sections = [
+        (
+            "Constants with the most violations:",
+            &top_offenders.constants,
+        ),
+        (
+            "Packs with the most inbound violations:",
+            &top_offenders.defining_packs,
+        ),
+        (
+            "Files with the most outbound violations:",
+            &top_offenders.referencing_files,
+        ),
+    ];
+    for (index, (heading, ranked)) in sections.into_iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("{}", heading);
+        for (name, count) in ranked.iter().take(limit) {
+            println!("  {} {}", count, name);
+        }
+    }
+    Ok(())
+}
+
+fn write_package_todos_to_disk(
+    configuration: &Configuration,
+    package_todos_by_pack_name: &HashMap<String, PackageTodo>,
+) {
     let all_packs = &configuration.pack_set.packs;
     all_packs.par_iter().for_each(|p| {
         let package_todo = package_todos_by_pack_name.get(&p.name);
@@ -164,8 +568,145 @@ pub fn write_violations_to_disk(
             None => delete_package_todo_from_disk(p),
         }
     });
+}
 
-    debug!("Finished writing violations to disk");
+pub(crate) fn write_consolidated_todo_to_disk(
+    configuration: &Configuration,
+    package_todos_by_pack_name: BTreeMap<String, PackageTodo>,
+) {
+    let consolidated_todo_path =
+        consolidated_todo_path(&configuration.absolute_root);
+
+    if package_todos_by_pack_name.is_empty() {
+        if consolidated_todo_path.exists() {
+            std::fs::remove_file(consolidated_todo_path).unwrap();
+        }
+        return;
+    }
+
+    let consolidated_todo_yml =
+        serialize_consolidated_todo(&package_todos_by_pack_name);
+    std::fs::write(consolidated_todo_path, consolidated_todo_yml).unwrap();
+}
+
+pub(crate) fn consolidated_todo_path(absolute_root: &Path) -> PathBuf {
+    absolute_root.join(CONSOLIDATED_TODO_FILE_NAME)
+}
+
+pub(crate) fn read(
+    files: &dyn FileProvider,
+    package_todo_path: &Path,
+) -> PackageTodo {
+    if !files.is_file(package_todo_path) {
+        return PackageTodo::default();
+    }
+
+    let package_todo_contents = files
+        .read_to_string(package_todo_path)
+        .expect("Could not read the package_todo.yml file");
+    serde_yaml::from_str(&package_todo_contents).unwrap_or_else(|e| {
+        panic!(
+            "Failed to deserialize the package_todo.yml file at {} with error {}",
+            package_todo_path.display(),
+            e
+        )
+    })
+}
+
+// Reads the single, root level todo file, which maps each referencing pack
+// to what would otherwise be that pack's package_todo.yml
+pub(crate) fn read_consolidated(
+    files: &dyn FileProvider,
+    absolute_root: &Path,
+) -> BTreeMap<String, PackageTodo> {
+    let consolidated_todo_path = consolidated_todo_path(absolute_root);
+    if !files.is_file(&consolidated_todo_path) {
+        return BTreeMap::new();
+    }
+
+    let contents = files
+        .read_to_string(&consolidated_todo_path)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Could not read {} due to error: {}",
+                consolidated_todo_path.display(),
+                e
+            )
+        });
+    let package_todos: Option<BTreeMap<String, PackageTodo>> =
+        serde_yaml::from_str(&contents).unwrap_or_else(|e| {
+            panic!(
+                "Failed to deserialize {} with error {}",
+                consolidated_todo_path.display(),
+                e
+            )
+        });
+
+    package_todos.unwrap_or_default()
+}
+
+// Moves every pack's package_todo.yml into a single packwerk_todo.yml.
+// Entries already in packwerk_todo.yml are kept unless the pack also has a
+// package_todo.yml, in which case the package_todo.yml wins.
+pub(crate) fn consolidate(configuration: &Configuration) {
+    let files = configuration.file_provider.as_ref();
+    let mut package_todos_by_pack_name =
+        read_consolidated(files, &configuration.absolute_root);
+
+    for pack in &configuration.pack_set.packs {
+        let package_todo_path = package_todo_path(pack);
+        if package_todo_path.exists() {
+            package_todos_by_pack_name
+                .insert(pack.name.clone(), read(files, &package_todo_path));
+            std::fs::remove_file(package_todo_path).unwrap();
+        }
+    }
+
+    write_consolidated_todo_to_disk(configuration, package_todos_by_pack_name);
+
+    println!(
+        "Moved violations into `{}`. Set `todo_storage: consolidated` in packwerk.yml to use it.",
+        CONSOLIDATED_TODO_FILE_NAME
+    );
+}
+
+// Moves the entries in packwerk_todo.yml back into each pack's package_todo.yml.
+// The entries of packs that no longer exist, e.g. after a pack is deleted,
+// are dropped with a warning.
+pub(crate) fn split(configuration: &Configuration) {
+    let package_todos_by_pack_name = read_consolidated(
+        configuration.file_provider.as_ref(),
+        &configuration.absolute_root,
+    );
+
+    for (pack_name, package_todo) in &package_todos_by_pack_name {
+        let Ok(pack) = configuration.pack_set.for_pack(pack_name) else {
+            eprintln!(
+                "Warning: `{}` has violations in {}, but that pack cannot be found, so they were dropped",
+                pack_name, CONSOLIDATED_TODO_FILE_NAME
+            );
+            continue;
+        };
+        write_package_todo_to_disk(
+            pack,
+            package_todo,
+            configuration.packs_first_mode,
+        );
+    }
+
+    let consolidated_todo_path =
+        consolidated_todo_path(&configuration.absolute_root);
+    if consolidated_todo_path.exists() {
+        std::fs::remove_file(consolidated_todo_path).unwrap();
+    }
+
+    println!(
+        "Moved violations into package_todo.yml files. Remove `todo_storage: consolidated` from packwerk.yml to use them."
+    );
+}
+
+pub(crate) fn package_todo_path(pack: &Pack) -> PathBuf {
+    pack.yml.parent().unwrap().join("package_todo.yml")
 }
 
 fn serialize_package_todo(
@@ -175,23 +716,45 @@ fn serialize_package_todo(
 ) -> String {
     let package_todo_yml = serde_yaml::to_string(&package_todo).unwrap();
 
-    // HACK: This is the other part of the hack above (search `HACK:` for more)
-    let package_todo_yml = package_todo_yml.replace("'#", "\"");
-    let package_todo_yml = package_todo_yml.replace("#'", "\"");
     let header = header(responsible_pack_name, packs_first_mode);
-    header + &package_todo_yml
+    header + &unquote_hack(package_todo_yml)
+}
+
+fn serialize_consolidated_todo(
+    package_todos_by_pack_name: &BTreeMap<String, PackageTodo>,
+) -> String {
+    // The root pack is quoted the same way it is as a defining pack
+    let quoted_package_todos: BTreeMap<String, &PackageTodo> =
+        package_todos_by_pack_name
+            .iter()
+            .map(|(pack_name, package_todo)| {
+                let quoted_pack_name = if pack_name == "." {
+                    String::from("#.#")
+                } else {
+                    pack_name.to_owned()
+                };
+                (quoted_pack_name, package_todo)
+            })
+            .collect();
+
+    let consolidated_todo_yml =
+        serde_yaml::to_string(&quoted_package_todos).unwrap();
+
+    consolidated_header() + &unquote_hack(consolidated_todo_yml)
+}
+
+// HACK: This is the other part of the hack above (search `HACK:` for more)
+fn unquote_hack(yml: String) -> String {
+    yml.replace("'#", "\"").replace("#'", "\"")
 }
 
-fn write_package_todo_to_disk(
+pub(crate) fn write_package_todo_to_disk(
     responsible_pack: &Pack,
     package_todo: &PackageTodo,
     packs_first_mode: bool,
 ) {
-    let package_todo_yml_absolute_filepath = responsible_pack
-        .yml
-        .parent()
-        .unwrap()
-        .join("package_todo.yml");
+    let package_todo_yml_absolute_filepath =
+        package_todo_path(responsible_pack);
 
     if !package_todo_yml_absolute_filepath.exists() {
         std::fs::File::create(&package_todo_yml_absolute_filepath).unwrap();
@@ -207,12 +770,9 @@ fn write_package_todo_to_disk(
         .unwrap();
 }
 
-fn delete_package_todo_from_disk(responsible_pack: &Pack) {
-    let package_todo_yml_absolute_filepath = responsible_pack
-        .yml
-        .parent()
-        .unwrap()
-        .join("package_todo.yml");
+pub(crate) fn delete_package_todo_from_disk(responsible_pack: &Pack) {
+    let package_todo_yml_absolute_filepath =
+        package_todo_path(responsible_pack);
 
     if package_todo_yml_absolute_filepath.exists() {
         // Delete package_todo_yml_absolute_filepath
@@ -239,6 +799,21 @@ fn header(responsible_pack_name: &String, packs_first_mode: bool) -> String {
 ", responsible_pack_name, command)
 }
 
+fn consolidated_header() -> String {
+    String::from(
+        "\
+# This file contains a list of dependencies that are not part of the long term plan for each
+# package, keyed by the package that has them.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# pks update
+---
+",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +840,7 @@ mod tests {
             ViolationGroup {
                 violation_types,
                 files,
+                ..ViolationGroup::default()
             },
         );
 
@@ -479,4 +1055,194 @@ packs/bar:
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_serialize_consolidated_todo() {
+        let expected: String = String::from(
+            "\
+# This file contains a list of dependencies that are not part of the long term plan for each
+# package, keyed by the package that has them.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# pks update
+---
+\".\":
+  packs/bar:
+    \"::Bar\":
+      violations:
+      - dependency
+      files:
+      - packs/foo/app/services/foo.rb
+  packs/baz:
+    \"::Baz\":
+      violations:
+      - dependency
+      - privacy
+      files:
+      - packs/foo/app/services/foo.rb
+",
+        );
+
+        let mut violations_by_defining_pack = BTreeMap::new();
+        violations_by_defining_pack
+            .insert(String::from("packs/bar"), bar_violations());
+        violations_by_defining_pack
+            .insert(String::from("packs/baz"), baz_violations());
+        let mut package_todos_by_pack_name = BTreeMap::new();
+        package_todos_by_pack_name.insert(
+            String::from("."),
+            PackageTodo {
+                violations_by_defining_pack,
+            },
+        );
+
+        let actual = serialize_consolidated_todo(&package_todos_by_pack_name);
+        assert_eq!(expected, actual);
+
+        let deserialized: BTreeMap<String, PackageTodo> =
+            serde_yaml::from_str(&actual).unwrap();
+        assert_eq!(package_todos_by_pack_name, deserialized);
+    }
+
+    #[test]
+    fn test_serialize_v2_metadata() {
+        let expected: String = String::from(
+            "\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/foo' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# pks update
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/foo.rb
+    occurrences:
+      dependency: 2
+    first_recorded: 2024-01-15
+    recorded_in: abc1234
+",
+        );
+
+        let mut violations_by_defining_pack = BTreeMap::new();
+        violations_by_defining_pack.insert(
+            String::from("packs/bar"),
+            BTreeMap::from([(
+                String::from("::Bar"),
+                ViolationGroup {
+                    violation_types: HashSet::from([String::from(
+                        "dependency",
+                    )]),
+                    files: HashSet::from([String::from(
+                        "packs/foo/app/services/foo.rb",
+                    )]),
+                    occurrences: BTreeMap::from([(
+                        String::from("dependency"),
+                        2,
+                    )]),
+                    first_recorded: Some(String::from("2024-01-15")),
+                    recorded_in: Some(String::from("abc1234")),
+                    expires_on: None,
+                },
+            )]),
+        );
+        let package_todo = PackageTodo {
+            violations_by_defining_pack,
+        };
+
+        let actual = serialize_package_todo(
+            &String::from("packs/foo"),
+            &package_todo,
+            true,
+        );
+        assert_eq!(expected, actual);
+
+        let deserialized: PackageTodo = serde_yaml::from_str(&actual).unwrap();
+        assert_eq!(package_todo, deserialized);
+    }
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!("1970-01-01", date_from_days(0));
+        assert_eq!("2024-01-15", date_from_days(19_737));
+        assert_eq!("2024-02-29", date_from_days(19_782));
+        assert_eq!("2000-03-01", date_from_days(11_017));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        assert!(is_expired("2024-01-14", "2024-01-15"));
+        assert!(!is_expired("2024-01-15", "2024-01-15"));
+        assert!(!is_expired("2024-1-1", "2024-01-15"));
+        assert!(!is_expired("someday", "2024-01-15"));
+    }
+
+    #[test]
+    fn test_top_offenders() {
+        let mut bar_violations = construct_violations(
+            String::from("::Bar"),
+            vec![String::from("dependency")],
+            vec![
+                String::from("packs/foo/app/services/foo.rb"),
+                String::from("packs/foo/app/services/other.rb"),
+            ],
+        );
+        bar_violations.extend(construct_violations(
+            String::from("::Bar::Baz"),
+            vec![String::from("privacy")],
+            vec![String::from("packs/foo/app/services/foo.rb")],
+        ));
+        let pack = |name: &str, violations_by_defining_pack| Pack {
+            name: String::from(name),
+            package_todo: PackageTodo {
+                violations_by_defining_pack,
+            },
+            ..Pack::default()
+        };
+        let packs = vec![
+            pack(
+                "packs/foo",
+                BTreeMap::from([(String::from("packs/bar"), bar_violations)]),
+            ),
+            pack(
+                "packs/qux",
+                BTreeMap::from([(
+                    String::from("packs/quux"),
+                    construct_violations(
+                        String::from("::Quux"),
+                        vec![String::from("dependency")],
+                        vec![String::from("packs/qux/app/services/qux.rb")],
+                    ),
+                )]),
+            ),
+        ];
+
+        let count = |name: &str, count: usize| (String::from(name), count);
+        assert_eq!(
+            TopOffenders {
+                constants: vec![
+                    count("::Bar", 2),
+                    count("::Bar::Baz", 1),
+                    count("::Quux", 1),
+                ],
+                defining_packs: vec![
+                    count("packs/bar", 3),
+                    count("packs/quux", 1)
+                ],
+                referencing_files: vec![
+                    count("packs/foo/app/services/foo.rb", 2),
+                    count("packs/foo/app/services/other.rb", 1),
+                    count("packs/qux/app/services/qux.rb", 1),
+                ],
+            },
+            top_offenders(&packs)
+        );
+    }
 }
@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::file_utils::{get_file_type, to_slash_path};
+use super::parsing::{
+    process_file, ParsedDefinition, ReferenceKind, UnresolvedReference,
+};
+use super::Configuration;
+
+#[derive(Serialize)]
+struct ParseOutput<'a> {
+    file: String,
+    parser: &'static str,
+    references: &'a Vec<UnresolvedReference>,
+    definitions: &'a Vec<ParsedDefinition>,
+}
+
+// Prints what the parser extracts from one file, before any constant is
+// resolved. Useful when reporting parser bugs or building tools on top of it.
+pub(crate) fn parse(
+    configuration: &Configuration,
+    file: &str,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let path = PathBuf::from(file);
+    let absolute_path = if path.is_absolute() {
+        path
+    } else {
+        configuration.absolute_root.join(path)
+    };
+
+    if !absolute_path.is_file() {
+        return Err(format!("`{}` is not a file", file).into());
+    }
+    if get_file_type(&absolute_path).is_none() {
+        return Err(format!(
+            "`{}` is not a Ruby, ERB or YAML file packs can parse",
+            file
+        )
+        .into());
+    }
+
+    let processed_file = process_file(&absolute_path, configuration);
+    let relative_path = to_slash_path(
+        absolute_path
+            .strip_prefix(&configuration.absolute_root)
+            .unwrap_or(&absolute_path),
+    );
+
+    if json {
+        let output = ParseOutput {
+            file: relative_path,
+            parser: if configuration.experimental_parser {
+                "experimental"
+            } else {
+                "packwerk"
+            },
+            references: &processed_file.unresolved_references,
+            definitions: &processed_file.definitions,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("References in {}:", relative_path);
+    for reference in &processed_file.unresolved_references {
+        let location = &reference.location;
+        let kind = match reference.kind {
+            ReferenceKind::Code => "",
+            ReferenceKind::Signature => " [signature]",
+        };
+        if reference.namespace_path.is_empty() {
+            println!(
+                "- {}:{} {}{}",
+                location.start_row, location.start_col, reference.name, kind
+            );
+        } else {
+            println!(
+                "- {}:{} {} (nested in {}){}",
+                location.start_row,
+                location.start_col,
+                reference.name,
+                reference.namespace_path.join("::"),
+                kind
+            );
+        }
+    }
+
+    println!("Definitions in {}:", relative_path);
+    for definition in &processed_file.definitions {
+        println!(
+            "- {}:{} {}",
+            definition.location.start_row,
+            definition.location.start_col,
+            definition.fully_qualified_name
+        );
+    }
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::packs::file_utils::file_read_contents;
 use crate::packs::{
     file_utils::convert_erb_to_ruby_without_sourcemaps, parsing::Range,
-    Configuration, ProcessedFile, UnresolvedReference,
+    Configuration, FileSigils, ProcessedFile, ReferenceConfidence,
+    UnresolvedReference,
 };
 use std::path::Path;
 
@@ -32,6 +33,7 @@ pub(crate) fn process_from_contents(
             // that doesn't necessarily map up to the original.
             // We need to add extra logic to support source maps (or use a proper parsing library).
             location: Range::default(),
+            confidence: ReferenceConfidence::Heuristic,
             ..r.clone()
         })
         .collect();
@@ -40,5 +42,6 @@ pub(crate) fn process_from_contents(
         absolute_path: path.to_path_buf(),
         unresolved_references: references_without_range,
         definitions: vec![],
+        sigils: FileSigils::default(),
     }
 }
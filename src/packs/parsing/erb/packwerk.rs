@@ -7,7 +7,9 @@ mod tests {
 
     use crate::packs::parsing::erb::packwerk::parser::process_from_contents;
     use crate::packs::parsing::Range;
-    use crate::packs::{Configuration, UnresolvedReference};
+    use crate::packs::{
+        Configuration, ReferenceConfidence, ReferenceKind, UnresolvedReference,
+    };
 
     #[test]
     fn trivial_case() {
@@ -18,7 +20,9 @@ mod tests {
             vec![UnresolvedReference {
                 name: String::from("Foo"),
                 namespace_path: vec![],
-                location: Range::default()
+                location: Range::default(),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Heuristic,
             }],
             process_from_contents(
                 contents,
@@ -38,12 +42,16 @@ mod tests {
                 UnresolvedReference {
                     name: String::from("Foo"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 },
                 UnresolvedReference {
                     name: String::from("Bar"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 }
             ],
             process_from_contents(
@@ -69,7 +77,9 @@ mod tests {
             vec![UnresolvedReference {
                 name: String::from("Foo"),
                 namespace_path: vec![],
-                location: Range::default()
+                location: Range::default(),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Heuristic,
             }],
             process_from_contents(
                 contents,
@@ -94,7 +104,9 @@ mod tests {
             vec![UnresolvedReference {
                 name: String::from("Foo"),
                 namespace_path: vec![],
-                location: Range::default()
+                location: Range::default(),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Heuristic,
             }],
             process_from_contents(
                 contents,
@@ -126,7 +138,9 @@ mod tests {
             vec![UnresolvedReference {
                 name: String::from("Foo"),
                 namespace_path: vec![],
-                location: Range::default()
+                location: Range::default(),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Heuristic,
             }],
             process_from_contents(
                 contents,
@@ -155,7 +169,9 @@ mod tests {
             vec![UnresolvedReference {
                 name: String::from("Foo"),
                 namespace_path: vec![],
-                location: Range::default()
+                location: Range::default(),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Heuristic,
             }],
             process_from_contents(
                 contents,
@@ -203,27 +219,37 @@ mod tests {
                 UnresolvedReference {
                     name: String::from("Foo"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 },
                 UnresolvedReference {
                     name: String::from("Bar"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 },
                 UnresolvedReference {
                     name: String::from("Baz"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 },
                 UnresolvedReference {
                     name: String::from("Boo"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 },
                 UnresolvedReference {
                     name: String::from("Bee"),
                     namespace_path: vec![],
-                    location: Range::default()
+                    location: Range::default(),
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Heuristic,
                 }
             ],
             process_from_contents(
@@ -1,16 +1,29 @@
 use std::{
     collections::HashSet,
+    fmt, fs,
+    panic::{catch_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
 };
 
+pub(crate) mod prescan;
 pub(crate) mod ruby;
-pub(crate) use ruby::experimental::parser::process_from_path as process_from_ruby_path_experimental;
-pub(crate) use ruby::packwerk::parser::process_from_path as process_from_ruby_path;
+pub(crate) use ruby::experimental::parser::process_from_contents as process_from_ruby_contents_experimental;
+pub(crate) use ruby::packwerk::parser::process_from_contents as process_from_ruby_contents;
 mod erb;
 pub(crate) use erb::experimental::parser::process_from_path as process_from_erb_path_experimental;
 pub(crate) use erb::packwerk::parser::process_from_path as process_from_erb_path;
+mod yaml;
+pub(crate) use yaml::process_from_path as process_from_yaml_path;
 
-use crate::packs::file_utils::is_stdin_file;
+use crate::packs::file_utils::{
+    file_read_contents, file_sigils, is_stdin_file,
+};
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 
@@ -31,13 +44,7 @@ pub fn process_file(
 
     let result = if let Some(file_type) = file_type_option {
         match file_type {
-            SupportedFileType::Ruby => {
-                if configuration.experimental_parser {
-                    process_from_ruby_path_experimental(path, configuration)
-                } else {
-                    process_from_ruby_path(path, configuration)
-                }
-            }
+            SupportedFileType::Ruby => process_ruby_file(path, configuration),
             SupportedFileType::Erb => {
                 if configuration.experimental_parser {
                     process_from_erb_path_experimental(path, configuration)
@@ -45,6 +52,9 @@ pub fn process_file(
                     process_from_erb_path(path, configuration)
                 }
             }
+            SupportedFileType::Yaml => {
+                process_from_yaml_path(path, configuration)
+            }
         }
     } else {
         // Later, we can perhaps have this error, since in theory the Configuration.intersect
@@ -53,6 +63,7 @@ pub fn process_file(
             absolute_path: path.to_path_buf(),
             unresolved_references: vec![],
             definitions: vec![], // TODO
+            sigils: FileSigils::default(),
         }
     };
 
@@ -63,11 +74,119 @@ pub fn process_file(
     result
 }
 
+// Files without anything that looks like a constant or an association can't
+// reference or define a constant, so they aren't parsed
+fn process_ruby_file(
+    path: &Path,
+    configuration: &Configuration,
+) -> ProcessedFile {
+    let contents = file_read_contents(path, configuration);
+    let sigils = file_sigils(&contents);
+    if !prescan::may_reference_constants(
+        &contents,
+        &configuration.custom_associations,
+    ) {
+        configuration.skipped_files.count();
+        return ProcessedFile {
+            absolute_path: path.to_path_buf(),
+            unresolved_references: vec![],
+            definitions: vec![],
+            sigils,
+        };
+    }
+
+    let processed_file = if configuration.experimental_parser {
+        process_from_ruby_contents_experimental(contents, path, configuration)
+    } else {
+        process_from_ruby_contents(contents, path, configuration)
+    };
+    ProcessedFile {
+        sigils,
+        ..processed_file
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct UnresolvedReference {
     pub name: String,
     pub namespace_path: Vec<String>,
     pub location: Range,
+    #[serde(default)]
+    pub kind: ReferenceKind,
+    #[serde(default)]
+    pub confidence: ReferenceConfidence,
+}
+
+// How the referencing code uses the constant
+#[derive(
+    Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    // Code that runs, e.g. a method call or a superclass
+    #[default]
+    Code,
+    // Only a type, in a Sorbet `sig` or an RBI file
+    Signature,
+}
+
+// How sure the parser is that the code references the constant, from least
+// to most sure, so `--min-confidence` can drop the references it guessed at
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ReferenceConfidence {
+    // Found in an ERB template, which is turned into Ruby by a rough
+    // approximation of the template language
+    Heuristic,
+    // Written in a string passed to `class_eval` and friends
+    MetaprogrammedString,
+    // The class of an association, e.g. `User` for `has_many :users`
+    InferredAssociation,
+    // The constant as written in the code
+    #[default]
+    Exact,
+}
+
+impl fmt::Display for ReferenceConfidence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ReferenceConfidence::Heuristic => "heuristic",
+            ReferenceConfidence::MetaprogrammedString => {
+                "metaprogrammed_string"
+            }
+            ReferenceConfidence::InferredAssociation => "inferred_association",
+            ReferenceConfidence::Exact => "exact",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// How the file defines the constant
+#[derive(
+    Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    // A class, module or constant assignment
+    #[default]
+    Constant,
+    // `autoload :Foo, "foo"`, which declares that the constant will be
+    // defined when it's first referenced
+    Autoload,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Default)]
@@ -82,6 +201,27 @@ pub struct Range {
 pub struct ParsedDefinition {
     pub fully_qualified_name: String,
     pub location: Range,
+    // The constant this one is assigned to, as written, when the definition
+    // is an alias like `InvoiceAlias = Billing::Invoice`. Always written,
+    // since bincode cache entries can't skip fields.
+    #[serde(default)]
+    pub alias_of: Option<String>,
+    #[serde(default)]
+    pub kind: DefinitionKind,
+}
+
+// The magic comments at the top of a Ruby file that change how it's checked
+#[derive(
+    Debug, Default, PartialEq, Eq, Serialize, Deserialize, Clone, Copy,
+)]
+pub struct FileSigils {
+    // `# pack_public: true`, which makes the file public wherever it is
+    #[serde(default)]
+    pub pack_public: bool,
+    // `# packs_strict: true`, which makes every violation in the file
+    // strict, so it is reported even if a todo file records it
+    #[serde(default)]
+    pub packs_strict: bool,
 }
 
 pub fn process_files_with_cache(
@@ -89,24 +229,174 @@ pub fn process_files_with_cache(
     cache: Box<dyn Cache + Send + Sync>,
     configuration: &Configuration,
 ) -> Vec<ProcessedFile> {
-    paths
+    let (processed_files, parse_errors) =
+        process_files_with_cache_and_errors(paths, cache, configuration);
+    for parse_error in parse_errors {
+        eprintln!("Error: {}", parse_error);
+    }
+    processed_files
+}
+
+// A file that was skipped because it couldn't be parsed. It still has a
+// processed file, with no references or definitions.
+#[derive(Debug, PartialEq, Eq, Serialize, Clone)]
+pub struct ParseError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "skipping {}, {}", self.path.display(), self.reason)
+    }
+}
+
+// Like `process_files_with_cache`, but hands back the files that were
+// skipped instead of printing them, sorted by path
+pub fn process_files_with_cache_and_errors(
+    paths: &HashSet<PathBuf>,
+    cache: Box<dyn Cache + Send + Sync>,
+    configuration: &Configuration,
+) -> (Vec<ProcessedFile>, Vec<ParseError>) {
+    // A parse that runs over `max_parse_duration` is left running on its own
+    // thread, which needs a configuration it can keep
+    let shared_configuration = configuration
+        .max_parse_duration
+        .map(|_| Arc::new(configuration.clone()));
+
+    let results: Vec<Result<ProcessedFile, ParseError>> = paths
         .par_iter()
-        .map(|absolute_path| -> ProcessedFile {
+        .map(|absolute_path| {
             if is_stdin_file(absolute_path, configuration) {
-                process_file(absolute_path, configuration)
-            } else {
-                match cache.get(absolute_path) {
-                    CacheResult::Processed(processed_file) => processed_file,
-                    CacheResult::Miss(empty_cache_entry) => {
-                        let processed_file =
-                            process_file(absolute_path, configuration);
-                        cache.write(&empty_cache_entry, &processed_file);
-                        processed_file
-                    }
-                }
+                return Ok(process_file(absolute_path, configuration));
+            }
+            match cache.get(absolute_path) {
+                CacheResult::Processed(processed_file) => Ok(processed_file),
+                // A file that was skipped isn't cached, so it's retried (and
+                // reported again) on the next run
+                CacheResult::Miss(empty_cache_entry) => parse_within_limits(
+                    absolute_path,
+                    configuration,
+                    shared_configuration.as_ref(),
+                )
+                .inspect(|processed_file| {
+                    cache.write(&empty_cache_entry, processed_file);
+                }),
             }
         })
-        .collect()
+        .collect();
+
+    let mut processed_files = Vec::with_capacity(results.len());
+    let mut parse_errors = vec![];
+    for result in results {
+        match result {
+            Ok(processed_file) => processed_files.push(processed_file),
+            Err(parse_error) => {
+                processed_files.push(ProcessedFile {
+                    absolute_path: parse_error.path.clone(),
+                    unresolved_references: vec![],
+                    definitions: vec![],
+                    sigils: FileSigils::default(),
+                });
+                parse_errors.push(parse_error);
+            }
+        }
+    }
+    parse_errors.sort_by(|a, b| a.path.cmp(&b.path));
+    (processed_files, parse_errors)
+}
+
+// Parses a file, or skips it with an error when it's over `max_file_size`,
+// takes longer than `max_parse_duration`, or crashes the parser with
+// `isolate_parse_crashes` on
+fn parse_within_limits(
+    path: &Path,
+    configuration: &Configuration,
+    shared_configuration: Option<&Arc<Configuration>>,
+) -> Result<ProcessedFile, ParseError> {
+    if let Some(max_file_size) = configuration.max_file_size {
+        let file_size = fs::metadata(path)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+        if file_size > max_file_size {
+            return Err(ParseError {
+                path: path.to_path_buf(),
+                reason: format!(
+                    "it is {} bytes, over the `max_file_size` of {}",
+                    file_size, max_file_size
+                ),
+            });
+        }
+    }
+
+    match (configuration.max_parse_duration, shared_configuration) {
+        (Some(max_parse_duration), Some(shared_configuration)) => {
+            parse_with_timeout(
+                path,
+                Arc::clone(shared_configuration),
+                max_parse_duration,
+            )
+        }
+        _ => parse(path, configuration),
+    }
+}
+
+fn parse(
+    path: &Path,
+    configuration: &Configuration,
+) -> Result<ProcessedFile, ParseError> {
+    if configuration.isolate_parse_crashes {
+        catch_parse_crash(path, || process_file(path, configuration))
+    } else {
+        Ok(process_file(path, configuration))
+    }
+}
+
+// The parser can't be interrupted, so a parse that takes too long is left to
+// finish on its own thread while the other files are parsed
+fn parse_with_timeout(
+    path: &Path,
+    configuration: Arc<Configuration>,
+    max_parse_duration: Duration,
+) -> Result<ProcessedFile, ParseError> {
+    let (sender, receiver) = mpsc::channel();
+    let owned_path = path.to_path_buf();
+    thread::spawn(move || {
+        let _ = sender.send(parse(&owned_path, &configuration));
+    });
+
+    match receiver.recv_timeout(max_parse_duration) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(ParseError {
+            path: path.to_path_buf(),
+            reason: format!(
+                "parsing it took longer than the `max_parse_duration` of {:?}",
+                max_parse_duration
+            ),
+        }),
+        Err(RecvTimeoutError::Disconnected) => {
+            panic!("The parser crashed on {}", path.display())
+        }
+    }
+}
+
+// Runs `process`, turning a panic into an error about the file so the
+// other files can still be checked
+fn catch_parse_crash(
+    path: &Path,
+    process: impl FnOnce() -> ProcessedFile,
+) -> Result<ProcessedFile, ParseError> {
+    catch_unwind(AssertUnwindSafe(process)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown error"));
+        ParseError {
+            path: path.to_path_buf(),
+            reason: format!("the parser crashed: {}", message),
+        }
+    })
 }
 
 #[cfg(test)]
@@ -141,4 +431,28 @@ mod tests {
     fn identifies_erb_files() {
         assert_is_erb("foo.erb");
     }
+
+    #[test]
+    fn catches_parser_crashes() {
+        let path = Path::new("packs/foo/app/services/crashy.rb");
+
+        assert_eq!(
+            Err(ParseError {
+                path: path.to_owned(),
+                reason: String::from("the parser crashed: unexpected token"),
+            }),
+            catch_parse_crash(path, || panic!("unexpected token"))
+        );
+
+        let processed_file = ProcessedFile {
+            absolute_path: path.to_owned(),
+            unresolved_references: vec![],
+            definitions: vec![],
+            sigils: FileSigils::default(),
+        };
+        assert_eq!(
+            Ok(processed_file.clone()),
+            catch_parse_crash(path, || processed_file)
+        );
+    }
 }
@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use memchr::memchr_iter;
+
+use super::ruby::ASSOCIATION_METHOD_NAMES;
+
+// How many files of a run were skipped without parsing, for `--timing`.
+// Files are processed in parallel, deep in the parsing stage, so they're
+// counted on the side, on the configuration. Its clones, e.g. the ones kept
+// by parses with a timeout, share the count.
+#[derive(Clone, Default)]
+pub struct SkippedFiles(Arc<AtomicUsize>);
+
+impl SkippedFiles {
+    pub(crate) fn count(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // The number of files skipped since the last call
+    pub(crate) fn take(&self) -> usize {
+        self.0.swap(0, Ordering::Relaxed)
+    }
+}
+
+// Whether Ruby code may reference or define a constant, i.e. whether it has
+// an identifier starting with an uppercase letter, or calls an association
+// like `has_many :users`, outside of comments. Only
+// lines that are nothing but a comment are left out, since a `#` elsewhere
+// may be in a string. When unsure, e.g. with non-ASCII characters, which
+// can start constants too, this says yes and the file is parsed.
+pub(crate) fn may_reference_constants(
+    contents: &str,
+    custom_associations: &[String],
+) -> bool {
+    let bytes = contents.as_bytes();
+    let mut line_start = 0;
+    for line_end in memchr_iter(b'\n', bytes).chain([bytes.len()]) {
+        let line = bytes[line_start..line_end].trim_ascii_start();
+        line_start = line_end + 1;
+
+        // `#{Foo}` at the start of a line is an interpolation in a heredoc
+        if line.starts_with(b"#") && !line.starts_with(b"#{") {
+            continue;
+        }
+        if has_constant_like_identifier(line)
+            || has_association(line, custom_associations)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+fn has_constant_like_identifier(code: &[u8]) -> bool {
+    let mut previous: Option<u8> = None;
+    for &byte in code {
+        if !byte.is_ascii() {
+            return true;
+        }
+        let starts_identifier = !previous.is_some_and(|previous| {
+            previous.is_ascii_alphanumeric() || previous == b'_'
+        });
+        if byte.is_ascii_uppercase() && starts_identifier {
+            return true;
+        }
+        previous = Some(byte);
+    }
+    false
+}
+
+fn has_association(code: &[u8], custom_associations: &[String]) -> bool {
+    code.split(|byte| !byte.is_ascii_alphanumeric() && *byte != b'_')
+        .any(|word| {
+            ASSOCIATION_METHOD_NAMES
+                .iter()
+                .copied()
+                .chain(custom_associations.iter().map(String::as_str))
+                .any(|association| association.as_bytes() == word)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_may_reference_constants() {
+        assert!(may_reference_constants("Foo.new", &[]));
+        assert!(may_reference_constants("x = ::Foo::Bar", &[]));
+        assert!(may_reference_constants("def foo\n  bar(Baz)\nend\n", &[]));
+        assert!(may_reference_constants(
+            "<<~SQL\n#{Foo.table_name}\nSQL\n",
+            &[]
+        ));
+        assert!(may_reference_constants("puts 'Ünïcode'", &[]));
+
+        assert!(may_reference_constants("  has_many :users\n", &[]));
+        assert!(may_reference_constants(
+            "cache_belongs_to :user",
+            &[String::from("cache_belongs_to")]
+        ));
+
+        assert!(!may_reference_constants("cache_belongs_to :user", &[]));
+        assert!(!may_reference_constants("has_many_users(1)", &[]));
+        assert!(!may_reference_constants("", &[]));
+        assert!(!may_reference_constants(
+            "# typed: true\n# Uses Foo\ndef foo_Bar\n  bar_baz(1)\nend\n",
+            &[]
+        ));
+        assert!(!may_reference_constants(
+            "  # Foo\nit { expect(x).to eq(1) }",
+            &[]
+        ));
+    }
+}
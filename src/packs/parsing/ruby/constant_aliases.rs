@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::packs::{
+    constant_resolver::{ConstantDefinition, ConstantResolver},
+    PackSet, ProcessedFile,
+};
+
+// Wraps another resolver so a reference to an alias, like `InvoiceAlias` in
+// `InvoiceAlias = Billing::Invoice`, resolves to the constant it's assigned to,
+// following chains of aliases until a constant that isn't one. An alias in
+// the same pack as its constant, like one in a facade, is the pack's own way
+// of making the constant public, so it isn't followed.
+pub struct AliasFollowingConstantResolver {
+    resolver: Box<dyn ConstantResolver + Send + Sync>,
+    // The fully qualified name of each alias to the one of its constant
+    aliases: HashMap<String, String>,
+}
+
+impl ConstantResolver for AliasFollowingConstantResolver {
+    fn resolve(
+        &self,
+        fully_or_partially_qualified_constant: &str,
+        namespace_path: &[&str],
+    ) -> Option<Vec<ConstantDefinition>> {
+        let constant_definitions = self
+            .resolver
+            .resolve(fully_or_partially_qualified_constant, namespace_path)?;
+
+        Some(
+            constant_definitions
+                .into_iter()
+                .flat_map(|constant| {
+                    self.follow(&constant.fully_qualified_name)
+                        .unwrap_or_else(|| vec![constant])
+                })
+                .collect(),
+        )
+    }
+
+    fn fully_qualified_constant_name_to_constant_definition_map(
+        &self,
+    ) -> &HashMap<String, Vec<ConstantDefinition>> {
+        self.resolver
+            .fully_qualified_constant_name_to_constant_definition_map()
+    }
+}
+
+impl AliasFollowingConstantResolver {
+    pub fn create(
+        resolver: Box<dyn ConstantResolver + Send + Sync>,
+        processed_files: &[ProcessedFile],
+        pack_set: &PackSet,
+    ) -> Box<dyn ConstantResolver + Send + Sync> {
+        let mut aliases = HashMap::new();
+
+        for (processed_file, definition) in
+            processed_files.iter().flat_map(|processed_file| {
+                processed_file
+                    .definitions
+                    .iter()
+                    .map(move |definition| (processed_file, definition))
+            })
+        {
+            let Some(alias_of) = &definition.alias_of else {
+                continue;
+            };
+
+            // The assigned constant is looked up from where the alias is
+            let namespace_path: Vec<&str> = definition
+                .fully_qualified_name
+                .split("::")
+                .filter(|namespace| !namespace.is_empty())
+                .collect();
+            let namespace_path =
+                &namespace_path[..namespace_path.len().saturating_sub(1)];
+
+            let target = resolver
+                .resolve(alias_of, namespace_path)
+                .and_then(|definitions| definitions.into_iter().next());
+            let pack_name =
+                |path| pack_set.for_file(path).map(|pack| pack.name.as_str());
+            if let Some(target) = target {
+                let alias_pack = pack_name(&processed_file.absolute_path);
+                if alias_pack.is_some()
+                    && alias_pack
+                        == pack_name(&target.absolute_path_of_definition)
+                {
+                    continue;
+                }
+                aliases.insert(
+                    definition.fully_qualified_name.clone(),
+                    target.fully_qualified_name,
+                );
+            }
+        }
+
+        Box::new(AliasFollowingConstantResolver { resolver, aliases })
+    }
+
+    // The definitions of the constant at the end of the chain of aliases
+    // starting at `fully_qualified_name`, or None if it isn't an alias
+    fn follow(
+        &self,
+        fully_qualified_name: &str,
+    ) -> Option<Vec<ConstantDefinition>> {
+        let mut visited = HashSet::from([fully_qualified_name]);
+        let mut current = self.aliases.get(fully_qualified_name)?;
+
+        while let Some(next) = self.aliases.get(current) {
+            // A cycle of aliases never gets to a constant, so we stop where
+            // it starts repeating
+            if !visited.insert(current) {
+                break;
+            }
+            current = next;
+        }
+
+        self.resolver
+            .fully_qualified_constant_name_to_constant_definition_map()
+            .get(current)
+            .filter(|definitions| !definitions.is_empty())
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::packs::parsing::{
+        DefinitionKind, FileSigils, ParsedDefinition, Range,
+    };
+
+    struct MapResolver {
+        map: HashMap<String, Vec<ConstantDefinition>>,
+    }
+
+    impl ConstantResolver for MapResolver {
+        fn resolve(
+            &self,
+            fully_or_partially_qualified_constant: &str,
+            _namespace_path: &[&str],
+        ) -> Option<Vec<ConstantDefinition>> {
+            let name = format!(
+                "::{}",
+                fully_or_partially_qualified_constant.trim_start_matches("::")
+            );
+            self.map.get(&name).cloned()
+        }
+
+        fn fully_qualified_constant_name_to_constant_definition_map(
+            &self,
+        ) -> &HashMap<String, Vec<ConstantDefinition>> {
+            &self.map
+        }
+    }
+
+    fn constant(name: &str, path: &str) -> ConstantDefinition {
+        ConstantDefinition {
+            fully_qualified_name: name.to_owned(),
+            absolute_path_of_definition: PathBuf::from(path),
+            kind: DefinitionKind::Constant,
+        }
+    }
+
+    fn alias(name: &str, alias_of: &str) -> ParsedDefinition {
+        ParsedDefinition {
+            fully_qualified_name: name.to_owned(),
+            location: Range::default(),
+            alias_of: Some(alias_of.to_owned()),
+            kind: DefinitionKind::Constant,
+        }
+    }
+
+    #[test]
+    fn test_follows_chains_of_aliases() {
+        let invoice = constant("::Billing::Invoice", "billing/invoice.rb");
+        let map = HashMap::from([
+            (String::from("::Billing::Invoice"), vec![invoice.clone()]),
+            (
+                String::from("::InvoiceAlias"),
+                vec![constant("::InvoiceAlias", "invoice_alias.rb")],
+            ),
+            (
+                String::from("::OtherAlias"),
+                vec![constant("::OtherAlias", "other_alias.rb")],
+            ),
+        ]);
+        let processed_files = vec![ProcessedFile {
+            absolute_path: PathBuf::from("aliases.rb"),
+            unresolved_references: vec![],
+            definitions: vec![
+                alias("::InvoiceAlias", "Billing::Invoice"),
+                alias("::OtherAlias", "InvoiceAlias"),
+            ],
+            sigils: FileSigils::default(),
+        }];
+
+        let resolver = AliasFollowingConstantResolver::create(
+            Box::new(MapResolver { map }),
+            &processed_files,
+            &PackSet::default(),
+        );
+
+        assert_eq!(
+            Some(vec![invoice.clone()]),
+            resolver.resolve("OtherAlias", &[])
+        );
+        assert_eq!(
+            Some(vec![invoice.clone()]),
+            resolver.resolve("InvoiceAlias", &[])
+        );
+        assert_eq!(
+            Some(vec![invoice]),
+            resolver.resolve("Billing::Invoice", &[])
+        );
+    }
+}
@@ -15,7 +15,7 @@ use std::{
 
 pub fn get_experimental_constant_resolver(
     absolute_root: &Path,
-    processed_files: &Vec<ProcessedFile>,
+    processed_files: &[ProcessedFile],
     ignored_definitions: &HashMap<String, HashSet<PathBuf>>,
 ) -> Box<dyn ConstantResolver + Send + Sync> {
     let constants = processed_files
@@ -32,6 +32,7 @@ pub fn get_experimental_constant_resolver(
                         absolute_path_of_definition: processed_file
                             .absolute_path
                             .to_owned(),
+                        kind: definition.kind,
                     }
                 })
                 .collect::<Vec<ConstantDefinition>>()
@@ -51,7 +52,10 @@ mod tests {
 
     use crate::packs::parsing::ruby::experimental::parser::process_from_contents;
     use crate::packs::parsing::{ParsedDefinition, Range};
-    use crate::packs::{Configuration, ProcessedFile, UnresolvedReference};
+    use crate::packs::{
+        Configuration, DefinitionKind, FileSigils, ProcessedFile,
+        ReferenceConfidence, ReferenceKind, UnresolvedReference,
+    };
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -69,6 +73,8 @@ mod tests {
                 end_row: 1,
                 end_col: 4,
             },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
         }];
 
         let definitions = vec![];
@@ -79,6 +85,7 @@ mod tests {
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -98,6 +105,8 @@ mod tests {
                 end_row: 1,
                 end_col: 9,
             },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
         }];
 
         let definitions = vec![];
@@ -108,6 +117,7 @@ mod tests {
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -128,6 +138,8 @@ mod tests {
                 end_row: 1,
                 end_col: 14,
             },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
         }];
 
         let definitions = vec![];
@@ -138,6 +150,7 @@ mod tests {
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -158,6 +171,8 @@ mod tests {
                 end_row: 1,
                 end_col: 19,
             },
+            kind: ReferenceKind::Code,
+            confidence: ReferenceConfidence::Exact,
         }];
 
         let definitions = vec![];
@@ -168,6 +183,7 @@ mod tests {
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -194,6 +210,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -222,6 +239,8 @@ end
                 end_row: 1,
                 end_col: 10,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -230,6 +249,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -258,6 +278,8 @@ end
                 end_row: 1,
                 end_col: 10,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -266,6 +288,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -293,6 +316,8 @@ end
                 end_row: 1,
                 end_col: 10,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -301,6 +326,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -332,6 +358,8 @@ end
                 end_row: 2,
                 end_col: 12,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -340,6 +368,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
         assert_eq!(expected, actual);
     }
@@ -372,6 +401,8 @@ end
                 end_row: 1,
                 end_col: 10,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -380,6 +411,7 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
 
         assert_eq!(expected, actual);
@@ -413,6 +445,8 @@ end
                 end_row: 1,
                 end_col: 10,
             },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
         }];
 
         let actual =
@@ -421,8 +455,146 @@ end
             absolute_path,
             unresolved_references,
             definitions,
+            sigils: FileSigils::default(),
         };
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn top_level_class_definition_inside_module() {
+        let contents: String = String::from(
+            "\
+module Foo
+  class ::Bar
+    def bar
+    end
+  end
+end
+            ",
+        );
+
+        let configuration = Configuration::default();
+
+        let absolute_path = PathBuf::from("path/to/file.rb");
+        let unresolved_references = vec![];
+
+        let definitions = vec![ParsedDefinition {
+            fully_qualified_name: String::from("::Bar"),
+            location: Range {
+                start_row: 2,
+                start_col: 8,
+                end_row: 2,
+                end_col: 14,
+            },
+            alias_of: None,
+            kind: DefinitionKind::Constant,
+        }];
+
+        let actual =
+            process_from_contents(contents, &absolute_path, &configuration);
+        let expected = ProcessedFile {
+            absolute_path,
+            unresolved_references,
+            definitions,
+            sigils: FileSigils::default(),
+        };
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn autoload_declarations() {
+        let contents: String = String::from(
+            "\
+autoload :Baz, \"baz\"
+Billing.autoload(:Invoice, \"billing/invoice\")
+
+module Foo
+  autoload :Bar, \"foo/bar\"
+end
+",
+        );
+
+        let configuration = Configuration::default();
+        let absolute_path = PathBuf::from("path/to/file.rb");
+
+        let actual =
+            process_from_contents(contents, &absolute_path, &configuration);
+        let definitions: Vec<(&str, DefinitionKind)> = actual
+            .definitions
+            .iter()
+            .map(|definition| {
+                (definition.fully_qualified_name.as_str(), definition.kind)
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("::Baz", DefinitionKind::Autoload),
+                ("::Billing::Invoice", DefinitionKind::Autoload),
+                ("::Foo::Bar", DefinitionKind::Autoload),
+                ("::Foo", DefinitionKind::Constant),
+            ],
+            definitions
+        );
+    }
+
+    // The constants in a `refine` block are looked up in the enclosing
+    // namespaces, and the ones it defines belong to the refinement, not to
+    // the enclosing module
+    #[test]
+    fn refinements() {
+        let contents: String = String::from(
+            "\
+module StringRefinements
+  refine Foo::Bar do
+    LIMIT = 1
+
+    def shout
+      Baz.new(LIMIT)
+    end
+  end
+end
+
+using StringRefinements
+",
+        );
+
+        let configuration = Configuration::default();
+        let actual = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        );
+        let references: Vec<(&str, Vec<&str>)> = actual
+            .unresolved_references
+            .iter()
+            .map(|reference| {
+                (
+                    reference.name.as_str(),
+                    reference
+                        .namespace_path
+                        .iter()
+                        .map(String::as_str)
+                        .collect(),
+                )
+            })
+            .collect();
+        let definitions: Vec<&str> = actual
+            .definitions
+            .iter()
+            .map(|definition| definition.fully_qualified_name.as_str())
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("Foo::Bar", vec!["StringRefinements"]),
+                ("Baz", vec!["StringRefinements"]),
+                ("LIMIT", vec!["StringRefinements"]),
+                ("StringRefinements", vec![]),
+            ],
+            references
+        );
+        assert_eq!(vec!["::StringRefinements"], definitions);
+    }
 }
@@ -7,7 +7,10 @@ use std::{
 
 use crate::packs::{
     constant_resolver::{ConstantDefinition, ConstantResolver},
-    parsing::ruby::namespace_calculator::combine_namespace_with_constant_name,
+    parsing::ruby::namespace_calculator::{
+        combine_namespace_with_constant_name, lookup_scope,
+    },
+    DefinitionKind,
 };
 
 #[derive(Default, Debug)]
@@ -22,19 +25,10 @@ impl ConstantResolver for ExperimentalConstantResolver {
         fully_or_partially_qualified_constant: &str,
         namespace_path: &[&str],
     ) -> Option<Vec<ConstantDefinition>> {
-        // If the fully_or_partially_qualified_constant is prefixed with ::, the namespace path is technically empty, since it's a global reference
+        // A global reference like `::Foo` skips the lexical nesting.
+        // Guesses at the fully qualified name add the leading `::` back.
         let (namespace_path, const_name) =
-            if fully_or_partially_qualified_constant.starts_with("::") {
-                // `resolve_constant` will add a leading :: before it makes a guess at the fully qualified name
-                // so we remove it here and represent it as a relative constant with no namespace path
-                let const_name = fully_or_partially_qualified_constant
-                    .strip_prefix("::")
-                    .unwrap();
-                let namespace_path: &[&str] = &[];
-                (namespace_path, const_name)
-            } else {
-                (namespace_path, fully_or_partially_qualified_constant)
-            };
+            lookup_scope(namespace_path, fully_or_partially_qualified_constant);
 
         Some(self.resolve_traversing_namespace_path(const_name, namespace_path))
     }
@@ -98,6 +92,21 @@ impl ExperimentalConstantResolver {
             }
         }
 
+        // An `autoload` only declares where the constant will be defined,
+        // so it stands in for the definition when the file defining it isn't
+        // parsed, e.g. when it's outside of the included files
+        for definitions in fully_qualified_constant_to_constant_map.values_mut()
+        {
+            if definitions
+                .iter()
+                .any(|definition| definition.kind != DefinitionKind::Autoload)
+            {
+                definitions.retain(|definition| {
+                    definition.kind != DefinitionKind::Autoload
+                });
+            }
+        }
+
         debug!("Finished building constant resolver");
 
         Box::new(ExperimentalConstantResolver {
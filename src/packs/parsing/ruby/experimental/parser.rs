@@ -1,18 +1,19 @@
-use crate::packs::file_utils::file_read_contents;
 use crate::packs::{
     parsing::{
         ruby::parse_utils::{
-            fetch_const_const_name, fetch_const_name, fetch_node_location,
+            eval_string_references, fetch_const_const_name, fetch_const_name,
+            fetch_node_location, get_autoload_definition,
             get_constant_assignment_definition, get_definition_from,
-            get_reference_from_active_record_association, loc_to_range,
+            get_eval_string, get_reference_from_active_record_association,
+            is_rbi_file, is_refinement_block, is_signature_block, loc_to_range,
+            parser_options,
         },
-        ParsedDefinition, UnresolvedReference,
+        ParsedDefinition, ReferenceConfidence, ReferenceKind,
+        UnresolvedReference,
     },
-    Configuration, ProcessedFile,
-};
-use lib_ruby_parser::{
-    nodes, traverse::visitor::Visitor, Node, Parser, ParserOptions,
+    Configuration, FileSigils, ProcessedFile,
 };
+use lib_ruby_parser::{nodes, traverse::visitor::Visitor, Node, Parser};
 use line_col::LineColLookup;
 use std::path::Path;
 
@@ -23,6 +24,47 @@ struct ReferenceCollector<'a> {
     pub line_col_lookup: LineColLookup<'a>,
     pub behavioral_change_in_namespace: bool,
     pub custom_associations: Vec<String>,
+    pub parse_eval_strings: bool,
+    pub in_signature: bool,
+    pub in_refinement: bool,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    fn new(
+        line_col_lookup: LineColLookup<'a>,
+        current_namespaces: Vec<String>,
+        custom_associations: Vec<String>,
+        parse_eval_strings: bool,
+    ) -> ReferenceCollector<'a> {
+        ReferenceCollector {
+            references: vec![],
+            current_namespaces,
+            definitions: vec![],
+            line_col_lookup,
+            behavioral_change_in_namespace: false,
+            custom_associations,
+            parse_eval_strings,
+            in_signature: false,
+            in_refinement: false,
+        }
+    }
+
+    // Constants defined in a `refine` block belong to the anonymous
+    // refinement module, so they aren't definitions of the enclosing
+    // namespace
+    fn add_definition(&mut self, definition: ParsedDefinition) {
+        if !self.in_refinement {
+            self.definitions.push(definition);
+        }
+    }
+
+    fn reference_kind(&self) -> ReferenceKind {
+        if self.in_signature {
+            ReferenceKind::Signature
+        } else {
+            ReferenceKind::Code
+        }
+    }
 }
 
 impl<'a> Visitor for ReferenceCollector<'a> {
@@ -63,7 +105,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         }
 
         if self.behavioral_change_in_namespace {
-            self.definitions.push(definition);
+            self.add_definition(definition);
         }
 
         // When we're done visiting the class/module, we restore the previous behavioral change flag
@@ -88,10 +130,45 @@ impl<'a> Visitor for ReferenceCollector<'a> {
                 );
 
             if let Some(association_reference) = association_reference {
-                self.references.push(association_reference);
+                self.references.push(UnresolvedReference {
+                    kind: self.reference_kind(),
+                    ..association_reference
+                });
+            }
+
+            if let Some(definition) = get_autoload_definition(
+                node,
+                &self.current_namespaces,
+                &self.line_col_lookup,
+            ) {
+                self.add_definition(definition);
             }
         }
         lib_ruby_parser::traverse::visitor::visit_send(self, node);
+
+        if self.parse_eval_strings {
+            if let Some(eval_string) = get_eval_string(
+                node,
+                &self.current_namespaces,
+                &self.line_col_lookup,
+            ) {
+                // Definitions made by the string are not collected
+                let references = eval_string_references(
+                    eval_string,
+                    |ast, lookup, namespaces| {
+                        let mut collector = ReferenceCollector::new(
+                            lookup,
+                            namespaces,
+                            self.custom_associations.clone(),
+                            self.parse_eval_strings,
+                        );
+                        collector.visit(ast);
+                        collector.references
+                    },
+                );
+                self.references.extend(references);
+            }
+        }
     }
 
     fn on_casgn(&mut self, node: &nodes::Casgn) {
@@ -102,7 +179,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         );
 
         if let Some(definition) = definition {
-            self.definitions.push(definition);
+            self.add_definition(definition);
         }
 
         if let Some(v) = node.value.to_owned() {
@@ -139,7 +216,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         }
 
         if self.behavioral_change_in_namespace {
-            self.definitions.push(definition);
+            self.add_definition(definition);
         }
 
         // When we're done visiting the class/module, we restore the previous behavioral change flag
@@ -149,6 +226,30 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         self.current_namespaces.pop();
     }
 
+    // `in Foo::Bar(x:)` and `in Foo[1, 2]` reference `Foo::Bar` and `Foo`
+    // before deconstructing them, so the constant is visited like any other
+    // reference, followed by the patterns inside of it. Constants nested in
+    // find, array and hash patterns are reached by the default traversal.
+    fn on_const_pattern(&mut self, node: &nodes::ConstPattern) {
+        self.visit(&node.const_);
+        self.visit(&node.pattern);
+    }
+
+    // Constants in a Sorbet `sig` are only used as types, which some teams
+    // don't want to count as a dependency
+    //
+    // The constants referenced in a `refine` block are looked up in the
+    // enclosing namespaces, like anywhere else
+    fn on_block(&mut self, node: &nodes::Block) {
+        let in_signature = self.in_signature;
+        let in_refinement = self.in_refinement;
+        self.in_signature = in_signature || is_signature_block(node);
+        self.in_refinement = in_refinement || is_refinement_block(node);
+        lib_ruby_parser::traverse::visitor::visit_block(self, node);
+        self.in_signature = in_signature;
+        self.in_refinement = in_refinement;
+    }
+
     fn on_const(&mut self, node: &nodes::Const) {
         let Ok(name) = fetch_const_const_name(node) else {
             return;
@@ -165,6 +266,8 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             name,
             namespace_path,
             location: loc_to_range(&node.expression_l, &self.line_col_lookup),
+            kind: self.reference_kind(),
+            confidence: ReferenceConfidence::Exact,
         })
     }
 
@@ -179,23 +282,12 @@ impl<'a> Visitor for ReferenceCollector<'a> {
     }
 }
 
-pub(crate) fn process_from_path(
-    path: &Path,
-    configuration: &Configuration,
-) -> ProcessedFile {
-    let contents = file_read_contents(path, configuration);
-    process_from_contents(contents, path, configuration)
-}
-
 pub(crate) fn process_from_contents(
     contents: String,
     path: &Path,
     configuration: &Configuration,
 ) -> ProcessedFile {
-    let options = ParserOptions {
-        buffer_name: "".to_string(),
-        ..Default::default()
-    };
+    let options = parser_options();
 
     let lookup = LineColLookup::new(&contents);
     let parser = Parser::new(contents.clone(), options);
@@ -210,18 +302,18 @@ pub(crate) fn process_from_contents(
                 absolute_path: path.to_owned(),
                 unresolved_references: vec![],
                 definitions: vec![],
+                sigils: FileSigils::default(),
             }
         }
     };
 
-    let mut collector = ReferenceCollector {
-        references: vec![],
-        current_namespaces: vec![],
-        definitions: vec![],
-        line_col_lookup: lookup,
-        behavioral_change_in_namespace: false,
-        custom_associations: configuration.custom_associations.clone(),
-    };
+    let mut collector = ReferenceCollector::new(
+        lookup,
+        vec![],
+        configuration.custom_associations.clone(),
+        configuration.parse_eval_strings,
+    );
+    collector.in_signature = is_rbi_file(path);
 
     collector.visit(&ast);
 
@@ -238,5 +330,6 @@ pub(crate) fn process_from_contents(
         absolute_path,
         unresolved_references,
         definitions,
+        sigils: FileSigils::default(),
     }
 }
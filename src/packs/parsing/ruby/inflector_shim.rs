@@ -121,6 +121,28 @@ pub fn camelize(s: &str, acronyms: &HashSet<String>) -> String {
     new_string
 }
 
+pub fn underscore(s: &str) -> String {
+    // Meant to emulate https://github.com/rails/rails/blob/e88857bbb9d4e1dd64555c34541301870de4a45b/activesupport/lib/active_support/inflector/methods.rb#L99,
+    // without acronyms, which underscore the same way as other words
+    //
+    // def underscore(camel_cased_word)
+    //   return camel_cased_word.to_s unless /[A-Z-]|::/.match?(camel_cased_word)
+    //   word = camel_cased_word.to_s.gsub("::", "/")
+    //   word.gsub!(inflections.acronyms_underscore_regex) { "#{$1 && '_' }#{$2.downcase}" }
+    //   word.gsub!(/([A-Z\d]+)([A-Z][a-z])/, '\1_\2')
+    //   word.gsub!(/([a-z\d])([A-Z])/, '\1_\2')
+    //   word.tr!("-", "_")
+    //   word.downcase!
+    //   word
+    // end
+    let word = s.replace("::", "/");
+    let re = Regex::new("([A-Z\\d]+)([A-Z][a-z])").unwrap();
+    let word = re.replace_all(&word, "${1}_${2}");
+    let re = Regex::new("([a-z\\d])([A-Z])").unwrap();
+    let word = re.replace_all(&word, "${1}_${2}");
+    word.replace('-', "_").to_lowercase()
+}
+
 /// Capitalizes the first character in s.
 fn capitalize(s: &str) -> String {
     let mut c = s.chars();
@@ -149,4 +171,11 @@ mod tests {
         let expected = "MyString401kThing";
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_underscore() {
+        assert_eq!("billing/api", underscore("Billing::Api"));
+        assert_eq!("html_tidy/api_client", underscore("HTMLTidy::APIClient"));
+        assert_eq!("invoice401k", underscore("Invoice401k"));
+    }
 }
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use lib_ruby_parser::{nodes, traverse::visitor::Visitor, Node, Parser};
+use line_col::LineColLookup;
+
+use super::parse_utils::{
+    fetch_const_const_name, fetch_const_name, loc_to_range, parser_options,
+};
+use crate::packs::file_utils::file_read_contents;
+use crate::packs::parsing::{
+    ReferenceConfidence, ReferenceKind, UnresolvedReference,
+};
+use crate::packs::Configuration;
+
+// A method called directly on a constant, e.g. `create!` in
+// `Billing::Invoice.create!`. Calls on instances of the constant can't be
+// told apart without types, so they aren't collected.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct MethodCall {
+    pub reference: UnresolvedReference,
+    pub method_name: String,
+}
+
+struct MethodCallCollector<'a> {
+    calls: Vec<MethodCall>,
+    current_namespaces: Vec<String>,
+    line_col_lookup: LineColLookup<'a>,
+}
+
+impl<'a> MethodCallCollector<'a> {
+    fn open_namespace(
+        &mut self,
+        name: &nodes::Node,
+        body: &Option<Box<nodes::Node>>,
+    ) {
+        let Ok(namespace) = fetch_const_name(name) else {
+            return;
+        };
+        self.current_namespaces.push(namespace);
+        if let Some(body) = body {
+            self.visit(body);
+        }
+        self.current_namespaces.pop();
+    }
+}
+
+impl<'a> Visitor for MethodCallCollector<'a> {
+    fn on_class(&mut self, node: &nodes::Class) {
+        if let Some(superclass) = &node.superclass {
+            self.visit(superclass);
+        }
+        self.open_namespace(&node.name, &node.body);
+    }
+
+    fn on_module(&mut self, node: &nodes::Module) {
+        self.open_namespace(&node.name, &node.body);
+    }
+
+    fn on_send(&mut self, node: &nodes::Send) {
+        if let Some(Node::Const(receiver)) = node.recv.as_deref() {
+            if let Ok(name) = fetch_const_const_name(receiver) {
+                let namespace_path = self
+                    .current_namespaces
+                    .iter()
+                    .filter(|namespace| **namespace != name)
+                    .cloned()
+                    .collect();
+                self.calls.push(MethodCall {
+                    reference: UnresolvedReference {
+                        name,
+                        namespace_path,
+                        location: loc_to_range(
+                            &receiver.expression_l,
+                            &self.line_col_lookup,
+                        ),
+                        kind: ReferenceKind::Code,
+                        confidence: ReferenceConfidence::Exact,
+                    },
+                    method_name: node.method_name.clone(),
+                });
+            }
+        }
+        lib_ruby_parser::traverse::visitor::visit_send(self, node);
+    }
+}
+
+pub(crate) fn method_calls_from_path(
+    path: &Path,
+    configuration: &Configuration,
+) -> Vec<MethodCall> {
+    method_calls_from_contents(&file_read_contents(path, configuration))
+}
+
+fn method_calls_from_contents(contents: &str) -> Vec<MethodCall> {
+    let options = parser_options();
+    let parser = Parser::new(contents.to_owned(), options);
+    let Some(ast) = parser.do_parse().ast else {
+        return vec![];
+    };
+    let mut collector = MethodCallCollector {
+        calls: vec![],
+        current_namespaces: vec![],
+        line_col_lookup: LineColLookup::new(contents),
+    };
+    collector.visit(&ast);
+    collector.calls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_method_calls_from_contents() {
+        let contents = "\
+module Checkout
+  def self.call
+    Billing::Invoice.create!(total: Money.zero)
+    invoice.save
+  end
+end
+";
+        let calls: Vec<(String, Vec<String>, String, usize)> =
+            method_calls_from_contents(contents)
+                .into_iter()
+                .map(|call| {
+                    (
+                        call.reference.name,
+                        call.reference.namespace_path,
+                        call.method_name,
+                        call.reference.location.start_row,
+                    )
+                })
+                .collect();
+
+        assert_eq!(
+            vec![
+                (
+                    String::from("Billing::Invoice"),
+                    vec![String::from("Checkout")],
+                    String::from("create!"),
+                    3,
+                ),
+                (
+                    String::from("Money"),
+                    vec![String::from("Checkout")],
+                    String::from("zero"),
+                    3,
+                ),
+            ],
+            calls
+        );
+    }
+}
@@ -1,8 +1,11 @@
+pub(crate) mod constant_aliases;
 pub(crate) mod experimental;
-mod inflector_shim;
-mod namespace_calculator;
+pub(crate) mod inflector_shim;
+pub(crate) mod method_calls;
+pub(crate) mod namespace_calculator;
 pub(crate) mod packwerk;
 mod parse_utils;
-mod rails_utils;
+pub(crate) use parse_utils::ASSOCIATION_METHOD_NAMES;
+pub(crate) mod rails_utils;
 mod ruby_utils;
 pub(crate) mod zeitwerk;
@@ -17,17 +17,23 @@
 // end
 // # inputs: ['Foo', 'Bar', 'Baz']
 // # outputs: ['Foo::Bar::Baz', 'Foo::Bar', 'Foo']
+//
+// Compact-style definitions only add the full name to the nesting,
+// not each of the namespaces in it:
+// module Foo
+//   class Bar::Baz
+//     puts Module.nesting.inspect
+//   end
+// end
+// # inputs: ['Foo', 'Bar::Baz']
+// # outputs: ['Foo::Bar::Baz', 'Foo']
 pub(crate) fn calculate_module_nesting(
     namespace_nesting: &[&str],
 ) -> Vec<String> {
     let mut nesting = Vec::new();
     let mut previous = String::from("");
     namespace_nesting.iter().for_each(|namespace| {
-        let new_nesting: String = if previous.is_empty() {
-            namespace.to_string()
-        } else {
-            format!("{}::{}", previous, namespace)
-        };
+        let new_nesting = qualify(&previous, namespace);
 
         previous = new_nesting.to_owned();
         nesting.insert(0, new_nesting);
@@ -40,9 +46,35 @@ pub fn combine_namespace_with_constant_name(
     namespace_path: &[&str],
     const_name: &str,
 ) -> String {
-    let mut fully_qualified_name_vec = namespace_path.to_vec();
-    fully_qualified_name_vec.push(const_name);
-    format!("::{}", fully_qualified_name_vec.join("::"))
+    let namespace = namespace_path
+        .iter()
+        .fold(String::from(""), |previous, namespace| {
+            qualify(&previous, namespace)
+        });
+    format!("::{}", qualify(&namespace, const_name))
+}
+
+// A name with a leading `::`, e.g. `class ::Foo`, is looked up from the top level,
+// so it replaces the enclosing namespace instead of being nested in it.
+fn qualify(enclosing_namespace: &str, name: &str) -> String {
+    match name.strip_prefix("::") {
+        Some(top_level_name) => top_level_name.to_owned(),
+        None if enclosing_namespace.is_empty() => name.to_owned(),
+        None => format!("{}::{}", enclosing_namespace, name),
+    }
+}
+
+// `::Foo` is looked up from the top level only, whatever the nesting of the
+// reference, so it's `Foo` with no namespace to traverse. Other names are
+// looked up from the nesting they're in.
+pub fn lookup_scope<'a>(
+    namespace_path: &'a [&'a str],
+    const_name: &'a str,
+) -> (&'a [&'a str], &'a str) {
+    match const_name.strip_prefix("::") {
+        Some(top_level_name) => (&[], top_level_name),
+        None => (namespace_path, const_name),
+    }
 }
 
 pub fn possible_fully_qualified_constants(
@@ -62,3 +94,90 @@ pub fn possible_fully_qualified_constants(
 
     possible_constants
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_nesting() {
+        assert_eq!(
+            calculate_module_nesting(&["Foo", "Bar", "Baz"]),
+            vec!["Foo::Bar::Baz", "Foo::Bar", "Foo"]
+        );
+    }
+
+    #[test]
+    fn test_module_nesting_with_compact_definition() {
+        // class Foo::Bar
+        assert_eq!(calculate_module_nesting(&["Foo::Bar"]), vec!["Foo::Bar"]);
+
+        // module Foo
+        //   class Bar::Baz
+        assert_eq!(
+            calculate_module_nesting(&["Foo", "Bar::Baz"]),
+            vec!["Foo::Bar::Baz", "Foo"]
+        );
+
+        // class Foo::Bar
+        //   module Baz
+        assert_eq!(
+            calculate_module_nesting(&["Foo::Bar", "Baz"]),
+            vec!["Foo::Bar::Baz", "Foo::Bar"]
+        );
+    }
+
+    #[test]
+    fn test_module_nesting_with_top_level_definition() {
+        // module Foo
+        //   class ::Bar
+        assert_eq!(
+            calculate_module_nesting(&["Foo", "::Bar"]),
+            vec!["Bar", "Foo"]
+        );
+    }
+
+    #[test]
+    fn test_possible_fully_qualified_constants_with_compact_definition() {
+        // `Foo` is not in the nesting of `class Foo::Bar`, so `Baz` cannot be `::Foo::Baz`
+        assert_eq!(
+            possible_fully_qualified_constants(&["Foo::Bar"], "Baz"),
+            vec!["::Baz", "::Foo::Bar::Baz"]
+        );
+    }
+
+    #[test]
+    fn test_lookup_scope() {
+        assert_eq!(
+            lookup_scope(&["Foo", "Bar"], "Baz"),
+            (&["Foo", "Bar"][..], "Baz")
+        );
+        assert_eq!(lookup_scope(&["Foo", "Bar"], "::Baz"), (&[][..], "Baz"));
+        assert_eq!(lookup_scope(&["Foo"], "::Bar::Baz"), (&[][..], "Bar::Baz"));
+    }
+
+    #[test]
+    fn test_possible_fully_qualified_constants_of_top_level_reference() {
+        // `::Baz` is never `::Foo::Baz`, even inside `module Foo`
+        assert_eq!(
+            possible_fully_qualified_constants(&["Foo"], "::Baz"),
+            vec!["::Baz"]
+        );
+    }
+
+    #[test]
+    fn test_combine_namespace_with_constant_name() {
+        assert_eq!(
+            combine_namespace_with_constant_name(&["Foo", "Bar::Baz"], "Boo"),
+            "::Foo::Bar::Baz::Boo"
+        );
+        assert_eq!(
+            combine_namespace_with_constant_name(&["Foo", "::Bar"], "Boo"),
+            "::Bar::Boo"
+        );
+        assert_eq!(
+            combine_namespace_with_constant_name(&["Foo"], "::Boo"),
+            "::Boo"
+        );
+    }
+}
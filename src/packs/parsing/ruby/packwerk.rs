@@ -6,7 +6,9 @@ mod tests {
 
     use crate::packs::parsing::ruby::packwerk::parser::process_from_contents;
     use crate::packs::parsing::Range;
-    use crate::packs::{Configuration, UnresolvedReference};
+    use crate::packs::{
+        Configuration, ReferenceConfidence, ReferenceKind, UnresolvedReference,
+    };
 
     #[test]
     fn trivial_case() {
@@ -21,7 +23,9 @@ mod tests {
                     start_col: 0,
                     end_row: 1,
                     end_col: 4
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }],
             process_from_contents(
                 contents,
@@ -45,7 +49,9 @@ mod tests {
                     start_col: 0,
                     end_row: 1,
                     end_col: 9
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }],
             process_from_contents(
                 contents,
@@ -69,7 +75,9 @@ mod tests {
                     start_col: 0,
                     end_row: 1,
                     end_col: 14
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }],
             process_from_contents(
                 contents,
@@ -93,7 +101,9 @@ mod tests {
                     start_col: 0,
                     end_row: 1,
                     end_col: 19
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }],
             process_from_contents(
                 contents,
@@ -123,7 +133,9 @@ end
                     start_col: 6,
                     end_row: 1,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }],
             process_from_contents(
                 contents,
@@ -154,7 +166,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 6
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -189,7 +203,9 @@ end
                     start_col: 4,
                     end_row: 3,
                     end_col: 8
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -230,7 +246,9 @@ end
                     start_col: 6,
                     end_row: 4,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -264,7 +282,9 @@ end
                         start_col: 7,
                         end_row: 1,
                         end_col: 11
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 },
                 UnresolvedReference {
                     name: String::from("Bar"),
@@ -274,7 +294,9 @@ end
                         start_col: 2,
                         end_row: 2,
                         end_col: 6
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 }
             ],
             process_from_contents(
@@ -308,7 +330,9 @@ end
                     start_col: 4,
                     end_row: 3,
                     end_col: 8
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -349,7 +373,9 @@ end
                     start_col: 6,
                     end_row: 4,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -391,6 +417,8 @@ end
                     end_row: 4,
                     end_col: 10
                 },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -424,7 +452,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 6
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -459,7 +489,9 @@ end
                     start_col: 9,
                     end_row: 2,
                     end_col: 13
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *process_from_contents(
                 contents,
@@ -499,7 +531,9 @@ end
                     start_col: 1,
                     end_row: 1,
                     end_col: 5
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference
         );
@@ -530,7 +564,9 @@ end
                     start_col: 1,
                     end_row: 1,
                     end_col: 5
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference1
         );
@@ -547,7 +583,9 @@ end
                     start_col: 6,
                     end_row: 1,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference2,
         );
@@ -579,7 +617,9 @@ end
                     start_col: 1,
                     end_row: 1,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference,
         );
@@ -611,7 +651,9 @@ end
                     start_col: 0,
                     end_row: 1,
                     end_col: 6
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference,
         );
@@ -662,7 +704,9 @@ end
                     start_col: 6,
                     end_row: 1,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             }]
         )
     }
@@ -699,7 +743,9 @@ end
                         start_col: 6,
                         end_row: 1,
                         end_col: 10
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 },
                 UnresolvedReference {
                     name: String::from("::Foo::Baz"),
@@ -709,7 +755,9 @@ end
                         start_col: 8,
                         end_row: 2,
                         end_col: 12
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 }
             ]
         );
@@ -745,7 +793,9 @@ end
                     start_col: 12,
                     end_row: 1,
                     end_col: 16
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *first_reference,
         );
@@ -780,7 +830,9 @@ end
                     start_col: 6,
                     end_row: 1,
                     end_col: 15
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *first_reference,
         );
@@ -816,7 +868,9 @@ end
                         start_col: 6,
                         end_row: 1,
                         end_col: 10
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 },
                 UnresolvedReference {
                     name: String::from("::Foo::Bar"),
@@ -826,7 +880,9 @@ end
                         start_col: 8,
                         end_row: 2,
                         end_col: 12
-                    }
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
                 }
             ]
         );
@@ -861,7 +917,9 @@ FOO = BAR
                     start_col: 6,
                     end_row: 1,
                     end_col: 10
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *first_reference
         )
@@ -900,7 +958,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 27
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -939,7 +999,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 47
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -977,7 +1039,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 29
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1016,7 +1080,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 24
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1052,7 +1118,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 21
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1089,7 +1157,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 22
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1125,7 +1195,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 17
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1164,7 +1236,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 25
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1200,7 +1274,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 72
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             },
             *first_reference,
         );
@@ -1238,7 +1314,9 @@ end
                     start_col: 2,
                     end_row: 2,
                     end_col: 6
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference,
         );
@@ -1305,9 +1383,520 @@ end
                     start_col: 6,
                     end_row: 1,
                     end_col: 15
-                }
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
             },
             *reference,
         );
     }
+
+    #[test]
+    fn it_uses_the_namespace_of_inherited_class_after_a_nested_class() {
+        let contents: String = String::from(
+            "\
+class Foo < Bar
+  class Baz
+  end
+  Bar
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+        assert_eq!(references.len(), 4);
+        let reference = references
+            .get(3)
+            .expect("There should be a reference at index 3");
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("Bar"),
+                namespace_path: vec![],
+                location: Range {
+                    start_row: 4,
+                    start_col: 2,
+                    end_row: 4,
+                    end_col: 6
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            },
+            *reference,
+        );
+    }
+
+    #[test]
+    fn top_level_class_definition_inside_module() {
+        let contents: String = String::from(
+            "\
+module Foo
+  class ::Bar
+    Baz
+  end
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+        assert_eq!(
+            vec![
+                UnresolvedReference {
+                    name: String::from("::Foo"),
+                    namespace_path: vec![],
+                    location: Range {
+                        start_row: 1,
+                        start_col: 7,
+                        end_row: 1,
+                        end_col: 11
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
+                },
+                UnresolvedReference {
+                    name: String::from("::Bar"),
+                    namespace_path: vec![String::from("Foo")],
+                    location: Range {
+                        start_row: 2,
+                        start_col: 8,
+                        end_row: 2,
+                        end_col: 14
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
+                },
+                UnresolvedReference {
+                    name: String::from("Baz"),
+                    namespace_path: vec![
+                        String::from("Foo"),
+                        String::from("::Bar")
+                    ],
+                    location: Range {
+                        start_row: 3,
+                        start_col: 4,
+                        end_row: 3,
+                        end_col: 8
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
+                },
+            ],
+            references,
+        );
+    }
+
+    #[test]
+    fn class_eval_string_references() {
+        let contents: String = String::from(
+            "\
+module Foo
+  Bar.class_eval \"Baz.call\"
+  Bar.class_eval \"#{Qux}\"
+end
+        ",
+        );
+
+        let configuration = Configuration {
+            parse_eval_strings: true,
+            ..Configuration::default()
+        };
+
+        let references = process_from_contents(
+            contents.clone(),
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+        // `Qux` is interpolated, so the string isn't parsed, but the interpolation
+        // is already visited as normal code
+        let names: Vec<&str> =
+            references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(vec!["::Foo", "Bar", "Baz", "Bar", "Qux"], names);
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("Baz"),
+                namespace_path: vec![String::from("Foo"), String::from("Bar")],
+                location: Range {
+                    start_row: 2,
+                    start_col: 18,
+                    end_row: 2,
+                    end_col: 22
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::MetaprogrammedString,
+            },
+            references[2]
+        );
+
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &Configuration::default(),
+        )
+        .unresolved_references;
+        assert!(!references.iter().any(|r| r.name == "Baz"));
+    }
+
+    #[test]
+    fn rescue_ensure_and_raise_references() {
+        let contents: String = String::from(
+            "\
+module Foo
+  def call
+    attempts ||= 0
+    Bar.call
+  rescue Bar::SpecialError, Baz::Error => e
+    retry if (attempts += 1) < Retries::MAX
+    raise Qux::Error, \"failed\"
+  ensure
+    Cleanup.run
+  end
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<&str> =
+            references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "::Foo",
+                "Bar",
+                "Bar::SpecialError",
+                "Baz::Error",
+                "Retries::MAX",
+                "Qux::Error",
+                "Cleanup"
+            ],
+            names
+        );
+        assert!(references[1..]
+            .iter()
+            .all(|r| r.namespace_path == vec![String::from("Foo")]));
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("Baz::Error"),
+                namespace_path: vec![String::from("Foo")],
+                location: Range {
+                    start_row: 5,
+                    start_col: 28,
+                    end_row: 5,
+                    end_col: 39
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            },
+            references[3]
+        );
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("Qux::Error"),
+                namespace_path: vec![String::from("Foo")],
+                location: Range {
+                    start_row: 7,
+                    start_col: 10,
+                    end_row: 7,
+                    end_col: 21
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            },
+            references[5]
+        );
+    }
+
+    #[test]
+    fn rescue_in_begin_block_and_rescue_modifier() {
+        let contents: String = String::from(
+            "\
+class Foo
+  begin
+    Bar.load
+  rescue Bar::NotFound
+    raise ::Errors::Missing
+  end
+
+  Baz.call rescue Fallback.call
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<(&str, &Vec<String>)> = references
+            .iter()
+            .map(|r| (r.name.as_str(), &r.namespace_path))
+            .collect();
+        let foo = vec![String::from("Foo")];
+        assert_eq!(
+            vec![
+                ("::Foo", &vec![]),
+                ("Bar", &foo),
+                ("Bar::NotFound", &foo),
+                ("::Errors::Missing", &foo),
+                ("Baz", &foo),
+                ("Fallback", &foo),
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn pattern_matching_references() {
+        let contents: String = String::from(
+            "\
+module Foo
+  case value
+  in Bar::Point(x:, y:)
+    x
+  in Baz[1, 2]
+    nil
+  in [*, Qux, *]
+    nil
+  in Corge | ::Grault
+    nil
+  in { kind: Garply => kind }
+    kind
+  end
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<&str> =
+            references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "::Foo",
+                "Bar::Point",
+                "Baz",
+                "Qux",
+                "Corge",
+                "::Grault",
+                "Garply"
+            ],
+            names
+        );
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("Bar::Point"),
+                namespace_path: vec![String::from("Foo")],
+                location: Range {
+                    start_row: 3,
+                    start_col: 5,
+                    end_row: 3,
+                    end_col: 16
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            },
+            references[1]
+        );
+    }
+
+    #[test]
+    fn method_signature_default_value_references() {
+        let contents: String = String::from(
+            "\
+class Foo
+  sig { params(policy: Policies::Base).returns(Result) }
+  def call(retries = Config::RETRIES, *rest, policy: DefaultPolicy, **opts)
+  end
+
+  def self.build(klass = Builders::Default, &block)
+  end
+
+  HANDLER = ->(error = Errors::Unknown) { error }
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<&str> =
+            references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            vec![
+                "::Foo",
+                "Policies::Base",
+                "Result",
+                "Config::RETRIES",
+                "DefaultPolicy",
+                "Builders::Default",
+                "Errors::Unknown"
+            ],
+            names
+        );
+        assert_eq!(
+            UnresolvedReference {
+                name: String::from("DefaultPolicy"),
+                namespace_path: vec![String::from("Foo")],
+                location: Range {
+                    start_row: 3,
+                    start_col: 53,
+                    end_row: 3,
+                    end_col: 67
+                },
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            },
+            references[4]
+        );
+    }
+
+    #[test]
+    fn signature_references() {
+        let contents: String = String::from(
+            "\
+class Foo
+  sig { params(user: Accounts::User).returns(Billing::Invoice) }
+  def bill(user)
+    Billing::Invoicer.call(user)
+  end
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let kinds: Vec<(&str, ReferenceKind)> = references
+            .iter()
+            .map(|r| (r.name.as_str(), r.kind))
+            .collect();
+        assert_eq!(
+            vec![
+                ("::Foo", ReferenceKind::Code),
+                ("Accounts::User", ReferenceKind::Signature),
+                ("Billing::Invoice", ReferenceKind::Signature),
+                ("Billing::Invoicer", ReferenceKind::Code),
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn rbi_file_references_are_signatures() {
+        let contents: String = String::from(
+            "\
+class Billing::Invoice
+  sig { returns(Accounts::User) }
+  def user; end
+
+  def total(currency = Money::Currency); end
+end
+        ",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("sorbet/rbi/dsl/billing/invoice.rbi"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<&str> =
+            references.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            vec!["::Billing::Invoice", "Accounts::User", "Money::Currency"],
+            names
+        );
+        assert!(references
+            .iter()
+            .all(|r| r.kind == ReferenceKind::Signature));
+    }
+
+    // The refined constant is a reference, and the constants in a `refine`
+    // block are looked up in the enclosing namespaces
+    #[test]
+    fn refinements() {
+        let contents: String = String::from(
+            "\
+module StringRefinements
+  refine Foo::Bar do
+    LIMIT = 1
+
+    def shout
+      Baz.new(LIMIT)
+    end
+  end
+end
+
+using StringRefinements
+",
+        );
+
+        let configuration = Configuration::default();
+        let references = process_from_contents(
+            contents,
+            &PathBuf::from("path/to/file.rb"),
+            &configuration,
+        )
+        .unresolved_references;
+
+        let names: Vec<(&str, Vec<&str>)> = references
+            .iter()
+            .map(|r| {
+                (
+                    r.name.as_str(),
+                    r.namespace_path.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        // `LIMIT` and `StringRefinements` are defined in the file, so they're
+        // local references
+        assert_eq!(
+            vec![
+                ("::StringRefinements", vec![]),
+                ("Foo::Bar", vec!["StringRefinements"]),
+                ("Baz", vec!["StringRefinements"]),
+            ],
+            names
+        );
+    }
 }
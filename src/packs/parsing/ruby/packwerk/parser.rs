@@ -1,21 +1,21 @@
-use crate::packs::file_utils::file_read_contents;
 use crate::packs::{
     parsing::{
         ruby::{
             namespace_calculator::possible_fully_qualified_constants,
             parse_utils::{
-                fetch_const_const_name, fetch_const_name, fetch_node_location,
+                eval_string_references, fetch_const_const_name,
+                fetch_const_name, fetch_node_location,
                 get_constant_assignment_definition, get_definition_from,
-                get_reference_from_active_record_association, loc_to_range,
+                get_eval_string, get_reference_from_active_record_association,
+                is_rbi_file, is_signature_block, loc_to_range, parser_options,
             },
         },
-        ParsedDefinition, Range, UnresolvedReference,
+        ParsedDefinition, Range, ReferenceConfidence, ReferenceKind,
+        UnresolvedReference,
     },
-    Configuration, ProcessedFile,
-};
-use lib_ruby_parser::{
-    nodes, traverse::visitor::Visitor, Node, Parser, ParserOptions,
+    Configuration, FileSigils, ProcessedFile,
 };
+use lib_ruby_parser::{nodes, traverse::visitor::Visitor, Node, Parser};
 use line_col::LineColLookup;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::Path};
@@ -34,6 +34,37 @@ struct ReferenceCollector<'a> {
     pub in_superclass: bool,
     pub superclasses: Vec<SuperclassReference>,
     pub custom_associations: Vec<String>,
+    pub parse_eval_strings: bool,
+    pub in_signature: bool,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    fn new(
+        line_col_lookup: LineColLookup<'a>,
+        current_namespaces: Vec<String>,
+        custom_associations: Vec<String>,
+        parse_eval_strings: bool,
+    ) -> ReferenceCollector<'a> {
+        ReferenceCollector {
+            references: vec![],
+            current_namespaces,
+            definitions: vec![],
+            line_col_lookup,
+            in_superclass: false,
+            superclasses: vec![],
+            custom_associations,
+            parse_eval_strings,
+            in_signature: false,
+        }
+    }
+
+    fn reference_kind(&self) -> ReferenceKind {
+        if self.in_signature {
+            ReferenceKind::Signature
+        } else {
+            ReferenceKind::Code
+        }
+    }
 }
 
 impl<'a> Visitor for ReferenceCollector<'a> {
@@ -48,6 +79,9 @@ impl<'a> Visitor for ReferenceCollector<'a> {
 
         let namespace = namespace_result.unwrap();
 
+        // Only the superclasses of this class should be popped when we leave it,
+        // and a class without a superclass doesn't add any
+        let superclasses_count = self.superclasses.len();
         if let Some(inner) = node.superclass.as_ref() {
             self.in_superclass = true;
             self.visit(inner);
@@ -71,6 +105,8 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             name,
             namespace_path,
             location,
+            kind: self.reference_kind(),
+            confidence: ReferenceConfidence::Exact,
         });
 
         // Note – is there a way to use lifetime specifiers to get rid of this and
@@ -83,7 +119,7 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         }
 
         self.current_namespaces.pop();
-        self.superclasses.pop();
+        self.superclasses.truncate(superclasses_count);
     }
 
     fn on_send(&mut self, node: &nodes::Send) {
@@ -96,10 +132,36 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             );
 
         if let Some(association_reference) = association_reference {
-            self.references.push(association_reference);
+            self.references.push(UnresolvedReference {
+                kind: self.reference_kind(),
+                ..association_reference
+            });
         }
 
         lib_ruby_parser::traverse::visitor::visit_send(self, node);
+
+        if self.parse_eval_strings {
+            if let Some(eval_string) = get_eval_string(
+                node,
+                &self.current_namespaces,
+                &self.line_col_lookup,
+            ) {
+                let references = eval_string_references(
+                    eval_string,
+                    |ast, lookup, namespaces| {
+                        let mut collector = ReferenceCollector::new(
+                            lookup,
+                            namespaces,
+                            self.custom_associations.clone(),
+                            self.parse_eval_strings,
+                        );
+                        collector.visit(ast);
+                        collector.references
+                    },
+                );
+                self.references.extend(references);
+            }
+        }
     }
 
     fn on_casgn(&mut self, node: &nodes::Casgn) {
@@ -143,6 +205,8 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             name,
             namespace_path,
             location,
+            kind: self.reference_kind(),
+            confidence: ReferenceConfidence::Exact,
         });
 
         // Note – is there a way to use lifetime specifiers to get rid of this and
@@ -157,6 +221,24 @@ impl<'a> Visitor for ReferenceCollector<'a> {
         self.current_namespaces.pop();
     }
 
+    // `in Foo::Bar(x:)` and `in Foo[1, 2]` reference `Foo::Bar` and `Foo`
+    // before deconstructing them, so the constant is visited like any other
+    // reference, followed by the patterns inside of it. Constants nested in
+    // find, array and hash patterns are reached by the default traversal.
+    fn on_const_pattern(&mut self, node: &nodes::ConstPattern) {
+        self.visit(&node.const_);
+        self.visit(&node.pattern);
+    }
+
+    // Constants in a Sorbet `sig` are only used as types, which some teams
+    // don't want to count as a dependency
+    fn on_block(&mut self, node: &nodes::Block) {
+        let in_signature = self.in_signature;
+        self.in_signature = in_signature || is_signature_block(node);
+        lib_ruby_parser::traverse::visitor::visit_block(self, node);
+        self.in_signature = in_signature;
+    }
+
     fn on_const(&mut self, node: &nodes::Const) {
         let Ok(name) = fetch_const_const_name(node) else {
             return;
@@ -196,27 +278,18 @@ impl<'a> Visitor for ReferenceCollector<'a> {
             name,
             namespace_path,
             location: loc_to_range(&node.expression_l, &self.line_col_lookup),
+            kind: self.reference_kind(),
+            confidence: ReferenceConfidence::Exact,
         })
     }
 }
 
-pub(crate) fn process_from_path(
-    path: &Path,
-    configuration: &Configuration,
-) -> ProcessedFile {
-    let contents = file_read_contents(path, configuration);
-    process_from_contents(contents, path, configuration)
-}
-
 pub(crate) fn process_from_contents(
     contents: String,
     path: &Path,
     configuration: &Configuration,
 ) -> ProcessedFile {
-    let options = ParserOptions {
-        buffer_name: "".to_string(),
-        ..Default::default()
-    };
+    let options = parser_options();
 
     let lookup = LineColLookup::new(&contents);
     let parser = Parser::new(contents.clone(), options);
@@ -231,19 +304,18 @@ pub(crate) fn process_from_contents(
                 absolute_path: path.to_owned(),
                 unresolved_references: vec![],
                 definitions: vec![],
+                sigils: FileSigils::default(),
             }
         }
     };
 
-    let mut collector = ReferenceCollector {
-        references: vec![],
-        current_namespaces: vec![],
-        definitions: vec![],
-        line_col_lookup: lookup,
-        in_superclass: false,
-        superclasses: vec![],
-        custom_associations: configuration.custom_associations.clone(),
-    };
+    let mut collector = ReferenceCollector::new(
+        lookup,
+        vec![],
+        configuration.custom_associations.clone(),
+        configuration.parse_eval_strings,
+    );
+    collector.in_signature = is_rbi_file(path);
 
     collector.visit(&ast);
 
@@ -305,11 +377,17 @@ pub(crate) fn process_from_contents(
     // The packwerk parser uses a ConstantResolver constructed by constants inferred from the file system
     // see zeitwerk_utils for more.
     // For a parser that uses parsed constants, see the experimental parser
-    let definitions = vec![];
+    // Aliases are kept all the same, so `follow_constant_aliases` can find them
+    let definitions = collector
+        .definitions
+        .into_iter()
+        .filter(|definition| definition.alias_of.is_some())
+        .collect();
 
     ProcessedFile {
         absolute_path,
         unresolved_references,
         definitions,
+        sigils: FileSigils::default(),
     }
 }
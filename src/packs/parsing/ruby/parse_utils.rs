@@ -1,11 +1,30 @@
 use std::collections::HashSet;
+use std::path::Path;
 
-use lib_ruby_parser::{nodes, Loc, Node};
+use lib_ruby_parser::source::{Decoder, DecoderResult};
+use lib_ruby_parser::{nodes, Loc, Node, Parser, ParserOptions};
 use line_col::LineColLookup;
 
-use crate::packs::parsing::{ParsedDefinition, Range, UnresolvedReference};
+use crate::packs::parsing::{
+    DefinitionKind, ParsedDefinition, Range, ReferenceConfidence,
+    ReferenceKind, UnresolvedReference,
+};
 
 use super::inflector_shim::to_class_case;
+use super::namespace_calculator::combine_namespace_with_constant_name;
+
+// Contents are decoded into UTF-8 when files are read, whatever their
+// `# encoding:` magic comment says, so the parser takes them as they are
+// instead of failing on encodings it can't decode itself
+pub fn parser_options() -> ParserOptions {
+    ParserOptions {
+        buffer_name: "".to_string(),
+        decoder: Some(Decoder::new(Box::new(|_encoding, input| {
+            DecoderResult::Ok(input)
+        }))),
+        ..Default::default()
+    }
+}
 
 #[derive(Debug)]
 pub enum ParseError {
@@ -25,26 +44,146 @@ pub fn fetch_node_location(node: &nodes::Node) -> Result<&Loc, ParseError> {
     }
 }
 
+// Methods whose (literal) string argument is evaluated as Ruby code
+const EVAL_METHOD_NAMES: [&str; 3] =
+    ["class_eval", "module_eval", "instance_eval"];
+
+// Ruby code passed as a string literal to `class_eval` and friends
+pub struct EvalString {
+    pub code: String,
+    // The namespaces constants in the code are resolved in: the receiver's,
+    // when it is a constant, e.g. `Foo.class_eval("Bar")` looks up `Foo::Bar`
+    pub namespaces: Vec<String>,
+    // Where the code starts in the file containing the `class_eval`
+    pub start: Range,
+}
+
+pub fn get_eval_string(
+    node: &nodes::Send,
+    current_namespaces: &[String],
+    line_col_lookup: &LineColLookup,
+) -> Option<EvalString> {
+    if !EVAL_METHOD_NAMES.contains(&node.method_name.as_str()) {
+        return None;
+    }
+
+    // Interpolated strings (`Dstr`) aren't known until runtime, so only
+    // plain literals are parsed
+    let Some(Node::Str(string)) = node.args.first() else {
+        return None;
+    };
+    let code = string.value.to_string().ok()?;
+
+    let mut namespaces = current_namespaces.to_owned();
+    if let Some(receiver) = &node.recv {
+        if let Ok(receiver_name) = fetch_const_name(receiver) {
+            namespaces.push(receiver_name);
+        }
+    }
+
+    let code_begin = match &string.begin_l {
+        Some(opening_quote) => opening_quote.end,
+        None => string.expression_l.begin,
+    };
+    let start = loc_to_range(
+        &Loc {
+            begin: code_begin,
+            end: code_begin,
+        },
+        line_col_lookup,
+    );
+
+    Some(EvalString {
+        code,
+        namespaces,
+        start,
+    })
+}
+
+// Moves a range within the code of an eval string to the same place in the
+// enclosing file. Only the first line of the code is shifted sideways.
+// Escape sequences in the literal can make columns drift on their line.
+pub fn offset_range(range: Range, start: &Range) -> Range {
+    let line_offset = start.start_row - 1;
+    let shift_col = |row: usize, col: usize| {
+        if row == 1 {
+            col + start.start_col
+        } else {
+            col
+        }
+    };
+
+    Range {
+        start_row: range.start_row + line_offset,
+        start_col: shift_col(range.start_row, range.start_col),
+        end_row: range.end_row + line_offset,
+        end_col: shift_col(range.end_row, range.end_col),
+    }
+}
+
+// References in the code of an eval string, positioned in the enclosing
+// file. `collect` visits the parsed code, given a lookup into it and the
+// namespaces it runs in, and returns the references it found.
+pub fn eval_string_references(
+    eval_string: EvalString,
+    collect: impl FnOnce(
+        &Node,
+        LineColLookup,
+        Vec<String>,
+    ) -> Vec<UnresolvedReference>,
+) -> Vec<UnresolvedReference> {
+    let lookup = LineColLookup::new(&eval_string.code);
+    let parser = Parser::new(eval_string.code.clone(), parser_options());
+    let Some(ast) = parser.do_parse().ast else {
+        return vec![];
+    };
+
+    collect(&ast, lookup, eval_string.namespaces)
+        .into_iter()
+        .map(|reference| UnresolvedReference {
+            location: offset_range(reference.location, &eval_string.start),
+            confidence: reference
+                .confidence
+                .min(ReferenceConfidence::MetaprogrammedString),
+            ..reference
+        })
+        .collect()
+}
+
+// A Sorbet signature, e.g. `sig { params(user: User).returns(Invoice) }`
+pub fn is_signature_block(node: &nodes::Block) -> bool {
+    matches!(&*node.call, Node::Send(send) if send.method_name == "sig")
+}
+
+// `refine Foo do ... end`, whose body is evaluated in an anonymous
+// refinement module, with the enclosing namespaces as its lexical scope
+pub fn is_refinement_block(node: &nodes::Block) -> bool {
+    matches!(
+        &*node.call,
+        Node::Send(send) if send.method_name == "refine" && send.recv.is_none()
+    )
+}
+
+// RBI files only declare types, so every reference in them is a signature
+pub fn is_rbi_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "rbi")
+}
+
 pub fn get_definition_from(
-    current_nesting: &String,
+    current_nesting: &str,
     parent_nesting: &[String],
     location: &Range,
 ) -> ParsedDefinition {
-    let name = current_nesting.to_owned();
-
-    let owned_namespace_path: Vec<String> = parent_nesting.to_vec();
-
-    let fully_qualified_name = if !owned_namespace_path.is_empty() {
-        let mut name_components = owned_namespace_path;
-        name_components.push(name);
-        format!("::{}", name_components.join("::"))
-    } else {
-        format!("::{}", name)
-    };
+    let parent_nesting: Vec<&str> =
+        parent_nesting.iter().map(|s| s.as_str()).collect();
+    let fully_qualified_name =
+        combine_namespace_with_constant_name(&parent_nesting, current_nesting);
 
     ParsedDefinition {
         fully_qualified_name,
         location: location.to_owned(),
+        alias_of: None,
+        kind: DefinitionKind::Constant,
     }
 }
 
@@ -95,7 +234,7 @@ fn fetch_casgn_name(node: &nodes::Casgn) -> Result<String, ParseError> {
     }
 }
 
-const ASSOCIATION_METHOD_NAMES: [&str; 4] = [
+pub(crate) const ASSOCIATION_METHOD_NAMES: [&str; 4] = [
     "has_one",
     "has_many",
     "belongs_to",
@@ -157,6 +296,8 @@ pub fn get_reference_from_active_record_association(
                 name: unwrapped_name,
                 namespace_path: current_namespaces.to_owned(),
                 location: loc_to_range(&node.expression_l, line_col_lookup),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::InferredAssociation,
             })
         } else {
             None
@@ -182,6 +323,44 @@ fn extract_class_name_from_kwargs(kwargs: &nodes::Kwargs) -> Option<String> {
     None
 }
 
+// `autoload :Foo, "foo/bar"` declares `Foo` in the current namespace, or in
+// the receiver's, like `Billing.autoload(:Invoice, ...)`. Only symbols and
+// plain strings name the constant.
+pub fn get_autoload_definition(
+    node: &nodes::Send,
+    current_namespaces: &[String],
+    line_col_lookup: &LineColLookup,
+) -> Option<ParsedDefinition> {
+    if node.method_name != "autoload" || node.args.len() != 2 {
+        return None;
+    }
+    let name = match node.args.first()? {
+        Node::Sym(symbol) => symbol.name.to_string().ok()?,
+        Node::Str(string) => string.value.to_string().ok()?,
+        _ => return None,
+    };
+
+    let mut namespaces = current_namespaces.to_owned();
+    match node.recv.as_deref() {
+        None | Some(Node::Self_(_)) => {}
+        Some(receiver @ Node::Const(_)) => {
+            namespaces.push(fetch_const_name(receiver).ok()?)
+        }
+        Some(_) => return None,
+    }
+    let namespaces: Vec<&str> = namespaces.iter().map(String::as_str).collect();
+
+    Some(ParsedDefinition {
+        fully_qualified_name: combine_namespace_with_constant_name(
+            &namespaces,
+            &name,
+        ),
+        location: loc_to_range(&node.expression_l, line_col_lookup),
+        alias_of: None,
+        kind: DefinitionKind::Autoload,
+    })
+}
+
 pub fn get_constant_assignment_definition(
     node: &nodes::Casgn,
     current_namespaces: Vec<String>,
@@ -193,16 +372,20 @@ pub fn get_constant_assignment_definition(
     }
 
     let name = name_result.unwrap();
-    let fully_qualified_name = if !current_namespaces.is_empty() {
-        let mut name_components = current_namespaces;
-        name_components.push(name);
-        format!("::{}", name_components.join("::"))
-    } else {
-        format!("::{}", name)
+    let current_namespaces: Vec<&str> =
+        current_namespaces.iter().map(|s| s.as_str()).collect();
+    let fully_qualified_name =
+        combine_namespace_with_constant_name(&current_namespaces, &name);
+
+    let alias_of = match node.value.as_deref() {
+        Some(value @ Node::Const(_)) => fetch_const_name(value).ok(),
+        _ => None,
     };
 
     Some(ParsedDefinition {
         fully_qualified_name,
         location: loc_to_range(&node.expression_l, line_col_lookup),
+        alias_of,
+        kind: DefinitionKind::Constant,
     })
 }
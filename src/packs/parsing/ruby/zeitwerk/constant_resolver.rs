@@ -4,7 +4,10 @@ use std::collections::HashMap;
 
 use crate::packs::{
     constant_resolver::{ConstantDefinition, ConstantResolver},
-    parsing::ruby::namespace_calculator::combine_namespace_with_constant_name,
+    parsing::ruby::namespace_calculator::{
+        combine_namespace_with_constant_name, lookup_scope,
+    },
+    DefinitionKind,
 };
 
 #[derive(Default, Debug)]
@@ -19,19 +22,10 @@ impl ConstantResolver for ZeitwerkConstantResolver {
         fully_or_partially_qualified_constant: &str,
         namespace_path: &[&str],
     ) -> Option<Vec<ConstantDefinition>> {
-        // If the fully_or_partially_qualified_constant is prefixed with ::, the namespace path is technically empty, since it's a global reference
+        // A global reference like `::Foo` skips the lexical nesting.
+        // Guesses at the fully qualified name add the leading `::` back.
         let (namespace_path, const_name) =
-            if fully_or_partially_qualified_constant.starts_with("::") {
-                // `resolve_constant` will add a leading :: before it makes a guess at the fully qualified name
-                // so we remove it here and represent it as a relative constant with no namespace path
-                let const_name = fully_or_partially_qualified_constant
-                    .strip_prefix("::")
-                    .unwrap();
-                let namespace_path: &[&str] = &[];
-                (namespace_path, const_name)
-            } else {
-                (namespace_path, fully_or_partially_qualified_constant)
-            };
+            lookup_scope(namespace_path, fully_or_partially_qualified_constant);
 
         self.resolve_constant(const_name, namespace_path, const_name)
     }
@@ -178,6 +172,7 @@ impl ZeitwerkConstantResolver {
             Some(ConstantDefinition {
                 fully_qualified_name,
                 absolute_path_of_definition,
+                kind: DefinitionKind::Constant,
             })
         } else {
             // In this case, we couldn't find a constant with the given name under the given namespace.
@@ -0,0 +1,221 @@
+use std::error::Error;
+use std::path::Path;
+
+use lib_ruby_parser::{nodes, traverse::visitor::Visitor, Parser};
+use line_col::LineColLookup;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::inferred_constants_from_pack_set;
+use crate::packs::file_utils::{file_read_contents, to_slash_path};
+use crate::packs::parsing::ruby::inflector_shim::underscore;
+use crate::packs::parsing::ruby::namespace_calculator::combine_namespace_with_constant_name;
+use crate::packs::parsing::ruby::parse_utils::{
+    fetch_const_name, get_constant_assignment_definition, parser_options,
+};
+use crate::packs::Configuration;
+
+// Every class, module and constant a file opens or assigns, whether or not
+// it changes behavior, by fully qualified name
+struct DefinitionCollector<'a> {
+    definitions: Vec<String>,
+    current_namespaces: Vec<String>,
+    line_col_lookup: LineColLookup<'a>,
+}
+
+impl<'a> DefinitionCollector<'a> {
+    fn open_namespace(
+        &mut self,
+        name: &nodes::Node,
+        body: &Option<Box<nodes::Node>>,
+    ) {
+        // Namespaces opened with metaprogramming can't be checked
+        let Ok(namespace) = fetch_const_name(name) else {
+            return;
+        };
+        let namespaces: Vec<&str> =
+            self.current_namespaces.iter().map(String::as_str).collect();
+        self.definitions.push(combine_namespace_with_constant_name(
+            &namespaces,
+            &namespace,
+        ));
+        self.current_namespaces.push(namespace);
+        if let Some(body) = body {
+            self.visit(body);
+        }
+        self.current_namespaces.pop();
+    }
+}
+
+impl<'a> Visitor for DefinitionCollector<'a> {
+    fn on_class(&mut self, node: &nodes::Class) {
+        self.open_namespace(&node.name, &node.body);
+    }
+
+    fn on_module(&mut self, node: &nodes::Module) {
+        self.open_namespace(&node.name, &node.body);
+    }
+
+    fn on_casgn(&mut self, node: &nodes::Casgn) {
+        if let Some(definition) = get_constant_assignment_definition(
+            node,
+            self.current_namespaces.clone(),
+            &self.line_col_lookup,
+        ) {
+            self.definitions.push(definition.fully_qualified_name);
+        }
+    }
+}
+
+fn defined_constants(contents: &str) -> Vec<String> {
+    let options = parser_options();
+    let parser = Parser::new(contents.to_owned(), options);
+    let Some(ast) = parser.do_parse().ast else {
+        return vec![];
+    };
+    let mut collector = DefinitionCollector {
+        definitions: vec![],
+        current_namespaces: vec![],
+        line_col_lookup: LineColLookup::new(contents),
+    };
+    collector.visit(&ast);
+    collector.definitions
+}
+
+// Why a file doesn't follow Zeitwerk's naming convention, if it doesn't.
+// Names are compared by the path they underscore to, the way the inflector
+// maps them back to files, so `SomeApiClass` is where `SomeAPIClass` is
+// expected when `API` is an acronym.
+fn placement_problems(expected: &str, defined: &[String]) -> Vec<String> {
+    let expected_path = underscore(expected);
+    let is_expected = |name: &str| underscore(name) == expected_path;
+    let is_nested_in = |name: &str, namespace: &str| {
+        underscore(name).starts_with(&format!("{}/", underscore(namespace)))
+    };
+
+    let mut problems = vec![];
+    if !defined.iter().any(|name| is_expected(name)) {
+        problems.push(format!(
+            "should define `{}`, which its path implies, but doesn't",
+            expected
+        ));
+    }
+    let misplaced: Vec<&String> = defined
+        .iter()
+        .filter(|name| {
+            !is_expected(name)
+                && !is_nested_in(name, expected)
+                && !is_nested_in(expected, name)
+        })
+        .collect();
+    for name in &misplaced {
+        // `module Bar; class Gadget` only reports `::Bar::Gadget`
+        if misplaced.iter().any(|other| is_nested_in(other, name)) {
+            continue;
+        }
+        problems.push(format!(
+            "defines `{}`, which is not `{}` or nested in it",
+            name, expected
+        ));
+    }
+    problems.dedup();
+    problems
+}
+
+// Checks that every autoloaded file defines the constant Zeitwerk expects
+// from its path, and nothing outside of it. A constant in the wrong file or
+// pack otherwise shows up later as a confusing unresolved reference.
+pub(crate) fn check_file_placement(
+    configuration: &Configuration,
+) -> Result<(), Box<dyn Error>> {
+    let mut expected_constants: Vec<(String, &Path)> = vec![];
+    let inferred_constants = inferred_constants_from_pack_set(configuration);
+    for definition in &inferred_constants {
+        let path = definition.absolute_path_of_definition.as_path();
+        if configuration.included_files.contains(path) {
+            expected_constants
+                .push((definition.fully_qualified_name.clone(), path));
+        }
+    }
+
+    let mut misplaced_files: Vec<(String, Vec<String>)> = expected_constants
+        .par_iter()
+        .filter_map(|(expected, path)| {
+            let contents = file_read_contents(path, configuration);
+            let problems =
+                placement_problems(expected, &defined_constants(&contents));
+            if problems.is_empty() {
+                return None;
+            }
+            let relative_path = to_slash_path(
+                path.strip_prefix(&configuration.absolute_root)
+                    .unwrap_or(path),
+            );
+            Some((relative_path, problems))
+        })
+        .collect();
+
+    if misplaced_files.is_empty() {
+        println!("Every autoloaded file defines the constant its path implies");
+        return Ok(());
+    }
+
+    misplaced_files.sort();
+    for (relative_path, problems) in &misplaced_files {
+        for problem in problems {
+            println!("{} {}", relative_path, problem);
+        }
+    }
+
+    Err(format!(
+        "{} file(s) don't define the constant their path implies",
+        misplaced_files.len()
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placement_problems() {
+        let defined = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert!(placement_problems(
+            "::Foo::Bar",
+            &defined(&["::Foo", "::Foo::Bar", "::Foo::Bar::Error"])
+        )
+        .is_empty());
+        assert_eq!(
+            vec![String::from(
+                "should define `::Foo::Bar`, which its path implies, but doesn't"
+            )],
+            placement_problems("::Foo::Bar", &defined(&["::Foo"]))
+        );
+        assert_eq!(
+            vec![String::from(
+                "defines `::Baz`, which is not `::Foo` or nested in it"
+            )],
+            placement_problems("::Foo", &defined(&["::Foo", "::Baz"]))
+        );
+        assert_eq!(
+            vec![String::from(
+                "defines `::Bar::Gadget`, which is not `::Foo` or nested in it"
+            )],
+            placement_problems(
+                "::Foo",
+                &defined(&["::Foo", "::Bar", "::Bar::Gadget"])
+            )
+        );
+        assert!(placement_problems(
+            "::MyModule::SomeAPIClass",
+            &defined(&["::MyModule", "::MyModule::SomeApiClass"])
+        )
+        .is_empty());
+    }
+}
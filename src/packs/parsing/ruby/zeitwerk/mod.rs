@@ -1,4 +1,5 @@
 mod constant_resolver;
+mod file_placement;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -10,70 +11,79 @@ use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::packs::{
-    caching::create_cache_dir_idempotently,
+    caching::{create_cache_dir_idempotently, is_read_only_error},
     constant_resolver::{ConstantDefinition, ConstantResolver},
-    file_utils::process_glob_pattern,
+    file_provider::FileProvider,
+    file_utils::to_slash_path,
     pack::Pack,
-    parsing::ruby::rails_utils::get_acronyms_from_disk,
-    PackSet,
+    parsing::ruby::rails_utils::get_acronyms,
+    Configuration, DefinitionKind,
 };
 
 use self::constant_resolver::ZeitwerkConstantResolver;
+pub(crate) use self::file_placement::check_file_placement;
 
 use super::inflector_shim;
 
 pub fn get_zeitwerk_constant_resolver(
-    pack_set: &PackSet,
-    absolute_root: &Path,
-    cache_dir: &Path,
-    cache_disabled: bool,
+    configuration: &Configuration,
 ) -> Box<dyn ConstantResolver + Send + Sync> {
-    let constants = inferred_constants_from_pack_set(
-        pack_set,
-        absolute_root,
-        cache_dir,
-        cache_disabled,
-    );
+    let constants = inferred_constants_from_pack_set(configuration);
 
     ZeitwerkConstantResolver::create(constants)
 }
 
 fn inferred_constants_from_pack_set(
-    pack_set: &PackSet,
-    absolute_root: &Path,
-    cache_dir: &Path,
-    cache_disabled: bool,
+    configuration: &Configuration,
 ) -> Vec<ConstantDefinition> {
-    let autoload_paths = get_autoload_paths(&pack_set.packs);
+    let packs = &configuration.pack_set.packs;
+    let root_namespace = configuration.root_namespace.as_deref();
+    let files = configuration.file_provider.as_ref();
+    let autoload_paths = match &configuration.load_paths {
+        Some(load_paths) => {
+            get_dumped_autoload_paths(packs, root_namespace, load_paths)
+        }
+        None => get_autoload_paths(files, packs, root_namespace),
+    };
     inferred_constants_from_autoload_paths(
         autoload_paths,
-        absolute_root,
-        cache_dir,
-        cache_disabled,
+        &configuration.absolute_root,
+        files,
+        &configuration.cache_directory,
+        configuration.reads_cache(),
+        configuration.writes_cache(),
     )
 }
 
+// `autoload_paths` maps each autoload path to the root namespace of the
+// constants defined under it, if there is one
 fn inferred_constants_from_autoload_paths(
-    autoload_paths: Vec<PathBuf>,
+    autoload_paths: HashMap<PathBuf, Option<String>>,
     absolute_root: &Path,
+    files: &dyn FileProvider,
     cache_dir: &Path,
-    cache_disabled: bool,
+    read_cache: bool,
+    write_cache: bool,
 ) -> Vec<ConstantDefinition> {
     debug!("Get constant resolver cache");
-    let cache_data = get_constant_resolver_cache(cache_dir);
+    let cache_data = if read_cache {
+        get_constant_resolver_cache(cache_dir)
+    } else {
+        ConstantResolverCache {
+            file_definition_map: HashMap::new(),
+        }
+    };
 
     debug!("Globbing out autoload paths");
     // First, we get a map of each autoload path to the files they map to.
     let autoload_paths_to_their_globbed_files = autoload_paths
-        .into_iter()
+        .keys()
+        .cloned()
         .par_bridge()
         .map(|absolute_autoload_path| {
             let glob_path = absolute_autoload_path.join("**/*.rb");
 
-            let files = glob::glob(glob_path.to_str().unwrap())
-                .expect("Failed to read glob pattern")
-                .filter_map(Result::ok)
-                .collect::<Vec<PathBuf>>();
+            let files = files.glob(glob_path.to_str().unwrap());
 
             (absolute_autoload_path, files)
         })
@@ -107,14 +117,17 @@ fn inferred_constants_from_autoload_paths(
     }
 
     debug!("Getting acronyms from disk");
-    let acronyms = &get_acronyms_from_disk(absolute_root);
+    let acronyms = &get_acronyms(files, absolute_root);
 
     debug!("Inferring constants from file name (using cache)");
     let constants: Vec<ConstantDefinition> = file_to_longest_path
         .into_iter()
         .par_bridge()
         .map(|(absolute_path_of_definition, absolute_autoload_path)| {
-            if let Some(fully_qualified_name) = cache_data
+            let root_namespace = autoload_paths
+                .get(absolute_autoload_path)
+                .and_then(|root_namespace| root_namespace.as_deref());
+            let constant = if let Some(fully_qualified_name) = cache_data
                 .file_definition_map
                 .get(absolute_path_of_definition)
             {
@@ -122,6 +135,7 @@ fn inferred_constants_from_autoload_paths(
                     fully_qualified_name: fully_qualified_name.to_owned(),
                     absolute_path_of_definition: absolute_path_of_definition
                         .to_owned(),
+                    kind: DefinitionKind::Constant,
                 }
             } else {
                 inferred_constant_from_file(
@@ -129,16 +143,46 @@ fn inferred_constants_from_autoload_paths(
                     absolute_autoload_path,
                     acronyms,
                 )
+            };
+
+            match root_namespace {
+                Some(root_namespace) => ConstantDefinition {
+                    fully_qualified_name: nest_in_root_namespace(
+                        &constant.fully_qualified_name,
+                        root_namespace,
+                    ),
+                    ..constant
+                },
+                None => constant,
             }
         })
         .collect::<Vec<ConstantDefinition>>();
 
     debug!("Caching constant definitions");
-    cache_constant_definitions(&constants, cache_dir, cache_disabled);
+    if write_cache {
+        cache_constant_definitions(&constants, cache_dir);
+    }
 
     constants
 }
 
+// `::Billing::Invoice` in root namespace `MyCo` is `::MyCo::Billing::Invoice`.
+// Constants whose directories already spell out the root namespace, like
+// `app/models/my_co/billing/invoice.rb`, are left as they are.
+fn nest_in_root_namespace(
+    fully_qualified_name: &str,
+    root_namespace: &str,
+) -> String {
+    let root = format!("::{}", root_namespace.trim_start_matches("::"));
+    if fully_qualified_name == root
+        || fully_qualified_name.starts_with(&format!("{}::", root))
+    {
+        fully_qualified_name.to_owned()
+    } else {
+        format!("{}{}", root, fully_qualified_name)
+    }
+}
+
 fn inferred_constant_from_file(
     absolute_path: &Path,
     absolute_autoload_path: &PathBuf,
@@ -149,14 +193,15 @@ fn inferred_constant_from_file(
 
     let relative_path = relative_path.with_extension("");
 
-    let relative_path_str = relative_path.to_str().unwrap();
-    let camelized_path = inflector_shim::camelize(relative_path_str, acronyms);
+    let relative_path_str = to_slash_path(&relative_path);
+    let camelized_path = inflector_shim::camelize(&relative_path_str, acronyms);
     let fully_qualified_name = format!("::{}", camelized_path);
 
     let absolute_path_of_definition = absolute_path.to_path_buf();
     ConstantDefinition {
         fully_qualified_name,
         absolute_path_of_definition,
+        kind: DefinitionKind::Constant,
     }
 }
 
@@ -181,12 +226,7 @@ fn get_constant_resolver_cache(cache_dir: &Path) -> ConstantResolverCache {
 fn cache_constant_definitions(
     constants: &Vec<ConstantDefinition>,
     cache_dir: &Path,
-    cache_disabled: bool,
 ) {
-    if cache_disabled {
-        return;
-    }
-
     let mut file_definition_map: HashMap<PathBuf, String> = HashMap::new();
     for constant in constants {
         file_definition_map.insert(
@@ -200,21 +240,63 @@ fn cache_constant_definitions(
     })
     .expect("Failed to serialize");
 
-    create_cache_dir_idempotently(cache_dir);
-    std::fs::write(cache_dir.join("constant_resolver.json"), cache_data_json)
-        .unwrap();
+    let write_result =
+        create_cache_dir_idempotently(cache_dir).and_then(|_| {
+            std::fs::write(
+                cache_dir.join("constant_resolver.json"),
+                cache_data_json,
+            )
+        });
+    match write_result {
+        Ok(()) => {}
+        Err(e) if is_read_only_error(&e) => {
+            debug!("Cache directory is not writable, skipping constant cache")
+        }
+        Err(e) => panic!("Failed to write constant resolver cache: {}", e),
+    }
 }
 
-fn get_autoload_paths(packs: &Vec<Pack>) -> Vec<PathBuf> {
-    let mut autoload_paths: Vec<PathBuf> = Vec::new();
+// The paths Rails reports in a load paths dump, each with the root namespace
+// of the pack it belongs to
+fn get_dumped_autoload_paths(
+    packs: &[Pack],
+    root_namespace: Option<&str>,
+    load_paths: &HashSet<PathBuf>,
+) -> HashMap<PathBuf, Option<String>> {
+    load_paths
+        .iter()
+        .map(|load_path| {
+            let owning_pack = packs
+                .iter()
+                .filter(|pack| {
+                    load_path.starts_with(pack.yml.parent().unwrap())
+                })
+                .max_by_key(|pack| pack.yml.components().count());
+            let pack_root_namespace = owning_pack
+                .and_then(|pack| pack.root_namespace.as_deref())
+                .or(root_namespace)
+                .map(String::from);
+            (load_path.to_owned(), pack_root_namespace)
+        })
+        .collect()
+}
+
+pub(crate) fn get_autoload_paths(
+    files: &dyn FileProvider,
+    packs: &Vec<Pack>,
+    root_namespace: Option<&str>,
+) -> HashMap<PathBuf, Option<String>> {
+    let mut autoload_paths: HashMap<PathBuf, Option<String>> = HashMap::new();
 
     debug!("Getting autoload paths");
 
     for pack in packs {
+        let mut pack_autoload_paths: Vec<PathBuf> = Vec::new();
+
         // App paths
         let app_paths = pack.yml.parent().unwrap().join("app").join("*");
         let app_glob_pattern = app_paths.to_str().unwrap();
-        process_glob_pattern(app_glob_pattern, &mut autoload_paths);
+        pack_autoload_paths.extend(files.glob(app_glob_pattern));
 
         // Concerns paths
         let concerns_paths = pack
@@ -226,7 +308,23 @@ fn get_autoload_paths(packs: &Vec<Pack>) -> Vec<PathBuf> {
             .join("concerns");
         let concerns_glob_pattern = concerns_paths.to_str().unwrap();
 
-        process_glob_pattern(concerns_glob_pattern, &mut autoload_paths);
+        pack_autoload_paths.extend(files.glob(concerns_glob_pattern));
+
+        // Custom paths declared in package.yml
+        for custom_path in &pack.autoload_paths {
+            let custom_paths = pack.yml.parent().unwrap().join(custom_path);
+            let custom_glob_pattern = custom_paths.to_str().unwrap();
+            pack_autoload_paths.extend(files.glob(custom_glob_pattern));
+        }
+
+        let pack_root_namespace = pack
+            .root_namespace
+            .as_deref()
+            .or(root_namespace)
+            .map(String::from);
+        for autoload_path in pack_autoload_paths {
+            autoload_paths.insert(autoload_path, pack_root_namespace.clone());
+        }
     }
 
     debug!("Finished getting autoload paths");
@@ -239,11 +337,13 @@ mod tests {
     use super::*;
     use crate::packs;
     use crate::packs::configuration;
+    use crate::packs::file_provider::WorkingTree;
 
     fn teardown() {
-        packs::delete_cache(configuration::get(&PathBuf::from(
-            "tests/fixtures/simple_app",
-        )));
+        packs::delete_cache(
+            configuration::get(&PathBuf::from("tests/fixtures/simple_app"))
+                .unwrap(),
+        );
     }
 
     use crate::test_util::{
@@ -258,7 +358,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::Foo".to_string(),
                 absolute_path_of_definition: get_absolute_root(SIMPLE_APP)
-                    .join("packs/foo/app/services/foo.rb")
+                    .join("packs/foo/app/services/foo.rb"),
+                kind: DefinitionKind::Constant,
             }],
             get_zeitwerk_constant_resolver_for_fixture(SIMPLE_APP)
                 .resolve(&String::from("Foo"), &[])
@@ -268,6 +369,97 @@ mod tests {
         teardown();
     }
 
+    #[test]
+    fn root_namespace_nests_inferred_constants() {
+        let fixture = "tests/fixtures/app_with_root_namespace";
+        let absolute_root = get_absolute_root(fixture);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(fixture);
+
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::MyCo::Billing::Invoice".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/billing/app/models/billing/invoice.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver
+                .resolve(&String::from("MyCo::Billing::Invoice"), &[])
+                .unwrap()
+        );
+
+        // A file already nested under the root namespace is not prefixed twice
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::MyCo::Payments::Charge".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/payments/app/models/my_co/payments/charge.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver
+                .resolve(&String::from("MyCo::Payments::Charge"), &[])
+                .unwrap()
+        );
+
+        // A pack's own root_namespace takes precedence over packwerk.yml
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Legacy::Order".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/legacy/app/models/order.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver
+                .resolve(&String::from("Legacy::Order"), &[])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn custom_autoload_paths() {
+        let fixture = "tests/fixtures/app_with_custom_autoload_paths";
+        let absolute_root = get_absolute_root(fixture);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(fixture);
+
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Ledger::Entry".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/ledger/lib/ledger/entry.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver
+                .resolve(&String::from("Ledger::Entry"), &[])
+                .unwrap()
+        );
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::LedgerApi".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/ledger/public/ledger_api.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver.resolve(&String::from("LedgerApi"), &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn load_paths_dump() {
+        let fixture = "tests/fixtures/app_with_load_paths_dump";
+        let absolute_root = get_absolute_root(fixture);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(fixture);
+
+        // `packs/foo/lib` is only an autoload path because the dump says so
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Foo::Util".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/foo/lib/foo/util.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver.resolve(&String::from("Foo::Util"), &[]).unwrap()
+        );
+    }
+
     #[test]
     fn nested_reference_to_unnested_constant() {
         let absolute_root = get_absolute_root(SIMPLE_APP);
@@ -277,7 +469,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::Foo".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("packs/foo/app/services/foo.rb")
+                    .join("packs/foo/app/services/foo.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver
                 .resolve(&String::from("Foo"), &["Foo", "Bar", "Baz"])
@@ -295,7 +488,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::Foo::Bar".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("packs/foo/app/services/foo/bar.rb")
+                    .join("packs/foo/app/services/foo/bar.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver.resolve("Bar", &["Foo"]).unwrap()
         );
@@ -312,7 +506,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::Bar".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("packs/bar/app/services/bar.rb")
+                    .join("packs/bar/app/services/bar.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver.resolve("::Bar", &["Foo"]).unwrap()
         );
@@ -328,7 +523,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::Bar::BAR".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("packs/bar/app/services/bar.rb")
+                    .join("packs/bar/app/services/bar.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver.resolve(&String::from("::Bar::BAR"), &[]).unwrap()
         );
@@ -346,7 +542,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::MyModule::SomeAPIClass".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("app/services/my_module/some_api_class.rb")
+                    .join("app/services/my_module/some_api_class.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver
                 .resolve(&String::from("::MyModule::SomeAPIClass"), &[])
@@ -357,7 +554,8 @@ mod tests {
             vec![ConstantDefinition {
                 fully_qualified_name: "::MyModule::SomeCSVClass".to_string(),
                 absolute_path_of_definition: absolute_root
-                    .join("app/services/my_module/some_csv_class.rb")
+                    .join("app/services/my_module/some_csv_class.rb"),
+                kind: DefinitionKind::Constant,
             }],
             resolver
                 .resolve(&String::from("::MyModule::SomeCSVClass"), &[])
@@ -367,22 +565,46 @@ mod tests {
         teardown();
     }
 
+    #[test]
+    fn top_level_reference_skips_nesting() {
+        let app = "tests/fixtures/app_with_top_level_references";
+        let absolute_root = get_absolute_root(app);
+        let resolver = get_zeitwerk_constant_resolver_for_fixture(app);
+
+        // Inside `module Foo`, `Bar` is `::Foo::Bar`...
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Foo::Bar".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/foo/app/services/foo/bar.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver.resolve(&String::from("Bar"), &["Foo"]).unwrap()
+        );
+
+        // ...but `::Bar` is the top-level one, in another pack
+        assert_eq!(
+            vec![ConstantDefinition {
+                fully_qualified_name: "::Bar".to_string(),
+                absolute_path_of_definition: absolute_root
+                    .join("packs/bar/app/services/bar.rb"),
+                kind: DefinitionKind::Constant,
+            }],
+            resolver.resolve(&String::from("::Bar"), &["Foo"]).unwrap()
+        );
+
+        teardown();
+    }
+
     #[test]
     fn test_file_map() {
         let absolute_root = &PathBuf::from("tests/fixtures/simple_app")
             .canonicalize()
             .expect("Could not canonicalize path");
 
-        let configuration = configuration::get(absolute_root);
-
-        let pack_set = configuration.pack_set;
+        let configuration = configuration::get(absolute_root).unwrap();
 
-        let constant_resolver = get_zeitwerk_constant_resolver(
-            &pack_set,
-            absolute_root,
-            &configuration.cache_directory,
-            !configuration.cache_enabled,
-        );
+        let constant_resolver = get_zeitwerk_constant_resolver(&configuration);
         let actual_constant_map = constant_resolver
             .fully_qualified_constant_name_to_constant_definition_map();
 
@@ -393,6 +615,7 @@ mod tests {
                 fully_qualified_name: "::Foo::Bar".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("packs/foo/app/services/foo/bar.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
 
@@ -402,6 +625,7 @@ mod tests {
                 fully_qualified_name: "::Bar".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("packs/bar/app/services/bar.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
         expected_constant_map.insert(
@@ -410,6 +634,7 @@ mod tests {
                 fully_qualified_name: "::Baz".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("packs/baz/app/services/baz.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
         expected_constant_map.insert(
@@ -418,6 +643,7 @@ mod tests {
                 fully_qualified_name: "::Foo".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("packs/foo/app/services/foo.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
         expected_constant_map.insert(
@@ -426,6 +652,7 @@ mod tests {
                 fully_qualified_name: "::SomeConcern".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("packs/bar/app/models/concerns/some_concern.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
         expected_constant_map.insert(
@@ -434,10 +661,52 @@ mod tests {
                 fully_qualified_name: "::SomeRootClass".to_owned(),
                 absolute_path_of_definition: absolute_root
                     .join("app/services/some_root_class.rb"),
+                kind: DefinitionKind::Constant,
             }],
         );
         assert_eq!(&expected_constant_map, actual_constant_map);
 
         teardown();
     }
+
+    #[test]
+    fn test_reads_the_cache_without_writing_it() {
+        let absolute_root = get_absolute_root(SIMPLE_APP);
+        let cache_dir = std::env::temp_dir().join("packs_resolver_cache_test");
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        let cache = serde_json::to_string(&ConstantResolverCache {
+            file_definition_map: HashMap::from([(
+                absolute_root.join("packs/foo/app/services/foo.rb"),
+                String::from("::CachedFoo"),
+            )]),
+        })
+        .unwrap();
+        let cache_file = cache_dir.join("constant_resolver.json");
+        std::fs::write(&cache_file, &cache).unwrap();
+        let autoload_paths: HashMap<PathBuf, Option<String>> =
+            HashMap::from([(
+                absolute_root.join("packs/foo/app/services"),
+                None,
+            )]);
+        let names = |read_cache: bool| -> Vec<String> {
+            inferred_constants_from_autoload_paths(
+                autoload_paths.clone(),
+                &absolute_root,
+                &WorkingTree,
+                &cache_dir,
+                read_cache,
+                false,
+            )
+            .into_iter()
+            .map(|constant| constant.fully_qualified_name)
+            .collect()
+        };
+
+        assert!(names(true).contains(&String::from("::CachedFoo")));
+        assert!(names(false).contains(&String::from("::Foo")));
+        assert_eq!(cache, std::fs::read_to_string(&cache_file).unwrap());
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
 }
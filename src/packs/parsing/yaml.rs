@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use regex::Regex;
+use serde_yaml::Value;
+
+use crate::packs::file_utils::file_read_contents;
+use crate::packs::parsing::{
+    Range, ReferenceConfidence, ReferenceKind, UnresolvedReference,
+};
+use crate::packs::{Configuration, FileSigils, ProcessedFile};
+
+pub(crate) fn process_from_path(
+    path: &Path,
+    configuration: &Configuration,
+) -> ProcessedFile {
+    let contents = file_read_contents(path, configuration);
+    let unresolved_references = references_from_contents(
+        &contents,
+        &configuration.config_reference_keys,
+    );
+
+    ProcessedFile {
+        absolute_path: path.to_owned(),
+        unresolved_references,
+        definitions: vec![],
+        sigils: FileSigils::default(),
+    }
+}
+
+// Config files like `config/sidekiq.yml` name the classes the app loads at
+// runtime, e.g. `class: Billing::InvoiceJob`. Those names are references to
+// the constants, looked up from the top level like `constantize` does.
+fn references_from_contents(
+    contents: &str,
+    keys: &[String],
+) -> Vec<UnresolvedReference> {
+    let Ok(document) = serde_yaml::from_str::<Value>(contents) else {
+        return vec![];
+    };
+    let constant_name = Regex::new(r"^(::)?[A-Z]\w*(::[A-Z]\w*)*$").unwrap();
+    let mut class_names = vec![];
+    collect_class_names(&document, keys, &constant_name, &mut class_names);
+
+    // YAML values don't keep their position, so each name is found in the
+    // file after the previous one, in the order the document lists them
+    let mut offset = 0;
+    class_names
+        .into_iter()
+        .filter_map(|class_name| {
+            let start = offset + contents[offset..].find(class_name)?;
+            offset = start + class_name.len();
+            let name = format!("::{}", class_name.trim_start_matches("::"));
+            Some(UnresolvedReference {
+                name,
+                namespace_path: vec![],
+                location: location_of(contents, start, class_name.len()),
+                kind: ReferenceKind::Code,
+                confidence: ReferenceConfidence::Exact,
+            })
+        })
+        .collect()
+}
+
+fn collect_class_names<'a>(
+    value: &'a Value,
+    keys: &[String],
+    constant_name: &Regex,
+    class_names: &mut Vec<&'a str>,
+) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let names_a_class = key
+                    .as_str()
+                    .is_some_and(|key| keys.iter().any(|k| k == key));
+                match value.as_str() {
+                    Some(class_name)
+                        if names_a_class
+                            && constant_name.is_match(class_name) =>
+                    {
+                        class_names.push(class_name)
+                    }
+                    _ => collect_class_names(
+                        value,
+                        keys,
+                        constant_name,
+                        class_names,
+                    ),
+                }
+            }
+        }
+        Value::Sequence(sequence) => {
+            for value in sequence {
+                collect_class_names(value, keys, constant_name, class_names);
+            }
+        }
+        Value::Tagged(tagged) => {
+            collect_class_names(&tagged.value, keys, constant_name, class_names)
+        }
+        _ => {}
+    }
+}
+
+// The row is 1-based and columns are 0-based, like the Ruby parsers'
+fn location_of(contents: &str, start: usize, length: usize) -> Range {
+    let line_start = contents[..start].rfind('\n').map_or(0, |i| i + 1);
+    let row = contents[..start].matches('\n').count() + 1;
+    let col = contents[line_start..start].chars().count();
+    Range {
+        start_row: row,
+        start_col: col,
+        end_row: row,
+        end_col: col + length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_references_from_contents() {
+        let contents = "\
+:schedule:
+  send_invoices:
+    cron: '0 * * * *'
+    class: Billing::SendInvoicesJob
+    queue: default
+  cleanup:
+    every: 1h
+    class: \"::CleanupJob\"
+    description: Not a class
+";
+        let references =
+            references_from_contents(contents, &[String::from("class")]);
+
+        assert_eq!(
+            vec![
+                UnresolvedReference {
+                    name: String::from("::Billing::SendInvoicesJob"),
+                    namespace_path: vec![],
+                    location: Range {
+                        start_row: 4,
+                        start_col: 11,
+                        end_row: 4,
+                        end_col: 35,
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
+                },
+                UnresolvedReference {
+                    name: String::from("::CleanupJob"),
+                    namespace_path: vec![],
+                    location: Range {
+                        start_row: 8,
+                        start_col: 12,
+                        end_row: 8,
+                        end_col: 24,
+                    },
+                    kind: ReferenceKind::Code,
+                    confidence: ReferenceConfidence::Exact,
+                },
+            ],
+            references
+        );
+    }
+
+    #[test]
+    fn test_invalid_yaml() {
+        assert!(
+            references_from_contents("class: [", &[String::from("class")])
+                .is_empty()
+        );
+    }
+}
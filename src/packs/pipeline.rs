@@ -0,0 +1,135 @@
+// The stages `check` runs, as library functions, so a tool built on packs
+// can run them one at a time: inspect or save what a stage returns, or swap
+// in its own stage, like a custom constant resolver.
+//
+//     let configuration = packs::configuration(project_root);
+//     let files = pipeline::discover_files(&configuration, vec![]);
+//     let processed_files = pipeline::parse(&configuration, &files);
+//     let resolver = pipeline::default_resolver(&configuration);
+//     let references =
+//         pipeline::resolve(&configuration, resolver.as_ref(), &processed_files);
+//     let violations = pipeline::check(&configuration, references);
+//     pipeline::report(&configuration, &files, violations)?;
+//
+// What each stage returns can be serialized, e.g. to JSON, and read back.
+// `check_all` runs every stage at once and returns what `check` would print.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::checker::{
+    self, get_checkers, report_violations, violations_for_references,
+};
+use super::process_files_with_cache;
+use super::reference_extractor::{
+    get_constant_resolver, needs_all_definitions, resolve_references,
+};
+
+pub use super::checker::reference::Reference;
+pub use super::checker::{
+    CheckResult, FilterCounts, Timing, Violation, ViolationIdentifier,
+};
+pub use super::configuration::Configuration;
+pub use super::constant_resolver::{ConstantDefinition, ConstantResolver};
+pub use super::parsing::{
+    FileSigils, ParseError, ParsedDefinition, Range, ReferenceConfidence,
+    ReferenceKind, UnresolvedReference,
+};
+pub use super::{ProcessedFile, SourceLocation};
+
+// The files a run checks
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct DiscoveredFiles {
+    // Absolute paths, sorted
+    pub files: Vec<PathBuf>,
+}
+
+// The included files, or the ones of `files` (relative to the root, or
+// absolute) that are included
+pub fn discover_files(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> DiscoveredFiles {
+    let mut files: Vec<PathBuf> =
+        configuration.intersect_files(files).into_iter().collect();
+    files.sort();
+    DiscoveredFiles { files }
+}
+
+// The references and definitions in each file, using the cache if it's on
+pub fn parse(
+    configuration: &Configuration,
+    files: &DiscoveredFiles,
+) -> Vec<ProcessedFile> {
+    let paths: HashSet<PathBuf> = files.files.iter().cloned().collect();
+    let mut processed_files = process_files_with_cache(
+        &paths,
+        configuration.get_cache(),
+        configuration,
+    );
+    processed_files.sort_by(|a, b| a.absolute_path.cmp(&b.absolute_path));
+    processed_files
+}
+
+// The resolver `check` uses. With the experimental parser, or with
+// `follow_constant_aliases`, it's built from the definitions in every
+// included file, so those are parsed too.
+pub fn default_resolver(
+    configuration: &Configuration,
+) -> Box<dyn ConstantResolver + Send + Sync> {
+    let all_processed_files = if needs_all_definitions(configuration) {
+        parse(configuration, &discover_files(configuration, vec![]))
+    } else {
+        vec![]
+    };
+    get_constant_resolver(configuration, &all_processed_files)
+}
+
+// Works out which constant, in which pack, each reference is to
+pub fn resolve(
+    configuration: &Configuration,
+    resolver: &(dyn ConstantResolver + Send + Sync),
+    processed_files: &[ProcessedFile],
+) -> Vec<Reference> {
+    resolve_references(configuration, resolver, processed_files)
+}
+
+// The violations of the references, by every checker, sorted
+pub fn check(
+    configuration: &Configuration,
+    references: Vec<Reference>,
+) -> Vec<Violation> {
+    let checkers = get_checkers(configuration);
+    let mut violations: Vec<Violation> =
+        violations_for_references(configuration, references, &checkers)
+            .into_iter()
+            .collect();
+    violations.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+    violations
+}
+
+// Prints the violations like `check` does, with the ones recorded in todo
+// files left out, and errors if the check fails
+pub fn report(
+    configuration: &Configuration,
+    files: &DiscoveredFiles,
+    violations: Vec<Violation>,
+) -> Result<(), Box<dyn Error>> {
+    let absolute_paths: HashSet<PathBuf> =
+        files.files.iter().cloned().collect();
+    let violations: HashSet<Violation> = violations.into_iter().collect();
+    report_violations(configuration, &absolute_paths, &violations)
+}
+
+// Every stage at once, like `check`, for the files of `files` that are
+// included (or every included file if it's empty), without printing
+// anything
+pub fn check_all(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> CheckResult {
+    checker::check(configuration, files)
+}
@@ -1,14 +1,18 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt,
-    fs::File,
     path::{Path, PathBuf},
 };
 
+use globset::GlobBuilder;
 use serde::{
     de::{self, value, SeqAccess, Visitor},
     Deserialize, Deserializer, Serialize,
 };
+use serde_yaml::{Mapping, Value};
+
+use super::file_provider::FileProvider;
+use super::file_utils::to_slash_path;
 
 const CONFIG_FILE_NAME: &str = "packwerk.yml";
 const PACKS_FIRST_CONFIG_FILE_NAME: &str = "packs.yml";
@@ -44,6 +48,11 @@ pub(crate) struct RawConfiguration {
     #[serde(default = "default_cache_directory")]
     pub cache_directory: String,
 
+    // How cache entries are written: packwerk-compatible JSON (json),
+    // or zstd-compressed bincode (bincode), which is smaller and faster to read
+    #[serde(default)]
+    pub cache_format: CacheFormat,
+
     // Autoload paths used to resolve constants
     #[serde(default)]
     pub autoload_paths: Option<Vec<String>>,
@@ -63,54 +72,553 @@ pub(crate) struct RawConfiguration {
     // Use packs copy
     #[serde(default)]
     pub packs_first_mode: bool,
+
+    // Whether or not references from the root pack are checked
+    #[serde(default = "default_enforce_from_root")]
+    pub enforce_from_root: bool,
+
+    // Size budgets that every (non-root) pack must stay within
+    #[serde(default)]
+    pub pack_budgets: PackBudgets,
+
+    // Where recorded violations are stored: in each pack's package_todo.yml
+    // (per_pack), or all together in packwerk_todo.yml (consolidated)
+    #[serde(default)]
+    pub todo_storage: TodoStorage,
+
+    // v2 also records how often each constant is referenced in violation,
+    // and when (and at which commit) it was first recorded
+    #[serde(default)]
+    pub todo_format: TodoFormat,
+
+    // References to drop before checking, e.g. from vendored or generated code
+    #[serde(default)]
+    pub reference_filter: ReferenceFilterSettings,
+
+    // When set, `validate` reports constants defined in more than one pack
+    #[serde(default)]
+    pub reopened_constants: Option<ReopenedConstantsSettings>,
+
+    // A module every constant in the app is nested in without a matching
+    // directory, e.g. `MyCo`. Packs can override it in their package.yml.
+    #[serde(default)]
+    pub root_namespace: Option<String>,
+
+    // Namespaces whose constants belong to a pack wherever they're defined,
+    // e.g. `Billing: packs/billing`, for a namespace that several packs share
+    // during a migration
+    #[serde(default)]
+    pub namespace_pack_mapping: HashMap<String, String>,
+
+    // A JSON dump of the app's real autoload and eager load paths, generated
+    // by Rails. When set, it replaces the paths inferred from each pack.
+    #[serde(default)]
+    pub load_paths_dump: Option<PathBuf>,
+
+    // Wording for each violation type (dependency, privacy, ...), replacing
+    // the default message. See `ViolationMessages::format` for the
+    // available placeholders.
+    #[serde(default)]
+    pub violation_messages: HashMap<String, String>,
+
+    // Documentation to link to for each violation code (P001, P002, ...),
+    // shown alongside every violation with that code
+    #[serde(default)]
+    pub violation_code_urls: HashMap<String, String>,
+
+    // Whether string literals passed to class_eval, module_eval and
+    // instance_eval are parsed as Ruby to find the references in them
+    #[serde(default)]
+    pub parse_eval_strings: bool,
+
+    // Whether a file that makes the parser panic is skipped, with an error,
+    // instead of aborting the whole run
+    #[serde(default)]
+    pub isolate_parse_crashes: bool,
+
+    // Files bigger than this many bytes are skipped, with an error, instead
+    // of parsed, e.g. huge generated files
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+
+    // Files that take longer than this many seconds to parse are skipped,
+    // with an error, instead of holding up the run
+    #[serde(default)]
+    pub max_parse_duration: Option<f64>,
+
+    // Whether packs that leave out an `enforce_*` setting use the root
+    // pack's, instead of not enforcing that checker
+    #[serde(default)]
+    pub inherit_enforcement_from_root: bool,
+
+    // Which definitions shadow others when a constant is defined in more than one place:
+    // only one in the referencing file (file), or the nearest one in the app (app)
+    #[serde(default)]
+    pub definition_scope: DefinitionScope,
+
+    // Whether a reference to an alias, like `InvoiceAlias = Billing::Invoice`,
+    // resolves to the constant it's assigned to
+    #[serde(default)]
+    pub follow_constant_aliases: bool,
+
+    // Directories of sub-apps with their own packwerk.yml, e.g. `gems/*`,
+    // whose packs are checked together with the app's
+    #[serde(default)]
+    pub additional_packwerk_dirs: Vec<String>,
+
+    // The sub-app directories `additional_packwerk_dirs` expands to, relative
+    // to the root
+    #[serde(skip)]
+    pub sub_app_dirs: Vec<String>,
+
+    // Globs for YAML (or JSON) config files, like `config/sidekiq.yml`, whose
+    // class names are references from the pack containing the file
+    #[serde(default)]
+    pub config_reference_files: Vec<String>,
+
+    // The keys whose values name a class in `config_reference_files`
+    #[serde(default = "default_config_reference_keys")]
+    pub config_reference_keys: Vec<String>,
+
+    // Problems that don't stop packs from running, like keys it doesn't
+    // know, which may be meant for another tool or a newer packwerk
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+// The keys of `RawConfiguration` that are read from packwerk.yml
+const KNOWN_KEYS: [&str; 33] = [
+    "include",
+    "exclude",
+    "package_paths",
+    "custom_associations",
+    "cache",
+    "cache_directory",
+    "cache_format",
+    "autoload_paths",
+    "architecture_layers",
+    "experimental_parser",
+    "ignored_definitions",
+    "packs_first_mode",
+    "enforce_from_root",
+    "pack_budgets",
+    "todo_storage",
+    "todo_format",
+    "reference_filter",
+    "reopened_constants",
+    "root_namespace",
+    "namespace_pack_mapping",
+    "load_paths_dump",
+    "violation_messages",
+    "violation_code_urls",
+    "parse_eval_strings",
+    "isolate_parse_crashes",
+    "max_file_size",
+    "max_parse_duration",
+    "inherit_enforcement_from_root",
+    "definition_scope",
+    "follow_constant_aliases",
+    "additional_packwerk_dirs",
+    "config_reference_files",
+    "config_reference_keys",
+];
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionScope {
+    #[default]
+    File,
+    App,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReferenceFilterSettings {
+    // Globs for files whose references are not checked
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    // Patterns for constants whose references are not checked, e.g. `::Generated::*`
+    #[serde(default)]
+    pub constants: Vec<String>,
+
+    // Whether references only used as types, in Sorbet `sig`s and RBI files,
+    // are not checked
+    #[serde(default)]
+    pub signatures: bool,
+
+    // Groups of pack name globs, e.g. `[packs/foo, packs/foo_*]`. References
+    // between two packs of the same group are not checked.
+    #[serde(default)]
+    pub pack_groups: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReopenedConstantsSettings {
+    // Patterns for constants that may be defined in more than one pack, e.g.
+    // `::ApplicationRecord` or `::Billing::*`
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheFormat {
+    #[default]
+    Json,
+    Bincode,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStorage {
+    #[default]
+    PerPack,
+    Consolidated,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoFormat {
+    #[default]
+    V1,
+    V2,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PackBudgets {
+    // Maximum number of included files in a pack
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
+    // Maximum number of constants in a pack's public folder
+    #[serde(default)]
+    pub max_public_constants: Option<usize>,
+
+    // Maximum number of packs a pack can depend on
+    #[serde(default)]
+    pub max_dependencies: Option<usize>,
+}
+
+// Keys that packwerk (the Ruby gem) reads from packwerk.yml, but that have no
+// meaning for packs. They're accepted so the same file works with both tools.
+const PACKWERK_ONLY_KEYS: [&str; 4] =
+    ["parallel", "offenses_formatter", "require", "load_paths"];
+
+// Every problem found while loading a configuration file, so that they can all
+// be fixed at once rather than one run at a time.
+pub struct ConfigurationError {
+    pub config_path: PathBuf,
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} problem(s) found in {}:",
+            self.problems.len(),
+            self.config_path.display()
+        )?;
+        for problem in &self.problems {
+            writeln!(f, "- {}", problem)?;
+        }
+        Ok(())
+    }
 }
 
-pub(crate) fn get(absolute_root: &Path) -> RawConfiguration {
+// `main` prints errors it returns with `Debug`, so this shows the same
+// readable report as `Display` rather than the struct's fields.
+impl fmt::Debug for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for ConfigurationError {}
+
+pub(crate) fn get(
+    absolute_root: &Path,
+    files: &dyn FileProvider,
+) -> Result<RawConfiguration, ConfigurationError> {
     let absolute_path_to_packwerk_yml = absolute_root.join(CONFIG_FILE_NAME);
     let absolute_path_to_packs_yml =
         absolute_root.join(PACKS_FIRST_CONFIG_FILE_NAME);
 
-    if absolute_path_to_packwerk_yml.exists() {
-        get_from_file_that_exists(absolute_path_to_packwerk_yml)
-    } else if absolute_path_to_packs_yml.exists() {
-        let mut config = get_from_file_that_exists(absolute_path_to_packs_yml);
+    let mut config = if files.is_file(&absolute_path_to_packwerk_yml) {
+        get_from_file_that_exists(
+            absolute_root,
+            absolute_path_to_packwerk_yml,
+            files,
+        )?
+    } else if files.is_file(&absolute_path_to_packs_yml) {
+        let mut config = get_from_file_that_exists(
+            absolute_root,
+            absolute_path_to_packs_yml,
+            files,
+        )?;
         config.packs_first_mode = true;
         config
     } else {
         RawConfiguration::default()
+    };
+
+    add_sub_apps(absolute_root, &mut config, files)?;
+    Ok(config)
+}
+
+// Adds the packs and files of each sub-app in `additional_packwerk_dirs`,
+// found with the patterns in the sub-app's own packwerk.yml
+fn add_sub_apps(
+    absolute_root: &Path,
+    config: &mut RawConfiguration,
+    files: &dyn FileProvider,
+) -> Result<(), ConfigurationError> {
+    for pattern in config.additional_packwerk_dirs.clone() {
+        let absolute_pattern =
+            absolute_root.join(&pattern).to_string_lossy().into_owned();
+        glob::Pattern::new(&absolute_pattern).map_err(|e| {
+            ConfigurationError {
+                config_path: absolute_root.join(CONFIG_FILE_NAME),
+                problems: vec![format!(
+                    "`additional_packwerk_dirs` has an invalid glob `{}`: {}",
+                    pattern, e
+                )],
+            }
+        })?;
+        let mut absolute_dirs: Vec<PathBuf> = files
+            .glob(&absolute_pattern)
+            .into_iter()
+            .filter(|path| files.is_file(&path.join(CONFIG_FILE_NAME)))
+            .collect();
+        absolute_dirs.sort();
+
+        for absolute_dir in absolute_dirs {
+            let sub_app = get_from_file_that_exists(
+                &absolute_dir,
+                absolute_dir.join(CONFIG_FILE_NAME),
+                files,
+            )?;
+            let dir = to_slash_path(
+                absolute_dir
+                    .strip_prefix(absolute_root)
+                    .unwrap_or(&absolute_dir),
+            );
+            let prefixed = |patterns: &[String]| -> Vec<String> {
+                patterns
+                    .iter()
+                    .map(|pattern| format!("{}/{}", dir, pattern))
+                    .collect()
+            };
+
+            // The sub-app's root package.yml is a pack too
+            config.package_paths.push(dir.clone());
+            config
+                .package_paths
+                .extend(prefixed(&sub_app.package_paths));
+            config.include.extend(prefixed(&sub_app.include));
+            config.exclude.extend(prefixed(&sub_app.exclude));
+            config.warnings.extend(
+                sub_app
+                    .warnings
+                    .iter()
+                    .map(|warning| format!("{}/{}", dir, warning)),
+            );
+            config.sub_app_dirs.push(dir);
+        }
     }
+
+    Ok(())
 }
 
 fn get_from_file_that_exists(
-    absolute_path_to_packwerk_yml: PathBuf,
-) -> RawConfiguration {
-    let mut file = File::open(absolute_path_to_packwerk_yml.clone())
-        .unwrap_or_else(|e| {
-            panic!(
-                "Could not open packwerk.yml at: {} due to error: {:?}",
-                absolute_path_to_packwerk_yml.display(),
-                e
-            )
-        });
-
-    let mut contents = String::new();
-    std::io::Read::read_to_string(&mut file, &mut contents).unwrap_or_else(
-        |e| {
-            panic!(
-                "Could not read packwerk.yml at: {} due to error: {:?}",
-                absolute_path_to_packwerk_yml.display(),
-                e
-            )
-        },
-    );
-
-    serde_yaml::from_str(&contents).unwrap_or_else(|e| {
-        panic!(
-            "Could not parse packwerk.yml at: {} due to error: {:?}",
-            absolute_path_to_packwerk_yml.display(),
-            e
-        )
-    })
+    absolute_root: &Path,
+    config_path: PathBuf,
+    files: &dyn FileProvider,
+) -> Result<RawConfiguration, ConfigurationError> {
+    let result = match files.read_to_string(&config_path) {
+        Ok(contents) => from_contents(absolute_root, &contents),
+        Err(e) => Err(vec![format!("could not be read: {}", e)]),
+    };
+
+    let relative_path = config_path
+        .strip_prefix(absolute_root)
+        .map(to_slash_path)
+        .unwrap_or_else(|_| to_slash_path(&config_path));
+    match result {
+        Ok(config) => Ok(RawConfiguration {
+            warnings: config
+                .warnings
+                .iter()
+                .map(|warning| format!("{}: {}", relative_path, warning))
+                .collect(),
+            ..config
+        }),
+        Err(problems) => Err(ConfigurationError {
+            config_path,
+            problems,
+        }),
+    }
+}
+
+pub(crate) fn from_contents(
+    absolute_root: &Path,
+    contents: &str,
+) -> Result<RawConfiguration, Vec<String>> {
+    let value: Value = serde_yaml::from_str(contents)
+        .map_err(|e| vec![format!("is not valid YAML: {}", e)])?;
+
+    let mapping = match value {
+        Value::Null => Mapping::new(),
+        Value::Mapping(mapping) => mapping,
+        _ => {
+            return Err(vec![String::from(
+                "must be a mapping of configuration keys to values",
+            )])
+        }
+    };
+
+    let mut problems: Vec<String> = vec![];
+    let mut warnings: Vec<String> = vec![];
+    let mut valid_mapping = Mapping::new();
+
+    for (key, value) in mapping {
+        let key_name = match key.as_str() {
+            Some(key_name) => key_name.to_owned(),
+            None => {
+                problems.push(format!("`{:?}` is not a valid key", key));
+                continue;
+            }
+        };
+
+        if KNOWN_KEYS.contains(&key_name.as_str()) {
+            // Each key is deserialized on its own so that every invalid value
+            // is reported, rather than only the first one serde runs into.
+            let mut single_key = Mapping::new();
+            single_key.insert(key.clone(), value.clone());
+            match serde_yaml::from_value::<RawConfiguration>(Value::Mapping(
+                single_key,
+            )) {
+                Ok(_) => {
+                    valid_mapping.insert(key, value);
+                }
+                Err(e) => problems.push(format!(
+                    "`{}` has an invalid value: {}",
+                    key_name, e
+                )),
+            }
+        } else if !PACKWERK_ONLY_KEYS.contains(&key_name.as_str()) {
+            warnings.push(unknown_key_warning(&key_name));
+        }
+    }
+
+    let raw_configuration = RawConfiguration {
+        warnings,
+        ..serde_yaml::from_value(Value::Mapping(valid_mapping))
+            .map_err(|e| vec![e.to_string()])?
+    };
+
+    problems.extend(validate_values(absolute_root, &raw_configuration));
+
+    if problems.is_empty() {
+        Ok(raw_configuration)
+    } else {
+        Err(problems)
+    }
+}
+
+fn unknown_key_warning(key_name: &str) -> String {
+    let suggestion = KNOWN_KEYS
+        .iter()
+        .map(|known_key| (edit_distance(key_name, known_key), known_key))
+        .filter(|(distance, _)| *distance <= 3)
+        .min();
+
+    match suggestion {
+        Some((_, known_key)) => format!(
+            "`{}` is not a known key, did you mean `{}`?",
+            key_name, known_key
+        ),
+        None => format!("`{}` is not a known key", key_name),
+    }
+}
+
+fn validate_values(
+    absolute_root: &Path,
+    raw_configuration: &RawConfiguration,
+) -> Vec<String> {
+    let mut problems = vec![];
+
+    let globs = [
+        ("include", &raw_configuration.include),
+        ("exclude", &raw_configuration.exclude),
+        ("package_paths", &raw_configuration.package_paths),
+        (
+            "reference_filter.paths",
+            &raw_configuration.reference_filter.paths,
+        ),
+        (
+            "reference_filter.constants",
+            &raw_configuration.reference_filter.constants,
+        ),
+    ];
+    let pack_groups = raw_configuration
+        .reference_filter
+        .pack_groups
+        .iter()
+        .map(|group| ("reference_filter.pack_groups", group));
+    for (key_name, patterns) in globs.into_iter().chain(pack_groups) {
+        for pattern in patterns {
+            if let Err(e) =
+                GlobBuilder::new(pattern).literal_separator(true).build()
+            {
+                problems.push(format!(
+                    "`{}` has an invalid glob `{}`: {}",
+                    key_name, pattern, e
+                ));
+            }
+        }
+    }
+
+    if let Some(autoload_paths) = &raw_configuration.autoload_paths {
+        for autoload_path in autoload_paths {
+            if !absolute_root.join(autoload_path).exists() {
+                problems.push(format!(
+                    "`autoload_paths` includes `{}`, which does not exist",
+                    autoload_path
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+// Levenshtein distance, used to suggest a key when a key is misspelled
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != *b_char);
+            current_row.push(
+                (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[b_chars.len()]
 }
 
 // Normally if a key is not set, serde will use the default value for that type.
@@ -145,10 +653,18 @@ fn default_custom_associations() -> Vec<String> {
     vec![]
 }
 
+fn default_config_reference_keys() -> Vec<String> {
+    vec![String::from("class")]
+}
+
 fn default_cache() -> bool {
     true
 }
 
+fn default_enforce_from_root() -> bool {
+    true
+}
+
 fn default_cache_directory() -> String {
     String::from("tmp/cache/packwerk")
 }
@@ -196,6 +712,7 @@ mod tests {
 
         assert!(raw_configuration.cache);
         assert_eq!(raw_configuration.cache_directory, "tmp/cache/packwerk");
+        assert!(raw_configuration.enforce_from_root);
     }
 
     #[test]
@@ -207,4 +724,80 @@ mod tests {
 
         assert_eq!(raw_configuration.package_paths, vec!["**/*"]);
     }
+
+    #[test]
+    fn test_deserialize_pack_budgets() {
+        let raw_configuration_string = String::from(
+            "pack_budgets:\n  max_files: 100\n  max_dependencies: 5",
+        );
+        let raw_configuration =
+            serde_yaml::from_str::<RawConfiguration>(&raw_configuration_string)
+                .expect("Could not deserialize pack_budgets");
+
+        let budgets = raw_configuration.pack_budgets;
+        assert_eq!(budgets.max_files, Some(100));
+        assert_eq!(budgets.max_public_constants, None);
+        assert_eq!(budgets.max_dependencies, Some(5));
+    }
+
+    #[test]
+    fn test_from_contents_reports_every_problem() {
+        let contents = "\
+cahce: false
+cache_directory: [1, 2]
+include:
+  - \"app/{**/*.rb\"
+parallel: true
+pack_budgets:
+  max_file: 10
+";
+        let problems = from_contents(Path::new("."), contents)
+            .expect_err("Expected configuration problems");
+
+        assert_eq!(problems.len(), 3);
+        assert!(
+            problems[0].starts_with("`cache_directory` has an invalid value")
+        );
+        assert!(problems[1].starts_with("`pack_budgets` has an invalid value"));
+        assert!(problems[2]
+            .starts_with("`include` has an invalid glob `app/{**/*.rb`"));
+    }
+
+    #[test]
+    fn test_from_contents_warns_about_unknown_keys() {
+        let contents = "cahce: false\nsome_other_tool: true\nparallel: true\n";
+        let raw_configuration = from_contents(Path::new("."), contents)
+            .expect("Unknown keys are not problems");
+
+        assert_eq!(
+            vec![
+                "`cahce` is not a known key, did you mean `cache`?",
+                "`some_other_tool` is not a known key",
+            ],
+            raw_configuration.warnings
+        );
+    }
+
+    #[test]
+    fn test_known_keys() {
+        // Every key the default configuration serializes is known
+        let Value::Mapping(default) =
+            serde_yaml::to_value(RawConfiguration::default()).unwrap()
+        else {
+            panic!("Expected a mapping");
+        };
+        let mut keys: Vec<&str> =
+            default.keys().filter_map(|key| key.as_str()).collect();
+        let mut known_keys = KNOWN_KEYS.to_vec();
+        keys.sort();
+        known_keys.sort();
+        assert_eq!(known_keys, keys);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("cache", "cache"), 0);
+        assert_eq!(edit_distance("cahce", "cache"), 2);
+        assert_eq!(edit_distance("exclde", "exclude"), 1);
+    }
 }
@@ -1,64 +1,176 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use tracing::debug;
 
 use crate::packs::{
     get_experimental_constant_resolver, get_zeitwerk_constant_resolver,
-    process_files_with_cache, ProcessedFile,
+    parsing::ruby::constant_aliases::AliasFollowingConstantResolver,
+    parsing::{process_files_with_cache_and_errors, ParseError},
+    ProcessedFile,
 };
 
-use super::{checker::reference::Reference, Configuration};
+use super::{
+    checker::reference::Reference, constant_resolver::ConstantResolver,
+    Configuration,
+};
 
 pub(crate) fn get_all_references(
     configuration: &Configuration,
     absolute_paths: &HashSet<PathBuf>,
 ) -> Vec<Reference> {
+    get_all_references_and_resolver(configuration, absolute_paths).0
+}
+
+// Like `get_all_references`, but also hands back the constant resolver so
+// callers can look at every definition, not just the referenced ones
+pub(crate) fn get_all_references_and_resolver(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+) -> (Vec<Reference>, Box<dyn ConstantResolver + Send + Sync>) {
+    let (references, constant_resolver, parse_errors) =
+        find_references(configuration, absolute_paths);
+    for parse_error in parse_errors {
+        eprintln!("Error: {}", parse_error);
+    }
+    (references, constant_resolver)
+}
+
+// Like `get_all_references`, but hands back the files that couldn't be
+// parsed instead of printing them
+pub(crate) fn get_all_references_and_parse_errors(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+) -> (Vec<Reference>, Vec<ParseError>) {
+    let (references, _, parse_errors) =
+        find_references(configuration, absolute_paths);
+    (references, parse_errors)
+}
+
+fn find_references(
+    configuration: &Configuration,
+    absolute_paths: &HashSet<PathBuf>,
+) -> (
+    Vec<Reference>,
+    Box<dyn ConstantResolver + Send + Sync>,
+    Vec<ParseError>,
+) {
     let cache = configuration.get_cache();
 
     debug!("Getting unresolved references (using cache if possible)");
 
-    let (constant_resolver, processed_files_to_check) = if configuration
-        .experimental_parser
-    {
-        // The experimental parser needs *all* processed files to get definitions
-        let all_processed_files: Vec<ProcessedFile> = process_files_with_cache(
-            &configuration.included_files,
-            cache,
-            configuration,
-        );
+    let (
+        constant_resolver,
+        processed_files_to_check,
+        other_processed_files,
+        parse_errors,
+    ) = if needs_all_definitions(configuration) {
+        // The experimental parser needs *all* processed files to get definitions,
+        // and following aliases needs them to find every alias
+        let (all_processed_files, parse_errors) =
+            process_files_with_cache_and_errors(
+                &configuration.included_files,
+                cache,
+                configuration,
+            );
 
-        let constant_resolver = get_experimental_constant_resolver(
-            &configuration.absolute_root,
-            &all_processed_files,
-            &configuration.ignored_definitions,
-        );
+        let constant_resolver =
+            get_constant_resolver(configuration, &all_processed_files);
 
-        let processed_files_to_check = all_processed_files
-            .into_iter()
-            .filter(|processed_file| {
+        let (processed_files_to_check, other_processed_files) =
+            all_processed_files.into_iter().partition(|processed_file| {
                 absolute_paths.contains(&processed_file.absolute_path)
-            })
-            .collect();
+            });
 
-        (constant_resolver, processed_files_to_check)
+        (
+            constant_resolver,
+            processed_files_to_check,
+            other_processed_files,
+            parse_errors,
+        )
     } else {
-        let processed_files: Vec<ProcessedFile> =
-            process_files_with_cache(absolute_paths, cache, configuration);
+        let (processed_files, parse_errors) =
+            process_files_with_cache_and_errors(
+                absolute_paths,
+                cache,
+                configuration,
+            );
 
         // The zeitwerk constant resolver doesn't look at processed files to get definitions
-        let constant_resolver = get_zeitwerk_constant_resolver(
-            &configuration.pack_set,
-            &configuration.absolute_root,
-            &configuration.cache_directory,
-            !configuration.cache_enabled,
-        );
+        let constant_resolver = get_constant_resolver(configuration, &[]);
+
+        (constant_resolver, processed_files, vec![], parse_errors)
+    };
 
-        (constant_resolver, processed_files)
+    let references = resolve_references_of(
+        configuration,
+        constant_resolver.as_ref(),
+        &processed_files_to_check,
+        &other_processed_files,
+    );
+
+    (references, constant_resolver, parse_errors)
+}
+
+// Whether the constant resolver is built from the definitions in every
+// included file, rather than from file names
+pub(crate) fn needs_all_definitions(configuration: &Configuration) -> bool {
+    configuration.experimental_parser || configuration.follow_constant_aliases
+}
+
+// The resolver for the configured parser. `all_processed_files` is only
+// looked at when `needs_all_definitions` is true.
+pub(crate) fn get_constant_resolver(
+    configuration: &Configuration,
+    all_processed_files: &[ProcessedFile],
+) -> Box<dyn ConstantResolver + Send + Sync> {
+    let constant_resolver = if configuration.experimental_parser {
+        get_experimental_constant_resolver(
+            &configuration.absolute_root,
+            all_processed_files,
+            &configuration.ignored_definitions,
+        )
+    } else {
+        get_zeitwerk_constant_resolver(configuration)
     };
 
+    if configuration.follow_constant_aliases {
+        AliasFollowingConstantResolver::create(
+            constant_resolver,
+            all_processed_files,
+            &configuration.pack_set,
+        )
+    } else {
+        constant_resolver
+    }
+}
+
+pub(crate) fn resolve_references(
+    configuration: &Configuration,
+    constant_resolver: &(dyn ConstantResolver + Send + Sync),
+    processed_files: &[ProcessedFile],
+) -> Vec<Reference> {
+    resolve_references_of(
+        configuration,
+        constant_resolver,
+        processed_files,
+        &[],
+    )
+}
+
+// Like `resolve_references`, with the files that were processed but aren't
+// checked, so they aren't processed again for their sigils
+fn resolve_references_of(
+    configuration: &Configuration,
+    constant_resolver: &(dyn ConstantResolver + Send + Sync),
+    processed_files: &[ProcessedFile],
+    other_processed_files: &[ProcessedFile],
+) -> Vec<Reference> {
     debug!("Turning unresolved references into fully qualified references");
-    let references: Vec<Reference> = processed_files_to_check
+    let mut references: Vec<Reference> = processed_files
         .par_iter()
         .flat_map(|processed_file| {
             let references: Vec<Reference> = processed_file
@@ -67,18 +179,78 @@ pub(crate) fn get_all_references(
                 .flat_map(|unresolved_ref| {
                     Reference::from_unresolved_reference(
                         configuration,
-                        constant_resolver.as_ref(),
+                        constant_resolver,
                         unresolved_ref,
                         &processed_file.absolute_path,
                     )
                 })
+                .map(|reference| Reference {
+                    strict_referencing_file: processed_file.sigils.packs_strict,
+                    ..reference
+                })
                 .collect::<Vec<Reference>>();
 
             references
         })
         .collect();
 
+    let known_files: Vec<&ProcessedFile> = processed_files
+        .iter()
+        .chain(other_processed_files)
+        .collect();
+    mark_public_defining_files(configuration, &known_files, &mut references);
+
     debug!("Finished turning unresolved references into fully qualified references");
 
     references
 }
+
+// Marks the references to constants defined in files with
+// `# pack_public: true`. The sigils of a file are found when it's parsed, so
+// the defining files that aren't among `processed_files`, e.g. when only
+// some files are checked, are processed too, from the cache where they can.
+pub(crate) fn mark_public_defining_files<'a>(
+    configuration: &Configuration,
+    processed_files: &[&ProcessedFile],
+    references: impl IntoIterator<Item = &'a mut Reference>,
+) {
+    let mut references: Vec<&mut Reference> = references.into_iter().collect();
+    let processed_paths: HashSet<&Path> = processed_files
+        .iter()
+        .map(|processed_file| processed_file.absolute_path.as_path())
+        .collect();
+    let unprocessed_paths: HashSet<PathBuf> = references
+        .iter()
+        .filter_map(|reference| reference.relative_defining_file.as_ref())
+        .map(|file| configuration.absolute_root.join(file))
+        .filter(|path| {
+            !processed_paths.contains(path.as_path()) && path.is_file()
+        })
+        .collect();
+    let (unprocessed_files, _) = if unprocessed_paths.is_empty() {
+        (vec![], vec![])
+    } else {
+        process_files_with_cache_and_errors(
+            &unprocessed_paths,
+            configuration.get_cache(),
+            configuration,
+        )
+    };
+
+    let public_files: HashSet<&Path> = processed_files
+        .iter()
+        .copied()
+        .chain(&unprocessed_files)
+        .filter(|processed_file| processed_file.sigils.pack_public)
+        .map(|processed_file| processed_file.absolute_path.as_path())
+        .collect();
+    for reference in references.iter_mut() {
+        reference.public_defining_file = reference
+            .relative_defining_file
+            .as_ref()
+            .is_some_and(|file| {
+                public_files
+                    .contains(configuration.absolute_root.join(file).as_path())
+            });
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::file_utils::{
+    to_slash_path, user_inputted_paths_to_absolute_filepaths,
+};
+use super::reference_extractor::get_all_references_and_resolver;
+use super::Configuration;
+
+const INDEX_FILE_NAME: &str = "reference_index.json";
+
+// Which constants each file references and defines, as of the last run, so
+// that a change to a file only re-checks the packs that use its constants
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ReferenceIndex {
+    references_by_file: BTreeMap<String, BTreeSet<String>>,
+    definitions_by_file: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ReferenceIndex {
+    // Replaces what the index knows about `files` with what they reference
+    // and define now. Files that no longer exist are dropped.
+    fn update(&mut self, configuration: &Configuration, files: &[String]) {
+        for file in files {
+            self.references_by_file.remove(file);
+            self.definitions_by_file.remove(file);
+        }
+
+        let absolute_paths: HashSet<PathBuf> = files
+            .iter()
+            .map(|file| configuration.absolute_root.join(file))
+            .filter(|path| configuration.included_files.contains(path))
+            .collect();
+        let (references, constant_resolver) =
+            get_all_references_and_resolver(configuration, &absolute_paths);
+
+        for reference in references {
+            if reference.defining_pack_name.is_some() {
+                self.references_by_file
+                    .entry(reference.relative_referencing_file)
+                    .or_default()
+                    .insert(reference.constant_name);
+            }
+        }
+
+        let definitions = constant_resolver
+            .fully_qualified_constant_name_to_constant_definition_map();
+        for definition in definitions.values().flatten() {
+            if absolute_paths.contains(&definition.absolute_path_of_definition)
+            {
+                self.definitions_by_file
+                    .entry(relative_path(
+                        configuration,
+                        &definition.absolute_path_of_definition,
+                    ))
+                    .or_default()
+                    .insert(definition.fully_qualified_name.clone());
+            }
+        }
+    }
+
+    // Files that reference any of the constants defined in `files`
+    fn referencing_files(&self, files: &[String]) -> BTreeSet<&String> {
+        let constants: BTreeSet<&String> = files
+            .iter()
+            .filter_map(|file| self.definitions_by_file.get(file))
+            .flatten()
+            .collect();
+
+        self.references_by_file
+            .iter()
+            .filter(|(_, referenced_constants)| {
+                referenced_constants
+                    .iter()
+                    .any(|constant| constants.contains(constant))
+            })
+            .map(|(file, _)| file)
+            .collect()
+    }
+}
+
+// The changed files, plus every file in the packs that reference a constant
+// the changed files define (or defined before they changed). Without files,
+// every file is checked and the index is rebuilt.
+pub(crate) fn files_affected_by(
+    configuration: &Configuration,
+    files: Vec<String>,
+) -> Vec<String> {
+    let affected_packs = packs_affected_by(configuration, &files);
+    if files.is_empty() {
+        return files;
+    }
+
+    let mut files_to_check: BTreeSet<String> = files.into_iter().collect();
+    for pack_name in &affected_packs {
+        files_to_check.extend(
+            configuration
+                .included_files_in_pack(pack_name)
+                .iter()
+                .map(|path| relative_path(configuration, path)),
+        );
+    }
+    files_to_check.into_iter().collect()
+}
+
+// The packs with files that reference a constant the changed files define
+// (or defined before they changed). The index is updated with the changed
+// files, or rebuilt if there are none or there isn't one yet.
+pub(crate) fn packs_affected_by(
+    configuration: &Configuration,
+    files: &[String],
+) -> BTreeSet<String> {
+    let index_path = configuration.cache_directory.join(INDEX_FILE_NAME);
+    let mut index = match read(&index_path) {
+        Some(index) if !files.is_empty() => index,
+        _ => {
+            debug!("Building the reference index");
+            let mut index = ReferenceIndex::default();
+            let all_files: Vec<String> = configuration
+                .included_files
+                .iter()
+                .map(|path| relative_path(configuration, path))
+                .collect();
+            index.update(configuration, &all_files);
+            index
+        }
+    };
+
+    let changed_files: Vec<String> = user_inputted_paths_to_absolute_filepaths(
+        &configuration.absolute_root,
+        files.to_vec(),
+    )
+    .iter()
+    .map(|path| relative_path(configuration, path))
+    .collect();
+
+    // Constants the files defined before they changed, so references to a
+    // constant that was moved or deleted are re-checked too
+    let mut affected_files: BTreeSet<String> = index
+        .referencing_files(&changed_files)
+        .into_iter()
+        .cloned()
+        .collect();
+    index.update(configuration, &changed_files);
+    affected_files
+        .extend(index.referencing_files(&changed_files).into_iter().cloned());
+
+    if configuration.writes_cache() {
+        write(&index_path, &index);
+    }
+
+    let affected_packs: BTreeSet<String> = affected_files
+        .iter()
+        .filter_map(|file| {
+            configuration
+                .pack_set
+                .for_file(&configuration.absolute_root.join(file))
+        })
+        .map(|pack| pack.name.clone())
+        .collect();
+    debug!("Packs affected by the changed files: {:?}", affected_packs);
+    affected_packs
+}
+
+fn relative_path(configuration: &Configuration, path: &Path) -> String {
+    to_slash_path(
+        path.strip_prefix(&configuration.absolute_root)
+            .unwrap_or(path),
+    )
+}
+
+fn read(path: &Path) -> Option<ReferenceIndex> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write(path: &Path, index: &ReferenceIndex) {
+    let result =
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                fs::write(
+                    path,
+                    serde_json::to_string(index).unwrap_or_default(),
+                )
+            });
+    if let Err(e) = result {
+        debug!("Could not write the reference index: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(items: &[&str]) -> BTreeSet<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_referencing_files() {
+        let index = ReferenceIndex {
+            references_by_file: BTreeMap::from([
+                (String::from("packs/foo/foo.rb"), set(&["::Bar"])),
+                (String::from("packs/baz/baz.rb"), set(&["::Qux"])),
+            ]),
+            definitions_by_file: BTreeMap::from([(
+                String::from("packs/bar/bar.rb"),
+                set(&["::Bar"]),
+            )]),
+        };
+
+        assert_eq!(
+            BTreeSet::from([&String::from("packs/foo/foo.rb")]),
+            index.referencing_files(&[String::from("packs/bar/bar.rb")])
+        );
+        assert!(index
+            .referencing_files(&[String::from("packs/foo/foo.rb")])
+            .is_empty());
+    }
+}
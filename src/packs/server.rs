@@ -0,0 +1,374 @@
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+
+use super::checker;
+use super::file_utils::to_slash_path;
+use super::list_packs;
+use super::pack::Pack;
+use super::package_todo::package_todo_path;
+use super::reference_index;
+use super::Configuration;
+
+// Bodies are only lists of files, so anything bigger is turned down before
+// it's read
+const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+// How long a connection may take to send its request or read the response
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+// A request read off a connection, without its headers
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+// The body of `POST /check`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckRequest {
+    files: Vec<String>,
+}
+
+// The configuration the requests are answered with, and when each included
+// file, package.yml and package_todo.yml was last modified as of the last
+// flush of the todo files
+struct Server {
+    configuration: Configuration,
+    modified_times: HashMap<PathBuf, SystemTime>,
+}
+
+// Serves the checks over HTTP on `address` until the process is stopped,
+// with the configuration loaded once, so each request only finds the
+// references of the files it checks, from the cache where it can:
+//
+// - `GET /violations` checks every file
+// - `GET /packs` lists the packs, as `list-packs --json` does
+// - `POST /check` with `{"files": [...]}` checks those files
+// - `POST /update` records the violations of the packs with files changed
+//   since the server started or last updated, like `update` does, and of
+//   the packs referencing the constants those files define
+//
+// Violations are reported as `check --format json` prints them. Requests
+// are handled one at a time.
+pub(crate) fn serve(
+    configuration: &Configuration,
+    address: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut server = Server {
+        configuration: configuration.clone(),
+        modified_times: modified_times(configuration),
+    };
+    let listener = TcpListener::bind(address)?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    std::io::stdout().flush()?;
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept a connection: {}", err);
+                continue;
+            }
+        };
+        if let Err(err) = server.handle_connection(stream) {
+            eprintln!("Failed to handle a request: {}", err);
+        }
+    }
+    Ok(())
+}
+
+impl Server {
+    fn handle_connection(
+        &mut self,
+        mut stream: TcpStream,
+    ) -> Result<(), Box<dyn Error>> {
+        // Requests are handled one at a time, so a client that stalls
+        // mustn't hold up the others for long
+        stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+        let (status, body) = match read_request(&mut BufReader::new(&stream)) {
+            Ok(request) => self.respond(&request),
+            Err((status, err)) => (status, error_body(&err)),
+        };
+        stream.write_all(response(status, &body).as_bytes())?;
+        Ok(())
+    }
+
+    // The status and JSON body to answer the request with
+    fn respond(&mut self, request: &Request) -> (u16, String) {
+        let configuration = &self.configuration;
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/violations") => {
+                let result = checker::check(configuration, vec![]);
+                (200, checker::json_report(configuration, &result))
+            }
+            ("GET", "/packs") => match list_packs::to_json(configuration) {
+                Ok(json) => (200, format!("{}\n", json)),
+                Err(err) => (500, error_body(&err.to_string())),
+            },
+            ("POST", "/check") => {
+                match serde_json::from_str::<CheckRequest>(&request.body) {
+                    Ok(check_request) => {
+                        let result =
+                            checker::check(configuration, check_request.files);
+                        (200, checker::json_report(configuration, &result))
+                    }
+                    Err(err) => (
+                        400,
+                        error_body(&format!(
+                            "Expected a body like {{\"files\": [...]}}: {}",
+                            err
+                        )),
+                    ),
+                }
+            }
+            ("POST", "/update") => match self.update() {
+                Ok(pack_names) => (
+                    200,
+                    format!(
+                        "{}\n",
+                        serde_json::json!({ "updated_packs": pack_names })
+                    ),
+                ),
+                Err(err) => (500, error_body(&err.to_string())),
+            },
+            (_, "/violations" | "/packs" | "/check" | "/update") => (
+                405,
+                error_body(&format!(
+                    "{} is not allowed on {}",
+                    request.method, request.path
+                )),
+            ),
+            _ => (404, error_body(&format!("No route for {}", request.path))),
+        }
+    }
+
+    // Rewrites the todo files of the packs with files that were added,
+    // changed or removed since the last update, or whose package.yml or
+    // package_todo.yml changed, and of the packs referencing the constants
+    // the changed files define, going by the reference index. Returns the
+    // names of the packs. The packs, todo files and included files are
+    // reloaded from disk, so the requests after it see them as they are now.
+    fn update(&mut self) -> Result<BTreeSet<String>, Box<dyn Error>> {
+        let configuration = self.configuration.reload()?;
+        let current_modified_times = modified_times(&configuration);
+
+        let changed_files: BTreeSet<&PathBuf> = self
+            .modified_times
+            .keys()
+            .chain(current_modified_times.keys())
+            .filter(|file| {
+                self.modified_times.get(*file)
+                    != current_modified_times.get(*file)
+            })
+            .collect();
+        let mut pack_names: BTreeSet<String> = changed_files
+            .iter()
+            .filter_map(|file| {
+                // Removed files are only known to the previous configuration
+                pack_for(&configuration, file)
+                    .or_else(|| pack_for(&self.configuration, file))
+            })
+            .map(|pack| pack.name.clone())
+            .collect();
+
+        let changed_included_files: Vec<String> = changed_files
+            .iter()
+            .filter(|file| {
+                configuration.included_files.contains(**file)
+                    || self.configuration.included_files.contains(**file)
+            })
+            .map(|file| {
+                to_slash_path(
+                    file.strip_prefix(&configuration.absolute_root)
+                        .unwrap_or(file),
+                )
+            })
+            .collect();
+        if !changed_included_files.is_empty() {
+            pack_names.extend(reference_index::packs_affected_by(
+                &configuration,
+                &changed_included_files,
+            ));
+        }
+
+        if !pack_names.is_empty() {
+            checker::update_packs(&configuration, &pack_names);
+        }
+
+        // Taken after the todo files are written, so writing them doesn't
+        // count as a change next time
+        self.configuration = configuration.reload()?;
+        self.modified_times = modified_times(&self.configuration);
+        Ok(pack_names)
+    }
+}
+
+// The pack an included file belongs to, or whose package.yml or
+// package_todo.yml the file is
+fn pack_for<'a>(
+    configuration: &'a Configuration,
+    file: &Path,
+) -> Option<&'a Pack> {
+    configuration.pack_set.for_file(file).or_else(|| {
+        configuration
+            .pack_set
+            .packs
+            .iter()
+            .find(|pack| pack.yml == file || package_todo_path(pack) == file)
+    })
+}
+
+fn modified_times(
+    configuration: &Configuration,
+) -> HashMap<PathBuf, SystemTime> {
+    let pack_files = configuration
+        .pack_set
+        .packs
+        .iter()
+        .flat_map(|pack| [pack.yml.clone(), package_todo_path(pack)]);
+    configuration
+        .included_files
+        .iter()
+        .cloned()
+        .chain(pack_files)
+        .filter_map(|file| {
+            let modified_time =
+                std::fs::metadata(&file).ok()?.modified().ok()?;
+            Some((file, modified_time))
+        })
+        .collect()
+}
+
+// The request, or the status and message to turn it down with
+fn read_request(reader: &mut impl BufRead) -> Result<Request, (u16, String)> {
+    let bad_request = |err: String| (400, err);
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|err| bad_request(err.to_string()))?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request(format!(
+            "Malformed request line `{}`",
+            request_line.trim()
+        )));
+    };
+    let path = target.split('?').next().unwrap_or_default();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|err| bad_request(err.to_string()))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| {
+                    bad_request(format!(
+                        "Malformed Content-Length `{}`",
+                        value.trim()
+                    ))
+                })?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err((
+            413,
+            format!(
+                "The body is {} bytes, over the limit of {}",
+                content_length, MAX_BODY_SIZE
+            ),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|err| bad_request(err.to_string()))?;
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        body: String::from_utf8(body)
+            .map_err(|err| bad_request(err.to_string()))?,
+    })
+}
+
+fn error_body(message: &str) -> String {
+    format!("{}\n", serde_json::json!({ "error": message }))
+}
+
+fn response(status: u16, body: &str) -> String {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /check?verbose=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 21\r\n\r\n{\"files\": [\"foo.rb\"]}";
+
+        assert_eq!(
+            Ok(Request {
+                method: String::from("POST"),
+                path: String::from("/check"),
+                body: String::from("{\"files\": [\"foo.rb\"]}"),
+            }),
+            read_request(&mut raw.as_bytes())
+        );
+        assert_eq!(
+            Err((400, String::from("Malformed request line ``"))),
+            read_request(&mut "".as_bytes())
+        );
+        assert_eq!(
+            Err((
+                413,
+                String::from(
+                    "The body is 5000000 bytes, over the limit of 4194304"
+                )
+            )),
+            read_request(
+                &mut "POST /check HTTP/1.1\r\nContent-Length: 5000000\r\n\r\n"
+                    .as_bytes()
+            )
+        );
+    }
+
+    #[test]
+    fn test_response() {
+        assert_eq!(
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 31\r\nConnection: close\r\n\r\n{\"error\":\"No route for /nope\"}\n",
+            response(404, &error_body("No route for /nope"))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+
+use super::reference_extractor::get_all_references;
+use super::Configuration;
+use super::PackSet;
+
+// Prints the `dependencies:` a pack needs based on what it references,
+// calling out the ones that would create a dependency cycle.
+pub(crate) fn suggest_dependencies(
+    configuration: &Configuration,
+    pack_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    let pack = configuration.pack_set.for_pack(pack_name).map_err(|_| {
+        format!(
+            "`{}` not found. Try `packs list-packs` to debug.",
+            pack_name
+        )
+    })?;
+
+    let pack_files = configuration.included_files_in_pack(&pack.name);
+
+    let mut reference_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for reference in get_all_references(configuration, &pack_files) {
+        if let Some(defining_pack_name) = reference.defining_pack_name {
+            if defining_pack_name != pack.name {
+                *reference_counts.entry(defining_pack_name).or_default() += 1;
+            }
+        }
+    }
+
+    if reference_counts.is_empty() {
+        println!("`{}` does not reference any other packs", pack.name);
+        return Ok(());
+    }
+
+    println!("Suggested dependencies for `{}`:", pack.name);
+    println!("dependencies:");
+    for (dependency, count) in &reference_counts {
+        println!("- {} # {} reference(s)", dependency, count);
+    }
+
+    let cycles: Vec<Vec<String>> = reference_counts
+        .keys()
+        .filter_map(|dependency| {
+            dependency_path(&configuration.pack_set, dependency, &pack.name)
+        })
+        .collect();
+
+    if !cycles.is_empty() {
+        println!("\nThese dependencies would create a cycle:");
+        for cycle in cycles {
+            println!("- {} -> {}", pack.name, cycle.join(" -> "));
+        }
+    }
+
+    Ok(())
+}
+
+// The shortest chain of declared dependencies from one pack to another, if any
+fn dependency_path(
+    pack_set: &PackSet,
+    from: &str,
+    to: &str,
+) -> Option<Vec<String>> {
+    let mut previous: HashMap<String, String> = HashMap::new();
+    let mut visited: HashSet<String> = HashSet::from([from.to_owned()]);
+    let mut queue: VecDeque<String> = VecDeque::from([from.to_owned()]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            let mut path = vec![current];
+            while let Some(parent) = previous.get(path.last().unwrap()) {
+                path.push(parent.to_owned());
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let Ok(current_pack) = pack_set.for_pack(&current) else {
+            continue;
+        };
+        let mut dependencies: Vec<&String> =
+            current_pack.dependencies.iter().collect();
+        dependencies.sort();
+        for dependency in dependencies {
+            if visited.insert(dependency.to_owned()) {
+                previous.insert(dependency.to_owned(), current.to_owned());
+                queue.push_back(dependency.to_owned());
+            }
+        }
+    }
+
+    None
+}
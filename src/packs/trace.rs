@@ -0,0 +1,78 @@
+use std::error::Error;
+
+use super::reference_extractor::get_all_references;
+use super::Configuration;
+
+// Lists every reference from one pack to another, e.g. to plan how to
+// break a dependency between them.
+pub(crate) fn trace(
+    configuration: &Configuration,
+    from_pack_name: &str,
+    to_pack_name: &str,
+) -> Result<(), Box<dyn Error>> {
+    let from_pack = find_pack(configuration, from_pack_name)?;
+    let to_pack = find_pack(configuration, to_pack_name)?;
+
+    let pack_files = configuration.included_files_in_pack(from_pack);
+    let mut references: Vec<_> = get_all_references(configuration, &pack_files)
+        .into_iter()
+        .filter(|reference| {
+            reference.defining_pack_name.as_deref() == Some(to_pack)
+        })
+        .collect();
+
+    if references.is_empty() {
+        println!("No references from `{}` to `{}`", from_pack, to_pack);
+        return Ok(());
+    }
+
+    references.sort_by(|a, b| {
+        (
+            &a.relative_referencing_file,
+            a.source_location.line,
+            a.source_location.column,
+            &a.constant_name,
+        )
+            .cmp(&(
+                &b.relative_referencing_file,
+                b.source_location.line,
+                b.source_location.column,
+                &b.constant_name,
+            ))
+    });
+
+    for reference in &references {
+        println!(
+            "{}:{}:{} {}",
+            reference.relative_referencing_file,
+            reference.source_location.line,
+            reference.source_location.column,
+            reference.constant_name
+        );
+    }
+    println!(
+        "\n{} reference(s) from `{}` to `{}`",
+        references.len(),
+        from_pack,
+        to_pack
+    );
+
+    Ok(())
+}
+
+fn find_pack<'a>(
+    configuration: &'a Configuration,
+    pack_name: &str,
+) -> Result<&'a str, Box<dyn Error>> {
+    configuration
+        .pack_set
+        .for_pack(pack_name)
+        .map(|pack| pack.name.as_str())
+        .map_err(|_| {
+            format!(
+                "`{}` not found. Try `packs list-packs` to debug.",
+                pack_name
+            )
+            .into()
+        })
+}
@@ -1,19 +1,31 @@
 use jwalk::WalkDirGeneric;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use std::{
     collections::{HashMap, HashSet},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 use tracing::debug;
 
 use super::{
-    file_utils::build_glob_set, pack::Pack, raw_configuration::RawConfiguration,
+    file_provider::FileProvider, file_utils::build_glob_set, pack::Pack,
+    raw_configuration::RawConfiguration,
 };
 
 pub struct WalkDirectoryResult {
     pub included_files: HashSet<PathBuf>,
     pub included_packs: HashSet<Pack>,
     pub owning_package_yml_for_file: HashMap<PathBuf, PathBuf>,
+    pub timings: WalkTimings,
+}
+
+// How long each part of the walk took, printed with `--timing`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WalkTimings {
+    pub walk: Duration,
+    pub read_packs: Duration,
+    pub pack_count: usize,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -34,42 +46,46 @@ impl jwalk::ClientState for ProcessReadDirState {
 // which is faster than walking the directory multiple times.
 // Likely, we can organize this better by moving each piece of logic into its own function so this function
 // allows for a sort of "visitor pattern" for different things that need to walk the directory.
+//
+// Providers that list their files up front, like the objects of a commit,
+// aren't walked: their files are sorted into packs by the same patterns.
 pub(crate) fn walk_directory(
     absolute_root: PathBuf,
     raw: &RawConfiguration,
+    files: &dyn FileProvider,
 ) -> WalkDirectoryResult {
     debug!("Beginning directory walk");
 
+    let walk_start = Instant::now();
+    if let Some(listed_files) = files.files() {
+        let (included_files, package_ymls, owning_package_yml_for_file) =
+            sort_listed_files(listed_files, &absolute_root, raw);
+        return read_packs(
+            &absolute_root,
+            included_files,
+            package_ymls,
+            owning_package_yml_for_file,
+            walk_start.elapsed(),
+            files,
+        );
+    }
     let mut included_files: HashSet<PathBuf> = HashSet::new();
-    let mut included_packs: HashSet<Pack> = HashSet::new();
+    let mut package_ymls: Vec<PathBuf> = Vec::new();
     let mut owning_package_yml_for_file: HashMap<PathBuf, PathBuf> =
         HashMap::new();
+    // Symlinks are followed, so files under them can be walked twice
+    let mut symlinks: HashSet<PathBuf> = HashSet::new();
+    let mut paths_through_symlinks: Vec<PathBuf> = Vec::new();
 
-    // Create this vector outside of the closure to avoid reallocating it
-    let default_excluded_dirs = [
-        "node_modules/**/*",
-        "vendor/**/*",
-        "tmp/**/*",
-        ".git/**/*",
-        "public/**/*",
-        "bin/**/*",
-        "log/**/*",
-        "frontend/**/**",
-        "sorbet/**/*",
-    ];
-    let mut all_excluded_dirs: Vec<String> = Vec::new();
-    all_excluded_dirs
-        .extend(default_excluded_dirs.iter().map(|s| s.to_string()));
-
-    let excluded_globs = &raw.exclude;
-    all_excluded_dirs.extend(excluded_globs.to_owned());
-
+    let all_excluded_dirs = excluded_dirs(raw);
     let all_excluded_dirs_set = build_glob_set(&all_excluded_dirs);
     let excluded_dirs_ref = Arc::new(all_excluded_dirs_set);
 
     let absolute_root_ref = Arc::new(absolute_root.clone());
 
     let includes_set = build_glob_set(&raw.include);
+    let config_reference_files_set =
+        build_glob_set(&raw.config_reference_files);
     let excludes_set = build_glob_set(&raw.exclude);
     let package_paths_set = build_glob_set(&raw.package_paths);
 
@@ -84,7 +100,9 @@ pub(crate) fn walk_directory(
     // we'll save a lot of time by just skipping the entire directory.
     //
     // For more information, check out the docs: https://docs.rs/jwalk/0.8.1/jwalk/#extended-example
-    let current_package_yml = PathBuf::from("package.yml");
+    // Files outside of other packs belong to the root pack, even if it is
+    // an implicit one without a package.yml
+    let current_package_yml = absolute_root.join("package.yml");
 
     let walk_dir = WalkDirGeneric::<ProcessReadDirState>::new(&absolute_root)
         .follow_links(true)
@@ -98,12 +116,16 @@ pub(crate) fn walk_directory(
                 // (with an increase to the reference count).
                 let cloned_excluded_dirs = excluded_dirs_ref.clone();
                 let cloned_absolute_root = absolute_root_ref.clone();
-                let package_yml = absolute_dirname.join("package.yml");
-
                 // Even if the parent has set this on children, the existence of a new
-                // package.yml file should override it.
-                if package_yml.exists() {
-                    read_dir_state.current_package_yml = package_yml;
+                // package.yml file should override it. The children were
+                // already read, so this needs no extra syscall per directory.
+                let has_package_yml = children.iter().flatten().any(|child| {
+                    child.file_name == "package.yml"
+                        && !child.file_type.is_dir()
+                });
+                if has_package_yml {
+                    read_dir_state.current_package_yml =
+                        absolute_dirname.join("package.yml");
                 }
 
                 children.iter_mut().for_each(|child_dir_entry_result| {
@@ -138,6 +160,10 @@ pub(crate) fn walk_directory(
 
         let unwrapped_entry = entry.unwrap();
 
+        if unwrapped_entry.path_is_symlink() {
+            symlinks.insert(unwrapped_entry.path());
+        }
+
         // Note that we could also get the dir from absolute_path.is_dir()
         // However, this data appears to be cached on the FileType struct, so we'll use that instead,
         // which is much faster!
@@ -146,6 +172,13 @@ pub(crate) fn walk_directory(
         }
 
         let absolute_path = unwrapped_entry.path();
+        if !symlinks.is_empty()
+            && absolute_path
+                .ancestors()
+                .any(|ancestor| symlinks.contains(ancestor))
+        {
+            paths_through_symlinks.push(absolute_path.clone());
+        }
 
         let relative_path = absolute_path
             .strip_prefix(&absolute_root)
@@ -163,12 +196,13 @@ pub(crate) fn walk_directory(
             // We can remove this once we fix the bug.
             && (package_paths_set.is_match(relative_path.parent().unwrap()) || absolute_path.parent().unwrap() == absolute_root)
         {
-            let pack = Pack::from_path(&absolute_path, &absolute_root);
-            included_packs.insert(pack);
+            package_ymls.push(absolute_path.clone());
         }
 
         // This could be one line, but I'm keeping it separate for debugging purposes
-        if includes_set.is_match(&relative_path) {
+        if includes_set.is_match(&relative_path)
+            || config_reference_files_set.is_match(&relative_path)
+        {
             if !excludes_set.is_match(&relative_path) {
                 included_files.insert(absolute_path.clone());
                 owning_package_yml_for_file
@@ -185,12 +219,185 @@ pub(crate) fn walk_directory(
         }
     }
 
+    remove_paths_to_walked_files(
+        paths_through_symlinks,
+        &mut included_files,
+        &mut owning_package_yml_for_file,
+        &mut package_ymls,
+    );
+
     debug!("Finished directory walk");
+    read_packs(
+        &absolute_root,
+        included_files,
+        package_ymls,
+        owning_package_yml_for_file,
+        walk_start.elapsed(),
+        files,
+    )
+}
+
+// The directories that aren't walked at all: the ones skipped by default,
+// and the `exclude` patterns
+fn excluded_dirs(raw: &RawConfiguration) -> Vec<String> {
+    // Create this vector outside of the closure to avoid reallocating it
+    let default_excluded_dirs = [
+        "node_modules/**/*",
+        "vendor/**/*",
+        "tmp/**/*",
+        ".git/**/*",
+        "public/**/*",
+        "bin/**/*",
+        "log/**/*",
+        "frontend/**/**",
+        "sorbet/**/*",
+    ];
+    // A directory skipped by default is still walked when an `include`
+    // pattern points into it, like `sorbet/rbi/**/*.rbi`
+    let is_included_explicitly = |excluded_dir: &str| {
+        let dir = excluded_dir.split('/').next().unwrap_or(excluded_dir);
+        raw.include
+            .iter()
+            .any(|pattern| pattern.starts_with(&format!("{}/", dir)))
+    };
+    let mut all_excluded_dirs: Vec<String> = Vec::new();
+    all_excluded_dirs.extend(
+        default_excluded_dirs
+            .iter()
+            .filter(|excluded_dir| !is_included_explicitly(excluded_dir))
+            .map(|s| s.to_string()),
+    );
+
+    let excluded_globs = &raw.exclude;
+    all_excluded_dirs.extend(excluded_globs.to_owned());
+
+    all_excluded_dirs
+}
+
+// The included files, the package.yml files of packs, and the package.yml
+// owning each included file, among files listed up front, as the walk finds
+// them
+fn sort_listed_files(
+    listed_files: &[PathBuf],
+    absolute_root: &Path,
+    raw: &RawConfiguration,
+) -> (HashSet<PathBuf>, Vec<PathBuf>, HashMap<PathBuf, PathBuf>) {
+    let excluded_dirs_set = build_glob_set(&excluded_dirs(raw));
+    let includes_set = build_glob_set(&raw.include);
+    let config_reference_files_set =
+        build_glob_set(&raw.config_reference_files);
+    let excludes_set = build_glob_set(&raw.exclude);
+    let package_paths_set = build_glob_set(&raw.package_paths);
+
+    // The walk doesn't go into excluded directories
+    let walked_files: Vec<(&PathBuf, &Path)> = listed_files
+        .iter()
+        .filter_map(|absolute_path| {
+            let relative_path =
+                absolute_path.strip_prefix(absolute_root).ok()?;
+            let in_excluded_dir = relative_path
+                .ancestors()
+                .skip(1)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .any(|dir| excluded_dirs_set.is_match(dir));
+            (!in_excluded_dir).then_some((absolute_path, relative_path))
+        })
+        .collect();
+
+    // Any directory with a package.yml owns the files below it, while only
+    // the ones matching `package_paths` are packs
+    let package_yml_dirs: HashSet<&Path> = walked_files
+        .iter()
+        .filter(|(_, relative_path)| relative_path.ends_with("package.yml"))
+        .filter_map(|(_, relative_path)| relative_path.parent())
+        .collect();
+    let package_ymls: Vec<PathBuf> = package_yml_dirs
+        .iter()
+        .filter(|dir| {
+            dir.as_os_str().is_empty() || package_paths_set.is_match(dir)
+        })
+        .map(|dir| absolute_root.join(dir).join("package.yml"))
+        .collect();
+
+    let mut included_files = HashSet::new();
+    let mut owning_package_yml_for_file = HashMap::new();
+    for (absolute_path, relative_path) in walked_files {
+        if !(includes_set.is_match(relative_path)
+            || config_reference_files_set.is_match(relative_path))
+            || excludes_set.is_match(relative_path)
+        {
+            continue;
+        }
+        let owning_dir = relative_path
+            .ancestors()
+            .skip(1)
+            .find(|dir| package_yml_dirs.contains(dir))
+            .unwrap_or(Path::new(""));
+        included_files.insert(absolute_path.clone());
+        owning_package_yml_for_file.insert(
+            absolute_path.clone(),
+            absolute_root.join(owning_dir).join("package.yml"),
+        );
+    }
+
+    (included_files, package_ymls, owning_package_yml_for_file)
+}
+
+// Reading package.yml files is most of the work on repos with thousands
+// of packs, so they're deserialized in parallel after the walk
+fn read_packs(
+    absolute_root: &Path,
+    included_files: HashSet<PathBuf>,
+    package_ymls: Vec<PathBuf>,
+    owning_package_yml_for_file: HashMap<PathBuf, PathBuf>,
+    walk: Duration,
+    files: &dyn FileProvider,
+) -> WalkDirectoryResult {
+    let read_packs_start = Instant::now();
+    let pack_count = package_ymls.len();
+    let included_packs: HashSet<Pack> = package_ymls
+        .into_par_iter()
+        .map(|package_yml| Pack::from_path(&package_yml, absolute_root, files))
+        .collect();
+    debug!("Finished reading package.yml files");
 
     WalkDirectoryResult {
         included_files,
         included_packs,
         owning_package_yml_for_file,
+        timings: WalkTimings {
+            walk,
+            read_packs: read_packs_start.elapsed(),
+            pack_count,
+        },
+    }
+}
+
+// A file reached through a symlink is only kept if the file it links to
+// isn't walked already, directly or through another symlink, so the same
+// file isn't checked twice and its violations aren't reported twice.
+// Symlinks to files outside of the root, e.g. a pack shared between apps,
+// are kept.
+fn remove_paths_to_walked_files(
+    mut paths_through_symlinks: Vec<PathBuf>,
+    included_files: &mut HashSet<PathBuf>,
+    owning_package_yml_for_file: &mut HashMap<PathBuf, PathBuf>,
+    package_ymls: &mut Vec<PathBuf>,
+) {
+    // The first of several paths to the same file is kept
+    paths_through_symlinks.sort();
+    let mut targets: HashSet<PathBuf> = HashSet::new();
+    for path in paths_through_symlinks {
+        let Ok(target) = dunce::canonicalize(&path) else {
+            continue;
+        };
+        let walked_directly =
+            included_files.contains(&target) || package_ymls.contains(&target);
+        if walked_directly || !targets.insert(target) {
+            included_files.remove(&path);
+            owning_package_yml_for_file.remove(&path);
+            package_ymls.retain(|package_yml| package_yml != &path);
+        }
     }
 }
 
@@ -199,6 +406,7 @@ mod tests {
     use std::{error::Error, path::PathBuf};
 
     use crate::packs::{
+        file_provider::WorkingTree,
         raw_configuration::RawConfiguration,
         walk_directory::{walk_directory, WalkDirectoryResult},
     };
@@ -215,7 +423,7 @@ mod tests {
         };
 
         let WalkDirectoryResult { included_files, .. } =
-            walk_directory(absolute_path.clone(), &raw_config);
+            walk_directory(absolute_path.clone(), &raw_config, &WorkingTree);
 
         let node_module_file =
             absolute_path.join("node_modules/subfolder/file.rb");
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::git;
+use super::process_files_with_cache;
+use super::Configuration;
+
+// How many files are parsed between two updates of the progress bar
+const FILES_PER_STEP: usize = 200;
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+// Parses files into the cache so the next `check` doesn't have to. By
+// default, only the files changed since `since` are parsed, by a copy of
+// this process running in the background, so a git hook calling it returns
+// right away and prints nothing. With `all`, every included file is parsed
+// in the foreground, with a progress bar.
+pub(crate) fn warm_cache(
+    configuration: &Configuration,
+    all: bool,
+    since: &str,
+    foreground: bool,
+) -> Result<(), Box<dyn Error>> {
+    if !configuration.cache_enabled {
+        return Err(
+            "The cache is off, either with `cache: false` in packwerk.yml or `--no-cache`, so there is nothing to warm".into(),
+        );
+    }
+    if configuration.cache_read_only {
+        return Err(
+            "`--no-cache-write` keeps the cache from being warmed".into()
+        );
+    }
+
+    if all {
+        return warm_all(configuration);
+    }
+    if !foreground {
+        Command::new(std::env::current_exe()?)
+            .args(std::env::args_os().skip(1))
+            .arg("--foreground")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        return Ok(());
+    }
+
+    let changed_files: HashSet<PathBuf> =
+        git::changed_files(&configuration.absolute_root, since)?
+            .into_iter()
+            .map(|file| configuration.absolute_root.join(file))
+            .collect();
+    let files: HashSet<PathBuf> = configuration
+        .included_files
+        .intersection(&changed_files)
+        .cloned()
+        .collect();
+    process_files_with_cache(&files, configuration.get_cache(), configuration);
+    Ok(())
+}
+
+fn warm_all(configuration: &Configuration) -> Result<(), Box<dyn Error>> {
+    let mut files: Vec<&PathBuf> =
+        configuration.included_files.iter().collect();
+    files.sort();
+    let show_progress = std::io::stderr().is_terminal();
+
+    let mut parsed = 0;
+    for step in files.chunks(FILES_PER_STEP) {
+        let step: HashSet<PathBuf> = step.iter().copied().cloned().collect();
+        process_files_with_cache(
+            &step,
+            configuration.get_cache(),
+            configuration,
+        );
+        parsed += step.len();
+        if show_progress {
+            eprint!("\r{}", progress_bar(parsed, files.len()));
+            std::io::stderr().flush()?;
+        }
+    }
+    if show_progress {
+        eprintln!();
+    }
+
+    println!("Warmed the cache for {} file(s)", files.len());
+    Ok(())
+}
+
+fn progress_bar(done: usize, total: usize) -> String {
+    let filled = (done * PROGRESS_BAR_WIDTH).checked_div(total).unwrap_or(0);
+    format!(
+        "[{}{}] {}/{} files",
+        "#".repeat(filled),
+        "-".repeat(PROGRESS_BAR_WIDTH - filled),
+        done,
+        total
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(
+            "[###############---------------] 50/100 files",
+            progress_bar(50, 100)
+        );
+        assert_eq!(
+            "[------------------------------] 0/0 files",
+            progress_bar(0, 0)
+        );
+    }
+}
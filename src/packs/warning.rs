@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+// Something `check` reports apart from violations, like a deprecated option
+// that still works but is going away. Warnings don't fail the check unless
+// `--deny-warnings` is passed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    // Behavior that will be removed in a future version
+    Deprecation,
+    // Something in packwerk.yml that packs ignores, like an unknown key
+    Configuration,
+}
+
+impl Warning {
+    pub(crate) fn deprecation(message: impl Into<String>) -> Warning {
+        Warning {
+            kind: WarningKind::Deprecation,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn configuration(message: impl Into<String>) -> Warning {
+        Warning {
+            kind: WarningKind::Configuration,
+            message: message.into(),
+        }
+    }
+
+    // The line printed for the warning in the text output
+    pub(crate) fn text(&self) -> String {
+        match self.kind {
+            WarningKind::Deprecation => {
+                format!("Deprecation warning: {}", self.message)
+            }
+            WarningKind::Configuration => {
+                format!("Configuration warning: {}", self.message)
+            }
+        }
+    }
+}
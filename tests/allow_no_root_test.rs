@@ -0,0 +1,45 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_missing_root_pack_is_an_error() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_without_root_pack")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No root pack found"))
+        .stderr(predicate::str::contains("Run `pks init` to create it"))
+        .stderr(predicate::str::contains("panicked").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_allow_no_root() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_without_root_pack")
+        .arg("--allow-no-root")
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/foo/package.yml"));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_without_root_pack")
+        .arg("--allow-no-root")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
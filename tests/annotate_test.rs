@@ -0,0 +1,115 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+
+const FOO: &str = "\
+module Foo
+  def calls_bar
+    ::Bar
+  end
+end
+";
+
+// `annotate` edits files, so it runs on an app of its own
+fn set_up_app(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let app = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo/app/services"))?;
+    fs::create_dir_all(app.join("packs/bar/app/services"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: true\n",
+    )?;
+    fs::write(
+        app.join("packs/bar/package.yml"),
+        "metadata:\n  docs_url: https://example.com/bar\n",
+    )?;
+    fs::write(app.join("packs/foo/app/services/foo.rb"), FOO)?;
+    fs::write(
+        app.join("packs/bar/app/services/bar.rb"),
+        "class Bar\nend\n",
+    )?;
+    Ok(app)
+}
+
+fn annotate(app: &Path) -> Result<String, Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(app)
+        .arg("annotate")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    Ok(String::from_utf8(output)?)
+}
+
+#[test]
+fn test_annotate() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_annotate_test")?;
+
+    let output = annotate(&app)?;
+
+    assert!(output.contains("Annotated 1 violation(s) in 1 file(s)"));
+    assert_eq!(
+        "\
+module Foo
+  def calls_bar
+    # TODO(packs): dependency violation on ::Bar — see https://example.com/bar
+    ::Bar
+  end
+end
+",
+        fs::read_to_string(app.join("packs/foo/app/services/foo.rb"))?
+    );
+
+    // Running it again replaces the annotations rather than adding more
+    annotate(&app)?;
+    assert_eq!(
+        1,
+        fs::read_to_string(app.join("packs/foo/app/services/foo.rb"))?
+            .matches("# TODO(packs):")
+            .count()
+    );
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_annotate_remove() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_annotate_remove_test")?;
+    fs::write(
+        app.join("packs/foo/app/services/foo.rb"),
+        FOO.replace(
+            "    ::Bar",
+            "    # TODO(packs): dependency violation on ::Bar\n    ::Bar",
+        ),
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("annotate")
+        .arg("--remove")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 annotation(s)"));
+
+    assert_eq!(
+        FOO,
+        fs::read_to_string(app.join("packs/foo/app/services/foo.rb"))?
+    );
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
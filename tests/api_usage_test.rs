@@ -0,0 +1,36 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_api_usage() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_api_usage")
+        .arg("api-usage")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/billing\n  ::Billing::Invoice\n    .create!: 2 call(s) from packs/checkout\n    .find: 1 call(s) from packs/checkout\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_api_usage_for_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_api_usage")
+        .arg("api-usage")
+        .arg("packs/unknown")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`packs/unknown` not found"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+// `::Foo::Bar` is only defined in vendor/, which is excluded, but
+// packs/foo declares it with `autoload`
+#[test]
+fn test_check_resolves_autoloaded_constants() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_autoload")
+        .arg("--experimental-parser")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("app/services/user.rb:3:4\nDependency violation: `::Foo::Bar` belongs to `packs/foo`, but `./package.yml` does not specify a dependency on `packs/foo`."));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_list_definitions_of_autoloaded_constants() -> Result<(), Box<dyn Error>>
+{
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_autoload")
+        .arg("--experimental-parser")
+        .arg("list-definitions")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"::Foo::Bar\" is autoloaded at \"packs/foo/app/services/foo.rb\"",
+        ))
+        .stdout(predicate::str::contains(
+            "\"::Foo\" is defined at \"packs/foo/app/services/foo.rb\"",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_file_placement() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_misplaced_files")
+        .arg("check-file-placement")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/bar/app/services/bar.rb should define `::Bar`, which its path implies, but doesn't\n\
+             packs/bar/app/services/bar.rb defines `::Baz`, which is not `::Bar` or nested in it\n\
+             packs/foo/app/services/foo/widget.rb defines `::Bar::Gadget`, which is not `::Foo::Widget` or nested in it\n",
+        ))
+        .stdout(predicate::str::contains("services/foo.rb").not())
+        .stderr(predicate::str::contains(
+            "2 file(s) don't define the constant their path implies",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_file_placement_without_misplaced_files(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_inflections")
+        .arg("check-file-placement")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Every autoloaded file defines the constant its path implies",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_markdown() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .failure()
+        .stdout(predicate::str::starts_with("## packs check\n"))
+        .stdout(predicate::str::contains("| New violations | 2 |"))
+        .stdout(predicate::str::contains(
+            "<summary><code>packs/foo</code>: 2 violation(s)</summary>",
+        ))
+        .stdout(predicate::str::contains(
+            "| `::Bar` | `packs/bar` | `packs/foo/app/services/foo.rb` |",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_markdown_without_new_violations() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .arg("--format")
+        .arg("markdown")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## packs check\n\nNo violations detected!\n",
+        ))
+        .stdout(predicate::str::contains("| New violations | 0 |"))
+        .stdout(predicate::str::contains("<details>").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use std::error::Error;
+
+mod common;
+
+fn ndjson_records(
+    project_root: &str,
+) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(project_root)
+        .arg("check")
+        .arg("--format")
+        .arg("ndjson")
+        .output()?;
+    String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[test]
+fn test_check_ndjson() -> Result<(), Box<dyn Error>> {
+    let records = ndjson_records("tests/fixtures/simple_app")?;
+
+    let (summary, violations) = records.split_last().unwrap();
+    assert_eq!(2, violations.len());
+    for violation in violations {
+        assert_eq!("violation", violation["type"]);
+        assert_eq!("packs/foo", violation["referencing_pack_name"]);
+        assert_eq!("::Bar", violation["constant_name"]);
+    }
+    assert_eq!("summary", summary["type"]);
+    assert_eq!(2, summary["violations_count"]);
+    assert_eq!(0, summary["recorded_violations_count"]);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_ndjson_without_new_violations() -> Result<(), Box<dyn Error>> {
+    let records = ndjson_records("tests/fixtures/contains_package_todo")?;
+
+    assert_eq!(1, records.len());
+    assert_eq!("summary", records[0]["type"]);
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_template() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--format")
+        .arg("template")
+        .arg("--template")
+        .arg("tests/fixtures/templates/summary.hbs")
+        .assert()
+        .failure()
+        .stdout(predicate::eq(
+            "2 new violation(s):\n\
+             - [P001] dependency: packs/foo -> ::Bar (packs/bar) in packs/foo/app/services/foo.rb\n\
+             - [P002] privacy: packs/foo -> ::Bar (packs/bar) in packs/foo/app/services/foo.rb\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_template_typo() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--format")
+        .arg("template")
+        .arg("--template")
+        .arg("tests/fixtures/templates/typo.hbs")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to render the template at tests/fixtures/templates/typo.hbs",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_template_format_without_template(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--format")
+        .arg("template")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--template <TEMPLATE>"));
+
+    common::teardown();
+    Ok(())
+}
@@ -21,6 +21,98 @@ fn test_check() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_check_with_output() -> Result<(), Box<dyn Error>> {
+    let output_path = std::env::temp_dir().join("packs_check_output.txt");
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--output")
+        .arg(&output_path)
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty());
+
+    let report = fs::read_to_string(&output_path)?;
+    let dependency_violation = report
+        .find("Dependency violation: `::Bar`")
+        .expect("report should contain the dependency violation");
+    let privacy_violation = report
+        .find("Privacy violation: `::Bar`")
+        .expect("report should contain the privacy violation");
+    // Violations in the same file are ordered by their message
+    assert!(dependency_violation < privacy_violation);
+    assert!(report.contains("2 violation(s) detected:"));
+    assert!(!report.contains(&*std::env::current_dir()?.to_string_lossy()));
+
+    fs::remove_file(output_path)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_absolute_paths() -> Result<(), Box<dyn Error>> {
+    let root = fs::canonicalize("tests/fixtures/simple_app")?;
+    let location = format!(
+        "{}:3:4\nDependency violation",
+        root.join("packs/foo/app/services/foo.rb").display()
+    );
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--paths")
+        .arg("absolute")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(location));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_paths_relative_to_cwd() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--paths")
+        .arg("cwd")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "tests/fixtures/simple_app/packs/foo/app/services/foo.rb:3:4\nDependency violation",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_hyperlinks() -> Result<(), Box<dyn Error>> {
+    let root = fs::canonicalize("tests/fixtures/simple_app")?;
+    let hyperlink = format!(
+        "\x1b]8;;file://{}\x1b\\packs/foo/app/services/foo.rb:3:4\x1b]8;;\x1b\\\nDependency violation",
+        root.join("packs/foo/app/services/foo.rb").display()
+    );
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--hyperlinks")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(hyperlink));
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_check_with_single_file() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("packs")?
@@ -131,6 +223,45 @@ fn test_check_with_stale_violations() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_check_with_stale_violations_and_warn_on_stale(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_stale_violations")
+        .arg("check")
+        .arg("--warn-on-stale")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Warning: there were stale violations found, please run `packs update`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_package_todo_file_and_fail_on_all(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_package_todo")
+        .arg("check")
+        .arg("--fail-on")
+        .arg("all")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "2 recorded violation(s) detected, failing because of `--fail-on all`",
+        ))
+        .stdout(predicate::str::contains("violation(s) detected:").not());
+
+    common::teardown();
+    Ok(())
+}
+
 #[test]
 fn test_check_without_stale_violations() -> Result<(), Box<dyn Error>> {
     Command::cargo_bin("packs")
@@ -37,7 +37,8 @@ fn test_auto_correct_unnecessary_dependencies() -> Result<(), Box<dyn Error>> {
         "enforce_privacy: true",
         "layer: technical_services",
         "dependencies:",
-        "  - packs/bar\n",
+        // The list keeps the indentation it had
+        "- packs/bar\n",
     ]
     .join("\n");
     let after_autocorrect = fs::read_to_string("tests/fixtures/app_with_unnecessary_dependencies/packs/foo/package.yml").unwrap();
@@ -0,0 +1,46 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_churn_weighted() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--churn-weighted")
+        .arg("--churn-months")
+        .arg("1200")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Violations by churn in the last 1200 month(s), most worth fixing first:",
+        ))
+        .stdout(predicate::str::contains(
+            "dependency   no       ::Bar in packs/foo/app/services/foo.rb",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_churn_weighted_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--churn-weighted")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"commits\":"))
+        .stdout(predicate::str::contains(
+            "\"file\": \"packs/foo/app/services/foo.rb\"",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::error::Error;
+use std::path::Path;
 use std::{fs, path::PathBuf};
 
 //
@@ -20,6 +22,61 @@ pub fn teardown() {
         });
 }
 
+// An empty directory in the temp dir, for a test that builds its own app,
+// e.g. a git repository
+#[allow(dead_code)]
+pub fn temp_app(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let app = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(&app)?;
+    Ok(app)
+}
+
+// Runs git in `repo` as a throwaway committer, failing if git fails
+#[allow(dead_code)]
+pub fn git(repo: &Path, args: &[&str]) -> Result<(), Box<dyn Error>> {
+    run_git(
+        std::process::Command::new("git")
+            .current_dir(repo)
+            .args(args),
+    )
+}
+
+// Like `git`, with the author and committer dates set to `date`, e.g.
+// 2024-01-10T12:00:00
+#[allow(dead_code)]
+pub fn git_at(
+    repo: &Path,
+    args: &[&str],
+    date: &str,
+) -> Result<(), Box<dyn Error>> {
+    run_git(
+        std::process::Command::new("git")
+            .current_dir(repo)
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date),
+    )
+}
+
+fn run_git(command: &mut std::process::Command) -> Result<(), Box<dyn Error>> {
+    let output = command
+        .env("GIT_AUTHOR_NAME", "packs")
+        .env("GIT_AUTHOR_EMAIL", "packs@example.com")
+        .env("GIT_COMMITTER_NAME", "packs")
+        .env("GIT_COMMITTER_EMAIL", "packs@example.com")
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
 #[allow(dead_code)]
 pub fn delete_foobar() {
     let directory = PathBuf::from("tests/fixtures/simple_app/packs/foobar");
@@ -135,4 +192,112 @@ dependencies:
     );
 
     fs::write(pack_yml, pack_yml_contents).unwrap();
+
+    let pack_yml = PathBuf::from(
+        "tests/fixtures/app_with_dependencies_to_add/packs/foo/package.yml",
+    );
+    let pack_yml_contents = String::from(
+        "\
+enforce_dependencies: true
+dependencies:
+- packs/bar
+",
+    );
+
+    fs::write(pack_yml, pack_yml_contents).unwrap();
+
+    let _ = fs::remove_file(
+        "tests/fixtures/app_with_dependencies_to_add/packs/bar/package_todo.yml",
+    );
+
+    let todo_format_v2_foo_todo = String::from("\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/foo' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# bin/packwerk update-todo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/foo.rb
+    occurrences:
+      dependency: 1
+    first_recorded: 2024-01-15
+    recorded_in: abc1234
+packs/baz:
+  \"::Baz\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/foo.rb
+    occurrences:
+      dependency: 1
+    first_recorded: 2023-06-01
+    recorded_in: def5678
+");
+
+    fs::write(
+        "tests/fixtures/app_with_todo_format_v2/packs/foo/package_todo.yml",
+        todo_format_v2_foo_todo,
+    )
+    .unwrap();
+
+    let hand_edited_foo_todo = String::from("\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/foo' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# bin/packwerk update-todo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - privacy
+    - dependency
+    - layering
+    files:
+    - packs/foo/app/services/foo.rb
+    - packs/foo/app/services/deleted.rb
+");
+
+    fs::write(
+        "tests/fixtures/app_with_hand_edited_todos/packs/foo/package_todo.yml",
+        hand_edited_foo_todo,
+    )
+    .unwrap();
+
+    let hand_edited_baz_todo = String::from("\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/baz' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# bin/packwerk update-todo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/baz/app/services/baz.rb
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/baz/app/services/baz.rb
+");
+
+    fs::write(
+        "tests/fixtures/app_with_hand_edited_todos/packs/baz/package_todo.yml",
+        hand_edited_baz_todo,
+    )
+    .unwrap();
 }
@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_completions_include_pack_names() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("completions")
+        .arg("zsh")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#compdef packs"))
+        .stdout(predicate::str::contains(
+            "':from -- The pack that depends on another pack:(packs/bar packs/baz packs/foo .)'",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_completions_for_unknown_shell() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("completions")
+        .arg("tcsh")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'tcsh'"));
+
+    common::teardown();
+    Ok(())
+}
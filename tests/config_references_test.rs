@@ -0,0 +1,22 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_config_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_config_references")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "config/sidekiq.yml:4:11\nDependency violation: `::Billing::InvoiceJob` belongs to `packs/billing`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, fs, process::Command};
+
+mod common;
+
+#[test]
+fn test_invalid_configuration_reports_every_problem(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_invalid_configuration")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("2 problem(s) found in"))
+        .stderr(predicate::str::contains("cahce").not())
+        .stderr(predicate::str::contains(
+            "- `include` has an invalid glob `app/{**/*.rb`",
+        ))
+        .stderr(predicate::str::contains(
+            "- `autoload_paths` includes `app/does_not_exist`, which does not exist",
+        ))
+        .stderr(predicate::str::contains("parallel").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_unknown_keys_are_warnings() -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_unknown_keys_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(&app)?;
+    fs::write(
+        app.join("packwerk.yml"),
+        "cache: false\ncahce: false\nsome_other_tool: true\n",
+    )?;
+    fs::write(app.join("package.yml"), "")?;
+
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg(&app)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Configuration warning: packwerk.yml: `cahce` is not a known key, did you mean `cache`?",
+        ))
+        .stdout(predicate::str::contains(
+            "Configuration warning: packwerk.yml: `some_other_tool` is not a known key",
+        ));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
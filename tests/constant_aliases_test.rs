@@ -0,0 +1,33 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+fn assert_alias_is_followed(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_constant_aliases")
+        .args(args)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Billing::Invoice` is private to `packs/billing`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("`::InvoiceAlias`").not())
+        // An alias in the pack of its constant is that pack's re-export
+        .stdout(predicate::str::contains("foo.rb:4").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_follows_constant_aliases() -> Result<(), Box<dyn Error>> {
+    assert_alias_is_followed(&[])
+}
+
+#[test]
+fn test_check_follows_constant_aliases_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    assert_alias_is_followed(&["--experimental-parser"])
+}
@@ -0,0 +1,20 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_custom_autoload_paths() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_custom_autoload_paths")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/consumer/app/services/consumer.rb:4:4\nPrivacy violation: `::Ledger::Entry` is private to `packs/ledger`, but referenced from `packs/consumer`"));
+
+    common::teardown();
+    Ok(())
+}
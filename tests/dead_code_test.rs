@@ -0,0 +1,39 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_dead_code_public_only() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dead_public_constants")
+        .arg("dead-code")
+        .arg("--public-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/bar/app/public/bar/unused.rb ::Bar::Unused\n\n1 unreferenced public constant(s) found",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_dead_code() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dead_public_constants")
+        .arg("dead-code")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/bar/app/public/bar/unused.rb ::Bar::Unused",
+        ))
+        .stdout(predicate::str::contains("::Bar::Internal").not())
+        .stdout(predicate::str::contains("::Bar::Used").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_app_definition_scope() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_shadowed_definitions")
+        .arg("--experimental-parser")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:4:4\nPrivacy violation: `::Other` is private to `packs/bar`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("`::Shared`").not());
+
+    common::teardown();
+    Ok(())
+}
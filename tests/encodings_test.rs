@@ -0,0 +1,31 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_encoding_magic_comments() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_encodings")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("3 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:6:2\nPrivacy violation: `::Bar` is private to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/with_bom.rb:2:2\nPrivacy violation: `::Bar` is private to `packs/bar`",
+        ))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/latin1.rb:4:2\nPrivacy violation: `::Bar` is private to `packs/bar`",
+        ))
+        .stderr(predicate::str::contains(
+            "shift_jis.rb: the `shift_jis` encoding is not supported – skipping this file",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_explain() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--explain")
+        .arg("packs/foo/app/services/foo.rb:7")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nesting: Foo"))
+        .stdout(predicate::str::contains(
+            "Candidates, in lookup order:\n  - ::Foo::Baz not defined\n  - ::Baz defined in packs/baz/app/services/baz.rb",
+        ))
+        .stdout(predicate::str::contains(
+            "Resolved to: ::Baz in packs/baz/app/services/baz.rb (pack `packs/baz`)",
+        ))
+        .stdout(predicate::str::contains("dependency: no violation"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_explain_violation() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--explain")
+        .arg("packs/foo/app/services/foo.rb:3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nesting: Foo"))
+        .stdout(predicate::str::contains("dependency: violation"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_explain_invalid_location() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--explain")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a location"));
+
+    common::teardown();
+    Ok(())
+}
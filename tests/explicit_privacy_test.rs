@@ -0,0 +1,74 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_check_with_explicit_privacy() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_explicit_privacy")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar::Receipt` is private to `packs/bar`, but referenced from `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_explicit_privacy() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_explicit_privacy")
+        .arg("validate")
+        .assert()
+        .success();
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_invalid_explicit_privacy() -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_invalid_explicit_privacy_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/bar/app/services/bar"))?;
+    fs::create_dir_all(app.join("packs/foo"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(
+        app.join("packs/bar/package.yml"),
+        "enforce_privacy: explicit\npublic_constants:\n  - Bar::Invoice\n  - Bar::Refund\n",
+    )?;
+    fs::write(
+        app.join("packs/bar/app/services/bar/invoice.rb"),
+        "module Bar\n  class Invoice\n  end\nend\n",
+    )?;
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: explicit\npublic_constants:\n  - Foo\n",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/package.yml sets `enforce_dependencies: explicit`, which only applies to `enforce_privacy`\n\
+             packs/foo/package.yml lists `public_constants`, which are only used with `enforce_privacy: explicit`\n\
+             packs/bar/package.yml lists `Bar::Refund` in `public_constants`, which is not defined in packs/bar",
+        ));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+const APP: &str = "tests/fixtures/app_with_sorbet_packages";
+
+#[test]
+fn test_export_sorbet_packages() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("export")
+        .arg("sorbet-packages")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 3 __package.rb file(s)"));
+
+    let foo = fs::read_to_string(format!("{}/packs/foo/__package.rb", APP))?;
+    let bar = fs::read_to_string(format!("{}/packs/bar/__package.rb", APP))?;
+    let baz = fs::read_to_string(format!("{}/packs/baz/__package.rb", APP))?;
+    let root_package_rb_exists =
+        fs::metadata(format!("{}/__package.rb", APP)).is_ok();
+    for pack in ["foo", "bar", "baz"] {
+        fs::remove_file(format!("{}/packs/{}/__package.rb", APP, pack))?;
+    }
+
+    assert!(foo.contains(
+        "class Packs::Foo < PackageSpec\n  import Packs::Bar\n  import Packs::Baz\nend\n"
+    ));
+    assert!(bar.contains("class Packs::Bar < PackageSpec\n  export Bar\nend\n"));
+    assert!(
+        baz.contains("class Packs::Baz < PackageSpec\n  export BazApi\nend\n")
+    );
+    assert!(!root_package_rb_exists);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_export_heatmap() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export")
+        .arg("heatmap")
+        .assert()
+        .success()
+        .stdout(
+            "\
+referencing_pack,.,packs/bar,packs/baz,packs/foo
+.,0,0,0,0
+packs/bar,0,0,0,0
+packs/baz,0,0,0,0
+packs/foo,0,1,1,0
+",
+        );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_export_normalized_heatmap_as_json() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("export")
+        .arg("heatmap")
+        .arg("--format")
+        .arg("json")
+        .arg("--normalize")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let heatmap: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        serde_json::json!([".", "packs/bar", "packs/baz", "packs/foo"]),
+        heatmap["packs"]
+    );
+    // packs/foo has 3 files
+    assert_eq!(
+        serde_json::json!([0.0, 0.3333, 0.3333, 0.0]),
+        heatmap["references"][3]
+    );
+
+    common::teardown();
+    Ok(())
+}
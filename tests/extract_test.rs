@@ -0,0 +1,110 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+
+// `extract` moves files, so it runs on an app of its own
+fn set_up_app(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let app = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("app/models/billing"))?;
+    fs::create_dir_all(app.join("packs/foo/app/services"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(app.join("packs/foo/package.yml"), "")?;
+    fs::write(app.join("app/models/billing.rb"), "module Billing\nend\n")?;
+    fs::write(
+        app.join("app/models/billing/invoice.rb"),
+        "module Billing\n  class Invoice\n    def foo\n      Foo\n    end\n  end\nend\n",
+    )?;
+    fs::write(app.join("app/models/user.rb"), "class User\nend\n")?;
+    fs::write(
+        app.join("packs/foo/app/services/foo.rb"),
+        "class Foo\nend\n",
+    )?;
+    Ok(app)
+}
+
+#[test]
+fn test_extract_dry_run() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_extract_dry_run_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("extract")
+        .arg("Billing::")
+        .arg("packs/billing")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "- app/models/billing.rb -> packs/billing/app/models/billing.rb",
+        ))
+        .stdout(predicate::str::contains(
+            "- app/models/billing/invoice.rb -> packs/billing/app/models/billing/invoice.rb",
+        ))
+        .stdout(predicate::str::contains("user.rb").not())
+        .stdout(predicate::str::contains(
+            "`packs/billing` needs these dependencies:\n- packs/foo",
+        ));
+
+    assert!(app.join("app/models/billing/invoice.rb").exists());
+    assert!(!app.join("packs/billing").exists());
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_extract() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_extract_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("extract")
+        .arg("Billing")
+        .arg("packs/billing")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Violations before and after:"));
+
+    assert!(!app.join("app/models/billing.rb").exists());
+    assert!(app.join("packs/billing/app/models/billing.rb").exists());
+    assert!(app
+        .join("packs/billing/app/models/billing/invoice.rb")
+        .exists());
+    assert!(app.join("app/models/user.rb").exists());
+    let package_yml =
+        fs::read_to_string(app.join("packs/billing/package.yml"))?;
+    assert!(package_yml.contains("enforce_dependencies: true"));
+    assert!(package_yml.contains("- packs/foo"));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_extract_into_existing_pack() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_extract_existing_pack_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("extract")
+        .arg("Billing")
+        .arg("packs/foo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`packs/foo` already exists"));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,6 @@
+module Billing
+  class Invoice
+    def self.create!(total:)
+    end
+  end
+end
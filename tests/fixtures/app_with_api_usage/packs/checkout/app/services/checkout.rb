@@ -0,0 +1,7 @@
+module Checkout
+  def self.call(id)
+    Billing::Invoice.create!(total: 10)
+    Billing::Invoice.create!(total: 20)
+    Billing::Invoice.find(id)
+  end
+end
@@ -0,0 +1,5 @@
+class User
+  def bar
+    Foo::Bar
+  end
+end
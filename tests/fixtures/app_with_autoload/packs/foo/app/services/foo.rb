@@ -0,0 +1,3 @@
+module Foo
+  autoload :Bar, "foo/bar"
+end
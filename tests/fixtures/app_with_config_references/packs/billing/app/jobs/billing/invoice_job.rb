@@ -0,0 +1,4 @@
+module Billing
+  class InvoiceJob
+  end
+end
@@ -0,0 +1 @@
+InvoiceAlias = Billing::Invoice
@@ -0,0 +1,3 @@
+module Billing::Api
+  Invoice = ::Billing::Invoice
+end
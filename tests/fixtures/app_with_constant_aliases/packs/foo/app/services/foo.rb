@@ -0,0 +1,6 @@
+class Foo
+  def call
+    InvoiceAlias
+    Billing::Api::Invoice
+  end
+end
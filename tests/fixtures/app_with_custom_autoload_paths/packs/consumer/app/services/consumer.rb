@@ -0,0 +1,6 @@
+class Consumer
+  def call
+    LedgerApi.new
+    Ledger::Entry.new
+  end
+end
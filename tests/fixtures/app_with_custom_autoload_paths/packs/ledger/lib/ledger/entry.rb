@@ -0,0 +1,4 @@
+module Ledger
+  class Entry
+  end
+end
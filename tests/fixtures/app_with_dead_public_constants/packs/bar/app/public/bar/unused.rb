@@ -0,0 +1,4 @@
+module Bar
+  class Unused
+  end
+end
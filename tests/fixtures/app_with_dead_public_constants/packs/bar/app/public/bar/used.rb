@@ -0,0 +1,7 @@
+module Bar
+  class Used
+    def self.call
+      Bar::Internal.new
+    end
+  end
+end
@@ -0,0 +1,6 @@
+module Foo
+  def self.call
+    Bar.call
+    Baz.call
+  end
+end
@@ -0,0 +1,7 @@
+# encoding: utf-8
+# frozen_string_literal: true
+
+module Foo
+  GREETING = "héllo"
+  Bar
+end
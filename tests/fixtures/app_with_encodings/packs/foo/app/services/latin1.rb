@@ -0,0 +1,5 @@
+# -*- coding: iso-8859-1 -*-
+module Latin1
+  GREETING = "hllo"
+  Bar
+end
@@ -0,0 +1,5 @@
+# encoding: Shift_JIS
+module ShiftJis
+  GREETING = ""
+  Bar
+end
@@ -0,0 +1,3 @@
+﻿module WithBom
+  Bar
+end
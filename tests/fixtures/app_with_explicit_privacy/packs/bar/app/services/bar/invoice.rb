@@ -0,0 +1,6 @@
+module Bar
+  class Invoice
+    def total
+    end
+  end
+end
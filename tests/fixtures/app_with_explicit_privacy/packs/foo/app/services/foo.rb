@@ -0,0 +1,9 @@
+module Foo
+  def invoice
+    Bar::Invoice.new
+  end
+
+  def receipt
+    Bar::Receipt.new
+  end
+end
@@ -0,0 +1,5 @@
+class Foo
+  def call
+    Billing::Api::Invoice
+  end
+end
@@ -0,0 +1,5 @@
+module Foo
+  def calls_bar
+    ::Bar
+  end
+end
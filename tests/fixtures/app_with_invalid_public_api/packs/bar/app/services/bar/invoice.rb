@@ -0,0 +1,10 @@
+module Bar
+  class Invoice
+    def total
+    end
+
+    # Not `void`, which public_api.yml lists
+    def void!
+    end
+  end
+end
@@ -0,0 +1,9 @@
+module Foo
+  def invoice_total
+    Bar::Invoice.new.total
+  end
+
+  def receipt
+    Bar::Receipt.new
+  end
+end
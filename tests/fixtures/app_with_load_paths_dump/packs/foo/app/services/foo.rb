@@ -0,0 +1,5 @@
+module Foo
+  def self.call
+    Foo::Util.new
+  end
+end
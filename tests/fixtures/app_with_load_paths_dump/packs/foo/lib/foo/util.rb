@@ -0,0 +1,4 @@
+module Foo
+  class Util
+  end
+end
@@ -0,0 +1,4 @@
+module Baz
+  def self.call
+  end
+end
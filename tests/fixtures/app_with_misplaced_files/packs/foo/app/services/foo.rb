@@ -0,0 +1,3 @@
+module Foo
+  class Error < StandardError; end
+end
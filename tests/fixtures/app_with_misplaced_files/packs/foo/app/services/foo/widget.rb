@@ -0,0 +1,10 @@
+module Foo
+  class Widget
+  end
+end
+
+# Belongs in packs/bar/app/services/bar/gadget.rb
+module Bar
+  class Gadget
+  end
+end
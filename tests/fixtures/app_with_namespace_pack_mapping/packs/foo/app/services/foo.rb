@@ -0,0 +1,5 @@
+module Foo
+  def invoices
+    [Billing::Invoice.new, Billing::OldInvoice.new]
+  end
+end
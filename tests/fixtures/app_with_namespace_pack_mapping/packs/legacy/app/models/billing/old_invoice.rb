@@ -0,0 +1,4 @@
+module Billing
+  class OldInvoice
+  end
+end
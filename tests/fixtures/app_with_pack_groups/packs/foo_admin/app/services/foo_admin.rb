@@ -0,0 +1,5 @@
+module FooAdmin
+  def self.call
+    Foo
+  end
+end
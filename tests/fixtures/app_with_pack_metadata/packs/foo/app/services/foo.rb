@@ -0,0 +1,5 @@
+class Foo
+  def call
+    Bar
+  end
+end
@@ -0,0 +1,5 @@
+# frozen_string_literal: true
+# pack_public: true
+
+module Bar
+end
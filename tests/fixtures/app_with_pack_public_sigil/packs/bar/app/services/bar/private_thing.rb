@@ -0,0 +1,6 @@
+# frozen_string_literal: true
+
+module Bar
+  class PrivateThing
+  end
+end
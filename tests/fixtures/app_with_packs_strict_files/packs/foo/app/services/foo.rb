@@ -0,0 +1,5 @@
+module Foo
+  def calls_bar_without_a_stated_dependency
+    Bar
+  end
+end
@@ -0,0 +1,6 @@
+# packs_strict: true
+module OtherFoo
+  def calls_bar_without_a_stated_dependency
+    ::Bar
+  end
+end
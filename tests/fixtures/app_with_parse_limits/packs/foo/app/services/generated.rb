@@ -0,0 +1,25 @@
+# This file is generated, do not edit
+class Generated
+  ROWS = [
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+    Bar,
+  ]
+end
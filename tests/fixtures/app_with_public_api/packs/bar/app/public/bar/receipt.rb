@@ -0,0 +1,4 @@
+module Bar
+  class Receipt
+  end
+end
@@ -0,0 +1,12 @@
+module Bar
+  class Invoice
+    def total
+    end
+
+    def paid?
+    end
+
+    def self.void!(invoice)
+    end
+  end
+end
@@ -0,0 +1,7 @@
+class Foo
+  has_many :bars
+
+  def call
+    Baz.new
+  end
+end
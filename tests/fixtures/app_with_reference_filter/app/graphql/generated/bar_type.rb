@@ -0,0 +1,5 @@
+class BarType
+  def call
+    Bar
+  end
+end
@@ -0,0 +1,4 @@
+module Bar
+  class GeneratedThing
+  end
+end
@@ -0,0 +1,6 @@
+class Foo
+  def call
+    Bar
+    Bar::GeneratedThing
+  end
+end
@@ -0,0 +1,4 @@
+class Bar
+  def shout
+  end
+end
@@ -0,0 +1,11 @@
+module Foo
+  module BarRefinements
+    refine Bar do
+      LIMIT = 3
+
+      def shout
+        Helper.new(LIMIT)
+      end
+    end
+  end
+end
@@ -0,0 +1,4 @@
+module Foo
+  class Helper
+  end
+end
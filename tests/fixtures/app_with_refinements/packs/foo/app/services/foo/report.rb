@@ -0,0 +1,5 @@
+module Foo
+  class Report
+    using BarRefinements
+  end
+end
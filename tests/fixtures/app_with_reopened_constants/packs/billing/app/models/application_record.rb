@@ -0,0 +1,3 @@
+class ApplicationRecord
+  self.abstract_class = true
+end
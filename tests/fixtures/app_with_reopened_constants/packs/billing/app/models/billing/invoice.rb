@@ -0,0 +1,6 @@
+module Billing
+  class Invoice
+    def total
+    end
+  end
+end
@@ -0,0 +1,6 @@
+module Billing
+  class Invoice
+    def to_report
+    end
+  end
+end
@@ -0,0 +1,4 @@
+module Reports
+  class Monthly
+  end
+end
@@ -0,0 +1,6 @@
+module MyCo
+  module Billing
+    class Invoice
+    end
+  end
+end
@@ -0,0 +1,4 @@
+module Legacy
+  class Order
+  end
+end
@@ -0,0 +1,6 @@
+module MyCo
+  module Payments
+    class Charge
+    end
+  end
+end
@@ -0,0 +1,6 @@
+class RootController
+  def show
+    FooApi.new
+    FooInternal.new
+  end
+end
@@ -0,0 +1,6 @@
+class Foo
+  def call
+    RootApi.new
+    RootInternal.new
+  end
+end
@@ -0,0 +1,5 @@
+class RootService
+  def call
+    Bar
+  end
+end
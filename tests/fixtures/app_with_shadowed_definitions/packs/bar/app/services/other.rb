@@ -0,0 +1,4 @@
+class Other
+  def self.call
+  end
+end
@@ -0,0 +1,4 @@
+class Shared
+  def self.call
+  end
+end
@@ -0,0 +1,6 @@
+class Foo
+  def call
+    Shared
+    Other
+  end
+end
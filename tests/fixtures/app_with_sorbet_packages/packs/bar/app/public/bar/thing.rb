@@ -0,0 +1,4 @@
+module Bar
+  class Thing
+  end
+end
@@ -0,0 +1,5 @@
+class Foo
+  sig { params(bar: Bar).void }
+  def call(bar)
+  end
+end
@@ -0,0 +1,4 @@
+class Foo
+  sig { returns(Bar) }
+  def bar; end
+end
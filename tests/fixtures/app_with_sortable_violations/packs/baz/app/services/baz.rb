@@ -0,0 +1,5 @@
+module Baz
+  def self.call
+    Bar
+  end
+end
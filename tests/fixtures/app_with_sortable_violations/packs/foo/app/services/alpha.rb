@@ -0,0 +1,5 @@
+module Alpha
+  def self.call
+    Zed
+  end
+end
@@ -0,0 +1,6 @@
+module Beta
+  def self.call
+    Bar
+    Zed
+  end
+end
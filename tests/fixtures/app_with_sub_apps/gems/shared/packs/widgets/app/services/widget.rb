@@ -0,0 +1,6 @@
+class Widget
+  def self.build
+    Gear.new
+    Bolt.new
+  end
+end
@@ -0,0 +1,3 @@
+module Bar
+  def self.call; end
+end
@@ -0,0 +1,3 @@
+module Baz
+  def self.call; end
+end
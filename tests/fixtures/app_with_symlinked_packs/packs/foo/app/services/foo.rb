@@ -0,0 +1,9 @@
+class Foo
+  def bar
+    Bar
+  end
+
+  def baz
+    Baz
+  end
+end
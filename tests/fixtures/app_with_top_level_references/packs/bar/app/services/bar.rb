@@ -0,0 +1,4 @@
+class Bar
+  def self.call
+  end
+end
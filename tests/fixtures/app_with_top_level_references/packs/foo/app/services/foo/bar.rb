@@ -0,0 +1,6 @@
+module Foo
+  class Bar
+    def self.call
+    end
+  end
+end
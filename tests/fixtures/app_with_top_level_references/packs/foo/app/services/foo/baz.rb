@@ -0,0 +1,11 @@
+module Foo
+  class Baz
+    def nested
+      Bar
+    end
+
+    def top_level
+      ::Bar
+    end
+  end
+end
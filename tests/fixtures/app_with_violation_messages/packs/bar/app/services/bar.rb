@@ -0,0 +1,4 @@
+module Bar
+  def self.call
+  end
+end
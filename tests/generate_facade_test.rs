@@ -0,0 +1,66 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+const APP: &str = "tests/fixtures/app_with_facade";
+
+#[test]
+fn test_generate_facade() -> Result<(), Box<dyn Error>> {
+    let facade_rb = format!("{}/packs/billing/app/public/billing/api.rb", APP);
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("generate-facade")
+        .arg("packs/billing")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Wrote packs/billing/app/public/billing/api.rb re-exporting 2 constant(s)",
+        ));
+
+    let facade = fs::read_to_string(&facade_rb)?;
+    let check = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .assert();
+    fs::remove_dir_all(format!("{}/packs/billing/app/public", APP))?;
+
+    assert_eq!(
+        "\
+# typed: true
+# Generated from package.yml by `pks generate-facade packs/billing`. Edit package.yml instead.
+
+module Billing::Api
+  Invoice = ::Billing::Invoice
+  Payment = ::Billing::Payment
+end
+",
+        facade
+    );
+    check.success();
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_generate_facade_without_facade() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("generate-facade")
+        .arg("packs/foo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "packs/foo has no `facade` in its package.yml",
+        ));
+
+    common::teardown();
+    Ok(())
+}
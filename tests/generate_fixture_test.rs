@@ -0,0 +1,64 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs, path::PathBuf};
+
+mod common;
+
+fn fixture_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&path);
+    path
+}
+
+#[test]
+fn test_generate_fixture() -> Result<(), Box<dyn Error>> {
+    let path = fixture_path("packs_generated_fixture");
+    Command::cargo_bin("packs")?
+        .arg("generate-fixture")
+        .arg(&path)
+        .arg("--packs")
+        .arg("4")
+        .arg("--files-per-pack")
+        .arg("3")
+        .arg("--violations")
+        .arg("7")
+        .assert()
+        .success();
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&path)
+        .arg("--no-cache")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("7 violation(s) detected:"));
+
+    fs::remove_dir_all(&path)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_generate_fixture_with_too_many_violations() -> Result<(), Box<dyn Error>>
+{
+    let path = fixture_path("packs_generated_fixture_too_many_violations");
+    Command::cargo_bin("packs")?
+        .arg("generate-fixture")
+        .arg(&path)
+        .arg("--packs")
+        .arg("2")
+        .arg("--files-per-pack")
+        .arg("1")
+        .arg("--violations")
+        .arg("2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "2 pack(s) with 1 file(s) each can have at most 1 violation(s)",
+        ));
+
+    assert!(!path.exists());
+    common::teardown();
+    Ok(())
+}
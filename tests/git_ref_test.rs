@@ -0,0 +1,134 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+fn setup(name: &str) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let repo = common::temp_app(name)?;
+    fs::create_dir_all(repo.join("packs/foo"))?;
+    fs::write(repo.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(repo.join("package.yml"), "")?;
+    fs::write(repo.join("packs/foo/package.yml"), "")?;
+    common::git(&repo, &["init", "-q"])?;
+    common::git(&repo, &["add", "."])?;
+    common::git(&repo, &["commit", "-qm", "Add packs/foo"])?;
+    // Only in the working tree
+    fs::remove_dir_all(repo.join("packs/foo"))?;
+    fs::create_dir_all(repo.join("packs/bar"))?;
+    fs::write(repo.join("packs/bar/package.yml"), "")?;
+    Ok(repo)
+}
+
+#[test]
+fn test_git_ref_reads_the_commit() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_git_ref_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("--git-ref")
+        .arg("HEAD")
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/foo"))
+        .stdout(predicate::str::contains("packs/bar").not());
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/bar"))
+        .stdout(predicate::str::contains("packs/foo").not());
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_git_ref_that_is_not_a_commit() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_git_ref_missing_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("--git-ref")
+        .arg("no-such-branch")
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`no-such-branch` is not a commit"));
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_git_ref_with_a_command_that_writes() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_git_ref_update_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("--git-ref")
+        .arg("HEAD")
+        .arg("update")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`--git-ref` only works with commands that don't write to the project",
+        ));
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_git_ref_checks_the_files_of_the_commit() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_git_ref_check_test")?;
+    fs::create_dir_all(repo.join("packs/bar/app/services"))?;
+    fs::write(
+        repo.join("packs/bar/package.yml"),
+        "enforce_privacy: true\n",
+    )?;
+    fs::write(
+        repo.join("packs/bar/app/services/bar.rb"),
+        "class Bar\n  def self.call; end\nend\n",
+    )?;
+    fs::create_dir_all(repo.join("app/services"))?;
+    fs::write(repo.join("app/services/caller.rb"), "Bar.call\n")?;
+    common::git(&repo, &["add", "."])?;
+    common::git(&repo, &["commit", "-qm", "Reference Bar"])?;
+    // Only in the working tree
+    fs::remove_file(repo.join("app/services/caller.rb"))?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("--git-ref")
+        .arg("HEAD")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "app/services/caller.rb:1:0\nPrivacy violation: `::Bar` is private to `packs/bar`",
+        ));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("check")
+        .assert()
+        .success();
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
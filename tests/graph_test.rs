@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_graph() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("graph")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph packs {\n"))
+        .stdout(predicate::str::contains(
+            "\"packs/foo\" -> \"packs/bar\" [label=\"",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_graph_cycles_only() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("graph")
+        .arg("--cycles-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("->").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_graph_with_unknown_focus() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("graph")
+        .arg("--focus")
+        .arg("packs/nope")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`packs/nope` not found"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+const PACKAGE_TODO: &str = "\
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+";
+
+#[test]
+fn test_history() -> Result<(), Box<dyn Error>> {
+    let repo = common::temp_app("packs_history_test")?;
+    fs::create_dir_all(repo.join("packs/foo"))?;
+    fs::write(repo.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(repo.join("package.yml"), "")?;
+    fs::write(repo.join("packs/foo/package.yml"), "")?;
+    fs::write(repo.join("packs/foo/package_todo.yml"), PACKAGE_TODO)?;
+    common::git_at(&repo, &["init", "-q"], "2024-01-10T12:00:00")?;
+    common::git_at(&repo, &["add", "."], "2024-01-10T12:00:00")?;
+    common::git_at(&repo, &["commit", "-qm", "Record"], "2024-01-10T12:00:00")?;
+    fs::write(
+        repo.join("packs/foo/package_todo.yml"),
+        PACKAGE_TODO.replace("    - dependency\n", ""),
+    )?;
+    common::git_at(&repo, &["commit", "-qam", "Fix"], "2024-03-05T12:00:00")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("history")
+        .arg("--since")
+        .arg("2023-12")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "\
+month,pack,violation_type,count
+2024-01,packs/foo,dependency,1
+2024-01,packs/foo,privacy,1
+2024-02,packs/foo,dependency,1
+2024-02,packs/foo,privacy,1
+2024-03,packs/foo,privacy,1
+2024-04,packs/foo,privacy,1
+",
+        ))
+        .stdout(predicate::str::contains("2023-12").not());
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
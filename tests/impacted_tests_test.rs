@@ -0,0 +1,82 @@
+use assert_cmd::Command;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+
+// packs/baz depends on packs/bar, which depends on packs/foo
+fn set_up_app(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let app = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&app);
+    for pack in ["foo", "bar", "baz", "qux"] {
+        fs::create_dir_all(app.join(format!("packs/{}/app/services", pack)))?;
+        fs::create_dir_all(app.join(format!("packs/{}/spec/services", pack)))?;
+        fs::write(
+            app.join(format!("packs/{}/app/services/{}.rb", pack, pack)),
+            "",
+        )?;
+        fs::write(
+            app.join(format!("packs/{}/spec/services/{}_spec.rb", pack, pack)),
+            "",
+        )?;
+    }
+    fs::create_dir_all(app.join("spec"))?;
+    fs::write(app.join("spec/root_spec.rb"), "")?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(app.join("packs/foo/package.yml"), "")?;
+    fs::write(
+        app.join("packs/bar/package.yml"),
+        "dependencies:\n- packs/foo\n",
+    )?;
+    fs::write(
+        app.join("packs/baz/package.yml"),
+        "dependencies:\n- packs/bar\n",
+    )?;
+    fs::write(app.join("packs/qux/package.yml"), "")?;
+    Ok(app)
+}
+
+#[test]
+fn test_impacted_tests() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_impacted_tests_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("impacted-tests")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .success()
+        .stdout(
+            "\
+packs/bar/spec/services/bar_spec.rb
+packs/baz/spec/services/baz_spec.rb
+packs/foo/spec/services/foo_spec.rb
+",
+        );
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_impacted_tests_of_deleted_file() -> Result<(), Box<dyn Error>> {
+    let app = set_up_app("packs_impacted_tests_deleted_file_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("impacted-tests")
+        .arg("packs/baz/app/services/deleted.rb")
+        .arg("app/models/user.rb")
+        .assert()
+        .success()
+        .stdout("packs/baz/spec/services/baz_spec.rb\nspec/root_spec.rb\n");
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_import_sorbet_packages() -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_import_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo"))?;
+    fs::create_dir_all(app.join("packs/bar"))?;
+    fs::create_dir_all(app.join("vendor/gem"))?;
+    fs::write(
+        app.join("packs/foo/__package.rb"),
+        "# typed: strict\n\nclass Packs::Foo < PackageSpec\n  import Packs::Bar\n  import Packs::Missing\n\n  export Foo::Api\nend\n",
+    )?;
+    fs::write(
+        app.join("packs/bar/__package.rb"),
+        "# typed: strict\n\nclass Packs::Bar < PackageSpec\n  visible_to Packs::Foo\nend\n",
+    )?;
+    fs::write(app.join("packs/bar/package.yml"), "enforce_privacy: true\n")?;
+    fs::write(
+        app.join("vendor/gem/__package.rb"),
+        "class Gem < PackageSpec\nend\n",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("import")
+        .arg("sorbet-packages")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Wrote package.yml files for 2 Sorbet package(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "These imported packages have no __package.rb in the project, so they were left out: Packs::Missing",
+        ))
+        .stdout(predicate::str::contains("1 export(s) were not imported"));
+
+    let foo = fs::read_to_string(app.join("packs/foo/package.yml"))?;
+    let bar = fs::read_to_string(app.join("packs/bar/package.yml"))?;
+    let vendored_package_yml_exists =
+        app.join("vendor/gem/package.yml").exists();
+    fs::remove_dir_all(&app)?;
+
+    assert!(foo.contains("enforce_dependencies: true"));
+    assert!(foo.contains("dependencies:\n  - packs/bar\n"));
+    // Existing package.yml files keep their settings
+    assert!(bar.contains("enforce_privacy: true"));
+    assert!(!bar.contains("visible_to"));
+    assert!(!vendored_package_yml_exists);
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use serial_test::serial;
+use std::{error::Error, fs, path::Path};
+
+mod common;
+
+const APP: &str = "tests/fixtures/uninitialized_app";
+
+fn reset_uninitialized_app() {
+    let root = Path::new(APP);
+    let _ = fs::remove_file(root.join("packwerk.yml"));
+    let _ = fs::remove_file(root.join("package.yml"));
+    if root.join("packs/app/app").exists() {
+        fs::rename(root.join("packs/app/app"), root.join("app")).unwrap();
+    }
+    if root.join("components/app/app").exists() {
+        fs::rename(root.join("components/app/app"), root.join("app")).unwrap();
+    }
+    let _ = fs::remove_dir_all(root.join("packs"));
+    let _ = fs::remove_dir_all(root.join("components"));
+    common::teardown();
+}
+
+#[test]
+#[serial]
+fn test_init() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("--no-cache")
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created `packwerk.yml`"))
+        .stdout(predicate::str::contains("Created root `package.yml`"))
+        .stdout(predicate::str::contains(
+            "Successfully updated package_todo.yml files!",
+        ));
+
+    let root_package_yml =
+        fs::read_to_string(Path::new(APP).join("package.yml"))?;
+    let packwerk_yml_exists = Path::new(APP).join("packwerk.yml").exists();
+
+    reset_uninitialized_app();
+
+    assert_eq!("enforce_dependencies: true\n", root_package_yml);
+    assert!(packwerk_yml_exists);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_init_with_starter_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("--no-cache")
+        .arg("init")
+        .arg("--starter-pack")
+        .arg("packs/app")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Moved `app/` into starter pack `packs/app`",
+        ));
+
+    let moved_file_exists = Path::new(APP)
+        .join("packs/app/app/services/user.rb")
+        .exists();
+    let starter_package_yml_exists =
+        Path::new(APP).join("packs/app/package.yml").exists();
+
+    reset_uninitialized_app();
+
+    assert!(moved_file_exists);
+    assert!(starter_package_yml_exists);
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_init_with_starter_pack_outside_of_packs() -> Result<(), Box<dyn Error>>
+{
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("--no-cache")
+        .arg("init")
+        .arg("--starter-pack")
+        .arg("components/app")
+        .assert()
+        .success();
+
+    let packwerk_yml = fs::read_to_string(Path::new(APP).join("packwerk.yml"))?;
+    let list_packs = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("--no-cache")
+        .arg("list-packs")
+        .output()?;
+
+    reset_uninitialized_app();
+
+    assert!(packwerk_yml.contains("- \"packs/*\"\n- \"components/app\"\n"));
+    assert!(String::from_utf8(list_packs.stdout)?
+        .contains("components/app/package.yml"));
+
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_init_with_starter_pack_not_in_package_paths(
+) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        Path::new(APP).join("packwerk.yml"),
+        "package_paths:\n- \"packs/*\"\n",
+    )?;
+
+    let assert = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("--no-cache")
+        .arg("init")
+        .arg("--starter-pack")
+        .arg("components/app")
+        .assert();
+    let app_not_moved = Path::new(APP).join("app").is_dir();
+
+    reset_uninitialized_app();
+
+    assert.failure().stderr(predicate::str::contains(
+        "`components/app` is not covered by `package_paths` in packwerk.yml",
+    ));
+    assert!(app_not_moved);
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::error::Error;
+
+mod common;
+
+#[test]
+#[serial]
+fn test_lint_todos() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_hand_edited_todos")
+        .arg("lint-todos")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "- packs/baz/package_todo.yml: packs/bar: duplicate entry with key \"::Bar\"",
+        ))
+        .stdout(predicate::str::contains(
+            "- packs/foo/package_todo.yml: entries are not sorted",
+        ))
+        .stdout(predicate::str::contains(
+            "- packs/foo/package_todo.yml: `layering` recorded for `::Bar` is not a violation type",
+        ))
+        .stdout(predicate::str::contains(
+            "- packs/foo/package_todo.yml: `packs/foo/app/services/deleted.rb` recorded for `::Bar` does not exist",
+        ))
+        .stderr(predicate::str::contains(
+            "4 problem(s) in todo files, run `packs lint-todos --fix` to fix them",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_validate_reports_hand_edited_todos() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_hand_edited_todos")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "4 problem(s) in todo files, which may have been edited by hand",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_lint_todos_fix() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_hand_edited_todos")
+        .arg("lint-todos")
+        .arg("--fix")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed 4 problem(s) in todo files"));
+
+    let foo_todo = std::fs::read_to_string(
+        "tests/fixtures/app_with_hand_edited_todos/packs/foo/package_todo.yml",
+    )?;
+    assert!(foo_todo.ends_with(
+        "packs/bar:\n  \"::Bar\":\n    violations:\n    - dependency\n    - privacy\n    files:\n    - packs/foo/app/services/foo.rb\n"
+    ));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_hand_edited_todos")
+        .arg("lint-todos")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found in todo files"));
+
+    common::teardown();
+    common::set_up_fixtures();
+    Ok(())
+}
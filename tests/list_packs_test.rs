@@ -16,3 +16,26 @@ fn lint_packs() -> Result<(), Box<dyn Error>> {
         .stdout(predicate::str::contains("packs/foo/package.yml"));
     Ok(())
 }
+
+#[test]
+fn list_packs_json() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("list-packs")
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"name\": \"packs/foo\""))
+        .stdout(predicate::str::contains(
+            "\"package_yml\": \"packs/foo/package.yml\"",
+        ))
+        .stdout(predicate::str::contains(
+            "\"dependencies\": [\n      \"packs/baz\"\n    ]",
+        ))
+        .stdout(predicate::str::contains(
+            "\"public_path\": \"packs/foo/app/public\"",
+        ))
+        .stdout(predicate::str::contains("\"file_count\": 3"));
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+
+mod common;
+
+#[test]
+fn test_metrics() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_budgets")
+        .arg("metrics")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/bar: 1 file(s), 1 public constant(s), 0 dependency(ies)\npacks/foo: 2 file(s), 0 public constant(s), 1 dependency(ies)\n  over budget: max_files is 2 (budget is 1)\n  over budget: max_dependencies is 1 (budget is 0)",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+// `Billing::OldInvoice` is defined in packs/legacy, but belongs to
+// packs/billing, which packs/foo depends on
+#[test]
+fn test_check_with_namespace_pack_mapping() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_namespace_pack_mapping")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_namespace_pack_mapping_to_unknown_pack(
+) -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_namespace_pack_mapping_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(&app)?;
+    fs::write(
+        app.join("packwerk.yml"),
+        "cache: false\nnamespace_pack_mapping:\n  Billing: packs/billing\n",
+    )?;
+    fs::write(app.join("package.yml"), "")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "`namespace_pack_mapping` in packwerk.yml maps `Billing` to `packs/billing`, which is not a pack",
+        ));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_order() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("order")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0 .\n0 packs/bar\n0 packs/baz\n1 packs/foo\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_order_with_cycles() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dependency_cycles")
+        .arg("order")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 packs/bar, packs/foo (cycle)"));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+const PACKAGE_TODO: &str = "\
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    - privacy
+    files:
+    - packs/foo/app/services/foo.rb
+";
+
+fn setup(name: &str) -> Result<std::path::PathBuf, Box<dyn Error>> {
+    let repo = common::temp_app(name)?;
+    fs::create_dir_all(repo.join("packs/foo"))?;
+    fs::create_dir_all(repo.join("packs/bar"))?;
+    fs::create_dir_all(repo.join("packs/baz"))?;
+    fs::write(repo.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(repo.join("package.yml"), "")?;
+    fs::write(repo.join("packs/foo/package.yml"), "owner: Foo Team\n")?;
+    fs::write(
+        repo.join("packs/bar/package.yml"),
+        "metadata:\n  owner: Bar Team\n",
+    )?;
+    fs::write(repo.join("packs/baz/package.yml"), "")?;
+    fs::write(
+        repo.join("packs/foo/package_todo.yml"),
+        PACKAGE_TODO.replace("    - dependency\n", ""),
+    )?;
+    common::git_at(&repo, &["init", "-q"], "2024-01-10T12:00:00")?;
+    common::git_at(&repo, &["add", "."], "2024-01-10T12:00:00")?;
+    common::git_at(&repo, &["commit", "-qm", "Record"], "2024-01-10T12:00:00")?;
+    fs::write(repo.join("packs/foo/package_todo.yml"), PACKAGE_TODO)?;
+    Ok(repo)
+}
+
+#[test]
+fn test_owners_report() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_owners_report_test")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("owners-report")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "| Team | Packs | Committed | Change | Committed against | Change |",
+        ))
+        .stdout(predicate::str::contains("| Bar Team | 1 | 0 | 0 | 2 | +1 |"))
+        .stdout(predicate::str::contains("| Foo Team | 1 | 2 | +1 | 0 | 0 |"))
+        .stdout(predicate::str::contains(
+            "## Foo Team\n\n- dependency: 1 committed, 0 committed against\n- privacy: 1 committed, 0 committed against\n",
+        ))
+        .stdout(predicate::str::contains("packs/baz").not());
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_owners_report_as_json() -> Result<(), Box<dyn Error>> {
+    let repo = setup("packs_owners_report_json_test")?;
+
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("owners-report")
+        .arg("--format")
+        .arg("json")
+        .output()?;
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert!(report["compared_to"].is_string());
+    assert_eq!("Bar Team", report["teams"][0]["team"]);
+    assert_eq!(2, report["teams"][0]["committed_against"]["total"]);
+    assert_eq!(1, report["teams"][0]["committed_against"]["change"]);
+    assert_eq!(
+        1,
+        report["teams"][0]["committed_against"]["by_violation_type"]
+            ["dependency"]
+    );
+    assert_eq!(
+        serde_json::json!(["packs/foo"]),
+        report["teams"][1]["packs"]
+    );
+
+    fs::remove_dir_all(&repo)?;
+    common::teardown();
+    Ok(())
+}
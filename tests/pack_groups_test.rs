@@ -0,0 +1,22 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_ignores_references_within_a_pack_group(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_groups")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/bar/app/services/bar.rb:3:4\nPrivacy violation: `::Foo` is private to `packs/foo`, but referenced from `packs/bar`"))
+        .stdout(predicate::str::contains("packs/foo_admin").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_includes_defining_pack_owner_and_docs(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_metadata")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`\n`packs/bar` is owned by @payments-team — see https://example.com/payments for its public API"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_json_includes_defining_pack_owner_and_docs(
+) -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_metadata")
+        .arg("check")
+        .arg("--json")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    let violation = &report["violations"][0];
+    assert_eq!(violation["violation_type"], "privacy");
+    assert_eq!(violation["constant_name"], "::Bar");
+    assert_eq!(violation["defining_pack_owner"], "@payments-team");
+    assert_eq!(
+        violation["defining_pack_docs_url"],
+        "https://example.com/payments"
+    );
+    assert_eq!(report["stale_violations"], serde_json::json!([]));
+
+    common::teardown();
+    Ok(())
+}
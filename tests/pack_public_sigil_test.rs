@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_pack_public_sigil() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_public_sigil")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar::PrivateThing` is private to `packs/bar`, but referenced from `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_file_with_pack_public_sigil() -> Result<(), Box<dyn Error>> {
+    // The defining files aren't checked, so they're parsed for their sigils
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_public_sigil")
+        .arg("check")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar::PrivateThing` is private to `packs/bar`, but referenced from `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{error::Error, fs};
+
+mod common;
+
+const PACKWERK_OUTPUT: &str = "\
+packs/foo/app/services/foo.rb:3:4
+Dependency violation: ::Bar belongs to 'packs/bar', but 'packs/foo/package.yml' does not specify a dependency on 'packs/bar'.
+
+packs/foo/app/services/foo.rb:3:4
+Privacy violation: '::Bar' is private to 'packs/bar' but referenced from 'packs/foo'.
+
+packs/foo/app/services/foo.rb:1:0
+Privacy violation: '::Missing' is private to 'packs/bar' but referenced from 'packs/foo'.
+
+3 offenses detected
+";
+
+#[test]
+fn test_parity_check() -> Result<(), Box<dyn Error>> {
+    let packwerk_output = std::env::temp_dir().join("packs_parity_check.txt");
+    fs::write(&packwerk_output, PACKWERK_OUTPUT)?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parity-check")
+        .arg("--packwerk-output")
+        .arg(&packwerk_output)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packwerk reported 3 violation(s), packs reported 2",
+        ))
+        .stdout(predicate::str::contains(
+            "Only reported by packwerk, packs found no reference to the constant in the file (1):\n- privacy packs/foo/app/services/foo.rb ::Missing",
+        ))
+        .stderr(predicate::str::contains(
+            "1 disagreement(s) between packs and packwerk",
+        ));
+
+    fs::remove_file(packwerk_output)?;
+    common::teardown();
+    Ok(())
+}
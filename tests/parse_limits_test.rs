@@ -0,0 +1,25 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_skips_files_over_max_file_size() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_parse_limits")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`",
+        ))
+        .stderr(predicate::str::contains(
+            "generated.rb, it is 253 bytes, over the `max_file_size` of 200",
+        ));
+
+    common::teardown();
+    Ok(())
+}
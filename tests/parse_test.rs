@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_parse() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parse")
+        .arg("packs/foo/app/services/foo.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "References in packs/foo/app/services/foo.rb:",
+        ))
+        .stdout(predicate::str::contains("- 7:4 Baz (nested in Foo)"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_parse_json() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parse")
+        .arg("packs/foo/app/services/foo.rb")
+        .arg("--json")
+        .output()?;
+    assert!(output.status.success());
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!("packs/foo/app/services/foo.rb", parsed["file"]);
+    assert_eq!("packwerk", parsed["parser"]);
+    let baz = parsed["references"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|reference| reference["name"] == "Baz")
+        .expect("Baz should be referenced");
+    assert_eq!(serde_json::json!(["Foo"]), baz["namespace_path"]);
+    assert_eq!(7, baz["location"]["start_row"]);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_parse_missing_file() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("parse")
+        .arg("packs/foo/app/services/missing.rb")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`packs/foo/app/services/missing.rb` is not a file",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+use packs::packs::pipeline::{
+    self, Configuration, ConstantDefinition, ConstantResolver, DiscoveredFiles,
+    Reference, Violation,
+};
+
+mod common;
+
+fn configuration() -> Configuration {
+    packs::packs::configuration(PathBuf::from("tests/fixtures/simple_app"))
+}
+
+#[test]
+fn test_pipeline_stages_find_the_violations_check_does(
+) -> Result<(), Box<dyn Error>> {
+    let configuration = configuration();
+
+    let files = pipeline::discover_files(
+        &configuration,
+        vec![String::from("packs/foo/app/services/foo.rb")],
+    );
+    assert_eq!(1, files.files.len());
+
+    let processed_files = pipeline::parse(&configuration, &files);
+    let resolver = pipeline::default_resolver(&configuration);
+    let references =
+        pipeline::resolve(&configuration, resolver.as_ref(), &processed_files);
+    let violations = pipeline::check(&configuration, references);
+
+    let violation_types: Vec<&str> = violations
+        .iter()
+        .map(|violation| violation.identifier.violation_type.as_str())
+        .collect();
+    assert_eq!(vec!["dependency", "privacy"], violation_types);
+    assert!(pipeline::report(&configuration, &files, violations).is_err());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_pipeline_stages_serialize() -> Result<(), Box<dyn Error>> {
+    let configuration = configuration();
+
+    let files = pipeline::discover_files(&configuration, vec![]);
+    let files: DiscoveredFiles =
+        serde_json::from_str(&serde_json::to_string(&files)?)?;
+    let processed_files = pipeline::parse(&configuration, &files);
+    let resolver = pipeline::default_resolver(&configuration);
+    let references =
+        pipeline::resolve(&configuration, resolver.as_ref(), &processed_files);
+    let references: Vec<Reference> =
+        serde_json::from_str(&serde_json::to_string(&references)?)?;
+    let violations = pipeline::check(&configuration, references);
+    let json = serde_json::to_string(&violations)?;
+    let violations_from_json: Vec<Violation> = serde_json::from_str(&json)?;
+
+    assert_eq!(violations, violations_from_json);
+
+    common::teardown();
+    Ok(())
+}
+
+// Resolves nothing, so every reference is to an unknown constant
+struct NothingResolver {
+    definitions: HashMap<String, Vec<ConstantDefinition>>,
+}
+
+impl ConstantResolver for NothingResolver {
+    fn resolve(
+        &self,
+        _fully_or_partially_qualified_constant: &str,
+        _namespace_path: &[&str],
+    ) -> Option<Vec<ConstantDefinition>> {
+        None
+    }
+
+    fn fully_qualified_constant_name_to_constant_definition_map(
+        &self,
+    ) -> &HashMap<String, Vec<ConstantDefinition>> {
+        &self.definitions
+    }
+}
+
+#[test]
+fn test_pipeline_with_a_custom_resolver() -> Result<(), Box<dyn Error>> {
+    let configuration = configuration();
+
+    let files = pipeline::discover_files(&configuration, vec![]);
+    let processed_files = pipeline::parse(&configuration, &files);
+    let resolver = NothingResolver {
+        definitions: HashMap::new(),
+    };
+    let references =
+        pipeline::resolve(&configuration, &resolver, &processed_files);
+
+    assert!(references
+        .iter()
+        .all(|reference| reference.defining_pack_name.is_none()));
+    assert!(pipeline::check(&configuration, references).is_empty());
+    assert!(pipeline::report(&configuration, &files, vec![]).is_ok());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_all_returns_what_check_finds() -> Result<(), Box<dyn Error>> {
+    let configuration = configuration();
+
+    let result = pipeline::check_all(&configuration, vec![]);
+
+    let violation_types: Vec<&str> = result
+        .violations
+        .iter()
+        .map(|violation| violation.identifier.violation_type.as_str())
+        .collect();
+    assert_eq!(vec!["dependency", "privacy"], violation_types);
+    assert_eq!(0, result.recorded_violations_count);
+    assert!(result.stale_todos.is_empty());
+    assert!(result.parse_errors.is_empty());
+    let stages: Vec<&str> = result
+        .timings
+        .iter()
+        .map(|timing| timing.stage.as_str())
+        .collect();
+    assert_eq!(vec!["finding references", "checking references"], stages);
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_all_returns_parse_errors() -> Result<(), Box<dyn Error>> {
+    let configuration = packs::packs::configuration(PathBuf::from(
+        "tests/fixtures/app_with_parse_limits",
+    ));
+
+    let result = pipeline::check_all(&configuration, vec![]);
+
+    assert_eq!(1, result.parse_errors.len());
+    assert!(result.parse_errors[0].path.ends_with("generated.rb"));
+    assert_eq!(
+        "it is 253 bytes, over the `max_file_size` of 200",
+        result.parse_errors[0].reason
+    );
+
+    common::teardown();
+    Ok(())
+}
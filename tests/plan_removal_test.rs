@@ -0,0 +1,44 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_plan_removal() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("plan-removal")
+        .arg("packs/foo")
+        .arg("packs/bar")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "# Removing the dependency of `packs/foo` on `packs/bar`\n\n1 reference(s) to 1 constant(s).",
+        ))
+        .stdout(predicate::str::contains(
+            "- [ ] `::Bar` (packs/bar/app/services/bar.rb)\n  - [ ] packs/foo/app/services/foo.rb:3:4\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_plan_removal_without_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("plan-removal")
+        .arg("packs/bar")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "`packs/bar` doesn't reference `packs/foo`, so `packs/foo` can be removed from its dependencies.",
+        ));
+
+    common::teardown();
+    Ok(())
+}
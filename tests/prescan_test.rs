@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+
+mod common;
+
+#[test]
+fn test_check_skips_files_without_constants() -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_prescan_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo/app/services"))?;
+    fs::create_dir_all(app.join("packs/bar/app/services"))?;
+    fs::create_dir_all(app.join("lib"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: true\n",
+    )?;
+    fs::write(app.join("packs/bar/package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/app/services/foo.rb"),
+        "class Foo\n  def helper\n    Helper\n  end\nend\n",
+    )?;
+    fs::write(
+        app.join("packs/bar/app/services/helper.rb"),
+        "class Helper\nend\n",
+    )?;
+    fs::write(
+        app.join("lib/tasks.rb"),
+        "# Runs Foo's tasks\ndef run_tasks\n  system('rake tasks')\nend\n",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("--timing")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("`::Helper`"))
+        .stderr(predicate::str::contains(
+            "Timing: skipped parsing 1 file(s) with nothing that looks like a constant",
+        ));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_parses_files_with_only_associations() -> Result<(), Box<dyn Error>>
+{
+    let app = std::env::temp_dir().join("packs_prescan_associations_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo/app/models/foo"))?;
+    fs::create_dir_all(app.join("packs/bar/app/models"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: true\n",
+    )?;
+    fs::write(app.join("packs/bar/package.yml"), "")?;
+    // Evaluated in the body of a model, so it has no constants of its own
+    fs::write(
+        app.join("packs/foo/app/models/foo/associations.rb"),
+        "has_many :bars\n",
+    )?;
+    fs::write(app.join("packs/bar/app/models/bar.rb"), "class Bar\nend\n")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("--timing")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("`::Bar`"))
+        .stderr(predicate::str::contains(
+            "Timing: skipped parsing 0 file(s) with nothing that looks like a constant",
+        ));
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
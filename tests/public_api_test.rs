@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_public_api() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains(
+            "Privacy violation: `::Bar::Receipt` is private to `packs/bar`, but referenced from `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_public_api() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_public_api")
+        .arg("validate")
+        .assert()
+        .success();
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_invalid_public_api() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_invalid_public_api")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/bar/public_api.yml lists `::Bar::Refund`, which is not defined in packs/bar\n\
+             packs/bar/public_api.yml lists `::Bar::Invoice#void`, which is not defined",
+        ));
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use serde_json::json;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_json_includes_quick_fixes() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--json")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    let violations = report["violations"].as_array().unwrap();
+    let fixes_for = |violation_type: &str| {
+        violations
+            .iter()
+            .find(|v| v["violation_type"] == violation_type)
+            .unwrap()["fixes"]
+            .clone()
+    };
+    assert_eq!(
+        json!([{
+            "title": "Add `packs/bar` to the dependencies of `packs/foo`",
+            "kind": "add_to_list",
+            "file": "packs/foo/package.yml",
+            "key": "dependencies",
+            "value": "packs/bar",
+        }]),
+        fixes_for("dependency")
+    );
+    assert_eq!(
+        json!([{
+            "title": "Move `::Bar` into the public folder of `packs/bar`",
+            "kind": "move_file",
+            "from": "packs/bar/app/services/bar.rb",
+            "to": "packs/bar/app/public/bar.rb",
+        }]),
+        fixes_for("privacy")
+    );
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use assert_cmd::prelude::*;
+use std::{error::Error, path::Path, process::Command};
+mod common;
+
+const CACHE_DIRECTORY: &str =
+    "tests/fixtures/app_with_cache/tmp/cache/packwerk";
+
+#[test]
+fn test_check_with_no_cache_write() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_cache")
+        .arg("--no-cache-write")
+        .arg("check")
+        .assert()
+        .success();
+
+    assert!(!Path::new(CACHE_DIRECTORY).exists());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_packs_cache_dir() -> Result<(), Box<dyn Error>> {
+    let cache_dir = std::env::temp_dir().join("packs_cache_dir_test");
+
+    Command::cargo_bin("packs")?
+        .env("PACKS_CACHE_DIR", &cache_dir)
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_cache")
+        .arg("check")
+        .assert()
+        .success();
+
+    assert!(!Path::new(CACHE_DIRECTORY).exists());
+    assert!(cache_dir.join("zeitwerk").exists());
+
+    std::fs::remove_dir_all(cache_dir)?;
+    common::teardown();
+    Ok(())
+}
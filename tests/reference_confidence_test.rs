@@ -0,0 +1,44 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_reports_every_confidence() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_reference_confidence")
+        .arg("check")
+        .arg("--json")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"constant_name\": \"::Bar\""))
+        .stdout(predicate::str::contains(
+            "\"confidence\": \"inferred_association\"",
+        ))
+        .stdout(predicate::str::contains("\"constant_name\": \"::Baz\""))
+        .stdout(predicate::str::contains("\"confidence\": \"exact\""));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_min_confidence() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_reference_confidence")
+        .arg("check")
+        .arg("--min-confidence")
+        .arg("exact")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::Baz` belongs to `packs/baz`",
+        ))
+        .stdout(predicate::str::contains("`::Bar`").not());
+
+    common::teardown();
+    Ok(())
+}
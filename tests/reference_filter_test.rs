@@ -0,0 +1,26 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_with_reference_filter() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_reference_filter")
+        .arg("--timing")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"))
+        // Class and module declarations count as references too, e.g.
+        // `module Bar` in each of packs/bar's files
+        .stderr(predicate::str::contains(
+            "Timing: reference filter: 4 reference(s) kept, 2 dropped by path, 2 dropped by constant, 0 dropped as signatures, 0 dropped within a pack group, 0 dropped from the root pack, 0 dropped below the minimum confidence",
+        ));
+
+    common::teardown();
+    Ok(())
+}
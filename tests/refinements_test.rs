@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+// `refine Bar` references `::Bar`, in packs/bar, while the constants in the
+// refinement and `using BarRefinements` are looked up in `Foo`, in packs/foo
+fn assert_refined_constant_is_referenced(
+    args: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_refinements")
+        .args(args)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo/bar_refinements.rb:3:11\nDependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`."));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_refinements() -> Result<(), Box<dyn Error>> {
+    assert_refined_constant_is_referenced(&[])
+}
+
+#[test]
+fn test_check_refinements_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    assert_refined_constant_is_referenced(&["--experimental-parser"])
+}
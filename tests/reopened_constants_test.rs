@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_validate_reopened_constants() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_reopened_constants")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "`::Billing::Invoice` is defined in more than one pack:\n\
+             - packs/billing/app/models/billing/invoice.rb (packs/billing)\n\
+             - packs/reports/app/models/billing/invoice.rb (packs/reports)",
+        ))
+        .stdout(predicate::str::contains("ApplicationRecord").not());
+
+    common::teardown();
+    Ok(())
+}
+
+// Without `reopened_constants` in packwerk.yml, constants may be defined in
+// several packs
+#[test]
+fn test_validate_reopened_constants_by_default() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("validate")
+        .assert()
+        .success();
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_reopened_constants_without_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_reopened_constants_test");
+    let _ = std::fs::remove_dir_all(&app);
+    std::fs::create_dir_all(&app)?;
+    std::fs::write(
+        app.join("packwerk.yml"),
+        "cache: false\nreopened_constants: {}\n",
+    )?;
+    std::fs::write(app.join("package.yml"), "")?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "`reopened_constants` in packwerk.yml needs `experimental_parser: true`",
+        ));
+
+    std::fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+mod common;
+
+#[test]
+fn test_report_compares_with_the_previous_report() -> Result<(), Box<dyn Error>>
+{
+    let state_file = std::env::temp_dir().join("packs_report_test.json");
+    let _ = std::fs::remove_file(&state_file);
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("report")
+        .arg("--state-file")
+        .arg(&state_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"violations_count\": 2"))
+        .stdout(predicate::str::contains("\"new_violations_count\": null"))
+        .stdout(predicate::str::contains(
+            "\"pack\": \"packs/foo\",\n      \"violations_count\": 2",
+        ));
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("report")
+        .arg("--state-file")
+        .arg(&state_file)
+        .arg("--format")
+        .arg("slack")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0 new and 0 resolved since the previous report",
+        ))
+        .stdout(predicate::str::contains("• `packs/foo`: 2"));
+
+    std::fs::remove_file(&state_file)?;
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_report_posts_to_webhook() -> Result<(), Box<dyn Error>> {
+    let state_file =
+        std::env::temp_dir().join("packs_report_webhook_test.json");
+    let _ = std::fs::remove_file(&state_file);
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/hooks/secret", listener.local_addr()?);
+
+    // Answers a single request, and hands back its request line and body
+    let webhook = std::thread::spawn(move || -> (String, String) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim_end().to_lowercase();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("content-length:") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        (&stream)
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+            .unwrap();
+        (request_line, String::from_utf8(body).unwrap())
+    });
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("report")
+        .arg("--state-file")
+        .arg(&state_file)
+        .arg("--webhook")
+        .arg(&url)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let (request_line, body) = webhook.join().unwrap();
+    assert_eq!("POST /hooks/secret HTTP/1.1\r\n", request_line);
+    assert!(body.contains("\"violations_count\": 2"));
+
+    std::fs::remove_file(&state_file)?;
+    common::teardown();
+    Ok(())
+}
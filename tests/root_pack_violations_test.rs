@@ -0,0 +1,64 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_summarizes_violations_from_root_pack(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_root_pack_violations")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("app/services/root_service.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `.`"))
+        .stdout(predicate::str::contains(
+            "1 violation(s) from unpacked code (the root pack)",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_enforce_from_root_disabled() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_root_pack_violations_unenforced")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:3:4\nPrivacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("root_service.rb").not())
+        .stdout(predicate::str::contains("from unpacked code").not())
+        // The root pack's todo still records its violation, which isn't
+        // stale just because it isn't reported
+        .stdout(predicate::str::contains("stale violations").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_root_pack_privacy() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_root_pack_privacy")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        // From a pack to the root pack
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb:4:4\nPrivacy violation: `::RootInternal` is private to `.`, but referenced from `packs/foo`"))
+        .stdout(predicate::str::contains("::RootApi").not())
+        // From the root pack to a pack
+        .stdout(predicate::str::contains("app/controllers/root_controller.rb:4:4\nPrivacy violation: `::FooInternal` is private to `packs/foo`, but referenced from `.`"))
+        .stdout(predicate::str::contains("::FooApi").not());
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use assert_cmd::cargo::cargo_bin;
+use std::error::Error;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+
+mod common;
+
+// Kills the server when the test ends, even if it fails
+struct Server {
+    child: Child,
+    address: String,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn start_server(
+    project_root: impl AsRef<Path>,
+) -> Result<Server, Box<dyn Error>> {
+    let mut child = Command::new(cargo_bin("packs"))
+        .arg("--project-root")
+        .arg(project_root.as_ref())
+        .arg("server")
+        .arg("--http")
+        .arg("127.0.0.1:0")
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line)?;
+    let address = line
+        .trim()
+        .strip_prefix("Listening on http://")
+        .ok_or(format!("Unexpected output: {}", line))?
+        .to_owned();
+    Ok(Server { child, address })
+}
+
+fn request(
+    server: &Server,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Result<String, Box<dyn Error>> {
+    let mut stream = TcpStream::connect(&server.address)?;
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+#[test]
+fn test_server_packs() -> Result<(), Box<dyn Error>> {
+    let server = start_server("tests/fixtures/simple_app")?;
+
+    let response = request(&server, "GET", "/packs", "")?;
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\"name\": \"packs/foo\""));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_server_check() -> Result<(), Box<dyn Error>> {
+    let server = start_server("tests/fixtures/simple_app")?;
+
+    let response = request(
+        &server,
+        "POST",
+        "/check",
+        r#"{"files": ["packs/foo/app/services/foo.rb"]}"#,
+    )?;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\"constant_name\": \"::Bar\""));
+
+    // The server keeps answering after the first request
+    let response = request(&server, "GET", "/violations", "")?;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\"constant_name\": \"::Bar\""));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_server_errors() -> Result<(), Box<dyn Error>> {
+    let server = start_server("tests/fixtures/simple_app")?;
+
+    let response = request(&server, "POST", "/check", "{}")?;
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(response.contains("Expected a body like {\\\"files\\\": [...]}"));
+
+    let response = request(&server, "DELETE", "/packs", "")?;
+    assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+    let response = request(&server, "GET", "/nope", "")?;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    common::teardown();
+    Ok(())
+}
+
+const BAR_TODO: &str = "\
+---
+\"packs/foo\":
+  \"::Foo\":
+    violations:
+    - dependency
+    files:
+    - packs/bar/app/services/bar.rb
+";
+
+#[test]
+fn test_server_update() -> Result<(), Box<dyn Error>> {
+    // `update` writes todo files, so it runs on an app of its own
+    let app = std::env::temp_dir().join("packs_server_update_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo/app/services"))?;
+    fs::create_dir_all(app.join("packs/bar/app/services"))?;
+    fs::write(app.join("packwerk.yml"), "cache: false\n")?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: true\n",
+    )?;
+    fs::write(app.join("packs/bar/package.yml"), "")?;
+    fs::write(
+        app.join("packs/foo/app/services/foo.rb"),
+        "module Foo\nend\n",
+    )?;
+    fs::write(
+        app.join("packs/bar/app/services/bar.rb"),
+        "class Bar\nend\n",
+    )?;
+    // Stale, but packs/bar has no changed files, so it's left alone
+    fs::write(app.join("packs/bar/package_todo.yml"), BAR_TODO)?;
+    let server = start_server(&app)?;
+
+    let response = request(&server, "POST", "/update", "")?;
+    assert!(response.ends_with("{\"updated_packs\":[]}\n"));
+
+    fs::write(
+        app.join("packs/foo/app/services/foo.rb"),
+        "module Foo\n  def calls_bar\n    ::Bar\n  end\nend\n",
+    )?;
+    let response = request(&server, "POST", "/update", "")?;
+    assert!(response.ends_with("{\"updated_packs\":[\"packs/foo\"]}\n"));
+    assert!(fs::read_to_string(app.join("packs/foo/package_todo.yml"))?
+        .contains("\"::Bar\""));
+    assert_eq!(
+        BAR_TODO,
+        fs::read_to_string(app.join("packs/bar/package_todo.yml"))?
+    );
+
+    // The violation is recorded now
+    let response = request(&server, "GET", "/violations", "")?;
+    assert!(response.contains("\"violations\": []"));
+
+    // packs/foo references a constant defined in the changed file
+    fs::write(
+        app.join("packs/bar/app/services/bar.rb"),
+        "class Bar\n  def self.call; end\nend\n",
+    )?;
+    let response = request(&server, "POST", "/update", "")?;
+    assert!(response
+        .ends_with("{\"updated_packs\":[\"packs/bar\",\"packs/foo\"]}\n"));
+    assert!(!app.join("packs/bar/package_todo.yml").exists());
+
+    fs::write(
+        app.join("packs/foo/package.yml"),
+        "enforce_dependencies: true\ndependencies:\n  - packs/bar\n",
+    )?;
+    let response = request(&server, "POST", "/update", "")?;
+    assert!(response.ends_with("{\"updated_packs\":[\"packs/foo\"]}\n"));
+    assert!(!app.join("packs/foo/package_todo.yml").exists());
+
+    fs::write(app.join("packs/bar/package_todo.yml"), BAR_TODO)?;
+    let response = request(&server, "POST", "/update", "")?;
+    assert!(response.ends_with("{\"updated_packs\":[\"packs/bar\"]}\n"));
+    assert!(!app.join("packs/bar/package_todo.yml").exists());
+
+    drop(server);
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
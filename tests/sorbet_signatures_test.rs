@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_ignoring_signature_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_sorbet_signatures")
+        .arg("--timing")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"))
+        .stderr(predicate::str::contains(
+            "Timing: reference filter: 2 reference(s) kept, 0 dropped by path, 0 dropped by constant, 3 dropped as signatures, 0 dropped within a pack group, 0 dropped from the root pack",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_parse_rbi_file() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_sorbet_signatures")
+        .arg("parse")
+        .arg("--json")
+        .arg("sorbet/rbi/dsl/foo.rbi")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""name": "Bar""#))
+        .stdout(predicate::str::contains(r#""kind": "signature""#))
+        .stdout(predicate::str::contains(r#""kind": "code""#).not());
+
+    common::teardown();
+    Ok(())
+}
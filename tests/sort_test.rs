@@ -0,0 +1,83 @@
+use assert_cmd::Command;
+use std::error::Error;
+
+mod common;
+
+// The referencing file and constant of each violation, in the order `check`
+// reports them
+fn violations_sorted_by(sort: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_sortable_violations")
+        .arg("check")
+        .arg("--json")
+        .arg("--sort")
+        .arg(sort)
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    Ok(report["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            format!(
+                "{} {}",
+                v["file"].as_str().unwrap(),
+                v["constant_name"].as_str().unwrap()
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn test_check_sorted_by_file() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        vec![
+            "packs/baz/app/services/baz.rb ::Bar",
+            "packs/foo/app/services/alpha.rb ::Zed",
+            "packs/foo/app/services/beta.rb ::Bar",
+            "packs/foo/app/services/beta.rb ::Zed",
+        ],
+        violations_sorted_by("file")?
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_sorted_by_constant() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        vec![
+            "packs/baz/app/services/baz.rb ::Bar",
+            "packs/foo/app/services/beta.rb ::Bar",
+            "packs/foo/app/services/alpha.rb ::Zed",
+            "packs/foo/app/services/beta.rb ::Zed",
+        ],
+        violations_sorted_by("constant")?
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_sorted_by_count() -> Result<(), Box<dyn Error>> {
+    assert_eq!(
+        vec![
+            "packs/foo/app/services/beta.rb ::Bar",
+            "packs/foo/app/services/beta.rb ::Zed",
+            "packs/baz/app/services/baz.rb ::Bar",
+            "packs/foo/app/services/alpha.rb ::Zed",
+        ],
+        violations_sorted_by("count")?
+    );
+
+    common::teardown();
+    Ok(())
+}
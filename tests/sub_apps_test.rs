@@ -0,0 +1,44 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_list_packs_includes_sub_app_packs() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_sub_apps")
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gems/shared/package.yml"))
+        .stdout(predicate::str::contains(
+            "gems/shared/packs/widgets/package.yml",
+        ))
+        .stdout(predicate::str::contains("packs/foo/package.yml"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_sub_apps() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_sub_apps")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::Widget` belongs to `gems/shared/packs/widgets`, but `packs/foo/package.yml` does not specify a dependency on `gems/shared/packs/widgets`.",
+        ))
+        // `packs/parts` in the sub-app's package.yml is the sub-app's pack
+        .stdout(predicate::str::contains(
+            "Dependency violation: `::Bolt` belongs to `packs/parts`, but `gems/shared/packs/widgets/package.yml` does not specify a dependency on `packs/parts`.",
+        ))
+        .stdout(predicate::str::contains("`::Gear`").not());
+
+    common::teardown();
+    Ok(())
+}
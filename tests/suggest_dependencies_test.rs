@@ -0,0 +1,40 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_suggest_dependencies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_suggested_dependencies")
+        .arg("--no-cache")
+        .arg("suggest-dependencies")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "dependencies:\n- packs/bar # 1 reference(s)\n- packs/baz # 1 reference(s)\n",
+        ))
+        .stdout(predicate::str::contains(
+            "These dependencies would create a cycle:\n- packs/foo -> packs/bar -> packs/baz -> packs/foo\n- packs/foo -> packs/baz -> packs/foo\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_suggest_dependencies_for_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_suggested_dependencies")
+        .arg("suggest-dependencies")
+        .arg("packs/nope")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`packs/nope` not found"));
+
+    common::teardown();
+    Ok(())
+}
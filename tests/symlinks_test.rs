@@ -0,0 +1,69 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+// `packs/bar_alias` and `packs/foo_alias` link to `packs/bar` and
+// `packs/foo`, and `packs/baz` links to `vendor/baz`, which isn't walked
+const APP: &str = "tests/fixtures/app_with_symlinked_packs";
+
+#[test]
+fn test_packs_reached_through_symlinks_are_listed_once(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("list-packs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/bar/package.yml"))
+        .stdout(predicate::str::contains("packs/baz/package.yml"))
+        .stdout(predicate::str::contains("_alias").not());
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("list-included-files")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("packs/foo/app/services/foo.rb"))
+        .stdout(predicate::str::contains("packs/baz/app/services/baz.rb"))
+        .stdout(predicate::str::contains("_alias").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_violations_through_symlinks_are_reported_once(
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 violation(s) detected:"))
+        .stdout(predicate::str::contains("_alias").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_a_file_through_a_symlink() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .arg("packs/foo_alias/app/services/foo.rb")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:3:4\nDependency violation: `::Bar` belongs to `packs/bar`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
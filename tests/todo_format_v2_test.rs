@@ -0,0 +1,115 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serial_test::serial;
+use std::error::Error;
+
+mod common;
+
+#[test]
+#[serial]
+fn test_todo_oldest() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_todo_format_v2")
+        .arg("todo")
+        .arg("oldest")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "2023-06-01 ::Baz (dependency) from `packs/foo` to `packs/baz`, 1 occurrence(s), recorded in def5678\n\
+            2024-01-15 ::Bar (dependency) from `packs/foo` to `packs/bar`, 1 occurrence(s), recorded in abc1234\n",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_todo_oldest_with_limit() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_todo_format_v2")
+        .arg("todo")
+        .arg("oldest")
+        .arg("--limit")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("::Baz"))
+        .stdout(predicate::str::contains("::Bar").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_todo_oldest_without_v2_metadata() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/contains_stale_violations")
+        .arg("todo")
+        .arg("oldest")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No recorded violation has a first recorded date",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_update_keeps_first_recorded() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_todo_format_v2")
+        .arg("update")
+        .assert()
+        .success();
+
+    let foo_todo = std::fs::read_to_string(
+        "tests/fixtures/app_with_todo_format_v2/packs/foo/package_todo.yml",
+    )?;
+    assert!(foo_todo.contains(
+        "  \"::Bar\":\n    violations:\n    - dependency\n    files:\n    - packs/foo/app/services/foo.rb\n    occurrences:\n      dependency: 1\n    first_recorded: 2024-01-15\n    recorded_in: abc1234\n"
+    ));
+    assert!(foo_todo.contains(
+        "    first_recorded: 2023-06-01\n    recorded_in: def5678\n"
+    ));
+
+    common::teardown();
+    common::set_up_fixtures();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_todo_top_offenders() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_todo_format_v2")
+        .arg("todo")
+        .arg("top-offenders")
+        .arg("--limit")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(
+            "\
+Constants with the most violations:
+  1 ::Bar
+
+Packs with the most inbound violations:
+  1 packs/bar
+
+Files with the most outbound violations:
+  2 packs/foo/app/services/foo.rb
+",
+        );
+
+    common::teardown();
+    Ok(())
+}
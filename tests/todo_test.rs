@@ -0,0 +1,135 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use serial_test::serial;
+use std::{error::Error, fs, path::Path, process::Command};
+
+mod common;
+
+const FIXTURE: &str = "tests/fixtures/app_with_consolidated_todo";
+
+#[test]
+#[serial]
+fn test_check_with_consolidated_todo() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(FIXTURE)
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_todo_split_and_consolidate() -> Result<(), Box<dyn Error>> {
+    let consolidated_todo_path = Path::new(FIXTURE).join("packwerk_todo.yml");
+    let package_todo_path =
+        Path::new(FIXTURE).join("packs/foo/package_todo.yml");
+    let original_consolidated_todo =
+        fs::read_to_string(&consolidated_todo_path)?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(FIXTURE)
+        .arg("todo")
+        .arg("split")
+        .assert()
+        .success();
+
+    assert!(!consolidated_todo_path.exists());
+    let expected_package_todo = String::from(
+        "\
+# This file contains a list of dependencies that are not part of the long term plan for the
+# 'packs/foo' package.
+# We should generally work to reduce this list over time.
+#
+# You can regenerate this file using the following command:
+#
+# bin/packwerk update-todo
+---
+packs/bar:
+  \"::Bar\":
+    violations:
+    - dependency
+    files:
+    - packs/foo/app/services/foo.rb
+",
+    );
+    assert_eq!(
+        expected_package_todo,
+        fs::read_to_string(&package_todo_path)?
+    );
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(FIXTURE)
+        .arg("todo")
+        .arg("consolidate")
+        .assert()
+        .success();
+
+    assert!(!package_todo_path.exists());
+    assert_eq!(
+        original_consolidated_todo,
+        fs::read_to_string(&consolidated_todo_path)?
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_todo_split_with_a_deleted_pack() -> Result<(), Box<dyn Error>> {
+    let app = std::env::temp_dir().join("packs_todo_split_deleted_pack_test");
+    let _ = fs::remove_dir_all(&app);
+    fs::create_dir_all(app.join("packs/foo"))?;
+    fs::write(
+        app.join("packwerk.yml"),
+        "cache: false\ntodo_storage: consolidated\n",
+    )?;
+    fs::write(app.join("package.yml"), "")?;
+    fs::write(app.join("packs/foo/package.yml"), "")?;
+    fs::write(
+        app.join("packwerk_todo.yml"),
+        "\
+---
+packs/deleted:
+  packs/foo:
+    \"::Foo\":
+      violations:
+      - dependency
+      files:
+      - packs/deleted/app/services/deleted.rb
+packs/foo:
+  packs/bar:
+    \"::Bar\":
+      violations:
+      - dependency
+      files:
+      - packs/foo/app/services/foo.rb
+",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&app)
+        .arg("todo")
+        .arg("split")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: `packs/deleted` has violations in packwerk_todo.yml, but that pack cannot be found, so they were dropped",
+        ));
+
+    assert!(fs::read_to_string(app.join("packs/foo/package_todo.yml"))?
+        .contains("\"::Bar\""));
+    assert!(!app.join("packwerk_todo.yml").exists());
+
+    fs::remove_dir_all(app)?;
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+// In packs/foo/app/services/foo/baz.rb, `Bar` is `::Foo::Bar`, in packs/foo,
+// while `::Bar` is the top-level constant in packs/bar
+fn assert_top_level_reference_skips_nesting(
+    args: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_top_level_references")
+        .args(args)
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 violation(s) detected:"))
+        .stdout(predicate::str::contains("packs/foo/app/services/foo/baz.rb:8:6\nDependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`."));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_top_level_reference() -> Result<(), Box<dyn Error>> {
+    assert_top_level_reference_skips_nesting(&[])
+}
+
+#[test]
+fn test_check_top_level_reference_with_experimental_parser(
+) -> Result<(), Box<dyn Error>> {
+    assert_top_level_reference_skips_nesting(&["--experimental-parser"])
+}
@@ -0,0 +1,56 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::{error::Error, process::Command};
+mod common;
+
+#[test]
+fn test_trace() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("trace")
+        .arg("packs/foo")
+        .arg("packs/bar")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:3:4 ::Bar\n\n1 reference(s) from `packs/foo` to `packs/bar`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_trace_without_references() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("trace")
+        .arg("packs/bar")
+        .arg("packs/foo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No references from `packs/bar` to `packs/foo`",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_trace_with_unknown_pack() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("trace")
+        .arg("packs/foo")
+        .arg("packs/nope")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`packs/nope` not found"));
+
+    common::teardown();
+    Ok(())
+}
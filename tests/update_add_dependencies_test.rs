@@ -0,0 +1,44 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+use serial_test::serial;
+use std::{collections::HashSet, error::Error, path::PathBuf};
+
+mod common;
+
+#[test]
+#[serial]
+fn test_update_add_dependencies() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_dependencies_to_add")
+        .arg("update")
+        .arg("--add-dependencies")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added `packs/baz` as dependencies of `packs/foo`",
+        ))
+        .stdout(predicate::str::contains(
+            "1 missing dependencies would create a cycle, so their violations were recorded instead",
+        ));
+
+    let config = packs::packs::configuration(PathBuf::from(
+        "tests/fixtures/app_with_dependencies_to_add",
+    ));
+    let foo = config.pack_set.for_pack("packs/foo").unwrap();
+    let expected: HashSet<String> =
+        HashSet::from([String::from("packs/bar"), String::from("packs/baz")]);
+    assert_eq!(expected, foo.dependencies);
+
+    let bar_todo = std::fs::read_to_string(
+        "tests/fixtures/app_with_dependencies_to_add/packs/bar/package_todo.yml",
+    )?;
+    assert!(bar_todo.contains(
+        "packs/foo:\n  \"::Foo\":\n    violations:\n    - dependency"
+    ));
+
+    common::teardown();
+    common::set_up_fixtures();
+    Ok(())
+}
@@ -28,3 +28,42 @@ packs/foo, packs/bar",
     common::teardown();
     Ok(())
 }
+
+#[test]
+fn test_validate_pack_budgets() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_pack_budgets")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "packs/foo is over budget: max_files is 2 (budget is 1)\npacks/foo is over budget: max_dependencies is 1 (budget is 0)",
+        ))
+        .stdout(predicate::str::contains("packs/bar is over budget").not());
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_validate_unenforced_recorded_violations() -> Result<(), Box<dyn Error>>
+{
+    Command::cargo_bin("packs")
+        .unwrap()
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_unenforced_todos")
+        .arg("validate")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("1 validation error(s) detected:"))
+        .stdout(predicate::str::contains(
+            "1 recorded violation(s) are no longer enforced, run `packs update` to remove them:\n- privacy violation on `::Bar` in packs/foo/app/services/foo.rb",
+        ))
+        .stdout(predicate::str::contains("dependency violation").not());
+
+    common::teardown();
+    Ok(())
+}
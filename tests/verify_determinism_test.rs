@@ -0,0 +1,21 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_verify_determinism() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("verify-determinism")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Results are identical on 1 thread and on",
+        ));
+
+    common::teardown();
+    Ok(())
+}
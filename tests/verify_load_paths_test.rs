@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_verify_load_paths_with_drift() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_load_paths_dump")
+        .arg("verify-load-paths")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Load paths in `config/load_paths.json` that packs does not infer:\n- packs/foo/lib\n",
+        ))
+        .stdout(predicate::str::contains("Load paths inferred by packs").not())
+        .stderr(predicate::str::contains(
+            "1 load path(s) differ between `config/load_paths.json` and packs",
+        ));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_verify_load_paths_without_dump() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("verify-load-paths")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No `load_paths_dump` is configured in packwerk.yml",
+        ));
+
+    common::teardown();
+    Ok(())
+}
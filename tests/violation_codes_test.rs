@@ -0,0 +1,55 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_prints_violation_codes() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_violation_codes")
+        .arg("check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Dependency violation: `::Bar` belongs to `packs/bar`, but `packs/foo/package.yml` does not specify a dependency on `packs/bar`.\n[P001] See https://wiki.example.com/modularity/P001\n"))
+        .stdout(predicate::str::contains("Privacy violation: `::Bar` is private to `packs/bar`, but referenced from `packs/foo`\n[P002]\n"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_json_includes_violation_codes() -> Result<(), Box<dyn Error>> {
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_violation_codes")
+        .arg("check")
+        .arg("--json")
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    let violations = report["violations"].as_array().unwrap();
+    let dependency = violations
+        .iter()
+        .find(|v| v["violation_type"] == "dependency")
+        .unwrap();
+    assert_eq!(dependency["code"], "P001");
+    assert_eq!(
+        dependency["help_url"],
+        "https://wiki.example.com/modularity/P001"
+    );
+    let privacy = violations
+        .iter()
+        .find(|v| v["violation_type"] == "privacy")
+        .unwrap();
+    assert_eq!(privacy["code"], "P002");
+    assert_eq!(privacy["help_url"], serde_json::Value::Null);
+
+    common::teardown();
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+fn cache_entries(directory: &Path) -> usize {
+    glob::glob(&format!("{}/**/*", directory.display()))
+        .expect("Failed to read glob pattern")
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .count()
+}
+
+#[test]
+fn test_warm_cache_all() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/app_with_cache")
+        .arg("warm-cache")
+        .arg("--all")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Warmed the cache for 1 file(s)"));
+
+    assert!(
+        cache_entries(Path::new(
+            "tests/fixtures/app_with_cache/tmp/cache/packwerk"
+        )) > 0
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_warm_cache_parses_changed_files() -> Result<(), Box<dyn Error>> {
+    let repo = common::temp_app("packs_warm_cache_test")?;
+    fs::create_dir_all(repo.join("packs/foo/app/services"))?;
+    fs::write(repo.join("packwerk.yml"), "cache: true\n")?;
+    fs::write(repo.join("package.yml"), "")?;
+    fs::write(repo.join("packs/foo/package.yml"), "")?;
+    fs::write(
+        repo.join("packs/foo/app/services/foo.rb"),
+        "class Foo; end\n",
+    )?;
+    fs::write(
+        repo.join("packs/foo/app/services/bar.rb"),
+        "class Bar; end\n",
+    )?;
+    common::git(&repo, &["init", "-q"])?;
+    common::git(&repo, &["add", "."])?;
+    common::git(&repo, &["commit", "-qm", "Add packs/foo"])?;
+    fs::write(
+        repo.join("packs/foo/app/services/foo.rb"),
+        "class Foo\n  Bar\nend\n",
+    )?;
+
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(&repo)
+        .arg("warm-cache")
+        .arg("--since")
+        .arg("HEAD")
+        .arg("--foreground")
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(1, cache_entries(&repo.join("tmp/cache/packwerk")));
+
+    fs::remove_dir_all(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn test_warm_cache_without_cache() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("warm-cache")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("The cache is off"));
+
+    common::teardown();
+    Ok(())
+}
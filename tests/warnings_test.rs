@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+// Passes the check, so only warnings can fail it
+const APP: &str = "tests/fixtures/app_with_sorbet_signatures";
+
+#[test]
+fn test_check_json_includes_deprecation_warnings() -> Result<(), Box<dyn Error>>
+{
+    let output = Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        serde_json::json!([{
+            "kind": "deprecation",
+            "message": "`--json` will be removed in a future version, use `--format json`",
+        }]),
+        report["warnings"]
+    );
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_without_warnings() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .arg("--format")
+        .arg("json")
+        .arg("--deny-warnings")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"warnings\": []"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_deny_warnings() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg(APP)
+        .arg("check")
+        .arg("--json")
+        .arg("--deny-warnings")
+        .assert()
+        .failure();
+
+    common::teardown();
+    Ok(())
+}
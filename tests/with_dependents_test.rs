@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::error::Error;
+
+mod common;
+
+#[test]
+fn test_check_changed_file_only() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("packs/bar/app/services/bar.rb")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No violations detected!"));
+
+    common::teardown();
+    Ok(())
+}
+
+#[test]
+fn test_check_with_dependents() -> Result<(), Box<dyn Error>> {
+    Command::cargo_bin("packs")?
+        .arg("--project-root")
+        .arg("tests/fixtures/simple_app")
+        .arg("check")
+        .arg("--with-dependents")
+        .arg("packs/bar/app/services/bar.rb")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "packs/foo/app/services/foo.rb:3:4\nDependency violation: `::Bar` belongs to `packs/bar`",
+        ));
+
+    common::teardown();
+    Ok(())
+}